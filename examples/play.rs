@@ -89,8 +89,9 @@ fn app(ui: &mut Ui) {
 
 mod styles {
     use coat::{
-        kurbo::{Size, Vec2},
+        kurbo::Size,
         piet::Color,
+        style::{Border, Shadow},
         widgets::button,
         BoxConstraints,
     };
@@ -103,18 +104,16 @@ mod styles {
 
         fn enabled(&self) -> button::Style {
             button::Style {
-                shadow_offset: Vec2::new(0.0, 0.0),
-                background: Color::rgba(0.0, 0.0, 0.0, 0.0),
-                border_radius: 2.0,
-                border_width: 1.0,
-                border_color: Color::rgb(0.5, 0.5, 0.87),
+                shadow: Shadow::NONE,
+                background: Color::rgba(0.0, 0.0, 0.0, 0.0).into(),
+                border: Border::uniform(1.0, Color::rgb(0.5, 0.5, 0.87)).radius(2.0),
                 text_color: Color::WHITE,
             }
         }
 
         fn hovered(&self) -> button::Style {
             button::Style {
-                background: Color::rgb(0.6, 0.6, 0.87),
+                background: Color::rgb(0.6, 0.6, 0.87).into(),
                 ..self.enabled()
             }
         }
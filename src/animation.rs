@@ -0,0 +1,174 @@
+//! Interpolation of values over time.
+//!
+//! Render objects animate by calling `request_anim_frame` on their context,
+//! they then receive an [`Event::AnimFrame`] with the nanoseconds elapsed
+//! since the previous frame. Build code uses [`Ui::animate`] instead.
+//!
+//! [`Event::AnimFrame`]: crate::event::Event::AnimFrame
+//! [`Ui::animate`]: crate::ui::Ui::animate
+
+use crate::{
+    kurbo::{Point, Size, Vec2},
+    piet::Color,
+};
+use std::time::{Duration, Instant};
+
+/// A value that can be interpolated.
+pub trait Interpolate: Clone + PartialEq {
+    /// The value at `t` between `self` (at `0.0`) and `other` (at `1.0`).
+    fn interpolate(&self, other: &Self, t: f64) -> Self;
+}
+
+impl Interpolate for f64 {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        self + (other - self) * t
+    }
+}
+
+impl Interpolate for Point {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        self.lerp(*other, t)
+    }
+}
+
+impl Interpolate for Vec2 {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        self.lerp(*other, t)
+    }
+}
+
+impl Interpolate for Size {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        Size::new(
+            self.width.interpolate(&other.width, t),
+            self.height.interpolate(&other.height, t),
+        )
+    }
+}
+
+impl Interpolate for Color {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        let (r1, g1, b1, a1) = self.as_rgba();
+        let (r2, g2, b2, a2) = other.as_rgba();
+        Color::rgba(
+            r1.interpolate(&r2, t),
+            g1.interpolate(&g2, t),
+            b1.interpolate(&b2, t),
+            a1.interpolate(&a2, t),
+        )
+    }
+}
+
+/// How the progress of an animation changes over time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Easing {
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+}
+
+impl Easing {
+    /// Map the linear progress `t` in `0.0..=1.0` to the eased progress.
+    pub fn apply(self, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t * t,
+            Easing::EaseOut => 1.0 - (1.0 - t).powi(3),
+            Easing::EaseInOut => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+                }
+            }
+        }
+    }
+}
+
+/// The transition of a value towards its latest target.
+pub(crate) struct Transition<T> {
+    from: T,
+    to: T,
+    start: Instant,
+    duration: Duration,
+    easing: Easing,
+}
+
+impl<T: Interpolate> Transition<T> {
+    pub(crate) fn new(value: T, now: Instant) -> Self {
+        Transition {
+            from: value.clone(),
+            to: value,
+            start: now,
+            duration: Duration::from_secs(0),
+            easing: Easing::Linear,
+        }
+    }
+
+    /// Start moving towards `target` from the current value, unless it already does.
+    pub(crate) fn retarget(&mut self, target: T, duration: Duration, easing: Easing, now: Instant) {
+        if target != self.to {
+            self.from = self.value(now);
+            self.to = target;
+            self.start = now;
+            self.duration = duration;
+            self.easing = easing;
+        }
+    }
+
+    /// The value the transition ends at.
+    pub(crate) fn target(&self) -> &T {
+        &self.to
+    }
+
+    pub(crate) fn value(&self, now: Instant) -> T {
+        if self.is_finished(now) {
+            return self.to.clone();
+        }
+        let t =
+            now.saturating_duration_since(self.start).as_secs_f64() / self.duration.as_secs_f64();
+        self.from.interpolate(&self.to, self.easing.apply(t))
+    }
+
+    pub(crate) fn is_finished(&self, now: Instant) -> bool {
+        now >= self.start + self.duration
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn easing_endpoints() {
+        for easing in [
+            Easing::Linear,
+            Easing::EaseIn,
+            Easing::EaseOut,
+            Easing::EaseInOut,
+        ] {
+            assert_eq!(easing.apply(0.0), 0.0);
+            assert_eq!(easing.apply(1.0), 1.0);
+            assert_eq!(easing.apply(2.0), 1.0);
+        }
+        assert_eq!(Easing::EaseInOut.apply(0.5), 0.5);
+    }
+
+    #[test]
+    fn transition_retarget() {
+        let start = Instant::now();
+        let mut transition = Transition::new(0.0, start);
+        transition.retarget(10.0, Duration::from_secs(1), Easing::Linear, start);
+
+        let half = start + Duration::from_millis(500);
+        assert!((transition.value(half) - 5.0).abs() < 1e-9);
+        assert!(!transition.is_finished(half));
+
+        // Retargeting continues from the current value.
+        transition.retarget(0.0, Duration::from_secs(1), Easing::Linear, half);
+        assert!((transition.value(half) - 5.0).abs() < 1e-9);
+        assert_eq!(transition.value(half + Duration::from_secs(1)), 0.0);
+    }
+}
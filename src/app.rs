@@ -1,129 +1,621 @@
 use crate::{
-    context::{ContextState, EventCtx, LayoutCtx, PaintCtx},
-    id::{ChildCounter, ChildId},
-    kurbo::Point,
-    tree::{Child, Children},
+    environment::Environment,
+    inspector,
+    kurbo::{Point, Rect},
+    menu::{self, MenuEntry, MENU_ITEM_SELECTED},
+    profile,
+    pump::{Declared, Platform, WindowTree},
+    recording::{EventLog, Recorder, Recording, Replayer},
+    services::Services,
+    state::Session,
+    task::TASK_FINISHED,
+    text::{FontSource, Fonts, Journals},
+    theme::Theme,
+    tree::Child,
     ui::Ui,
+    wheel,
+    window::{WindowConfig, CLOSE_WINDOW_NOW},
+};
+use druid::{
+    commands, AppDelegate, Command, Cursor, DelegateCtx, ExtEventSink, Handled, MenuDesc, Scale,
+    Selector, SingleUse, Target, TimerToken, WindowDesc, WindowHandle, WindowId,
+};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    path::PathBuf,
+    rc::Rc,
+    time::{Duration, Instant},
 };
-use druid::{ExtEventSink, WindowDesc};
 
 pub struct App {
     name: String,
+    touch_mode: bool,
+    session: Option<PathBuf>,
+    journal_dir: Option<PathBuf>,
+    debug_layout: bool,
+    record_events: Option<PathBuf>,
+    replay_events: Option<PathBuf>,
+    theme: Theme,
+    services: Services,
+    fonts: Vec<FontSource>,
 }
 
 impl App {
     pub fn new(name: impl Into<String>) -> Self {
-        App { name: name.into() }
+        App {
+            name: name.into(),
+            touch_mode: false,
+            session: None,
+            journal_dir: None,
+            debug_layout: false,
+            record_events: None,
+            replay_events: None,
+            theme: Theme::default(),
+            services: Services::platform(),
+            fonts: Vec::new(),
+        }
+    }
+
+    /// Whether the app is primarily used with touch input.
+    ///
+    /// This expands the hit area of small interactive widgets.
+    /// See [`LayoutCtx::set_hit_target`] for more information.
+    ///
+    /// The default value is `false`.
+    pub fn touch_mode(mut self, touch_mode: bool) -> Self {
+        self.touch_mode = touch_mode;
+        self
+    }
+
+    /// Persist the UI state in a session document at `path`.
+    ///
+    /// The state is restored when the app is started again.
+    /// See [`Persistent`] for more information.
+    ///
+    /// [`Persistent`]: crate::state::Persistent
+    pub fn session(mut self, path: impl Into<PathBuf>) -> Self {
+        self.session = Some(path.into());
+        self
+    }
+
+    /// Store the edit journals of text widgets in the directory at `path`.
+    ///
+    /// See [`Journal`] for more information.
+    ///
+    /// [`Journal`]: crate::text::Journal
+    pub fn journal_dir(mut self, path: impl Into<PathBuf>) -> Self {
+        self.journal_dir = Some(path.into());
+        self
+    }
+
+    /// Report misuse of the layout system, like unbounded constraints
+    /// reaching widgets that can not handle them, with the widget path.
+    ///
+    /// Widgets that are larger than their constraints are also marked with
+    /// yellow and black stripes over the part that overflows their parent.
+    ///
+    /// The default value is `false`.
+    pub fn debug_layout(mut self, debug_layout: bool) -> Self {
+        self.debug_layout = debug_layout;
+        self
+    }
+
+    /// Record the input events of the main window to a file at `path`.
+    ///
+    /// See [`recording`] for more information.
+    ///
+    /// [`recording`]: crate::recording
+    pub fn record_events(mut self, path: impl Into<PathBuf>) -> Self {
+        self.record_events = Some(path.into());
+        self
+    }
+
+    /// Replay the input events recorded in the file at `path` in the main window,
+    /// instead of recording them.
+    ///
+    /// See [`recording`] for more information.
+    ///
+    /// [`recording`]: crate::recording
+    pub fn replay_events(mut self, path: impl Into<PathBuf>) -> Self {
+        self.replay_events = Some(path.into());
+        self
+    }
+
+    /// The look of the built-in widgets.
+    ///
+    /// To switch themes while the app is running, [`provide`] another one.
+    ///
+    /// The default value is [`Theme::dark`].
+    ///
+    /// [`provide`]: crate::ui::Ui::provide
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /// The platform capabilities widgets use, like the clipboard.
+    ///
+    /// The default value is [`Services::platform`].
+    pub fn services(mut self, services: Services) -> Self {
+        self.services = services;
+        self
+    }
+
+    /// Make the font in `source` available to the app, next to the
+    /// installed ones.
+    ///
+    /// Text styles refer to it by its family name:
+    ///
+    /// ```ignore
+    /// let mut theme = Theme::default();
+    /// theme.font = FontDescriptor::new(FontFamily::new_unchecked("Inter")).with_size(15.0);
+    /// App::new("Notes")
+    ///     .register_font(&include_bytes!("../fonts/Inter-Regular.ttf")[..])
+    ///     .register_font(Path::new("fonts/Inter-Bold.ttf"))
+    ///     .theme(theme)
+    ///     .run(app)
+    /// ```
+    ///
+    /// Fonts that can not be loaded are reported in the log, and text set in
+    /// them falls back to the default font. Not every platform can load
+    /// fonts, the Cairo backend on Linux can not.
+    pub fn register_font(mut self, source: impl Into<FontSource>) -> Self {
+        self.fonts.push(source.into());
+        self
     }
 
     pub fn run(self, app: impl FnMut(&mut Ui) + 'static) -> Result<(), druid::PlatformError> {
         simple_logger::SimpleLogger::new().init().unwrap();
 
-        let window = WindowDesc::new(|| AppWidget::new(app)).title(self.name);
-        druid::AppLauncher::with_window(window).launch(())
+        let session = self
+            .session
+            .map(|path| Rc::new(RefCell::new(Session::load(path))));
+        let journals = self
+            .journal_dir
+            .map(|dir| Rc::new(RefCell::new(Journals::new(dir))));
+        let event_log = match (self.replay_events, self.record_events) {
+            (Some(path), _) => match Recording::load(&path) {
+                Ok(recording) => Some(EventLog::Replay(Replayer::new(recording))),
+                Err(err) => {
+                    log::error!("Failed to load recording {}: {}", path.display(), err);
+                    None
+                }
+            },
+            (None, Some(path)) => match Recorder::create(&path) {
+                Ok(recorder) => Some(EventLog::Record(recorder)),
+                Err(err) => {
+                    log::error!("Failed to record to {}: {}", path.display(), err);
+                    None
+                }
+            },
+            (None, None) => None,
+        };
+        let window = window_desc(
+            self.name,
+            app,
+            self.touch_mode,
+            session,
+            journals,
+            self.debug_layout,
+            event_log,
+            Environment::default()
+                .with(Box::new(self.theme))
+                .with(Box::new(self.services))
+                .with(Box::new(Fonts::read(self.fonts))),
+        );
+        let result = druid::AppLauncher::with_window(window)
+            .delegate(Delegate)
+            .launch(());
+        profile::dump();
+        result
+    }
+}
+
+/// Asks the app to open a new window.
+pub(crate) const OPEN_WINDOW: Selector<SingleUse<WindowDesc<AppWidgetData>>> =
+    Selector::new("coat.app.open-window");
+
+/// Sent to all windows after a window was closed.
+const WINDOW_CLOSED: Selector<WindowId> = Selector::new("coat.app.window-closed");
+
+/// Describe a window with its own tree, built by `app`.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn window_desc(
+    title: String,
+    app: impl FnMut(&mut Ui) + 'static,
+    touch_mode: bool,
+    session: Option<Rc<RefCell<Session>>>,
+    journals: Option<Rc<RefCell<Journals>>>,
+    debug_layout: bool,
+    event_log: Option<EventLog>,
+    environment: Environment,
+) -> WindowDesc<AppWidgetData> {
+    WindowDesc::new(move || {
+        AppWidget::new(
+            app,
+            touch_mode,
+            session,
+            journals,
+            debug_layout,
+            event_log,
+            environment,
+        )
+    })
+    .title(title)
+}
+
+/// Opens requested windows and reports closed ones.
+struct Delegate;
+
+impl AppDelegate<AppWidgetData> for Delegate {
+    fn command(
+        &mut self,
+        ctx: &mut DelegateCtx,
+        _target: Target,
+        cmd: &Command,
+        _data: &mut AppWidgetData,
+        _env: &druid::Env,
+    ) -> Handled {
+        if let Some(window) = cmd.get(OPEN_WINDOW) {
+            if let Some(window) = window.take() {
+                ctx.new_window(window);
+            }
+            return Handled::Yes;
+        }
+        Handled::No
+    }
+
+    fn window_removed(
+        &mut self,
+        id: WindowId,
+        _data: &mut AppWidgetData,
+        _env: &druid::Env,
+        ctx: &mut DelegateCtx,
+    ) {
+        ctx.submit_command(WINDOW_CLOSED.with(id).to(Target::Global));
     }
 }
 
 struct AppWidget {
-    app: Box<dyn FnMut(&mut Ui)>,
-    root: Children,
-    child_counter: ChildCounter,
-    focus_widget: Option<ChildId>,
+    tree: WindowTree,
     ext_event_sink: Option<ExtEventSink>,
-    mouse_pos: Option<Point>,
+    /// The last mouse move since the last frame, not delivered yet.
+    pending_move: Option<druid::MouseEvent>,
+    /// The last wheel event since the last frame with the summed up delta.
+    pending_wheel: Option<druid::MouseEvent>,
+    /// The positions of the mouse moves coalesced into `pending_move`.
+    pointer_trail: Vec<Point>,
+    has_layers: bool,
+    window_menu: Option<Vec<MenuEntry>>,
+    /// The window properties applied so far.
+    window_config: WindowConfig,
+    /// The cursor requested by the widgets under the pointer.
+    cursor: Option<Cursor>,
+    /// The cursor build code declared for the whole window.
+    cursor_override: Option<Cursor>,
+    /// Maps the tokens of scheduled timers to the tokens the tree knows them by.
+    timers: HashMap<TimerToken, TimerToken>,
+    /// The timer for the next build pass requested by build code.
+    build_timer: Option<(TimerToken, Instant)>,
+    /// The inspector overlay is shown, see [`inspector`].
+    inspecting: bool,
+    /// The scale of the window in the last layout pass.
+    scale: Option<Scale>,
+    /// Records the events of the window or replays recorded ones.
+    event_log: Option<EventLog>,
 }
 
 impl AppWidget {
-    pub fn new(app: impl FnMut(&mut Ui) + 'static) -> Self {
+    pub fn new(
+        app: impl FnMut(&mut Ui) + 'static,
+        touch_mode: bool,
+        session: Option<Rc<RefCell<Session>>>,
+        journals: Option<Rc<RefCell<Journals>>>,
+        debug_layout: bool,
+        event_log: Option<EventLog>,
+        environment: Environment,
+    ) -> Self {
         AppWidget {
-            app: Box::new(app),
-            root: Children::new(),
-            child_counter: ChildCounter::new(),
-            focus_widget: None,
+            tree: WindowTree::new(
+                app,
+                environment,
+                touch_mode,
+                session,
+                journals,
+                debug_layout,
+            ),
             ext_event_sink: None,
-            mouse_pos: None,
+            pending_move: None,
+            pending_wheel: None,
+            pointer_trail: Vec::new(),
+            has_layers: false,
+            window_menu: None,
+            window_config: WindowConfig::default(),
+            cursor: None,
+            cursor_override: None,
+            timers: HashMap::new(),
+            build_timer: None,
+            inspecting: false,
+            scale: None,
+            event_log,
         }
     }
 
     fn root(&mut self) -> &mut Child {
-        &mut self.root.renders[0]
+        self.tree.root()
     }
-}
 
-type AppWidgetData = ();
+    /// Schedule the timers requested by the tree, so the window routes them back to us.
+    fn schedule_timers(
+        &mut self,
+        timers: Vec<(TimerToken, Duration)>,
+        mut request_timer: impl FnMut(Duration) -> TimerToken,
+    ) {
+        for (token, deadline) in timers {
+            self.timers.insert(request_timer(deadline), token);
+        }
+    }
 
-impl druid::Widget<AppWidgetData> for AppWidget {
-    fn event(
+    /// Schedule a build pass at `deadline`, unless one is scheduled earlier.
+    fn schedule_build(
         &mut self,
-        ctx: &mut druid::EventCtx,
-        event: &druid::Event,
-        _data: &mut AppWidgetData,
-        _env: &druid::Env,
+        deadline: Option<Instant>,
+        request_timer: impl FnOnce(Duration) -> TimerToken,
     ) {
-        ctx.set_active(true);
-        ctx.request_focus();
-        let ext_handle = ctx.get_external_handle();
+        let deadline = match (deadline, self.build_timer) {
+            (Some(deadline), Some((_, current))) if deadline < current => deadline,
+            (Some(deadline), None) => deadline,
+            _ => return,
+        };
+        let delay = deadline.saturating_duration_since(Instant::now());
+        self.build_timer = Some((request_timer(delay), deadline));
+    }
+
+    /// Apply what a build pass declared, returns the menu to show if the
+    /// declared window menu changed.
+    fn apply_declared(
+        &mut self,
+        declared: Declared,
+        window: &WindowHandle,
+    ) -> Option<MenuDesc<AppWidgetData>> {
+        let window_size = self.tree.window_size;
+        self.window_config
+            .apply(declared.window_config, window, window_size);
+        self.cursor_override = declared.cursor_override;
+        if declared.window_menu == self.window_menu {
+            return None;
+        }
+        self.window_menu = declared.window_menu;
+        let entries = self.window_menu.as_deref().unwrap_or_default();
+        Some(menu::menu_desc(entries))
+    }
+
+    /// Take the regions of the window the tree needs to repaint.
+    fn take_invalid(&mut self) -> Vec<Rect> {
+        let window_rect = self.tree.window_size.to_rect();
+        let has_layers = self.has_layers;
+        let invalid = &mut self.root().state.invalid;
+        let rects = if has_layers && !invalid.is_empty() {
+            // Layers can paint outside of their ancestors bounds.
+            vec![window_rect]
+        } else {
+            invalid.rects().to_vec()
+        };
+        invalid.clear();
+        rects
+    }
+
+    /// Write the session document, if it changed.
+    fn save_session(&self) {
+        if let Some(session) = &self.tree.session {
+            let mut session = session.borrow_mut();
+            if let Err(err) = session.save() {
+                log::warn!(
+                    "Failed to save session {}: {}",
+                    session.path().display(),
+                    err
+                );
+            }
+        }
+    }
+
+    /// Handle an event of the window, or a replayed one.
+    fn window_event(&mut self, ctx: &mut druid::EventCtx, event: &druid::Event) {
+        // Moving to another monitor resizes the window, or at least repaints it,
+        // but the layout pass is where widgets learn about the new scale.
+        if self.scale.is_some() && self.scale != ctx.window().get_scale().ok() {
+            ctx.request_layout();
+        }
+        if inspector::AVAILABLE && inspector::is_toggle(event) {
+            self.inspecting = !self.inspecting;
+            ctx.request_paint();
+            ctx.set_handled();
+            return;
+        }
+        if self.inspecting {
+            match event {
+                druid::Event::MouseDown(mouse) => {
+                    log::info!("{}", inspector::describe(self.root(), mouse.pos));
+                    return;
+                }
+                druid::Event::MouseUp(_) => return,
+                druid::Event::MouseMove(mouse) => {
+                    self.tree.mouse_pos = Some(mouse.pos);
+                    ctx.request_paint();
+                }
+                _ => {}
+            }
+        }
 
         match event {
-            druid::Event::MouseMove(event)
-            | druid::Event::MouseUp(event)
-            | druid::Event::MouseDown(event) => {
-                self.mouse_pos = Some(event.pos);
+            // Delivered once per frame, so that fast dragging over slow layouts
+            // does not build up a backlog of events.
+            druid::Event::MouseMove(mouse) => {
+                self.pointer_trail.push(mouse.pos);
+                self.pending_move = Some(mouse.clone());
+                ctx.request_anim_frame();
+            }
+            druid::Event::Wheel(wheel) => {
+                let delta = wheel::normalize(wheel.wheel_delta, wheel.mods.shift());
+                let delta = match &self.pending_wheel {
+                    Some(pending) => pending.wheel_delta + delta,
+                    None => delta,
+                };
+                let mut wheel = wheel.clone();
+                wheel.wheel_delta = delta;
+                self.pending_wheel = Some(wheel);
+                ctx.request_anim_frame();
+            }
+            event => {
+                // Coalesced events happened before this one.
+                self.flush_coalesced(ctx);
+                self.handle_event(ctx, event);
             }
-            _ => {}
         }
+    }
 
-        let focus_widget = self.focus_widget;
+    /// Deliver the mouse moves and wheel events coalesced since the last frame.
+    fn flush_coalesced(&mut self, ctx: &mut druid::EventCtx) {
+        if let Some(wheel) = self.pending_wheel.take() {
+            self.handle_event(ctx, &druid::Event::Wheel(wheel));
+        }
+        if let Some(mouse) = self.pending_move.take() {
+            self.handle_event(ctx, &druid::Event::MouseMove(mouse));
+        }
+    }
 
-        let mut context_state = ContextState {
-            ext_handle: &ext_handle,
-            window_id: ctx.window_id(),
-            window: &ctx.window().clone(),
-            text: ctx.text().clone(),
-            focus_widget,
-        };
+    fn handle_event(&mut self, ctx: &mut druid::EventCtx, event: &druid::Event) {
+        ctx.set_active(true);
+        ctx.request_focus();
 
-        let root = self.root();
-        let mut event_ctx = EventCtx {
-            state: &mut context_state,
-            child_state: &mut root.state,
-            is_handled: false,
-            is_root: true,
+        let routed_timer;
+        let event = match event {
+            druid::Event::Timer(token) => {
+                if self.build_timer.map(|(build_token, _)| build_token) == Some(*token) {
+                    self.build_timer = None;
+                    self.root().state.request_update = true;
+                }
+                match self.timers.remove(token) {
+                    Some(token) => {
+                        routed_timer = druid::Event::Timer(token);
+                        &routed_timer
+                    }
+                    None => event,
+                }
+            }
+            event => event,
         };
 
-        root.object.event(&mut event_ctx, event, &mut root.children);
-        ctx.request_paint_rect(root.state.invalid.bounding_box());
-        ctx.request_layout();
+        match event {
+            druid::Event::Command(cmd) => {
+                if let Some(id) = cmd.get(MENU_ITEM_SELECTED) {
+                    self.tree.menu_selection = Some(*id);
+                    self.root().state.request_update = true;
+                    ctx.set_handled();
+                }
+                if let Some(id) = cmd.get(WINDOW_CLOSED) {
+                    self.tree.closed_windows.push(*id);
+                    self.root().state.request_update = true;
+                }
+                if cmd.is(commands::CLOSE_WINDOW) && self.window_config.intercept_close {
+                    self.tree.close_requested = true;
+                    self.root().state.request_update = true;
+                    ctx.set_handled();
+                }
+                if cmd.is(CLOSE_WINDOW_NOW) {
+                    ctx.window().close();
+                    ctx.set_handled();
+                }
+                if cmd.is(TASK_FINISHED) {
+                    self.root().state.request_update = true;
+                    ctx.set_handled();
+                }
+            }
+            druid::Event::WindowSize(size) => {
+                self.window_config.enforce_min_size(ctx.window(), *size);
+            }
+            _ => {}
+        }
 
-        let old_focus_widget = self.focus_widget;
-        if let Some(focus_change) = self.root().state.request_focus {
-            match focus_change {
-                crate::tree::FocusChange::Resign => self.focus_widget = None,
-                crate::tree::FocusChange::Focus(id) => self.focus_widget = Some(id),
-                crate::tree::FocusChange::Next => {}
-                crate::tree::FocusChange::Previous => {}
+        let pointer_trail = match event {
+            druid::Event::MouseMove(_) => std::mem::take(&mut self.pointer_trail),
+            _ => Vec::new(),
+        };
+        let platform = Platform::new(
+            ctx.get_external_handle(),
+            ctx.window_id(),
+            ctx.window().clone(),
+            ctx.text().clone(),
+        );
+        let requests = self.tree.pump(&platform, event, pointer_trail);
+        // Only pointer events visit the hot widgets, which choose the cursor.
+        if matches!(
+            event,
+            druid::Event::MouseMove(_) | druid::Event::MouseDown(_) | druid::Event::MouseUp(_)
+        ) {
+            self.cursor = self.root().state.take_cursor();
+        }
+
+        for command in requests.commands {
+            ctx.submit_command(command);
+        }
+        self.schedule_timers(requests.timers, |delay| ctx.request_timer(delay));
+        if let Some(declared) = requests.declared {
+            if let Some(menu) = self.apply_declared(declared, ctx.window()) {
+                ctx.set_menu(menu);
             }
         }
-        if self.focus_widget != old_focus_widget {
-            let new_focus_widget = self.focus_widget;
-            self.root().update_focus(new_focus_widget);
+        for rect in self.take_invalid() {
+            ctx.request_paint_rect(rect);
+        }
+        // Most events and build passes only repaint a few widgets, a full
+        // layout would invalidate the whole window.
+        if self.root().state.needs_layout {
+            ctx.request_layout();
         }
+        if self.tree.build_anim_frame || self.root().state.request_anim {
+            ctx.request_anim_frame();
+        }
+        self.schedule_build(requests.build_deadline, |delay| ctx.request_timer(delay));
+        let cursor = self.cursor_override.as_ref().or(self.cursor.as_ref());
+        ctx.set_cursor(cursor.unwrap_or(&Cursor::Arrow));
+        self.save_session();
+    }
+}
 
-        let mut needs_update = self.root().needs_update();
-        while needs_update {
-            needs_update = self.root().needs_update();
+pub(crate) type AppWidgetData = ();
 
-            let ext_handle = ctx.get_external_handle();
-            let mut context_state = ContextState {
-                ext_handle: &ext_handle,
-                window_id: ctx.window_id(),
-                window: &ctx.window().clone(),
-                text: ctx.text().clone(),
-                focus_widget,
-            };
-            let mut cx = Ui::new(&mut self.root, &mut context_state, &mut self.child_counter);
-            (self.app)(&mut cx);
+impl druid::Widget<AppWidgetData> for AppWidget {
+    fn event(
+        &mut self,
+        ctx: &mut druid::EventCtx,
+        event: &druid::Event,
+        _data: &mut AppWidgetData,
+        _env: &druid::Env,
+    ) {
+        match &mut self.event_log {
+            Some(EventLog::Record(recorder)) => recorder.record(event),
+            Some(EventLog::Replay(replayer)) => {
+                if let druid::Event::Timer(token) = event {
+                    if replayer.is_timer(*token) {
+                        let (events, delay) = replayer.due();
+                        if let Some(delay) = delay {
+                            replayer.set_timer(ctx.request_timer(delay));
+                        }
+                        for event in events {
+                            match event {
+                                druid::Event::WindowSize(size) => ctx.window().set_size(size),
+                                event => self.window_event(ctx, &event),
+                            }
+                        }
+                        return;
+                    }
+                }
+            }
+            None => {}
         }
+        self.window_event(ctx, event);
     }
 
     fn lifecycle(
@@ -137,18 +629,44 @@ impl druid::Widget<AppWidgetData> for AppWidget {
             let ext_handle = ctx.get_external_handle();
             self.ext_event_sink = Some(ext_handle.clone());
 
-            let mut context_state = ContextState {
-                ext_handle: &ext_handle,
-                window_id: ctx.window_id(),
-                window: &ctx.window().clone(),
-                text: ctx.text().clone(),
-                focus_widget: self.focus_widget,
-            };
-            let mut cx = Ui::new(&mut self.root, &mut context_state, &mut self.child_counter);
-            (self.app)(&mut cx);
+            if let Some(fonts) = self.tree.environment.get::<Fonts>() {
+                fonts.load(ctx.text());
+            }
+
+            if let Some(EventLog::Replay(replayer)) = &mut self.event_log {
+                if let Some(delay) = replayer.start() {
+                    replayer.set_timer(ctx.request_timer(delay));
+                }
+            }
+
+            let platform = Platform::new(
+                ext_handle,
+                ctx.window_id(),
+                ctx.window().clone(),
+                ctx.text().clone(),
+            );
+            let requests = self.tree.build(&platform);
+
+            for command in requests.commands {
+                ctx.submit_command(command);
+            }
+            self.schedule_timers(requests.timers, |delay| ctx.request_timer(delay));
+            if let Some(declared) = requests.declared {
+                if let Some(menu) = self.apply_declared(declared, ctx.window()) {
+                    ctx.set_menu(menu);
+                }
+            }
+            self.schedule_build(requests.build_deadline, |delay| ctx.request_timer(delay));
+            if self.tree.build_anim_frame || self.root().state.request_anim {
+                ctx.request_anim_frame();
+            }
+            for rect in self.take_invalid() {
+                ctx.request_paint_rect(rect);
+            }
+            self.save_session();
         }
         if matches!(event, druid::LifeCycle::HotChanged(false)) {
-            self.mouse_pos = None;
+            self.tree.mouse_pos = None;
         }
     }
 
@@ -169,51 +687,38 @@ impl druid::Widget<AppWidgetData> for AppWidget {
         _data: &AppWidgetData,
         env: &druid::Env,
     ) -> druid::Size {
-        let ext_handle = ctx.get_external_handle();
-        let mouse_pos = self.mouse_pos;
-
-        let mut context_state = ContextState {
-            ext_handle: &ext_handle,
-            window_id: ctx.window_id(),
-            window: &ctx.window().clone(),
-            text: ctx.text().clone(),
-            focus_widget: self.focus_widget,
-        };
-
-        let root = self.root();
-        let mut layout_ctx = LayoutCtx {
-            state: &mut context_state,
-            child_state: &mut root.state,
-            mouse_pos,
-            env,
-        };
-
-        root.state.size = root.object.layout(&mut layout_ctx, bc, &mut root.children);
-        root.state.size
+        let platform = Platform::new(
+            ctx.get_external_handle(),
+            ctx.window_id(),
+            ctx.window().clone(),
+            ctx.text().clone(),
+        );
+        let scale = ctx.window().get_scale().unwrap_or_default();
+        let old_scale = self.scale.replace(scale);
+        if old_scale.is_some() && old_scale != Some(scale) {
+            self.tree.change_scale(&platform, scale);
+        }
+        let (size, requests) = self.tree.layout(&platform, bc, env);
+        self.schedule_timers(requests.timers, |delay| ctx.request_timer(delay));
+        size
     }
 
     fn paint(&mut self, ctx: &mut druid::PaintCtx, _data: &AppWidgetData, env: &druid::Env) {
-        let ext_handle = self.ext_event_sink.clone().unwrap();
-
-        let mut context_state = ContextState {
-            ext_handle: &ext_handle,
-            window_id: ctx.window_id(),
-            window: &ctx.window().clone(),
-            text: ctx.text().clone(),
-            focus_widget: self.focus_widget,
-        };
+        let platform = Platform::new(
+            self.ext_event_sink.clone().unwrap(),
+            ctx.window_id(),
+            ctx.window().clone(),
+            ctx.text().clone(),
+        );
+        let region = ctx.region().clone();
+        let depth = ctx.depth();
+        self.has_layers = self
+            .tree
+            .paint(&platform, ctx.render_ctx, region, depth, env);
 
-        let root = self.root();
-        let mut paint_ctx = PaintCtx {
-            state: &mut context_state,
-            child_state: &mut root.state,
-            z_ops: Vec::new(),
-            region: ctx.region().clone(),
-            depth: ctx.depth(),
-            render_ctx: ctx.render_ctx,
-            env,
-        };
-
-        root.object.paint(&mut paint_ctx, &mut root.children);
+        if self.inspecting {
+            let mouse_pos = self.tree.mouse_pos;
+            inspector::paint(self.root(), mouse_pos, ctx.render_ctx);
+        }
     }
 }
@@ -0,0 +1,92 @@
+//! Helpers for sizing widgets relative to their [`BoxConstraints`].
+
+use crate::{kurbo::Size, BoxConstraints};
+
+/// Sizes derived from [`BoxConstraints`] that layout code often needs.
+pub trait BoxConstraintsExt {
+    /// The largest size with `width / height == ratio` that satisfies the
+    /// constraints, so `16.0 / 9.0` is the size of a widescreen video.
+    ///
+    /// If both axes are unbounded, the smallest size with the ratio is used.
+    /// If the constraints do not allow the ratio, it is given up.
+    fn fit_aspect_ratio(&self, ratio: f64) -> Size;
+
+    /// The constraints tightened to the given fractions of the maximum
+    /// width and height, like `Some(0.5)` for half of the parent.
+    ///
+    /// Axes without a fraction, or with an unbounded maximum, are kept.
+    fn fraction(&self, width: Option<f64>, height: Option<f64>) -> BoxConstraints;
+}
+
+impl BoxConstraintsExt for BoxConstraints {
+    fn fit_aspect_ratio(&self, ratio: f64) -> Size {
+        let (min, max) = (self.min(), self.max());
+        let mut width = if max.width.is_finite() {
+            max.width
+        } else if max.height.is_finite() {
+            max.height * ratio
+        } else {
+            min.width.max(min.height * ratio)
+        };
+        let mut height = width / ratio;
+        if height > max.height {
+            height = max.height;
+            width = height * ratio;
+        }
+        if width < min.width {
+            width = min.width;
+            height = width / ratio;
+        }
+        if height < min.height {
+            height = min.height;
+            width = height * ratio;
+        }
+        self.constrain(Size::new(width, height))
+    }
+
+    fn fraction(&self, width: Option<f64>, height: Option<f64>) -> BoxConstraints {
+        let tighten = |fraction: Option<f64>, min: f64, max: f64| match fraction {
+            Some(fraction) if max.is_finite() => {
+                let extent = (max * fraction).max(min).min(max);
+                (extent, extent)
+            }
+            _ => (min, max),
+        };
+        let (min, max) = (self.min(), self.max());
+        let (min_width, max_width) = tighten(width, min.width, max.width);
+        let (min_height, max_height) = tighten(height, min.height, max.height);
+        BoxConstraints::new(
+            Size::new(min_width, min_height),
+            Size::new(max_width, max_height),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aspect_ratio() {
+        let bc = BoxConstraints::new(Size::ZERO, Size::new(320.0, 400.0));
+        assert_eq!(bc.fit_aspect_ratio(16.0 / 9.0), Size::new(320.0, 180.0));
+        // Too wide for the height.
+        let bc = BoxConstraints::new(Size::ZERO, Size::new(800.0, 90.0));
+        assert_eq!(bc.fit_aspect_ratio(16.0 / 9.0), Size::new(160.0, 90.0));
+        // Unbounded width.
+        let bc = BoxConstraints::new(Size::ZERO, Size::new(f64::INFINITY, 90.0));
+        assert_eq!(bc.fit_aspect_ratio(2.0), Size::new(180.0, 90.0));
+        // Tight constraints win.
+        let bc = BoxConstraints::tight(Size::new(100.0, 100.0));
+        assert_eq!(bc.fit_aspect_ratio(2.0), Size::new(100.0, 100.0));
+    }
+
+    #[test]
+    fn fraction() {
+        let bc = BoxConstraints::new(Size::ZERO, Size::new(400.0, f64::INFINITY));
+        let half = bc.fraction(Some(0.5), Some(0.5));
+        assert_eq!(half.min(), Size::new(200.0, 0.0));
+        assert_eq!(half.max(), Size::new(200.0, f64::INFINITY));
+        assert_eq!(bc.fraction(None, None).max(), bc.max());
+    }
+}
@@ -1,13 +1,34 @@
 use crate::{
+    environment::LayoutDirection,
     id::ChildId,
-    kurbo::{Affine, Insets, Point, Rect, Size},
-    piet::{Piet, PietText, RenderContext},
-    tree::{ChildState, CursorChange, FocusChange},
+    kurbo::{Affine, Insets, Point, Rect, Shape, Size},
+    localization::{self, Args},
+    menu::MenuEntry,
+    piet::{
+        Color, Device, ImageFormat, InterpolationMode, Piet, PietImage, PietText, RenderContext,
+    },
+    profile::FrameStats,
+    pump::{Platform, WindowTree},
+    raster::{RasterCache, RasterKey},
+    services::Services,
+    state::Session,
+    text::{Journal, Journals},
+    theme::Theme,
+    tree::{ChildState, CursorChange, FocusChange, ROUTE_COMMAND},
+    window::WindowConfig,
+    BoxConstraints,
+};
+use druid::{
+    Command, Cursor, ExtEventSink, Region, Scale, Selector, TimerToken, WindowHandle, WindowId,
 };
-use druid::{Cursor, ExtEventSink, Region, TimerToken, WindowHandle, WindowId};
 use std::{
+    any::Any,
+    cell::{RefCell, RefMut},
+    collections::{hash_map::DefaultHasher, HashSet},
+    hash::{Hash, Hasher},
     ops::{Deref, DerefMut},
-    time::Duration,
+    rc::Rc,
+    time::{Duration, Instant},
 };
 
 /// A macro for implementing methods on multiple contexts.
@@ -30,8 +51,152 @@ pub(crate) struct ContextState<'a> {
     pub(crate) window_id: WindowId,
     pub(crate) window: &'a WindowHandle,
     pub(crate) text: PietText,
+    /// The size of the window content area.
+    pub(crate) window_size: Size,
     /// The id of the widget that currently has focus.
     pub(crate) focus_widget: Option<ChildId>,
+    /// Input comes from touch, hit targets should be expanded.
+    pub(crate) touch_mode: bool,
+    /// The window menu item selected since the last build pass.
+    pub(crate) menu_selection: Option<u32>,
+    /// The window menu declared during the current build pass.
+    pub(crate) window_menu: Option<Vec<MenuEntry>>,
+    /// The window properties declared during the current build pass.
+    pub(crate) window_config: WindowConfig,
+    /// The user asked to close the window since the last build pass.
+    pub(crate) close_requested: bool,
+    /// The cursor declared for the whole window during the current build pass.
+    pub(crate) cursor_override: Option<Cursor>,
+    /// A widget captured the pointer before the current event pass.
+    pub(crate) pointer_captured: bool,
+    /// Commands to submit after the current build pass.
+    pub(crate) commands: Vec<Command>,
+    /// The windows that were closed since the last build pass.
+    pub(crate) closed_windows: Vec<WindowId>,
+    /// The persisted UI state, only available while building.
+    pub(crate) session: Option<Rc<RefCell<Session>>>,
+    /// The edit journals, if the app enabled them.
+    pub(crate) journals: Option<Rc<RefCell<Journals>>>,
+    /// Timers requested by widgets, not yet scheduled with the window.
+    pub(crate) timers: Vec<(TimerToken, Duration)>,
+    /// When the next build pass has been requested by build code.
+    pub(crate) build_deadline: Option<Instant>,
+    /// Build code requested a build pass in the next animation frame.
+    pub(crate) anim_frame: bool,
+    /// Layout diagnostics, if the app enabled them.
+    pub(crate) layout_debug: Option<Rc<RefCell<LayoutDebug>>>,
+    /// The bitmaps of [`PaintCtx::paint_cached`].
+    pub(crate) raster_cache: Rc<RefCell<RasterCache<PietImage>>>,
+    /// The window positions of the mouse moves coalesced into the current one.
+    pub(crate) pointer_trail: Vec<Point>,
+    /// The times of the latest frame, only available while building.
+    pub(crate) frame_stats: FrameStats,
+    /// The time of the current pass, controlled by the test harness.
+    pub(crate) now: Instant,
+}
+
+impl<'a> ContextState<'a> {
+    /// The state for a pass over `tree`, with nothing requested or declared yet.
+    pub(crate) fn new(platform: &'a Platform, tree: &WindowTree) -> Self {
+        ContextState {
+            ext_handle: &platform.ext_handle,
+            window_id: platform.window_id,
+            window: &platform.window,
+            text: platform.text.clone(),
+            window_size: tree.window_size,
+            focus_widget: tree.focus_widget,
+            touch_mode: tree.touch_mode,
+            menu_selection: None,
+            window_menu: None,
+            window_config: WindowConfig::default(),
+            close_requested: false,
+            cursor_override: None,
+            pointer_captured: false,
+            commands: Vec::new(),
+            closed_windows: Vec::new(),
+            session: None,
+            journals: tree.journals.clone(),
+            timers: Vec::new(),
+            build_deadline: None,
+            anim_frame: false,
+            layout_debug: tree.layout_debug.clone(),
+            raster_cache: tree.raster_cache.clone(),
+            pointer_trail: Vec::new(),
+            frame_stats: FrameStats::default(),
+            now: platform.now,
+        }
+    }
+}
+
+/// Reports misuse of the layout system together with the path of the widget.
+#[derive(Default)]
+pub(crate) struct LayoutDebug {
+    /// The names of the widgets that are being laid out, starting at the root.
+    pub(crate) path: Vec<&'static str>,
+    /// For each widget of the path, the index of the ancestor that left its
+    /// max width and its max height unbounded, like a scroll container.
+    unbounded_by: Vec<(Option<usize>, Option<usize>)>,
+    /// Issues are only reported once.
+    reported: HashSet<String>,
+}
+
+impl LayoutDebug {
+    /// Start the layout of the widget `name` within `bc`.
+    pub(crate) fn enter(&mut self, name: &'static str, bc: &BoxConstraints) {
+        let parent = self.path.len().checked_sub(1);
+        let (width, height) = self.unbounded_by.last().copied().unwrap_or_default();
+        let max = bc.max();
+        let width = if max.width.is_finite() {
+            None
+        } else {
+            width.or(parent)
+        };
+        let height = if max.height.is_finite() {
+            None
+        } else {
+            height.or(parent)
+        };
+        self.path.push(name);
+        self.unbounded_by.push((width, height));
+    }
+
+    /// Finish the layout of the widget entered last.
+    pub(crate) fn exit(&mut self) {
+        self.path.pop();
+        self.unbounded_by.pop();
+    }
+
+    pub(crate) fn report(&mut self, issue: &str) {
+        let message = format!("{} at `{}`", issue, self.path.join(" > "));
+        if !self.reported.contains(&message) {
+            log::warn!("{}", message);
+            self.reported.insert(message);
+        }
+    }
+
+    /// Report that the widget entered last took the infinite parts of `size`,
+    /// naming the ancestor that left them unbounded.
+    pub(crate) fn report_infinite(&mut self, size: Size) {
+        let (width, height) = self.unbounded_by.last().copied().unwrap_or_default();
+        let axes = [
+            (size.width, width, "width"),
+            (size.height, height, "height"),
+        ];
+        for &(extent, unbounded_by, axis) in &axes {
+            if extent.is_finite() {
+                continue;
+            }
+            let issue = match unbounded_by {
+                Some(index) => format!(
+                    "Infinite {0}, `{1}` left the max {0} of its content unbounded, \
+                     give the widget a bounded max {0}",
+                    axis, self.path[index]
+                ),
+                None => format!("Infinite {0}, give the widget a bounded max {0}", axis),
+            };
+            self.report(&issue);
+        }
+    }
 }
 
 pub struct UpdateCtx<'a, 'b> {
@@ -44,6 +209,8 @@ pub struct EventCtx<'a, 'b> {
     pub(crate) child_state: &'a mut ChildState,
     pub(crate) is_handled: bool,
     pub(crate) is_root: bool,
+    /// The raised child receiving the event before its siblings, see [`EventCtx::set_z_index`].
+    pub(crate) raised_child: Option<ChildId>,
 }
 
 pub struct LifeCycleCtx<'a, 'b> {
@@ -67,9 +234,25 @@ pub struct PaintCtx<'a, 'b, 'c> {
     pub(crate) z_ops: Vec<ZOrderPaintOp>,
     /// The currently visible region.
     pub(crate) region: Region,
+    /// The part of the widget that is not clipped away, see [`PaintCtx::viewport`].
+    pub(crate) viewport: Rect,
     /// The approximate depth in the tree at the time of painting.
     pub(crate) depth: u32,
     pub(crate) env: &'a druid::Env,
+    /// The raised children that were skipped, to be painted after their siblings.
+    pub(crate) raised: Vec<RaisedPaint>,
+    /// The raised child being painted after its siblings.
+    pub(crate) raised_child: Option<ChildId>,
+}
+
+/// A raised child that its container asked to paint, see [`EventCtx::set_z_index`].
+pub(crate) struct RaisedPaint {
+    pub(crate) id: ChildId,
+    pub(crate) z_index: u32,
+    /// The transform, region and viewport the container painted the child with.
+    pub(crate) transform: Affine,
+    pub(crate) region: Region,
+    pub(crate) viewport: Rect,
 }
 
 /// Z-order paint operations with transformations.
@@ -108,6 +291,80 @@ impl_context_method!(
             self.state.window_id
         }
 
+        /// The number of physical pixels per logical pixel of the window.
+        ///
+        /// It changes when the window moves to a monitor with another
+        /// density, which widgets learn from [`LifeCycle::ScaleChanged`].
+        ///
+        /// [`LifeCycle::ScaleChanged`]: crate::lifecycle::LifeCycle::ScaleChanged
+        pub fn scale(&self) -> Scale {
+            self.state.window.get_scale().unwrap_or_default()
+        }
+
+        /// The value of type `T` provided to this widget with [`Ui::provide`].
+        ///
+        /// [`Ui::provide`]: crate::ui::Ui::provide
+        pub fn provided<T: Any>(&self) -> Option<&T> {
+            self.child_state.environment.get()
+        }
+
+        /// The style of type `S` this widget was last painted with.
+        ///
+        /// See [`PaintCtx::publish_style`] for more information.
+        pub fn resolved_style<S: Any + Clone>(&self) -> Option<S> {
+            self.child_state.resolved_style()
+        }
+
+        /// The [`LayoutDirection`] provided to this widget, left to right by default.
+        pub fn layout_direction(&self) -> LayoutDirection {
+            self.child_state.environment.layout_direction()
+        }
+
+        /// The [`Theme`] provided to this widget, or the default one.
+        pub fn theme(&self) -> &Theme {
+            match self.provided() {
+                Some(theme) => theme,
+                None => Theme::fallback(),
+            }
+        }
+
+        /// The message `id` of the [`Localization`] provided to this widget,
+        /// or the `id` itself, see [`Ui::localized`].
+        ///
+        /// [`Localization`]: crate::localization::Localization
+        /// [`Ui::localized`]: crate::ui::Ui::localized
+        pub fn localized(&self, id: &str) -> String {
+            self.localized_with(id, &Args::new())
+        }
+
+        /// The message `id` of the provided [`Localization`], with `args` inserted.
+        ///
+        /// [`Localization`]: crate::localization::Localization
+        pub fn localized_with(&self, id: &str, args: &Args) -> String {
+            localization::localized(self.provided(), id, args)
+        }
+
+        /// The [`Services`] provided to this widget, or the platform ones.
+        pub fn services(&self) -> &Services {
+            match self.provided() {
+                Some(services) => services,
+                None => Services::fallback(),
+            }
+        }
+
+        /// The size of the window content area, as of the last layout pass.
+        pub fn window_size(&self) -> Size {
+            self.state.window_size
+        }
+
+        /// The time of the current pass.
+        ///
+        /// Timed behavior should use it instead of `Instant::now`, so that
+        /// tests can control it with the [`Harness`](crate::testing::Harness).
+        pub fn now(&self) -> Instant {
+            self.state.now
+        }
+
         /// Get an object which can create text layouts.
         pub fn text(&mut self) -> &mut PietText {
             &mut self.state.text
@@ -165,14 +422,11 @@ impl_context_method!(
         /// A widget can request focus using the [`request_focus`] method.
         /// It's also possible to register for automatic focus via [`register_for_focus`].
         ///
-        /// If a widget gains or loses focus it will get a [`LifeCycle::FocusChanged`] event.
-        ///
         /// Only one widget at a time is focused. However due to the way events are routed,
         /// all ancestors of that widget will also receive keyboard events.
         ///
         /// [`request_focus`]: struct.EventCtx.html#method.request_focus
         /// [`register_for_focus`]: struct.LifeCycleCtx.html#method.register_for_focus
-        /// [`LifeCycle::FocusChanged`]: enum.LifeCycle.html#variant.FocusChanged
         /// [`has_focus`]: #method.has_focus
         pub fn is_focused(&self) -> bool {
             self.state.focus_widget == Some(self.child_state.id)
@@ -187,6 +441,16 @@ impl_context_method!(
         pub fn has_focus(&self) -> bool {
             self.child_state.has_focus
         }
+
+        /// Whether the widget is disabled by an ancestor, see [`Ui::disabled`].
+        ///
+        /// Disabled widgets receive no mouse, wheel or keyboard events, except
+        /// for finishing a press that started before they were disabled.
+        ///
+        /// [`Ui::disabled`]: crate::ui::Ui::disabled
+        pub fn is_disabled(&self) -> bool {
+            self.child_state.environment.is_disabled()
+        }
     }
 );
 
@@ -262,7 +526,7 @@ impl_context_method!(EventCtx<'_, '_>, UpdateCtx<'_, '_>, {
     /// preferences of a child widget. (If that isn't what you want, use [`set_cursor`] instead.)
     ///
     /// [`clear_cursor`]: EventCtx::clear_cursor
-    /// [`set_cursor`]: EventCtx::override_cursor
+    /// [`set_cursor`]: EventCtx::set_cursor
     /// [`hot`]: EventCtx::is_hot
     /// [`active`]: EventCtx::is_active
     pub fn override_cursor(&mut self, cursor: &Cursor) {
@@ -316,11 +580,70 @@ impl_context_method!(EventCtx<'_, '_>, UpdateCtx<'_, '_>, LifeCycleCtx<'_, '_>,
         self.child_state.needs_layout = true;
     }
 
+    /// Paint this widget above its siblings with a lower z-index, and give
+    /// it mouse events before them.
+    ///
+    /// The default z-index is `0`, which keeps the order the container paints
+    /// its children in. Raised widgets are painted once the container painted
+    /// everything else, with the transform it painted them with but outside
+    /// of its clips, so they can pop out of it, like dragged items or focused
+    /// cards.
+    pub fn set_z_index(&mut self, z_index: u32) {
+        if z_index != self.child_state.z_index {
+            self.child_state.z_index = z_index;
+            if z_index == 0 {
+                self.child_state.painted_raised = false;
+            }
+            self.request_paint();
+        }
+    }
+
+    /// Ask the enclosing scroll containers to scroll `rect` into view.
+    ///
+    /// The rect is in the coordinate space of this widget. The request is
+    /// handled in the next layout pass, see [`LayoutCtx::take_scroll_to_view`].
+    /// Widgets that receive focus are brought into view automatically.
+    pub fn scroll_to_view(&mut self, rect: Rect) {
+        let origin = self.child_state.window_origin().to_vec2();
+        self.child_state.scroll_to_view = Some(rect + origin);
+        self.child_state.needs_layout = true;
+    }
+
     /// Request an animation frame.
+    ///
+    /// The widget receives an [`Event::AnimFrame`] with the nanoseconds
+    /// elapsed since the previous frame.
+    ///
+    /// [`Event::AnimFrame`]: crate::event::Event::AnimFrame
     pub fn request_anim_frame(&mut self) {
         self.child_state.request_anim = true;
     }
 
+    /// The edit journal with the given name, if the app enabled journaling.
+    ///
+    /// See [`Journal`] for more information.
+    pub fn journal(&mut self, name: &str) -> Option<RefMut<'_, Journal>> {
+        let journals = self.state.journals.as_ref()?;
+        Some(RefMut::map(journals.borrow_mut(), |journals| {
+            journals.journal(name)
+        }))
+    }
+
+    /// Submit a command to all widgets of the window.
+    ///
+    /// It is delivered as an [`Event::Command`] after the current pass.
+    ///
+    /// [`Event::Command`]: crate::event::Event::Command
+    pub fn submit_command(&mut self, cmd: impl Into<Command>) {
+        self.state.commands.push(cmd.into());
+    }
+
+    /// Submit a command to the widget with the given id only.
+    pub fn submit_command_to(&mut self, target: ChildId, cmd: impl Into<Command>) {
+        let cmd = ROUTE_COMMAND.with((target, cmd.into()));
+        self.state.commands.push(cmd);
+    }
+
     /*
 
     /// Indicate that your children have changed.
@@ -384,7 +707,10 @@ impl_context_method!(
         /// Request a timer event.
         ///
         /// The return value is a token, which can be used to associate the
-        /// request with the event.
+        /// request with the event. Once `deadline` has passed, this widget
+        /// receives an [`Event::Timer`] with that token.
+        ///
+        /// [`Event::Timer`]: crate::event::Event::Timer
         pub fn request_timer(&mut self, deadline: Duration) -> TimerToken {
             self.state.request_timer(&mut self.child_state, deadline)
         }
@@ -392,37 +718,50 @@ impl_context_method!(
 );
 
 impl EventCtx<'_, '_> {
-    /*
-
-    /// Submit a [`Notification`].
-    ///
-    /// The provided argument can be a [`Selector`] or a [`Command`]; this lets
-    /// us work with the existing API for addding a payload to a [`Selector`].
+    /// Submit a notification to the ancestors of this widget.
     ///
-    /// If the argument is a `Command`, the command's target will be ignored.
+    /// It bubbles up until an ancestor takes it with [`take_notifications`],
+    /// for example a [`CommandHandler`].
     ///
-    /// # Examples
-    ///
-    /// ```
-    /// # use druid::{Event, EventCtx, Selector};
-    /// const IMPORTANT_EVENT: Selector<String> = Selector::new("druid-example.important-event");
-    ///
-    /// fn check_event(ctx: &mut EventCtx, event: &Event) {
-    ///     if is_this_the_event_we_were_looking_for(event) {
-    ///         ctx.submit_notification(IMPORTANT_EVENT.with("That's the one".to_string()))
-    ///     }
-    /// }
-    ///
-    /// # fn is_this_the_event_we_were_looking_for(event: &Event) -> bool { true }
-    /// ```
-    ///
-    /// [`Selector`]: crate::Selector
+    /// [`take_notifications`]: EventCtx::take_notifications
+    /// [`CommandHandler`]: crate::widgets::CommandHandler
     pub fn submit_notification(&mut self, note: impl Into<Command>) {
-        let note = note.into().into_notification(self.child_state.id);
-        self.notifications.push_back(note);
+        self.child_state.notifications.push(note.into());
     }
 
-    */
+    /// Take the payloads of the notifications with the given selector,
+    /// which descendants submitted while handling the current event.
+    pub fn take_notifications<T: Any + Clone>(&mut self, selector: Selector<T>) -> Vec<T> {
+        let mut payloads = Vec::new();
+        self.child_state
+            .notifications
+            .retain(|note| match note.get(selector) {
+                Some(payload) => {
+                    payloads.push(payload.clone());
+                    false
+                }
+                None => true,
+            });
+        payloads
+    }
+
+    /// The positions of all mouse moves that were coalesced into the current
+    /// [`MouseMove`], oldest first, in the coordinate space of this widget.
+    ///
+    /// Mouse moves and wheel events arriving faster than the frame rate are
+    /// delivered once per frame. Wheel events carry the summed up delta, moves
+    /// only the last position. Widgets that need every position, like drawing
+    /// tools, read them here. Empty for other events.
+    ///
+    /// [`MouseMove`]: crate::event::Event::MouseMove
+    pub fn pointer_trail(&self) -> Vec<Point> {
+        let origin = self.window_origin().to_vec2();
+        self.state
+            .pointer_trail
+            .iter()
+            .map(|pos| *pos - origin)
+            .collect()
+    }
 
     /// Set the "active" state of the widget.
     ///
@@ -432,6 +771,38 @@ impl EventCtx<'_, '_> {
         // TODO: plumb mouse grab through to platform (through druid-shell)
     }
 
+    /// Capture the pointer, so that only this widget receives mouse events
+    /// until [`release_pointer`] is called or all mouse buttons are released.
+    ///
+    /// An [active] widget keeps receiving mouse events when the pointer leaves
+    /// it, but so do the widgets under the pointer, which also become hot.
+    /// While the pointer is captured, the other widgets neither receive mouse
+    /// events nor change their hot status, so drags of slider thumbs or
+    /// split sashes can not interact with what they cross. Capturing also
+    /// makes the widget active.
+    ///
+    /// [`release_pointer`]: EventCtx::release_pointer
+    /// [active]: EventCtx::is_active
+    pub fn capture_pointer(&mut self) {
+        self.child_state.captures_pointer = true;
+        self.set_active(true);
+    }
+
+    /// End the capture of [`capture_pointer`], which also makes the widget inactive.
+    ///
+    /// [`capture_pointer`]: EventCtx::capture_pointer
+    pub fn release_pointer(&mut self) {
+        self.child_state.captures_pointer = false;
+        self.set_active(false);
+    }
+
+    /// Whether this widget captured the pointer with [`capture_pointer`].
+    ///
+    /// [`capture_pointer`]: EventCtx::capture_pointer
+    pub fn has_pointer_capture(&self) -> bool {
+        self.child_state.captures_pointer
+    }
+
     /*
 
     /// Create a new window.
@@ -629,6 +1000,49 @@ impl LayoutCtx<'_, '_> {
     pub fn set_baseline_offset(&mut self, baseline: f64) {
         self.child_state.baseline_offset = baseline
     }
+
+    /// Mark this widget as a target for pointer input.
+    ///
+    /// While touch input is active, the hit area of small targets is expanded to
+    /// [`MIN_TOUCH_TARGET`] around their center. This does not change the layout,
+    /// and the expanded area also receives events outside of the parent.
+    ///
+    /// [`MIN_TOUCH_TARGET`]: crate::tree::MIN_TOUCH_TARGET
+    pub fn set_hit_target(&mut self, hit_target: bool) {
+        self.child_state.is_hit_target = hit_target;
+    }
+
+    /// Report that this widget can not handle the constraints it got.
+    ///
+    /// Issues are only reported if the app enabled [`App::debug_layout`].
+    ///
+    /// [`App::debug_layout`]: crate::app::App::debug_layout
+    pub fn report_layout_issue(&mut self, issue: &str) {
+        if let Some(layout_debug) = &self.state.layout_debug {
+            layout_debug.borrow_mut().report(issue);
+        }
+    }
+
+    /// Take the area the children laid out so far asked to scroll into view,
+    /// in the coordinate space of this widget.
+    ///
+    /// Scroll containers call this after laying out their content and adjust
+    /// their offset. They pass the part that is still hidden on to their own
+    /// ancestors with [`scroll_to_view`].
+    ///
+    /// [`scroll_to_view`]: LayoutCtx::scroll_to_view
+    pub fn take_scroll_to_view(&mut self) -> Option<Rect> {
+        let origin = self.child_state.window_origin().to_vec2();
+        let rect = self.child_state.scroll_to_view.take()?;
+        Some(rect - origin)
+    }
+
+    /// Ask the enclosing scroll containers to scroll `rect` into view,
+    /// in the coordinate space of this widget.
+    pub fn scroll_to_view(&mut self, rect: Rect) {
+        let origin = self.child_state.window_origin().to_vec2();
+        self.child_state.scroll_to_view = Some(rect + origin);
+    }
 }
 
 impl PaintCtx<'_, '_, '_> {
@@ -652,6 +1066,244 @@ impl PaintCtx<'_, '_, '_> {
         &self.region
     }
 
+    /// The part of the widget that is visible in the window, in its own coordinates.
+    ///
+    /// This is the window, clipped by the widget and its ancestors with [`clip`].
+    /// Children outside of it are not painted, containers with many children,
+    /// like scroll areas, can use it to skip them early.
+    ///
+    /// [`clip`]: PaintCtx::clip
+    #[inline]
+    pub fn viewport(&self) -> Rect {
+        self.viewport
+    }
+
+    /// The width of a single physical pixel, for the thinnest possible lines.
+    pub fn hairline(&self) -> f64 {
+        1.0 / self.scale().y()
+    }
+
+    /// `width` rounded to whole physical pixels, but at least one unless it
+    /// is zero, so that lines and borders are not blurred across pixels.
+    pub fn snap_width(&self, width: f64) -> f64 {
+        if width <= 0.0 {
+            return 0.0;
+        }
+        let scale = self.scale().y();
+        (width * scale).round().max(1.0) / scale
+    }
+
+    /// The corner of the physical pixel nearest to `point`, in the
+    /// coordinates of this widget.
+    ///
+    /// Transforms of the ancestors, like zooming, are not taken into account.
+    pub fn snap_point(&self, point: Point) -> Point {
+        let scale = self.scale();
+        let origin = self.child_state.window_origin().to_vec2();
+        let window = point + origin;
+        let snapped = Point::new(
+            (window.x * scale.x()).round() / scale.x(),
+            (window.y * scale.y()).round() / scale.y(),
+        );
+        snapped - origin
+    }
+
+    /// `rect` with its edges moved to the nearest physical pixel boundaries.
+    pub fn snap_rect(&self, rect: Rect) -> Rect {
+        Rect::from_points(
+            self.snap_point(rect.origin()),
+            self.snap_point(Point::new(rect.x1, rect.y1)),
+        )
+    }
+
+    /// Clip the painting of this widget and its children to `shape`.
+    ///
+    /// Like clipping the render context, but also narrows the [`viewport`]
+    /// to the bounds of `shape`, so that children outside of it are not painted at all.
+    /// The parts of children outside of the bounds do not become hot either,
+    /// so they do not receive the pointer events meant for what is painted there.
+    ///
+    /// [`viewport`]: PaintCtx::viewport
+    pub fn clip(&mut self, shape: impl Shape) {
+        let bounds = shape.bounding_box();
+        self.render_ctx.clip(shape);
+        self.viewport = self.viewport.intersect(bounds);
+        self.region.intersect_with(bounds);
+    }
+
+    /// Paint vector content into `rect` through the raster cache of the window.
+    ///
+    /// `draw` paints the content for the given size, tinted with `tint`,
+    /// into a bitmap the first time `key` is painted at this size, scale and tint.
+    /// Afterwards the bitmap is reused, also by other widgets painting the same key.
+    /// Falls back to painting directly if no bitmap can be created, or if it
+    /// would take up too much of the cache.
+    pub fn paint_cached(
+        &mut self,
+        key: impl Hash,
+        rect: Rect,
+        tint: &Color,
+        draw: impl Fn(&mut Piet, Size, &Color),
+    ) {
+        let scale = self.state.window.get_scale().map_or(1.0, |scale| scale.x());
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        let key = RasterKey {
+            content: hasher.finish(),
+            width: (rect.width() * scale).ceil() as usize,
+            height: (rect.height() * scale).ceil() as usize,
+            tint: tint.as_rgba_u32(),
+        };
+        if key.width == 0 || key.height == 0 {
+            return;
+        }
+
+        let cache = self.state.raster_cache.clone();
+        let mut cache = cache.borrow_mut();
+        if cache.get(&key).is_none() {
+            let image = match cache.fits(&key) {
+                true => self
+                    .rasterize(&key, scale, rect.size(), tint, &draw)
+                    .map_err(|err| log::warn!("Failed to rasterize vector content: {}", err))
+                    .ok(),
+                false => None,
+            };
+            match image {
+                Some(image) => {
+                    cache.insert(key, image);
+                }
+                None => {
+                    self.with_save(|ctx| {
+                        ctx.transform(Affine::translate(rect.origin().to_vec2()));
+                        draw(ctx.render_ctx, rect.size(), tint);
+                    });
+                    return;
+                }
+            }
+        }
+        if let Some(image) = cache.get(&key) {
+            self.render_ctx
+                .draw_image(image, rect, InterpolationMode::Bilinear);
+        }
+    }
+
+    fn rasterize(
+        &mut self,
+        key: &RasterKey,
+        scale: f64,
+        size: Size,
+        tint: &Color,
+        draw: &impl Fn(&mut Piet, Size, &Color),
+    ) -> Result<PietImage, druid::piet::Error> {
+        let mut device = Device::new()?;
+        let mut target = device.bitmap_target(key.width, key.height, scale)?;
+        let mut rc = target.render_context();
+        draw(&mut rc, size, tint);
+        rc.finish()?;
+        drop(rc);
+
+        let mut pixels = vec![0; key.width * key.height * 4];
+        target.copy_raw_pixels(ImageFormat::RgbaPremul, &mut pixels)?;
+        self.render_ctx
+            .make_image(key.width, key.height, &pixels, ImageFormat::RgbaPremul)
+    }
+
+    /// Paint what `f` paints with `opacity` as a whole.
+    ///
+    /// Unlike making every color transparent, overlapping parts do not show
+    /// through each other. The painting goes through a bitmap of the paint
+    /// rect of the widget, which is skipped if `opacity` is `1.0`. Falls back
+    /// to painting directly if no bitmap can be created.
+    pub fn with_opacity(&mut self, opacity: f64, f: impl FnOnce(&mut PaintCtx)) {
+        let opacity = opacity.clamp(0.0, 1.0);
+        if opacity >= 1.0 {
+            f(self);
+            return;
+        }
+        let scale = self.state.window.get_scale().map_or(1.0, |scale| scale.x());
+        let rect =
+            self.child_state.paint_rect() - self.child_state.layout_rect().origin().to_vec2();
+        let width = (rect.width() * scale).ceil() as usize;
+        let height = (rect.height() * scale).ceil() as usize;
+        if opacity <= 0.0 || width == 0 || height == 0 {
+            return;
+        }
+
+        let mut f = Some(f);
+        match self.paint_layer(rect.origin(), width, height, scale, opacity, &mut f) {
+            Ok(image) => {
+                let size = Size::new(width as f64 / scale, height as f64 / scale);
+                let rect = Rect::from_origin_size(rect.origin(), size);
+                self.render_ctx
+                    .draw_image(&image, rect, InterpolationMode::Bilinear);
+            }
+            Err(err) => {
+                log::warn!("Failed to paint with opacity: {}", err);
+                if let Some(f) = f {
+                    f(self);
+                }
+            }
+        }
+    }
+
+    /// Paint into a bitmap whose top left corner is at `origin`, and fade it by `opacity`.
+    fn paint_layer(
+        &mut self,
+        origin: Point,
+        width: usize,
+        height: usize,
+        scale: f64,
+        opacity: f64,
+        f: &mut Option<impl FnOnce(&mut PaintCtx)>,
+    ) -> Result<PietImage, druid::piet::Error> {
+        let mut device = Device::new()?;
+        let mut target = device.bitmap_target(width, height, scale)?;
+        let mut rc = target.render_context();
+        rc.transform(Affine::translate(-origin.to_vec2()));
+        let to_window = self.render_ctx.current_transform() * rc.current_transform().inverse();
+        let mut layer_ctx = PaintCtx {
+            state: self.state,
+            child_state: self.child_state,
+            render_ctx: &mut rc,
+            z_ops: Vec::new(),
+            region: self.region.clone(),
+            viewport: self.viewport,
+            depth: self.depth,
+            env: self.env,
+            raised: Vec::new(),
+            raised_child: None,
+        };
+        if let Some(f) = f.take() {
+            f(&mut layer_ctx);
+        }
+        self.z_ops.append(&mut layer_ctx.z_ops);
+        // Raised children are painted later, on the window.
+        for mut raised in layer_ctx.raised.drain(..) {
+            raised.transform = to_window * raised.transform;
+            self.raised.push(raised);
+        }
+        rc.finish()?;
+        drop(rc);
+
+        let mut pixels = vec![0; width * height * 4];
+        target.copy_raw_pixels(ImageFormat::RgbaPremul, &mut pixels)?;
+        // Premultiplied colors fade by scaling all channels.
+        for byte in &mut pixels {
+            *byte = (f64::from(*byte) * opacity).round() as u8;
+        }
+        self.render_ctx
+            .make_image(width, height, &pixels, ImageFormat::RgbaPremul)
+    }
+
+    /// Record the style this widget is painted with.
+    ///
+    /// Widgets with a style sheet publish the style they resolved for their
+    /// current state, so the inspector, tests and theming tools can check which
+    /// values actually applied with `resolved_style`.
+    pub fn publish_style<S: Any>(&self, style: S) {
+        *self.child_state.resolved_style.borrow_mut() = Some(Box::new(style));
+    }
+
     /// Creates a temporary `PaintCtx` with a new visible region, and calls
     /// the provided function with that `PaintCtx`.
     ///
@@ -664,11 +1316,15 @@ impl PaintCtx<'_, '_, '_> {
             render_ctx: self.render_ctx,
             z_ops: Vec::new(),
             region: region.into(),
+            viewport: self.viewport,
             depth: self.depth + 1,
             env: self.env,
+            raised: Vec::new(),
+            raised_child: self.raised_child,
         };
         f(&mut child_ctx);
         self.z_ops.append(&mut child_ctx.z_ops);
+        self.raised.append(&mut child_ctx.raised);
     }
 
     /// Saves the current context, executes the closures, and restores the context.
@@ -697,9 +1353,13 @@ impl PaintCtx<'_, '_, '_> {
             log::error!("Failed to save RenderContext: '{}'", e);
             return;
         }
+        let viewport = self.viewport;
+        let region = self.region.clone();
 
         f(self);
 
+        self.viewport = viewport;
+        self.region = region;
         if let Err(e) = self.render_ctx.restore() {
             log::error!("Failed to restore RenderContext: '{}'", e);
         }
@@ -723,11 +1383,22 @@ impl PaintCtx<'_, '_, '_> {
 }
 
 impl<'a> ContextState<'a> {
-    fn request_timer(&self, child_state: &mut ChildState, deadline: Duration) -> TimerToken {
-        let timer_token = self.window.request_timer(deadline);
+    fn request_timer(&mut self, child_state: &mut ChildState, deadline: Duration) -> TimerToken {
+        // The app schedules the timer with the window after the current pass,
+        // which routes it back to us.
+        let timer_token = TimerToken::next();
+        self.timers.push((timer_token, deadline));
         child_state.add_timer(timer_token);
         timer_token
     }
+
+    /// Request a build pass no later than `deadline`.
+    pub(crate) fn request_build_at(&mut self, deadline: Instant) {
+        self.build_deadline = Some(match self.build_deadline {
+            Some(current) => current.min(deadline),
+            None => deadline,
+        });
+    }
 }
 
 impl<'c> Deref for PaintCtx<'_, '_, 'c> {
@@ -743,3 +1414,35 @@ impl<'c> DerefMut for PaintCtx<'_, '_, 'c> {
         self.render_ctx
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_what_left_the_constraints_unbounded() {
+        let bounded = BoxConstraints::tight(Size::new(100.0, 100.0));
+        let unbounded = BoxConstraints::new(Size::ZERO, Size::new(100.0, f64::INFINITY));
+        let mut debug = LayoutDebug::default();
+        debug.enter("Root", &bounded);
+        debug.enter("Outer", &bounded);
+        debug.enter("Inner", &unbounded);
+        debug.enter("Label", &unbounded);
+        debug.report_infinite(Size::new(10.0, f64::INFINITY));
+        debug.exit();
+        debug.exit();
+        debug.enter("Other", &bounded);
+        debug.report_infinite(Size::new(f64::INFINITY, 10.0));
+
+        let mut reported: Vec<_> = debug.reported.iter().cloned().collect();
+        reported.sort();
+        assert_eq!(
+            reported,
+            [
+                "Infinite height, `Outer` left the max height of its content unbounded, \
+                 give the widget a bounded max height at `Root > Outer > Inner > Label`",
+                "Infinite width, give the widget a bounded max width at `Root > Outer > Other`",
+            ]
+        );
+    }
+}
@@ -0,0 +1,133 @@
+//! Values provided to a subtree.
+
+use std::{any::Any, rc::Rc};
+
+/// Provided by [`Ui::disabled`], whether the subtree is disabled.
+///
+/// [`Ui::disabled`]: crate::ui::Ui::disabled
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Disabled(pub(crate) bool);
+
+/// The direction in which text and rows of widgets flow.
+///
+/// Provide [`LayoutDirection::RightToLeft`] with [`Ui::provide`] for
+/// languages like Arabic and Hebrew. It mirrors rows, the leading edge of
+/// columns, directional padding and the placement of text in labels.
+///
+/// ```ignore
+/// ui.provide(LayoutDirection::RightToLeft, |ui| app(ui));
+/// ```
+///
+/// [`Ui::provide`]: crate::ui::Ui::provide
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LayoutDirection {
+    #[default]
+    LeftToRight,
+    RightToLeft,
+}
+
+/// The values that the ancestors of a widget provided with [`Ui::provide`].
+///
+/// [`Ui::provide`]: crate::ui::Ui::provide
+#[derive(Clone, Default)]
+pub struct Environment {
+    head: Option<Rc<Entry>>,
+}
+
+struct Entry {
+    value: Box<dyn Any>,
+    parent: Environment,
+}
+
+impl Environment {
+    /// The value of type `T` provided by the closest ancestor.
+    pub fn get<T: Any>(&self) -> Option<&T> {
+        let mut entry = self.head.as_deref();
+        while let Some(current) = entry {
+            if let Some(value) = current.value.downcast_ref() {
+                return Some(value);
+            }
+            entry = current.parent.head.as_deref();
+        }
+        None
+    }
+
+    /// Whether the widgets with this environment are disabled, see [`Ui::disabled`].
+    ///
+    /// [`Ui::disabled`]: crate::ui::Ui::disabled
+    pub(crate) fn is_disabled(&self) -> bool {
+        matches!(self.get(), Some(Disabled(true)))
+    }
+
+    /// The provided [`LayoutDirection`], left to right by default.
+    pub(crate) fn layout_direction(&self) -> LayoutDirection {
+        self.get().copied().unwrap_or_default()
+    }
+
+    /// This environment, with `value` provided in addition.
+    pub(crate) fn with(&self, value: Box<dyn Any>) -> Environment {
+        let entry = Entry {
+            value,
+            parent: self.clone(),
+        };
+        Environment {
+            head: Some(Rc::new(entry)),
+        }
+    }
+
+    /// The environment this one was created from with [`with`], and the value it added.
+    ///
+    /// [`with`]: Environment::with
+    pub(crate) fn split(&self) -> Option<(&Environment, &dyn Any)> {
+        let entry = self.head.as_deref()?;
+        Some((&entry.parent, entry.value.as_ref()))
+    }
+
+    /// Returns `true` if both are the same environment, not just equal ones.
+    pub(crate) fn same(&self, other: &Environment) -> bool {
+        match (&self.head, &other.head) {
+            (Some(a), Some(b)) => Rc::ptr_eq(a, b),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn closest_value_wins() {
+        let root = Environment::default();
+        let outer = root.with(Box::new(1u32)).with(Box::new("text"));
+        let inner = outer.with(Box::new(2u32));
+
+        assert_eq!(root.get::<u32>(), None);
+        assert_eq!(outer.get::<u32>(), Some(&1));
+        assert_eq!(inner.get::<u32>(), Some(&2));
+        assert_eq!(inner.get::<&str>(), Some(&"text"));
+        assert!(inner.split().unwrap().0.same(&outer));
+    }
+
+    #[test]
+    fn disabled() {
+        let root = Environment::default();
+        let disabled = root.with(Box::new(Disabled(true)));
+        assert!(!root.is_disabled());
+        assert!(disabled.is_disabled());
+        assert!(disabled.with(Box::new(1u32)).is_disabled());
+        assert!(!disabled.with(Box::new(Disabled(false))).is_disabled());
+    }
+
+    #[test]
+    fn layout_direction() {
+        let root = Environment::default();
+        assert_eq!(root.layout_direction(), LayoutDirection::LeftToRight);
+        let rtl = root.with(Box::new(LayoutDirection::RightToLeft));
+        assert_eq!(
+            rtl.with(Box::new(1u32)).layout_direction(),
+            LayoutDirection::RightToLeft
+        );
+    }
+}
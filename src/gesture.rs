@@ -0,0 +1,259 @@
+//! Gestures recognized from the mouse events of a window.
+
+use crate::{
+    event::{Event, MouseButton},
+    kurbo::{Affine, Point, Vec2},
+};
+use druid::{Selector, TimerToken};
+use std::time::Duration;
+
+/// How far the pointer has to move before a press becomes a drag.
+pub const DRAG_THRESHOLD: f64 = 4.0;
+/// How long the pointer has to be held down for a long press.
+pub const LONG_PRESS_DURATION: Duration = Duration::from_millis(500);
+
+/// Carries the gestures in [`Event::Command`], see [`Gesture::from_event`].
+pub(crate) const GESTURE: Selector<Gesture> = Selector::new("coat.gesture");
+
+/// A gesture made with the left mouse button, or with touch.
+///
+/// Gestures are delivered with [`Event::Command`] to the widgets that would
+/// receive the mouse event completing the gesture, just before that event.
+/// Like mouse moves, drags reach the hot and the active widgets, widgets
+/// usually only react to them while active. Positions are in the coordinate
+/// space of the receiving widget.
+///
+/// ```ignore
+/// match Gesture::from_event(event) {
+///     Some(Gesture::DoubleClick(pos)) => self.select_word_at(pos),
+///     Some(Gesture::DragMove { delta, .. }) if ctx.is_active() => self.pan(delta),
+///     _ => {}
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Gesture {
+    /// The second click of a double click, delivered before its mouse down.
+    DoubleClick(Point),
+    /// The pointer was held down without moving for [`LONG_PRESS_DURATION`].
+    LongPress(Point),
+    /// The pointer moved further than [`DRAG_THRESHOLD`] while pressed.
+    DragStart {
+        /// Where the pointer was pressed.
+        origin: Point,
+        pos: Point,
+        /// The movement since the pointer was pressed.
+        delta: Vec2,
+    },
+    /// The pointer moved while dragging.
+    DragMove {
+        pos: Point,
+        /// The movement since the last drag gesture.
+        delta: Vec2,
+    },
+    /// The pointer was released after dragging, delivered before its mouse up.
+    DragEnd {
+        pos: Point,
+        /// The movement since the last drag gesture.
+        delta: Vec2,
+    },
+}
+
+impl Gesture {
+    /// The gesture carried by `event`, if it is one.
+    pub fn from_event(event: &Event) -> Option<&Gesture> {
+        match event {
+            Event::Command(cmd) => cmd.get(GESTURE),
+            _ => None,
+        }
+    }
+
+    /// Where the pointer is.
+    pub fn pos(&self) -> Point {
+        match *self {
+            Gesture::DoubleClick(pos) | Gesture::LongPress(pos) => pos,
+            Gesture::DragStart { pos, .. }
+            | Gesture::DragMove { pos, .. }
+            | Gesture::DragEnd { pos, .. } => pos,
+        }
+    }
+
+    /// Map the positions and movements of the gesture with `affine`.
+    pub(crate) fn transform(&self, affine: Affine) -> Gesture {
+        let linear = |delta: Vec2| affine * delta.to_point() - affine * Point::ORIGIN;
+        match *self {
+            Gesture::DoubleClick(pos) => Gesture::DoubleClick(affine * pos),
+            Gesture::LongPress(pos) => Gesture::LongPress(affine * pos),
+            Gesture::DragStart { origin, pos, delta } => Gesture::DragStart {
+                origin: affine * origin,
+                pos: affine * pos,
+                delta: linear(delta),
+            },
+            Gesture::DragMove { pos, delta } => Gesture::DragMove {
+                pos: affine * pos,
+                delta: linear(delta),
+            },
+            Gesture::DragEnd { pos, delta } => Gesture::DragEnd {
+                pos: affine * pos,
+                delta: linear(delta),
+            },
+        }
+    }
+}
+
+/// A press of the left button, which may become a gesture.
+#[derive(Debug, Clone, Copy)]
+struct Press {
+    origin: Point,
+    /// The position of the last drag gesture.
+    last: Point,
+    dragging: bool,
+    /// The timer for a long press, until the pointer moved or was released.
+    long_press: Option<TimerToken>,
+}
+
+/// Recognizes gestures in the mouse events of a window.
+#[derive(Debug, Default)]
+pub(crate) struct GestureRecognizer {
+    press: Option<Press>,
+}
+
+impl GestureRecognizer {
+    /// The gesture completed by `event`, positions are in window coordinates.
+    ///
+    /// `request_timer` is called on presses, the timer event has to be
+    /// passed back to recognize long presses.
+    pub(crate) fn event(
+        &mut self,
+        event: &Event,
+        request_timer: impl FnOnce(Duration) -> TimerToken,
+    ) -> Option<Gesture> {
+        match event {
+            Event::MouseDown(mouse) if mouse.button == MouseButton::Left => {
+                self.press = Some(Press {
+                    origin: mouse.pos,
+                    last: mouse.pos,
+                    dragging: false,
+                    long_press: Some(request_timer(LONG_PRESS_DURATION)),
+                });
+                (mouse.count == 2).then_some(Gesture::DoubleClick(mouse.pos))
+            }
+            Event::MouseMove(mouse) => {
+                let press = self.press.as_mut()?;
+                let delta = mouse.pos - press.last;
+                if press.dragging {
+                    press.last = mouse.pos;
+                    Some(Gesture::DragMove {
+                        pos: mouse.pos,
+                        delta,
+                    })
+                } else if delta.hypot() > DRAG_THRESHOLD {
+                    press.last = mouse.pos;
+                    press.dragging = true;
+                    press.long_press = None;
+                    Some(Gesture::DragStart {
+                        origin: press.origin,
+                        pos: mouse.pos,
+                        delta,
+                    })
+                } else {
+                    None
+                }
+            }
+            Event::MouseUp(mouse) if mouse.button == MouseButton::Left => {
+                let press = self.press.take()?;
+                press.dragging.then_some(Gesture::DragEnd {
+                    pos: mouse.pos,
+                    delta: mouse.pos - press.last,
+                })
+            }
+            Event::Timer(token) => {
+                let press = self.press.as_mut()?;
+                if press.long_press != Some(*token) {
+                    return None;
+                }
+                press.long_press = None;
+                Some(Gesture::LongPress(press.origin))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use druid::{Modifiers, MouseButtons, MouseEvent};
+
+    fn mouse(x: f64, button: MouseButton, count: u8) -> MouseEvent {
+        MouseEvent {
+            pos: Point::new(x, 0.0),
+            window_pos: Point::new(x, 0.0),
+            buttons: MouseButtons::new(),
+            mods: Modifiers::default(),
+            count,
+            focus: false,
+            button,
+            wheel_delta: Vec2::ZERO,
+        }
+    }
+
+    #[test]
+    fn recognize_drag() {
+        let mut gestures = GestureRecognizer::default();
+        let timer = |_| TimerToken::next();
+        let down = Event::MouseDown(mouse(0.0, MouseButton::Left, 1));
+        assert_eq!(gestures.event(&down, timer), None);
+        // Within the threshold.
+        let small = Event::MouseMove(mouse(2.0, MouseButton::None, 0));
+        assert_eq!(gestures.event(&small, timer), None);
+        let start = Event::MouseMove(mouse(10.0, MouseButton::None, 0));
+        assert_eq!(
+            gestures.event(&start, timer),
+            Some(Gesture::DragStart {
+                origin: Point::ORIGIN,
+                pos: Point::new(10.0, 0.0),
+                delta: Vec2::new(10.0, 0.0),
+            })
+        );
+        let moved = Event::MouseMove(mouse(12.0, MouseButton::None, 0));
+        assert_eq!(
+            gestures.event(&moved, timer),
+            Some(Gesture::DragMove {
+                pos: Point::new(12.0, 0.0),
+                delta: Vec2::new(2.0, 0.0),
+            })
+        );
+        let up = Event::MouseUp(mouse(15.0, MouseButton::Left, 1));
+        assert_eq!(
+            gestures.event(&up, timer),
+            Some(Gesture::DragEnd {
+                pos: Point::new(15.0, 0.0),
+                delta: Vec2::new(3.0, 0.0),
+            })
+        );
+        assert_eq!(gestures.event(&moved, timer), None);
+    }
+
+    #[test]
+    fn recognize_presses() {
+        let mut gestures = GestureRecognizer::default();
+        let token = TimerToken::next();
+        let down = Event::MouseDown(mouse(5.0, MouseButton::Left, 2));
+        assert_eq!(
+            gestures.event(&down, |_| token),
+            Some(Gesture::DoubleClick(Point::new(5.0, 0.0)))
+        );
+        let other = Event::Timer(TimerToken::next());
+        assert_eq!(gestures.event(&other, |_| token), None);
+        assert_eq!(
+            gestures.event(&Event::Timer(token), |_| token),
+            Some(Gesture::LongPress(Point::new(5.0, 0.0)))
+        );
+
+        // Moving away cancels the long press.
+        gestures.event(&down, |_| token);
+        let start = Event::MouseMove(mouse(20.0, MouseButton::None, 0));
+        gestures.event(&start, |_| token);
+        assert_eq!(gestures.event(&Event::Timer(token), |_| token), None);
+    }
+}
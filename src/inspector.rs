@@ -0,0 +1,123 @@
+//! Draws the layout of the tree over a window, for debugging.
+//!
+//! In debug builds, the inspector is toggled with Ctrl+Shift+I
+//! (Cmd+Shift+I on macOS). While it is shown, it outlines the layout rect
+//! of every widget and marks baselines. The widget under the pointer is
+//! highlighted together with the space around its children, like padding.
+//! Clicking a widget logs its path and its subtree with sizes and origins,
+//! instead of delivering the click.
+
+use crate::{
+    kurbo::{Line, Point, Rect},
+    piet::{Color, Piet, RenderContext},
+    tree::Child,
+};
+use druid::{Event, HotKey, SysMods};
+use std::fmt::Write;
+
+const BOUNDS_COLOR: Color = Color::rgba8(0x00, 0xa0, 0xff, 0xa0);
+const BASELINE_COLOR: Color = Color::rgba8(0xff, 0x40, 0x40, 0xc0);
+const HOVERED_COLOR: Color = Color::rgba8(0x00, 0xa0, 0xff, 0x50);
+const SPACING_COLOR: Color = Color::rgba8(0x60, 0xd0, 0x60, 0x50);
+
+/// Whether the inspector can be used in this build.
+pub(crate) const AVAILABLE: bool = cfg!(debug_assertions);
+
+/// Returns `true` if `event` toggles the inspector.
+pub(crate) fn is_toggle(event: &Event) -> bool {
+    match event {
+        Event::KeyDown(key) => HotKey::new(SysMods::CmdShift, "I").matches(key),
+        _ => false,
+    }
+}
+
+/// Outline the tree below `root` and highlight the widget at `mouse_pos`.
+pub(crate) fn paint(root: &Child, mouse_pos: Option<Point>, render_ctx: &mut Piet) {
+    paint_bounds(root, render_ctx);
+    let hovered = mouse_pos.and_then(|pos| path_at(root, pos).last().copied());
+    if let Some(hovered) = hovered {
+        render_ctx.fill(window_rect(hovered), &SPACING_COLOR);
+        for child in &hovered.children.renders {
+            render_ctx.fill(window_rect(child), &HOVERED_COLOR);
+        }
+        if hovered.children.renders.is_empty() {
+            render_ctx.fill(window_rect(hovered), &HOVERED_COLOR);
+        }
+    }
+}
+
+fn paint_bounds(node: &Child, render_ctx: &mut Piet) {
+    let rect = window_rect(node);
+    render_ctx.stroke(rect.inset(-0.5), &BOUNDS_COLOR, 1.0);
+    let baseline_offset = node.state.baseline_offset;
+    if baseline_offset != 0.0 {
+        let y = rect.y1 - baseline_offset;
+        let line = Line::new((rect.x0, y), (rect.x1, y));
+        render_ctx.stroke(line, &BASELINE_COLOR, 1.0);
+    }
+    for child in &node.children.renders {
+        paint_bounds(child, render_ctx);
+    }
+}
+
+/// Describe the path to the widget at `pos` and its subtree.
+pub(crate) fn describe(root: &Child, pos: Point) -> String {
+    let path = path_at(root, pos);
+    let mut text = String::new();
+    let names: Vec<_> = path.iter().map(|node| node.object.name()).collect();
+    let _ = writeln!(text, "{}", names.join(" > "));
+    if let Some(node) = path.last() {
+        describe_subtree(node, 0, &mut text);
+    }
+    text
+}
+
+fn describe_subtree(node: &Child, depth: usize, text: &mut String) {
+    let origin = node.state.origin;
+    let window_origin = node.state.window_origin();
+    let size = node.state.size();
+    let _ = writeln!(
+        text,
+        "{:indent$}{} {:?} size {}x{} at ({}, {}), in window ({}, {})",
+        "",
+        node.object.name(),
+        node.state.id,
+        size.width,
+        size.height,
+        origin.x,
+        origin.y,
+        window_origin.x,
+        window_origin.y,
+        indent = depth * 2
+    );
+    for child in &node.children.renders {
+        describe_subtree(child, depth + 1, text);
+    }
+}
+
+/// The widgets containing `pos`, from `root` to the innermost one.
+fn path_at(root: &Child, pos: Point) -> Vec<&Child> {
+    let mut path = Vec::new();
+    let mut node = root;
+    if !window_rect(node).contains(pos) {
+        return path;
+    }
+    loop {
+        path.push(node);
+        // The topmost child wins, which is painted last.
+        let child = node
+            .children
+            .renders
+            .iter()
+            .rev()
+            .find(|child| window_rect(child).contains(pos));
+        match child {
+            Some(child) => node = child,
+            None => return path,
+        }
+    }
+}
+
+fn window_rect(node: &Child) -> Rect {
+    Rect::from_origin_size(node.state.window_origin(), node.state.size())
+}
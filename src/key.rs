@@ -8,8 +8,12 @@ use std::panic::Location;
 /// These come from `#[track_caller]` annotations. It is a newtype
 /// so we can use it as a key in various contexts; the traits we
 /// want are not implemented on the inner type.
+///
+/// Calls inside of [`Ui::keyed`] additionally carry the hash of the key.
+///
+/// [`Ui::keyed`]: crate::ui::Ui::keyed
 #[derive(Clone, Copy, Debug)]
-pub struct Caller(&'static Location<'static>);
+pub struct Caller(&'static Location<'static>, Option<u64>);
 
 impl Caller {
     /// The pointer to the location metadata
@@ -20,11 +24,20 @@ impl Caller {
     fn as_ptr(&self) -> *const Location<'static> {
         self.0
     }
+
+    /// The same call location, within the given key.
+    pub(crate) fn with_key(self, key: Option<u64>) -> Self {
+        Caller(self.0, key)
+    }
+
+    pub(crate) fn is_keyed(&self) -> bool {
+        self.1.is_some()
+    }
 }
 
 impl PartialEq for Caller {
     fn eq(&self, other: &Caller) -> bool {
-        self.as_ptr() == other.as_ptr()
+        self.as_ptr() == other.as_ptr() && self.1 == other.1
     }
 }
 
@@ -32,24 +45,25 @@ impl Eq for Caller {}
 
 impl Hash for Caller {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        self.as_ptr().hash(state)
+        self.as_ptr().hash(state);
+        self.1.hash(state);
     }
 }
 
 impl PartialOrd for Caller {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.as_ptr().partial_cmp(&other.as_ptr())
+        Some(self.cmp(other))
     }
 }
 
 impl Ord for Caller {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.as_ptr().cmp(&other.as_ptr())
+        (self.as_ptr(), self.1).cmp(&(other.as_ptr(), other.1))
     }
 }
 
 impl From<&'static Location<'static>> for Caller {
     fn from(inner: &'static Location<'static>) -> Self {
-        Caller(inner)
+        Caller(inner, None)
     }
 }
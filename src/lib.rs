@@ -2,24 +2,46 @@
 
 pub use druid::{kurbo, piet};
 
+pub mod animation;
 pub mod app;
+pub mod constraints;
 pub mod context;
+pub mod environment;
+pub mod gesture;
 pub mod id;
+pub(crate) mod inspector;
 pub mod key;
+pub mod lifecycle;
+pub mod localization;
+pub mod menu;
 pub mod object;
+pub mod profile;
+pub(crate) mod pump;
+pub(crate) mod raster;
+pub mod recording;
+pub mod services;
 pub mod state;
+pub mod style;
+pub mod task;
+pub mod testing;
+pub mod theme;
 pub mod tree;
 pub mod ui;
+pub mod wheel;
 pub mod widgets;
+pub mod window;
 
 pub mod bloom;
 pub mod text;
 
 pub mod event {
-    pub use druid::{Event, LifeCycle};
+    pub use crate::gesture::Gesture;
+    pub use crate::lifecycle::{LifeCycle, ViewContext};
+    pub use druid::{Cursor, Event};
     pub use druid::{MouseButton, MouseButtons, MouseEvent};
 }
 
+pub use constraints::BoxConstraintsExt;
 pub use druid::BoxConstraints;
 
 pub trait VisualEq {
@@ -35,3 +57,93 @@ pub trait VisualEq {
     /// be considered equal when they have the same bit representation.
     fn eq(&self, other: &Self) -> bool;
 }
+
+macro_rules! impl_visual_eq_for_partial_eq {
+    ($($ty:ty),*) => {
+        $(
+            impl VisualEq for $ty {
+                fn eq(&self, other: &Self) -> bool {
+                    self == other
+                }
+            }
+        )*
+    };
+}
+
+impl_visual_eq_for_partial_eq!(
+    (),
+    bool,
+    char,
+    u8,
+    u16,
+    u32,
+    u64,
+    u128,
+    usize,
+    i8,
+    i16,
+    i32,
+    i64,
+    i128,
+    isize,
+    str,
+    String
+);
+
+impl VisualEq for f32 {
+    fn eq(&self, other: &Self) -> bool {
+        self.to_bits() == other.to_bits()
+    }
+}
+
+impl VisualEq for f64 {
+    fn eq(&self, other: &Self) -> bool {
+        self.to_bits() == other.to_bits()
+    }
+}
+
+impl<T: VisualEq + ?Sized> VisualEq for &T {
+    fn eq(&self, other: &Self) -> bool {
+        T::eq(self, other)
+    }
+}
+
+impl<T: ?Sized> VisualEq for std::rc::Rc<T> {
+    fn eq(&self, other: &Self) -> bool {
+        std::rc::Rc::ptr_eq(self, other)
+    }
+}
+
+impl<T: VisualEq> VisualEq for Option<T> {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Some(a), Some(b)) => a.eq(b),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+}
+
+impl<T: VisualEq> VisualEq for [T] {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().zip(other).all(|(a, b)| a.eq(b))
+    }
+}
+
+impl<T: VisualEq> VisualEq for Vec<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self[..].eq(&other[..])
+    }
+}
+
+impl<A: VisualEq, B: VisualEq> VisualEq for (A, B) {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.eq(&other.0) && self.1.eq(&other.1)
+    }
+}
+
+impl<A: VisualEq, B: VisualEq, C: VisualEq> VisualEq for (A, B, C) {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.eq(&other.0) && self.1.eq(&other.1) && self.2.eq(&other.2)
+    }
+}
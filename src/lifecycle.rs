@@ -0,0 +1,53 @@
+//! Changes of the tree and the state of a widget, delivered to render objects.
+
+use crate::kurbo::{Point, Size};
+use druid::Scale;
+
+/// An event about a change of a render object or its place in the tree.
+///
+/// The events of one render object arrive in a fixed order: first
+/// `WidgetAdded`, then any of the others, and last `WidgetRemoved`.
+/// Within a subtree, children are added before their parent, so a parent
+/// finds all of its initial children when it is added, and removed after
+/// their parent, so the parent still finds them when it is removed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LifeCycle {
+    /// The render object was created and its content was built.
+    ///
+    /// This is the first event of a render object, delivered during the
+    /// build pass that created it, before its first layout.
+    WidgetAdded,
+    /// The render object is no longer built and will be dropped.
+    ///
+    /// Content kept by [`Retain`] is only removed once the retained state is
+    /// dropped. Render objects of a closed window are dropped without it.
+    ///
+    /// [`Retain`]: crate::widgets::Retain
+    WidgetRemoved,
+    /// The size of the widget changed in a layout pass.
+    Size(Size),
+    /// The pointer entered or left the widget.
+    HotChanged(bool),
+    /// The widget was disabled or enabled by an ancestor, see [`Ui::disabled`].
+    ///
+    /// [`Ui::disabled`]: crate::ui::Ui::disabled
+    DisabledChanged(bool),
+    /// The position of the widget in the window changed.
+    ///
+    /// Delivered after the layout pass that moved the widget, and after
+    /// its first layout pass.
+    ViewContextChanged(ViewContext),
+    /// The window moved to a monitor with another pixel density.
+    ///
+    /// Delivered to every widget before the layout pass at the new scale,
+    /// so that bitmaps rendered for the old scale can be replaced.
+    ScaleChanged(Scale),
+}
+
+/// Where a widget is shown in its window.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ViewContext {
+    /// The origin of the widget in window coordinates, see
+    /// [`EventCtx::window_origin`](crate::context::EventCtx::window_origin).
+    pub window_origin: Point,
+}
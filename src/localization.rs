@@ -0,0 +1,358 @@
+//! Translated messages, looked up in the localization provided to a subtree.
+//!
+//! Messages are written in [Fluent], which picks plural forms by the rules
+//! of the language of the locale:
+//!
+//! ```text
+//! # Comments start with a hash.
+//! hello = Hello, { $name }!
+//! emails = { $count ->
+//!     [0] No new emails.
+//!     [one] One new email.
+//!    *[other] { $count } new emails.
+//! }
+//! ```
+//!
+//! Provide a [`Localization`] with [`Ui::provide`] and look up messages with
+//! [`Ui::localized`], or with [`EventCtx::localized`] in render objects.
+//! Everything built with the messages of a localization is built again when
+//! another one is provided, so switching the language only needs another
+//! value passed to `provide`:
+//!
+//! ```ignore
+//! ui.provide(state.localizations[&state.locale].clone(), |ui| {
+//!     Label::new(ui.localized("title")).build(ui);
+//!     let args = Args::new().with("count", inbox.len());
+//!     Label::new(ui.localized_with("emails", &args)).build(ui);
+//! });
+//! ```
+//!
+//! [Fluent]: https://projectfluent.org
+//! [`Ui::provide`]: crate::ui::Ui::provide
+//! [`Ui::localized`]: crate::ui::Ui::localized
+//! [`EventCtx::localized`]: crate::context::EventCtx::localized
+
+use fluent_bundle::{FluentArgs, FluentBundle, FluentError, FluentResource, FluentValue};
+use std::{fmt, rc::Rc};
+use unic_langid::LanguageIdentifier;
+
+/// The messages of one locale.
+///
+/// Cloning a localization is cheap, the messages are shared. Keep it in the
+/// state of the app rather than parsing it in every build pass.
+#[derive(Clone)]
+pub struct Localization {
+    bundle: Rc<Bundle>,
+}
+
+struct Bundle {
+    locale: String,
+    /// The source of the messages, to compare localizations.
+    source: String,
+    fluent: FluentBundle<FluentResource>,
+}
+
+impl Localization {
+    /// A localization for `locale` without any messages.
+    pub fn new(locale: impl Into<String>) -> Self {
+        Localization::parse(locale, "").unwrap()
+    }
+
+    /// Read the messages for `locale`, like `en-US`, from the Fluent `source`.
+    ///
+    /// Locales that are not valid language identifiers use the plural rules
+    /// of English.
+    pub fn parse(locale: impl Into<String>, source: &str) -> Result<Self, ParseError> {
+        let locale = locale.into();
+        let language = locale.parse::<LanguageIdentifier>().unwrap_or_else(|_| {
+            log::warn!("`{}` is not a valid locale", locale);
+            LanguageIdentifier::default()
+        });
+        let resource = FluentResource::try_new(source.to_owned()).map_err(|(_, errors)| {
+            let error = &errors[0];
+            ParseError {
+                line: line_at(source, error.pos.0),
+                message: format!("{:?}", error.kind),
+            }
+        })?;
+        let mut fluent = FluentBundle::new(&[language]);
+        // The isolation marks around arguments would end up in copied text.
+        fluent.set_use_isolating(false);
+        if let Err(errors) = fluent.add_resource(resource) {
+            if let Some(FluentError::Overriding { id, .. }) = errors.first() {
+                return Err(ParseError {
+                    line: definitions(source, id).nth(1).unwrap_or(0),
+                    message: format!("the message `{}` is defined twice", id),
+                });
+            }
+        }
+        Ok(Localization {
+            bundle: Rc::new(Bundle {
+                locale,
+                source: source.to_owned(),
+                fluent,
+            }),
+        })
+    }
+
+    /// The locale of the messages, like `en-US`.
+    pub fn locale(&self) -> &str {
+        &self.bundle.locale
+    }
+
+    /// Whether there is a message with the given `id`.
+    pub fn has_message(&self, id: &str) -> bool {
+        self.bundle.fluent.has_message(id)
+    }
+
+    /// The message `id` with `args` inserted, or `None` if there is no such
+    /// message.
+    ///
+    /// Arguments missing from `args` are shown as `{$name}`.
+    pub fn format(&self, id: &str, args: &Args) -> Option<String> {
+        let fluent = &self.bundle.fluent;
+        let pattern = fluent.get_message(id)?.value?;
+        let args: FluentArgs = args
+            .values
+            .iter()
+            .map(|(name, value)| {
+                let value = match value {
+                    ArgValue::Text(text) => FluentValue::from(text.as_str()),
+                    ArgValue::Number(n) => FluentValue::from(*n),
+                };
+                (name.as_str(), value)
+            })
+            .collect();
+        let mut errors = Vec::new();
+        let text = fluent.format_pattern(pattern, Some(&args), &mut errors);
+        Some(text.into_owned())
+    }
+}
+
+/// Two localizations are equal if they have the same locale and messages.
+impl PartialEq for Localization {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.bundle, &other.bundle)
+            || (self.bundle.locale == other.bundle.locale
+                && self.bundle.source == other.bundle.source)
+    }
+}
+
+impl fmt::Debug for Localization {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Localization")
+            .field("locale", &self.bundle.locale)
+            .finish()
+    }
+}
+
+/// The message `id` of `localization`, or the `id` itself.
+pub(crate) fn localized(localization: Option<&Localization>, id: &str, args: &Args) -> String {
+    localization
+        .and_then(|localization| localization.format(id, args))
+        .unwrap_or_else(|| id.to_owned())
+}
+
+/// The arguments inserted into a message, by name.
+///
+/// ```ignore
+/// let args = Args::new().with("name", &user.name).with("count", unread);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Args {
+    values: Vec<(String, ArgValue)>,
+}
+
+impl Args {
+    pub fn new() -> Self {
+        Args::default()
+    }
+
+    /// Add the argument `name`, replacing an earlier one with the same name.
+    pub fn with(mut self, name: impl Into<String>, value: impl Into<ArgValue>) -> Self {
+        let name = name.into();
+        let value = value.into();
+        match self.values.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, old)) => *old = value,
+            None => self.values.push((name, value)),
+        }
+        self
+    }
+
+    /// The value of the argument `name`.
+    pub fn get(&self, name: &str) -> Option<&ArgValue> {
+        self.values
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| value)
+    }
+}
+
+/// The value of an argument of a message.
+///
+/// Numbers select variants by their plural category, text by its value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArgValue {
+    Text(String),
+    Number(f64),
+}
+
+impl From<&str> for ArgValue {
+    fn from(text: &str) -> Self {
+        ArgValue::Text(text.to_owned())
+    }
+}
+
+impl From<&String> for ArgValue {
+    fn from(text: &String) -> Self {
+        ArgValue::Text(text.clone())
+    }
+}
+
+impl From<String> for ArgValue {
+    fn from(text: String) -> Self {
+        ArgValue::Text(text)
+    }
+}
+
+macro_rules! impl_from_number_for_arg_value {
+    ($($ty:ty),*) => {
+        $(
+            impl From<$ty> for ArgValue {
+                fn from(n: $ty) -> Self {
+                    ArgValue::Number(n as f64)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_number_for_arg_value!(f32, f64, i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+/// An error in the source of a [`Localization`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    /// The line of the error, starting at 1.
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// The line of the byte `offset` in `source`, starting at 1.
+fn line_at(source: &str, offset: usize) -> usize {
+    source[..offset.min(source.len())].matches('\n').count() + 1
+}
+
+/// The lines on which the message `id` is defined.
+fn definitions<'a>(source: &'a str, id: &'a str) -> impl Iterator<Item = usize> + 'a {
+    source.lines().enumerate().filter_map(move |(index, line)| {
+        let value = line.strip_prefix(id)?;
+        match value.trim_start().starts_with('=') {
+            true => Some(index + 1),
+            false => None,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "
+# The window title.
+title = Inbox
+hello = Hello, { $name }!
+braces = Use { \"{\" } and { \"}\" }.
+about =
+    First line
+    second line
+emails = { $count ->
+    [0] No new emails.
+    [one] One new email.
+   *[other] { $count } new emails.
+} Refresh?
+theme = { $mode ->
+    [dark] Dark
+   *[other] Light
+}
+";
+
+    #[test]
+    fn format_messages() {
+        let en = Localization::parse("en-US", SOURCE).unwrap();
+        let none = Args::new();
+        assert!(en.has_message("title"));
+        assert_eq!(en.format("title", &none).unwrap(), "Inbox");
+        assert_eq!(en.format("missing", &none), None);
+        assert_eq!(en.format("hello", &none).unwrap(), "Hello, {$name}!");
+        assert_eq!(
+            en.format("hello", &Args::new().with("name", "Ada"))
+                .unwrap(),
+            "Hello, Ada!"
+        );
+        assert_eq!(en.format("braces", &none).unwrap(), "Use { and }.");
+        assert_eq!(
+            en.format("about", &none).unwrap(),
+            "First line\nsecond line"
+        );
+
+        let emails = |count: f64| {
+            let args = Args::new().with("count", count);
+            en.format("emails", &args).unwrap()
+        };
+        assert_eq!(emails(0.0), "No new emails. Refresh?");
+        assert_eq!(emails(1.0), "One new email. Refresh?");
+        assert_eq!(emails(21.0), "21 new emails. Refresh?");
+        assert_eq!(emails(1.5), "1.5 new emails. Refresh?");
+
+        let theme = |mode: &str| en.format("theme", &Args::new().with("mode", mode));
+        assert_eq!(theme("dark").unwrap(), "Dark");
+        assert_eq!(theme("high-contrast").unwrap(), "Light");
+        assert_eq!(en.format("theme", &none).unwrap(), "Light");
+    }
+
+    #[test]
+    fn plural_rules_of_the_locale() {
+        let source = "files = { $count ->
+    [one] { $count } file
+    [few] { $count } files (few)
+   *[other] { $count } files
+}";
+        let files = |locale: &str, count: f64| {
+            let localization = Localization::parse(locale, source).unwrap();
+            let args = Args::new().with("count", count);
+            localization.format("files", &args).unwrap()
+        };
+        assert_eq!(files("en", 1.0), "1 file");
+        assert_eq!(files("en", 3.0), "3 files");
+        assert_eq!(files("ru", 21.0), "21 file");
+        assert_eq!(files("pl", 22.0), "22 files (few)");
+        assert_eq!(files("ja", 1.0), "1 files");
+        assert_eq!(files("not a locale", 1.0), "1 file");
+    }
+
+    #[test]
+    fn parse_errors() {
+        let line = |source: &str| Localization::parse("en", source).unwrap_err().line;
+        assert_eq!(line("a = ok\nb = { $x"), 2);
+        assert_eq!(line("a = ok\n  more\n!b = text"), 3);
+        assert_eq!(line("a = { $x ->\n  [one] One\n}"), 3);
+        assert_eq!(line("a = one\na = two"), 2);
+        assert_eq!(line("  indented"), 1);
+    }
+
+    #[test]
+    fn equality() {
+        let a = Localization::parse("en", "a = A").unwrap();
+        assert_eq!(a, a.clone());
+        assert_eq!(a, Localization::parse("en", "a = A").unwrap());
+        assert_ne!(a, Localization::parse("de", "a = A").unwrap());
+    }
+}
@@ -0,0 +1,131 @@
+//! Declarative window menus.
+
+use druid::{Data, LocalizedString, MenuDesc, Selector};
+
+/// Sent to the window when a menu item is selected.
+pub(crate) const MENU_ITEM_SELECTED: Selector<u32> = Selector::new("coat.menu.item-selected");
+
+/// A description of a menu entry, built anew in every build pass.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum MenuEntry {
+    Item {
+        title: String,
+        id: u32,
+        enabled: bool,
+        checked: bool,
+    },
+    Separator,
+    SubMenu {
+        title: String,
+        entries: Vec<MenuEntry>,
+    },
+}
+
+/// Builds the entries of the native window menu.
+///
+/// Items are identified by the order in which they are added,
+/// so the menu should be built the same way in every build pass.
+pub struct WindowMenu<'a> {
+    entries: &'a mut Vec<MenuEntry>,
+    next_id: &'a mut u32,
+    selected: Option<u32>,
+}
+
+impl<'a> WindowMenu<'a> {
+    pub(crate) fn new(
+        entries: &'a mut Vec<MenuEntry>,
+        next_id: &'a mut u32,
+        selected: Option<u32>,
+    ) -> Self {
+        WindowMenu {
+            entries,
+            next_id,
+            selected,
+        }
+    }
+
+    /// Add an item, returns `true` if it was selected since the last build pass.
+    pub fn item(&mut self, title: impl Into<String>) -> bool {
+        self.push_item(title.into(), true, false)
+    }
+
+    /// Add an item that can not be selected.
+    pub fn disabled_item(&mut self, title: impl Into<String>) {
+        self.push_item(title.into(), false, false);
+    }
+
+    /// Add an item with a check mark, which is toggled on selection.
+    ///
+    /// Returns `true` if it was selected since the last build pass.
+    pub fn checkbox(&mut self, title: impl Into<String>, checked: &mut bool) -> bool {
+        let selected = self.push_item(title.into(), true, *checked);
+        if selected {
+            *checked = !*checked;
+            if let Some(MenuEntry::Item { checked: entry, .. }) = self.entries.last_mut() {
+                *entry = *checked;
+            }
+        }
+        selected
+    }
+
+    /// Add a separator line.
+    pub fn separator(&mut self) {
+        self.entries.push(MenuEntry::Separator);
+    }
+
+    /// Add a nested menu.
+    pub fn submenu(&mut self, title: impl Into<String>, items: impl FnOnce(&mut WindowMenu)) {
+        let mut entries = Vec::new();
+        items(&mut WindowMenu::new(
+            &mut entries,
+            self.next_id,
+            self.selected,
+        ));
+        self.entries.push(MenuEntry::SubMenu {
+            title: title.into(),
+            entries,
+        });
+    }
+
+    fn push_item(&mut self, title: String, enabled: bool, checked: bool) -> bool {
+        let id = *self.next_id;
+        *self.next_id += 1;
+        self.entries.push(MenuEntry::Item {
+            title,
+            id,
+            enabled,
+            checked,
+        });
+        enabled && self.selected == Some(id)
+    }
+}
+
+/// Convert the entries into a menu druid can show.
+pub(crate) fn menu_desc<T: Data>(entries: &[MenuEntry]) -> MenuDesc<T> {
+    append_entries(MenuDesc::empty(), entries)
+}
+
+fn append_entries<T: Data>(mut menu: MenuDesc<T>, entries: &[MenuEntry]) -> MenuDesc<T> {
+    for entry in entries {
+        menu = match entry {
+            MenuEntry::Item {
+                title,
+                id,
+                enabled,
+                checked,
+            } => {
+                let title = LocalizedString::new("coat-menu-item").with_placeholder(title.clone());
+                let item = druid::MenuItem::new(title, MENU_ITEM_SELECTED.with(*id))
+                    .disabled_if(|| !enabled)
+                    .selected_if(|| *checked);
+                menu.append(item)
+            }
+            MenuEntry::Separator => menu.append_separator(),
+            MenuEntry::SubMenu { title, entries } => {
+                let title = LocalizedString::new("coat-menu").with_placeholder(title.clone());
+                menu.append(append_entries(MenuDesc::new(title), entries))
+            }
+        };
+    }
+    menu
+}
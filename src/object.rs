@@ -1,7 +1,7 @@
 use crate::{
     context::{EventCtx, LayoutCtx, LifeCycleCtx, PaintCtx, UpdateCtx},
     event::{Event, LifeCycle},
-    kurbo::Size,
+    kurbo::{Point, Size},
     tree::Children,
     BoxConstraints,
 };
@@ -16,7 +16,7 @@ pub mod prelude {
         piet::RenderContext,
         tree::{Child, Children},
         ui::Ui,
-        BoxConstraints,
+        BoxConstraints, BoxConstraintsExt,
     };
     pub use std::panic::Location;
 }
@@ -38,6 +38,68 @@ pub trait RenderObjectInterface {
     fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, children: &mut Children)
         -> Size;
     fn paint(&mut self, ctx: &mut PaintCtx, children: &mut Children);
+
+    /// The smallest width the widget can take at `height` without its
+    /// content being cut off or overflowing.
+    ///
+    /// The intrinsic sizes let containers like tables measure their children
+    /// before they lay them out, instead of guessing. They must not change
+    /// the layout of the widget. By default, the widget is as large as its
+    /// largest child, or zero without children.
+    fn compute_min_intrinsic_width(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        height: f64,
+        children: &mut Children,
+    ) -> f64 {
+        children.min_intrinsic_width(ctx, height)
+    }
+
+    /// The width beyond which the widget does not get any shorter at
+    /// `height`, like the width of a paragraph without any line breaks.
+    fn compute_max_intrinsic_width(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        height: f64,
+        children: &mut Children,
+    ) -> f64 {
+        children.max_intrinsic_width(ctx, height)
+    }
+
+    /// The smallest height the widget can take at `width` without its
+    /// content being cut off or overflowing.
+    fn compute_min_intrinsic_height(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        width: f64,
+        children: &mut Children,
+    ) -> f64 {
+        children.min_intrinsic_height(ctx, width)
+    }
+
+    /// The height beyond which the widget does not get any narrower at
+    /// `width`. For most widgets, this is the same as the minimum.
+    fn compute_max_intrinsic_height(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        width: f64,
+        children: &mut Children,
+    ) -> f64 {
+        children.max_intrinsic_height(ctx, width)
+    }
+
+    /// Whether `pos`, which is within the layout rect, is part of the widget.
+    ///
+    /// The widget only becomes hot, and only receives the mouse events of
+    /// widgets that are not active, where this returns `true`. Widgets that
+    /// are not rectangular, like round buttons or diagonal connectors,
+    /// return `false` outside of their visual shape, so that clicks there
+    /// reach the widgets below. Expanded touch targets outside of the layout
+    /// rect are tested at the nearest point of it.
+    fn hit_test(&self, pos: Point) -> bool {
+        let _ = pos;
+        true
+    }
 }
 
 pub trait AnyRenderObject: Any {
@@ -49,6 +111,24 @@ pub trait AnyRenderObject: Any {
     fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, children: &mut Children)
         -> Size;
     fn paint(&mut self, ctx: &mut PaintCtx, children: &mut Children);
+    fn hit_test(&self, pos: Point) -> bool;
+
+    fn compute_intrinsic(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        intrinsic: Intrinsic,
+        extent: f64,
+        children: &mut Children,
+    ) -> f64;
+}
+
+/// One of the intrinsic sizes of [`RenderObjectInterface`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Intrinsic {
+    MinWidth,
+    MaxWidth,
+    MinHeight,
+    MaxHeight,
 }
 
 impl<R> AnyRenderObject for R
@@ -60,7 +140,13 @@ where
     }
 
     fn name(&self) -> &'static str {
-        "" //<R::Props<'_> as Properties>::name()
+        // The type name without its module path, but with generic arguments.
+        let name = std::any::type_name::<R>();
+        let end = name.find('<').unwrap_or(name.len());
+        match name[..end].rfind("::") {
+            Some(start) => &name[start + 2..],
+            None => name,
+        }
     }
 
     fn event(&mut self, ctx: &mut EventCtx, event: &Event, children: &mut Children) {
@@ -83,4 +169,23 @@ where
     fn paint(&mut self, ctx: &mut PaintCtx, children: &mut Children) {
         R::paint(self, ctx, children)
     }
+
+    fn hit_test(&self, pos: Point) -> bool {
+        R::hit_test(self, pos)
+    }
+
+    fn compute_intrinsic(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        intrinsic: Intrinsic,
+        extent: f64,
+        children: &mut Children,
+    ) -> f64 {
+        match intrinsic {
+            Intrinsic::MinWidth => R::compute_min_intrinsic_width(self, ctx, extent, children),
+            Intrinsic::MaxWidth => R::compute_max_intrinsic_width(self, ctx, extent, children),
+            Intrinsic::MinHeight => R::compute_min_intrinsic_height(self, ctx, extent, children),
+            Intrinsic::MaxHeight => R::compute_max_intrinsic_height(self, ctx, extent, children),
+        }
+    }
 }
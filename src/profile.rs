@@ -0,0 +1,170 @@
+//! Cumulative layout and paint times per widget type.
+//!
+//! Profiling is enabled by setting the `COAT_PROFILE` environment variable.
+//! The times are logged when the app exits, and can be queried with [`times`].
+//! The time of a widget excludes the time of its children.
+//!
+//! Independent of that, each window measures how long its phases took,
+//! which build code can read with [`Ui::frame_stats`].
+//!
+//! With the `tracing` feature, the phases are also recorded as `tracing`
+//! spans: a `debug` span named after each phase, and within layout and paint
+//! a `trace` span for each widget.
+//!
+//! [`Ui::frame_stats`]: crate::ui::Ui::frame_stats
+
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+/// The environment variable that enables profiling.
+pub const PROFILE_VAR: &str = "COAT_PROFILE";
+
+/// The cumulative times of one widget type.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WidgetTimes {
+    pub name: &'static str,
+    pub layout: Duration,
+    pub layout_count: u64,
+    pub paint: Duration,
+    pub paint_count: u64,
+}
+
+/// How long the phases of the latest frame of a window took.
+///
+/// Each phase reports its most recent run, so the times can come from
+/// different frames if a phase did not run in every frame.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FrameStats {
+    /// Delivering the latest event to the tree.
+    pub event: Duration,
+    /// The build passes following the latest event.
+    pub build: Duration,
+    pub layout: Duration,
+    pub paint: Duration,
+    /// The number of widgets in the tree after the latest build pass.
+    pub widgets: usize,
+}
+
+#[derive(Clone, Copy)]
+pub(crate) enum Phase {
+    Layout,
+    Paint,
+}
+
+/// An entered `tracing` span, exited when dropped.
+///
+/// Empty without the `tracing` feature.
+pub(crate) struct Span {
+    #[cfg(feature = "tracing")]
+    _span: tracing::span::EnteredSpan,
+}
+
+/// Enter the span of a phase of a window, like `"build"`.
+#[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+pub(crate) fn phase_span(phase: &'static str) -> Span {
+    Span {
+        #[cfg(feature = "tracing")]
+        _span: tracing::debug_span!("phase", phase).entered(),
+    }
+}
+
+/// Enter the span of the widget `name` in `phase`.
+#[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+pub(crate) fn widget_span(name: &'static str, phase: Phase) -> Span {
+    #[cfg(feature = "tracing")]
+    let phase = match phase {
+        Phase::Layout => "layout",
+        Phase::Paint => "paint",
+    };
+    Span {
+        #[cfg(feature = "tracing")]
+        _span: tracing::trace_span!("widget", name, phase).entered(),
+    }
+}
+
+struct Profile {
+    times: HashMap<&'static str, WidgetTimes>,
+    /// The time spent in the children of the widgets currently measured.
+    children: Vec<Duration>,
+}
+
+thread_local! {
+    static PROFILE: Option<RefCell<Profile>> = std::env::var_os(PROFILE_VAR).map(|_| {
+        RefCell::new(Profile {
+            times: HashMap::new(),
+            children: Vec::new(),
+        })
+    });
+}
+
+/// Start measuring a widget, returns `None` if profiling is disabled.
+pub(crate) fn enter() -> Option<Instant> {
+    PROFILE.with(|profile| {
+        let profile = profile.as_ref()?;
+        profile.borrow_mut().children.push(Duration::default());
+        Some(Instant::now())
+    })
+}
+
+/// Stop measuring a widget that was started at `start`.
+pub(crate) fn exit(start: Option<Instant>, name: &'static str, phase: Phase) {
+    let start = match start {
+        Some(start) => start,
+        None => return,
+    };
+    let total = start.elapsed();
+    PROFILE.with(|profile| {
+        if let Some(profile) = profile {
+            let mut profile = profile.borrow_mut();
+            let children = profile.children.pop().unwrap_or_default();
+            if let Some(parent) = profile.children.last_mut() {
+                *parent += total;
+            }
+
+            let times = profile.times.entry(name).or_insert_with(|| WidgetTimes {
+                name,
+                ..WidgetTimes::default()
+            });
+            let own = total.checked_sub(children).unwrap_or_default();
+            match phase {
+                Phase::Layout => {
+                    times.layout += own;
+                    times.layout_count += 1;
+                }
+                Phase::Paint => {
+                    times.paint += own;
+                    times.paint_count += 1;
+                }
+            }
+        }
+    });
+}
+
+/// The times recorded on this thread, the slowest widget types first.
+pub fn times() -> Vec<WidgetTimes> {
+    PROFILE.with(|profile| {
+        let mut times: Vec<_> = match profile {
+            Some(profile) => profile.borrow().times.values().cloned().collect(),
+            None => Vec::new(),
+        };
+        times.sort_by_key(|times| std::cmp::Reverse(times.layout + times.paint));
+        times
+    })
+}
+
+/// Log the times recorded on this thread, if profiling is enabled.
+pub fn dump() {
+    for times in times() {
+        log::info!(
+            "{}: layout {:?} ({} times), paint {:?} ({} times)",
+            times.name,
+            times.layout,
+            times.layout_count,
+            times.paint,
+            times.paint_count
+        );
+    }
+}
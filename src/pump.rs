@@ -0,0 +1,385 @@
+//! The passes over the tree of a window, shared by the app and the test
+//! [`Harness`](crate::testing::Harness).
+//!
+//! The window delivers events with [`WindowTree::pump`], which builds the
+//! tree again as long as it requests it, and does what the passes
+//! [`Requests`] afterwards, like scheduling timers.
+
+use crate::{
+    context::{ContextState, EventCtx, LayoutCtx, LayoutDebug, PaintCtx},
+    environment::Environment,
+    event::Event,
+    gesture::{GestureRecognizer, GESTURE},
+    id::{ChildCounter, ChildId},
+    kurbo::{Point, Size},
+    menu::MenuEntry,
+    piet::{Piet, PietImage, PietText, RenderContext},
+    profile::{self, FrameStats},
+    raster::RasterCache,
+    state::Session,
+    text::Journals,
+    theme::Theme,
+    tree::{Child, Children, FocusChange},
+    ui::Ui,
+    window::WindowConfig,
+    BoxConstraints,
+};
+use druid::{Command, Cursor, ExtEventSink, Region, Scale, TimerToken, WindowHandle, WindowId};
+use std::{
+    cell::RefCell,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+/// What the window provides to the passes over its tree.
+pub(crate) struct Platform {
+    pub(crate) ext_handle: ExtEventSink,
+    pub(crate) window_id: WindowId,
+    pub(crate) window: WindowHandle,
+    pub(crate) text: PietText,
+    /// The time of the passes, see [`EventCtx::now`].
+    pub(crate) now: Instant,
+}
+
+/// The tree of a window, and what its passes keep between them.
+pub(crate) struct WindowTree {
+    app: Box<dyn FnMut(&mut Ui)>,
+    pub(crate) root: Children,
+    child_counter: ChildCounter,
+    /// The values provided to the whole window, like the theme.
+    pub(crate) environment: Environment,
+    /// The size of the window content area, as of the last layout pass.
+    pub(crate) window_size: Size,
+    pub(crate) focus_widget: Option<ChildId>,
+    pub(crate) touch_mode: bool,
+    pub(crate) mouse_pos: Option<Point>,
+    gestures: GestureRecognizer,
+    pub(crate) session: Option<Rc<RefCell<Session>>>,
+    pub(crate) journals: Option<Rc<RefCell<Journals>>>,
+    pub(crate) layout_debug: Option<Rc<RefCell<LayoutDebug>>>,
+    pub(crate) raster_cache: Rc<RefCell<RasterCache<PietImage>>>,
+    pub(crate) frame_stats: FrameStats,
+    /// The window menu item selected since the last build pass.
+    pub(crate) menu_selection: Option<u32>,
+    /// The user asked to close the window since the last build pass.
+    pub(crate) close_requested: bool,
+    /// The windows that were closed since the last build pass.
+    pub(crate) closed_windows: Vec<WindowId>,
+    /// Build code requested a build pass in the next animation frame.
+    pub(crate) build_anim_frame: bool,
+}
+
+/// What the passes over the tree ask the window to do.
+#[derive(Default)]
+pub(crate) struct Requests {
+    pub(crate) commands: Vec<Command>,
+    /// Timers to deliver as [`Event::Timer`] with the same token after the delay.
+    pub(crate) timers: Vec<(TimerToken, Duration)>,
+    /// When build code asked for the next build pass.
+    pub(crate) build_deadline: Option<Instant>,
+    /// What the last build pass declared, if the tree was built.
+    pub(crate) declared: Option<Declared>,
+}
+
+/// The window properties declared by a build pass.
+pub(crate) struct Declared {
+    pub(crate) window_menu: Option<Vec<MenuEntry>>,
+    pub(crate) window_config: WindowConfig,
+    pub(crate) cursor_override: Option<Cursor>,
+}
+
+impl Platform {
+    /// The platform of a window at the current time.
+    pub(crate) fn new(
+        ext_handle: ExtEventSink,
+        window_id: WindowId,
+        window: WindowHandle,
+        text: PietText,
+    ) -> Self {
+        Platform {
+            ext_handle,
+            window_id,
+            window,
+            text,
+            now: Instant::now(),
+        }
+    }
+}
+
+impl Requests {
+    /// Add what a pass requested.
+    fn add(&mut self, state: ContextState) {
+        self.commands.extend(state.commands);
+        self.timers.extend(state.timers);
+        self.build_deadline = match (self.build_deadline, state.build_deadline) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+    }
+}
+
+impl WindowTree {
+    pub(crate) fn new(
+        app: impl FnMut(&mut Ui) + 'static,
+        environment: Environment,
+        touch_mode: bool,
+        session: Option<Rc<RefCell<Session>>>,
+        journals: Option<Rc<RefCell<Journals>>>,
+        debug_layout: bool,
+    ) -> Self {
+        WindowTree {
+            app: Box::new(app),
+            root: Children::new(),
+            child_counter: ChildCounter::new(),
+            environment,
+            window_size: Size::ZERO,
+            focus_widget: None,
+            touch_mode,
+            mouse_pos: None,
+            gestures: GestureRecognizer::default(),
+            session,
+            journals,
+            layout_debug: if debug_layout {
+                Some(Rc::new(RefCell::new(LayoutDebug::default())))
+            } else {
+                None
+            },
+            raster_cache: Rc::new(RefCell::new(RasterCache::new())),
+            frame_stats: FrameStats::default(),
+            menu_selection: None,
+            close_requested: false,
+            closed_windows: Vec::new(),
+            build_anim_frame: false,
+        }
+    }
+
+    pub(crate) fn root(&mut self) -> &mut Child {
+        &mut self.root.renders[0]
+    }
+
+    /// Deliver `event` to the tree, then build it as long as it requests it.
+    ///
+    /// `pointer_trail` are the positions of the mouse moves coalesced into
+    /// `event`, see [`EventCtx::pointer_trail`].
+    pub(crate) fn pump(
+        &mut self,
+        platform: &Platform,
+        event: &Event,
+        pointer_trail: Vec<Point>,
+    ) -> Requests {
+        let mut requests = Requests::default();
+        let start = Instant::now();
+        {
+            let _span = profile::phase_span("event");
+            self.deliver(platform, event, pointer_trail, &mut requests);
+        }
+        self.frame_stats.event = start.elapsed();
+        if self.root().needs_update() {
+            self.build_passes(platform, &mut requests);
+        }
+        requests
+    }
+
+    /// Build the tree, then again as long as it requests it.
+    pub(crate) fn build(&mut self, platform: &Platform) -> Requests {
+        let mut requests = Requests::default();
+        self.build_passes(platform, &mut requests);
+        requests
+    }
+
+    fn deliver(
+        &mut self,
+        platform: &Platform,
+        event: &Event,
+        pointer_trail: Vec<Point>,
+        requests: &mut Requests,
+    ) {
+        // Gestures are delivered before the event completing them.
+        let timers = &mut requests.timers;
+        let gesture = self.gestures.event(event, |delay| {
+            let token = TimerToken::next();
+            timers.push((token, delay));
+            token
+        });
+        if let Some(gesture) = gesture {
+            let gesture = Event::Command(GESTURE.with(gesture));
+            self.deliver(platform, &gesture, Vec::new(), requests);
+            if let Event::Timer(_) = event {
+                // The timer of a long press, nobody else waits for it.
+                return;
+            }
+        }
+
+        match event {
+            Event::MouseMove(mouse) | Event::MouseUp(mouse) | Event::MouseDown(mouse) => {
+                self.mouse_pos = Some(mouse.pos);
+            }
+            Event::AnimFrame(_) => {
+                // Requested again by everyone who still animates.
+                self.root().state.request_anim = false;
+                if self.build_anim_frame {
+                    self.build_anim_frame = false;
+                    self.root().state.request_update = true;
+                }
+            }
+            _ => {}
+        }
+
+        let mut state = ContextState::new(platform, self);
+        state.pointer_trail = pointer_trail;
+        let root = &mut self.root.renders[0];
+        // Recomputed from the children during the event pass.
+        state.pointer_captured = std::mem::take(&mut root.state.has_capture);
+        let mut event_ctx = EventCtx {
+            state: &mut state,
+            child_state: &mut root.state,
+            is_handled: false,
+            is_root: true,
+            raised_child: None,
+        };
+        root.children.event_layers(&mut event_ctx, event);
+        root.children.event_raised(&mut event_ctx, event);
+        root.object.event(&mut event_ctx, event, &mut root.children);
+        // Nobody handled these.
+        root.state.notifications.clear();
+        requests.add(state);
+
+        let old_focus_widget = self.focus_widget;
+        match self.root().state.request_focus.take() {
+            Some(FocusChange::Resign) => self.focus_widget = None,
+            Some(FocusChange::Focus(id)) => self.focus_widget = Some(id),
+            _ => {}
+        }
+        if self.focus_widget != old_focus_widget {
+            let focus_widget = self.focus_widget;
+            self.root().update_focus(focus_widget);
+        }
+    }
+
+    fn build_passes(&mut self, platform: &Platform, requests: &mut Requests) {
+        let start = Instant::now();
+        let _span = profile::phase_span("build");
+        loop {
+            self.build_once(platform, requests);
+            if !self.root().needs_update() {
+                break;
+            }
+        }
+        self.frame_stats.build = start.elapsed();
+        self.frame_stats.widgets = count_widgets(&self.root);
+    }
+
+    fn build_once(&mut self, platform: &Platform, requests: &mut Requests) {
+        let mut state = ContextState::new(platform, self);
+        state.session = self.session.clone();
+        state.frame_stats = self.frame_stats;
+        state.menu_selection = self.menu_selection.take();
+        state.close_requested = std::mem::take(&mut self.close_requested);
+        state.closed_windows = std::mem::take(&mut self.closed_windows);
+        let mut ui = Ui::new(
+            &mut self.root,
+            &mut state,
+            &mut self.child_counter,
+            self.environment.clone(),
+        );
+        (self.app)(&mut ui);
+        if ui.finish() {
+            self.root().state.needs_layout = true;
+        }
+        self.build_anim_frame |= state.anim_frame;
+        requests.declared = Some(Declared {
+            window_menu: state.window_menu.take(),
+            window_config: std::mem::take(&mut state.window_config),
+            cursor_override: state.cursor_override.take(),
+        });
+        requests.add(state);
+    }
+
+    /// Tell the tree that the window moved to a monitor with another scale.
+    pub(crate) fn change_scale(&mut self, platform: &Platform, scale: Scale) {
+        let mut state = ContextState::new(platform, self);
+        self.root().change_scale(&mut state, scale);
+    }
+
+    /// Lay out the tree within `bc`, returns its size.
+    pub(crate) fn layout(
+        &mut self,
+        platform: &Platform,
+        bc: &BoxConstraints,
+        env: &druid::Env,
+    ) -> (Size, Requests) {
+        let start = Instant::now();
+        let _span = profile::phase_span("layout");
+        self.window_size = bc.max();
+        let mut state = ContextState::new(platform, self);
+        let mouse_pos = self.mouse_pos;
+        let root = &mut self.root.renders[0];
+        root.state.needs_layout = false;
+        let mut layout_ctx = LayoutCtx {
+            state: &mut state,
+            child_state: &mut root.state,
+            mouse_pos,
+            env,
+        };
+        root.state.size = root.object.layout(&mut layout_ctx, bc, &mut root.children);
+        // Nobody could scroll there.
+        root.state.scroll_to_view = None;
+        root.update_window_origin(&mut state, Point::ORIGIN);
+        let size = root.state.size;
+        self.frame_stats.layout = start.elapsed();
+
+        let mut requests = Requests::default();
+        requests.add(state);
+        (size, requests)
+    }
+
+    /// Paint the `region` of the window, returns whether layers were painted.
+    ///
+    /// Layers can paint outside of the bounds of their ancestors.
+    pub(crate) fn paint(
+        &mut self,
+        platform: &Platform,
+        render_ctx: &mut Piet,
+        region: Region,
+        depth: u32,
+        env: &druid::Env,
+    ) -> bool {
+        let start = Instant::now();
+        let _span = profile::phase_span("paint");
+        let viewport = self.window_size.to_rect();
+        let mut state = ContextState::new(platform, self);
+        let root = &mut self.root.renders[0];
+        // The root widget's theme, so providing a theme to everything switches the background too.
+        let background = match root.state.environment.get::<Theme>() {
+            Some(theme) => theme.colors.background.clone(),
+            None => Theme::fallback().colors.background.clone(),
+        };
+        render_ctx.fill(region.bounding_box(), &background);
+
+        let mut paint_ctx = PaintCtx {
+            state: &mut state,
+            child_state: &mut root.state,
+            z_ops: Vec::new(),
+            region,
+            viewport,
+            depth,
+            render_ctx,
+            env,
+            raised: Vec::new(),
+            raised_child: None,
+        };
+        root.object.paint(&mut paint_ctx, &mut root.children);
+        root.children.paint_raised(&mut paint_ctx);
+        let has_layers = root.children.paint_layers(&mut paint_ctx);
+        self.frame_stats.paint = start.elapsed();
+        has_layers
+    }
+}
+
+fn count_widgets(children: &Children) -> usize {
+    children
+        .renders
+        .iter()
+        .map(|child| 1 + count_widgets(&child.children))
+        .sum()
+}
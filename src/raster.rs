@@ -0,0 +1,129 @@
+//! Caching vector graphics as bitmaps.
+//!
+//! Icons and other vector content that is painted over and over can be painted
+//! with [`PaintCtx::paint_cached`], which renders it into a bitmap once and
+//! reuses that bitmap across frames and widgets. The bitmap is rendered again
+//! when the scale of the window or the tint changes.
+//!
+//! [`PaintCtx::paint_cached`]: crate::context::PaintCtx::paint_cached
+
+use std::collections::HashMap;
+
+/// The number of bytes the cached bitmaps of a window may use.
+const MAX_BYTES: usize = 32 << 20;
+
+/// Identifies a bitmap: the content, its size in pixels and its tint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct RasterKey {
+    pub(crate) content: u64,
+    pub(crate) width: usize,
+    pub(crate) height: usize,
+    pub(crate) tint: u32,
+}
+
+impl RasterKey {
+    fn bytes(&self) -> usize {
+        self.width * self.height * 4
+    }
+}
+
+struct Entry<I> {
+    image: I,
+    last_used: u64,
+}
+
+/// The bitmaps of a window, the least recently used ones are dropped first.
+pub(crate) struct RasterCache<I> {
+    entries: HashMap<RasterKey, Entry<I>>,
+    bytes: usize,
+    clock: u64,
+}
+
+impl<I> RasterCache<I> {
+    pub(crate) fn new() -> Self {
+        RasterCache {
+            entries: HashMap::new(),
+            bytes: 0,
+            clock: 0,
+        }
+    }
+
+    pub(crate) fn get(&mut self, key: &RasterKey) -> Option<&I> {
+        self.clock += 1;
+        let entry = self.entries.get_mut(key)?;
+        entry.last_used = self.clock;
+        Some(&entry.image)
+    }
+
+    /// Whether the bitmap for `key` is small enough to be cached.
+    ///
+    /// Larger bitmaps would evict most of the others.
+    pub(crate) fn fits(&self, key: &RasterKey) -> bool {
+        key.bytes() <= MAX_BYTES / 4
+    }
+
+    /// Cache a bitmap that is not cached yet.
+    pub(crate) fn insert(&mut self, key: RasterKey, image: I) -> &I {
+        debug_assert!(!self.entries.contains_key(&key));
+        self.clock += 1;
+        self.bytes += key.bytes();
+        while self.bytes > MAX_BYTES {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| *key);
+            match oldest {
+                Some(oldest) => {
+                    self.entries.remove(&oldest);
+                    self.bytes -= oldest.bytes();
+                }
+                None => break,
+            }
+        }
+        let entry = Entry {
+            image,
+            last_used: self.clock,
+        };
+        &self.entries.entry(key).or_insert(entry).image
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(content: u64, size: usize) -> RasterKey {
+        RasterKey {
+            content,
+            width: size,
+            height: size,
+            tint: 0,
+        }
+    }
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let mut cache = RasterCache::new();
+        // Each bitmap takes a third of the budget.
+        let size = ((MAX_BYTES / 3 / 4) as f64).sqrt() as usize;
+        cache.insert(key(1, size), 1);
+        cache.insert(key(2, size), 2);
+        cache.insert(key(3, size), 3);
+        assert_eq!(cache.get(&key(1, size)), Some(&1));
+
+        cache.insert(key(4, size), 4);
+        assert_eq!(cache.get(&key(2, size)), None);
+        assert_eq!(cache.get(&key(1, size)), Some(&1));
+        assert_eq!(cache.get(&key(3, size)), Some(&3));
+        assert_eq!(cache.get(&key(1, size * 2)), None);
+    }
+
+    #[test]
+    fn large_bitmaps_do_not_fit() {
+        let cache = RasterCache::<()>::new();
+        let size = ((MAX_BYTES / 4 / 4) as f64).sqrt() as usize;
+        assert!(cache.fits(&key(1, size)));
+        assert!(!cache.fits(&key(1, size * 2)));
+    }
+}
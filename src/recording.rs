@@ -0,0 +1,409 @@
+//! Recording the input of a window and replaying it.
+//!
+//! A window started with [`App::record_events`] writes every mouse, wheel,
+//! keyboard and resize event it receives to a file, with the time since the
+//! window was opened. Such a [`Recording`] can be replayed with the same
+//! timing in a window started with [`App::replay_events`], or without a
+//! window with [`Harness::replay`], to reproduce bugs or to benchmark
+//! the same interaction again and again.
+//!
+//! Recordings are text files with one event per line:
+//!
+//! ```text
+//! 0.000 resize 800 600
+//! 1.250 move 120 48 0 -
+//! 1.312 down 120 48 2 - left 1
+//! 1.390 up 120 48 0 - left 1
+//! 2.004 key-down S false A
+//! ```
+//!
+//! Mouse events list the position, the pressed buttons as a bit set, the
+//! modifiers and, depending on the event, the button and click count or the
+//! wheel delta. Modifiers are written as `S`, `C`, `A` and `M` for Shift,
+//! Control, Alt and Meta, or `-` if none are pressed. Key events end with
+//! the key, which may contain spaces. The physical key code is not recorded.
+//!
+//! [`App::record_events`]: crate::app::App::record_events
+//! [`App::replay_events`]: crate::app::App::replay_events
+//! [`Harness::replay`]: crate::testing::Harness::replay
+
+use crate::{
+    event::{Event, MouseButton, MouseButtons, MouseEvent},
+    kurbo::{Point, Size, Vec2},
+};
+use druid::{keyboard_types::KeyState, KbKey, KeyEvent, Modifiers, TimerToken};
+use std::{
+    fs::{self, File},
+    io::{self, Write},
+    path::Path,
+    time::{Duration, Instant},
+};
+
+const BUTTONS: [(MouseButton, &str); 6] = [
+    (MouseButton::None, "none"),
+    (MouseButton::Left, "left"),
+    (MouseButton::Right, "right"),
+    (MouseButton::Middle, "middle"),
+    (MouseButton::X1, "x1"),
+    (MouseButton::X2, "x2"),
+];
+
+const MODIFIERS: [(Modifiers, char); 4] = [
+    (Modifiers::SHIFT, 'S'),
+    (Modifiers::CONTROL, 'C'),
+    (Modifiers::ALT, 'A'),
+    (Modifiers::META, 'M'),
+];
+
+/// Recorded events and the time they happened at, see the [module docs](self).
+#[derive(Debug, Clone, Default)]
+pub struct Recording {
+    events: Vec<(Duration, Event)>,
+}
+
+impl Recording {
+    pub fn new() -> Self {
+        Recording::default()
+    }
+
+    /// Add `event` at `time`, if it is an event that can be recorded.
+    ///
+    /// Returns `false` if the event was ignored.
+    pub fn push(&mut self, time: Duration, event: &Event) -> bool {
+        if format_event(event).is_none() {
+            return false;
+        }
+        self.events.push((time, event.clone()));
+        true
+    }
+
+    /// The events in the order they happened, with the time since the start of the recording.
+    pub fn events(&self) -> &[(Duration, Event)] {
+        &self.events
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Recording::parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut text = String::new();
+        for (time, event) in &self.events {
+            if let Some(line) = format_line(*time, event) {
+                text.push_str(&line);
+            }
+        }
+        fs::write(path, text)
+    }
+
+    /// Read a recording from its text format, see the [module docs](self).
+    pub fn parse(text: &str) -> io::Result<Self> {
+        let mut events = Vec::new();
+        for (ix, line) in text.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            match parse_line(line) {
+                Some(event) => events.push(event),
+                None => {
+                    let message = format!("Invalid event in line {}: {:?}", ix + 1, line);
+                    return Err(io::Error::new(io::ErrorKind::InvalidData, message));
+                }
+            }
+        }
+        Ok(Recording { events })
+    }
+}
+
+/// What a window does with its events.
+pub(crate) enum EventLog {
+    Record(Recorder),
+    Replay(Replayer),
+}
+
+/// Writes the events of a window to a file as they happen.
+pub(crate) struct Recorder {
+    file: File,
+    start: Instant,
+}
+
+impl Recorder {
+    pub(crate) fn create(path: &Path) -> io::Result<Self> {
+        Ok(Recorder {
+            file: File::create(path)?,
+            start: Instant::now(),
+        })
+    }
+
+    pub(crate) fn record(&mut self, event: &Event) {
+        let line = match format_line(self.start.elapsed(), event) {
+            Some(line) => line,
+            None => return,
+        };
+        // Written right away, so that the recording survives a crash.
+        if let Err(err) = self.file.write_all(line.as_bytes()) {
+            log::warn!("Failed to record event: {}", err);
+        }
+    }
+}
+
+/// Delivers the events of a recording to a window with their original timing.
+pub(crate) struct Replayer {
+    recording: Recording,
+    next: usize,
+    start: Instant,
+    timer: Option<TimerToken>,
+}
+
+impl Replayer {
+    pub(crate) fn new(recording: Recording) -> Self {
+        Replayer {
+            recording,
+            next: 0,
+            start: Instant::now(),
+            timer: None,
+        }
+    }
+
+    /// Start replaying now, returns the delay until the first event.
+    pub(crate) fn start(&mut self) -> Option<Duration> {
+        self.start = Instant::now();
+        self.next = 0;
+        self.delay()
+    }
+
+    pub(crate) fn is_timer(&self, token: TimerToken) -> bool {
+        self.timer == Some(token)
+    }
+
+    pub(crate) fn set_timer(&mut self, token: TimerToken) {
+        self.timer = Some(token);
+    }
+
+    /// The events that are due, returns the delay until the next one.
+    pub(crate) fn due(&mut self) -> (Vec<Event>, Option<Duration>) {
+        self.timer = None;
+        let elapsed = self.start.elapsed();
+        let mut events = Vec::new();
+        while let Some((time, event)) = self.recording.events.get(self.next) {
+            if *time > elapsed {
+                break;
+            }
+            events.push(event.clone());
+            self.next += 1;
+        }
+        (events, self.delay())
+    }
+
+    fn delay(&self) -> Option<Duration> {
+        let (time, _) = self.recording.events.get(self.next)?;
+        Some(time.saturating_sub(self.start.elapsed()))
+    }
+}
+
+fn format_line(time: Duration, event: &Event) -> Option<String> {
+    Some(format!(
+        "{:.3} {}\n",
+        time.as_secs_f64(),
+        format_event(event)?
+    ))
+}
+
+fn format_event(event: &Event) -> Option<String> {
+    let text = match event {
+        Event::WindowSize(size) => format!("resize {} {}", size.width, size.height),
+        Event::MouseMove(mouse) => format!("move {}", format_mouse(mouse)),
+        Event::MouseDown(mouse) | Event::MouseUp(mouse) => {
+            let kind = match event {
+                Event::MouseDown(_) => "down",
+                _ => "up",
+            };
+            let button = button_name(mouse.button);
+            format!(
+                "{} {} {} {}",
+                kind,
+                format_mouse(mouse),
+                button,
+                mouse.count
+            )
+        }
+        Event::Wheel(mouse) => {
+            let delta = mouse.wheel_delta;
+            format!("wheel {} {} {}", format_mouse(mouse), delta.x, delta.y)
+        }
+        Event::KeyDown(key) => {
+            let mods = format_mods(key.mods);
+            format!("key-down {} {} {}", mods, key.repeat, key.key)
+        }
+        Event::KeyUp(key) => format!("key-up {} {}", format_mods(key.mods), key.key),
+        _ => return None,
+    };
+    Some(text)
+}
+
+fn format_mouse(mouse: &MouseEvent) -> String {
+    let buttons = BUTTONS
+        .iter()
+        .enumerate()
+        .filter(|(_, (button, _))| mouse.buttons.contains(*button))
+        .fold(0u8, |bits, (ix, _)| bits | 1 << ix);
+    format!(
+        "{} {} {} {}",
+        mouse.pos.x,
+        mouse.pos.y,
+        buttons,
+        format_mods(mouse.mods)
+    )
+}
+
+fn format_mods(mods: Modifiers) -> String {
+    let text: String = MODIFIERS
+        .iter()
+        .filter(|(modifier, _)| mods.contains(*modifier))
+        .map(|(_, c)| *c)
+        .collect();
+    if text.is_empty() {
+        "-".to_string()
+    } else {
+        text
+    }
+}
+
+fn button_name(button: MouseButton) -> &'static str {
+    BUTTONS
+        .iter()
+        .find(|(b, _)| *b == button)
+        .map_or("none", |(_, name)| name)
+}
+
+fn parse_line(line: &str) -> Option<(Duration, Event)> {
+    let (time, rest) = line.split_once(' ')?;
+    let time = Duration::from_secs_f64(time.parse().ok()?);
+    let (kind, args) = rest.split_once(' ')?;
+    let mut parts = args.split(' ');
+    let event = match kind {
+        "resize" => {
+            let width = parts.next()?.parse().ok()?;
+            let height = parts.next()?.parse().ok()?;
+            Event::WindowSize(Size::new(width, height))
+        }
+        "move" => Event::MouseMove(parse_mouse(&mut parts)?),
+        "down" | "up" => {
+            let mut mouse = parse_mouse(&mut parts)?;
+            let button = parts.next()?;
+            mouse.button = BUTTONS.iter().find(|(_, name)| *name == button)?.0;
+            mouse.count = parts.next()?.parse().ok()?;
+            match kind {
+                "down" => Event::MouseDown(mouse),
+                _ => Event::MouseUp(mouse),
+            }
+        }
+        "wheel" => {
+            let mut mouse = parse_mouse(&mut parts)?;
+            let x = parts.next()?.parse().ok()?;
+            let y = parts.next()?.parse().ok()?;
+            mouse.wheel_delta = Vec2::new(x, y);
+            Event::Wheel(mouse)
+        }
+        "key-down" => {
+            let (mods, args) = args.split_once(' ')?;
+            let (repeat, key) = args.split_once(' ')?;
+            let mut key = KeyEvent::for_test(parse_mods(mods)?, key.parse::<KbKey>().ok()?);
+            key.repeat = repeat.parse().ok()?;
+            Event::KeyDown(key)
+        }
+        "key-up" => {
+            let (mods, key) = args.split_once(' ')?;
+            let mut key = KeyEvent::for_test(parse_mods(mods)?, key.parse::<KbKey>().ok()?);
+            key.state = KeyState::Up;
+            Event::KeyUp(key)
+        }
+        _ => return None,
+    };
+    Some((time, event))
+}
+
+fn parse_mouse<'a>(parts: &mut impl Iterator<Item = &'a str>) -> Option<MouseEvent> {
+    let x = parts.next()?.parse().ok()?;
+    let y = parts.next()?.parse().ok()?;
+    let bits: u8 = parts.next()?.parse().ok()?;
+    let buttons = BUTTONS
+        .iter()
+        .enumerate()
+        .filter(|(ix, _)| bits & 1 << ix != 0)
+        .fold(MouseButtons::new(), |buttons, (_, (button, _))| {
+            buttons.with(*button)
+        });
+    let pos = Point::new(x, y);
+    Some(MouseEvent {
+        pos,
+        window_pos: pos,
+        buttons,
+        mods: parse_mods(parts.next()?)?,
+        count: 0,
+        focus: false,
+        button: MouseButton::None,
+        wheel_delta: Vec2::ZERO,
+    })
+}
+
+fn parse_mods(text: &str) -> Option<Modifiers> {
+    let mut mods = Modifiers::empty();
+    if text == "-" {
+        return Some(mods);
+    }
+    for c in text.chars() {
+        let (modifier, _) = MODIFIERS.iter().find(|(_, name)| *name == c)?;
+        mods.set(*modifier, true);
+    }
+    Some(mods)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let text = concat!(
+            "0.000 resize 800 600\n",
+            "1.250 move 120.5 48 0 -\n",
+            "1.312 down 120.5 48 2 C left 1\n",
+            "1.390 up 120.5 48 0 C left 1\n",
+            "1.500 wheel 10 20 0 - 0 -53\n",
+            "2.004 key-down S false A\n",
+            "2.100 key-up - ArrowLeft\n",
+            "2.200 key-down - true  \n",
+        );
+        let recording = Recording::parse(text).unwrap();
+        assert_eq!(recording.events().len(), 8);
+        let (time, event) = &recording.events()[2];
+        assert_eq!(*time, Duration::from_millis(1312));
+        match event {
+            Event::MouseDown(mouse) => {
+                assert_eq!(mouse.pos, Point::new(120.5, 48.0));
+                assert!(mouse.buttons.has_left());
+                assert!(mouse.mods.ctrl());
+                assert_eq!(mouse.button, MouseButton::Left);
+            }
+            event => panic!("Unexpected event {:?}", event),
+        }
+        match &recording.events()[7].1 {
+            Event::KeyDown(key) => assert_eq!(key.key, KbKey::Character(" ".into())),
+            event => panic!("Unexpected event {:?}", event),
+        }
+
+        let formatted: String = recording
+            .events()
+            .iter()
+            .filter_map(|(time, event)| format_line(*time, event))
+            .collect();
+        assert_eq!(formatted, text);
+    }
+
+    #[test]
+    fn invalid_line() {
+        let err = Recording::parse("0.000 move 1 2\n").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,362 @@
+//! Platform capabilities, replaceable for tests.
+//!
+//! Widgets reach platform features like the clipboard through the [`Services`]
+//! of the app instead of calling the platform directly, read with
+//! `ctx.services()`. The app sets them with [`App::services`], and a different
+//! set can be [`provide`]d to a subtree, for example fakes in unit tests.
+//!
+//! Any trait object can be registered, the built-in ones are [`Clipboard`],
+//! [`Opener`], [`FileDialogs`], [`Notifications`] and [`Tray`].
+//!
+//! [`App::services`]: crate::app::App::services
+//! [`provide`]: crate::ui::Ui::provide
+
+use druid::{commands, Application, FileDialogOptions};
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    io,
+    process::Command,
+    rc::Rc,
+    thread,
+};
+
+/// Reads and writes the text on the system clipboard.
+pub trait Clipboard {
+    fn get_string(&self) -> Option<String>;
+    fn put_string(&self, text: &str);
+}
+
+/// Opens files and URLs with the default application of the system.
+pub trait Opener {
+    fn open(&self, target: &str) -> io::Result<()>;
+}
+
+/// Shows the file dialogs of the system.
+///
+/// The dialogs do not block. Each method returns the command that shows the
+/// dialog once submitted with `submit_command`, and the chosen path is then
+/// delivered to the widgets of the window as an [`Event::Command`] with
+/// [`OPEN_FILE`] or [`SAVE_FILE_AS`].
+///
+/// [`Event::Command`]: crate::event::Event::Command
+/// [`OPEN_FILE`]: druid::commands::OPEN_FILE
+/// [`SAVE_FILE_AS`]: druid::commands::SAVE_FILE_AS
+pub trait FileDialogs {
+    fn open_file(&self, options: FileDialogOptions) -> druid::Command;
+    fn save_file(&self, options: FileDialogOptions) -> druid::Command;
+}
+
+/// Shows notifications on the desktop.
+pub trait Notifications {
+    fn notify(&self, title: &str, body: &str) -> io::Result<()>;
+}
+
+/// An icon of the app in the system tray.
+pub trait Tray {
+    /// Show the icon with `tooltip`, or change the tooltip of the shown icon.
+    fn show(&self, tooltip: &str) -> io::Result<()>;
+    fn hide(&self);
+}
+
+/// A registry of services, looked up by their trait object type.
+///
+/// ```ignore
+/// let services = Services::platform().with::<dyn Clipboard>(Rc::new(FakeClipboard::default()));
+/// let clipboard = services.get::<dyn Clipboard>().unwrap();
+/// ```
+#[derive(Clone, Default)]
+pub struct Services {
+    /// Each entry is an `Rc<S>` for the `S` it is keyed with.
+    services: HashMap<TypeId, Rc<dyn Any>>,
+}
+
+impl Services {
+    /// No services at all.
+    pub fn new() -> Self {
+        Services::default()
+    }
+
+    /// The implementations of the built-in services for the current platform.
+    pub fn platform() -> Self {
+        Services::new()
+            .with::<dyn Clipboard>(Rc::new(PlatformClipboard))
+            .with::<dyn Opener>(Rc::new(PlatformOpener))
+            .with::<dyn FileDialogs>(Rc::new(PlatformFileDialogs))
+            .with::<dyn Notifications>(Rc::new(PlatformNotifications))
+            .with::<dyn Tray>(Rc::new(PlatformTray))
+    }
+
+    /// These services, with `service` replacing any previous service of type `S`.
+    pub fn with<S: ?Sized + 'static>(mut self, service: Rc<S>) -> Self {
+        self.services.insert(TypeId::of::<S>(), Rc::new(service));
+        self
+    }
+
+    /// The service of type `S`, if one was registered.
+    pub fn get<S: ?Sized + 'static>(&self) -> Option<Rc<S>> {
+        let service = self.services.get(&TypeId::of::<S>())?;
+        service.downcast_ref::<Rc<S>>().cloned()
+    }
+
+    /// The clipboard, see [`Clipboard`].
+    pub fn clipboard(&self) -> Option<Rc<dyn Clipboard>> {
+        self.get()
+    }
+
+    /// The opener, see [`Opener`].
+    pub fn opener(&self) -> Option<Rc<dyn Opener>> {
+        self.get()
+    }
+
+    /// The file dialogs, see [`FileDialogs`].
+    pub fn file_dialogs(&self) -> Option<Rc<dyn FileDialogs>> {
+        self.get()
+    }
+
+    /// The notifications, see [`Notifications`].
+    pub fn notifications(&self) -> Option<Rc<dyn Notifications>> {
+        self.get()
+    }
+
+    /// The tray icon, see [`Tray`].
+    pub fn tray(&self) -> Option<Rc<dyn Tray>> {
+        self.get()
+    }
+
+    /// The services used where none were provided.
+    pub(crate) fn fallback() -> &'static Services {
+        thread_local! {
+            static FALLBACK: &'static Services = Box::leak(Box::new(Services::platform()));
+        }
+        FALLBACK.with(|services| *services)
+    }
+}
+
+// Services are equal if they are the same instances.
+impl PartialEq for Services {
+    fn eq(&self, other: &Self) -> bool {
+        self.services.len() == other.services.len()
+            && self.services.iter().all(|(key, service)| {
+                other
+                    .services
+                    .get(key)
+                    .is_some_and(|other| Rc::ptr_eq(service, other))
+            })
+    }
+}
+
+struct PlatformClipboard;
+
+impl Clipboard for PlatformClipboard {
+    fn get_string(&self) -> Option<String> {
+        Application::global().clipboard().get_string()
+    }
+
+    fn put_string(&self, text: &str) {
+        Application::global().clipboard().put_string(text);
+    }
+}
+
+struct PlatformOpener;
+
+impl Opener for PlatformOpener {
+    fn open(&self, target: &str) -> io::Result<()> {
+        // No shell in between, which would interpret characters of the target.
+        let mut command = if cfg!(target_os = "windows") {
+            let mut command = Command::new("rundll32");
+            command.arg("url.dll,FileProtocolHandler");
+            command
+        } else if cfg!(target_os = "macos") {
+            Command::new("open")
+        } else {
+            Command::new("xdg-open")
+        };
+        command.arg(target);
+        // The opener exits as soon as it handed the target over.
+        spawn_detached(command)
+    }
+}
+
+struct PlatformFileDialogs;
+
+impl FileDialogs for PlatformFileDialogs {
+    fn open_file(&self, options: FileDialogOptions) -> druid::Command {
+        commands::SHOW_OPEN_PANEL.with(options)
+    }
+
+    fn save_file(&self, options: FileDialogOptions) -> druid::Command {
+        commands::SHOW_SAVE_PANEL.with(options)
+    }
+}
+
+struct PlatformNotifications;
+
+impl Notifications for PlatformNotifications {
+    fn notify(&self, title: &str, body: &str) -> io::Result<()> {
+        // The texts are passed as arguments, never as part of a script.
+        let mut command = if cfg!(target_os = "macos") {
+            let mut command = Command::new("osascript");
+            command.args([
+                "-e",
+                "on run argv",
+                "-e",
+                "display notification (item 2 of argv) with title (item 1 of argv)",
+                "-e",
+                "end run",
+            ]);
+            command
+        } else if cfg!(target_os = "windows") {
+            return Err(unsupported("notifications"));
+        } else {
+            Command::new("notify-send")
+        };
+        command.arg(title).arg(body);
+        spawn_detached(command)
+    }
+}
+
+/// Druid has no tray icons, apps that need one register their own [`Tray`].
+struct PlatformTray;
+
+impl Tray for PlatformTray {
+    fn show(&self, _tooltip: &str) -> io::Result<()> {
+        Err(unsupported("tray icons"))
+    }
+
+    fn hide(&self) {}
+}
+
+/// Run `command` without waiting for it to exit.
+fn spawn_detached(mut command: Command) -> io::Result<()> {
+    let mut child = command.spawn()?;
+    // The child must still be waited for to not stay around as a zombie.
+    thread::spawn(move || child.wait());
+    Ok(())
+}
+
+fn unsupported(feature: &str) -> io::Error {
+    let message = format!("No {} on this platform", feature);
+    io::Error::new(io::ErrorKind::Unsupported, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use druid::Selector;
+    use std::cell::RefCell;
+
+    #[derive(Default)]
+    struct FakeClipboard(RefCell<Option<String>>);
+
+    impl Clipboard for FakeClipboard {
+        fn get_string(&self) -> Option<String> {
+            self.0.borrow().clone()
+        }
+
+        fn put_string(&self, text: &str) {
+            *self.0.borrow_mut() = Some(text.to_string());
+        }
+    }
+
+    const FAKE_DIALOG: Selector<&'static str> = Selector::new("coat.test.fake-dialog");
+
+    /// Records which dialogs were asked for, without showing any.
+    #[derive(Default)]
+    struct FakeFileDialogs(RefCell<Vec<&'static str>>);
+
+    impl FileDialogs for FakeFileDialogs {
+        fn open_file(&self, _options: FileDialogOptions) -> druid::Command {
+            self.0.borrow_mut().push("open");
+            FAKE_DIALOG.with("open")
+        }
+
+        fn save_file(&self, _options: FileDialogOptions) -> druid::Command {
+            self.0.borrow_mut().push("save");
+            FAKE_DIALOG.with("save")
+        }
+    }
+
+    #[derive(Default)]
+    struct FakeNotifications(RefCell<Vec<(String, String)>>);
+
+    impl Notifications for FakeNotifications {
+        fn notify(&self, title: &str, body: &str) -> io::Result<()> {
+            let notification = (title.to_string(), body.to_string());
+            self.0.borrow_mut().push(notification);
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct FakeTray(RefCell<Option<String>>);
+
+    impl Tray for FakeTray {
+        fn show(&self, tooltip: &str) -> io::Result<()> {
+            *self.0.borrow_mut() = Some(tooltip.to_string());
+            Ok(())
+        }
+
+        fn hide(&self) {
+            *self.0.borrow_mut() = None;
+        }
+    }
+
+    #[test]
+    fn replace_service() {
+        let fake = Rc::new(FakeClipboard::default());
+        let services = Services::new().with::<dyn Clipboard>(fake.clone());
+        assert!(services.opener().is_none());
+
+        services.clipboard().unwrap().put_string("copied");
+        assert_eq!(fake.get_string().as_deref(), Some("copied"));
+
+        let copy = services.clone();
+        assert!(copy == services);
+        let other = services.with::<dyn Clipboard>(Rc::new(FakeClipboard::default()));
+        assert!(copy != other);
+    }
+
+    #[test]
+    fn platform_services() {
+        let services = Services::platform();
+        let dialogs = services.file_dialogs().unwrap();
+        let options = FileDialogOptions::new().title("Export");
+        assert!(dialogs
+            .open_file(options.clone())
+            .is(commands::SHOW_OPEN_PANEL));
+        assert!(dialogs.save_file(options).is(commands::SHOW_SAVE_PANEL));
+
+        let tray = services.tray().unwrap();
+        let err = tray.show("Syncing").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::Unsupported);
+        assert!(services.notifications().is_some());
+    }
+
+    #[test]
+    fn fake_platform_services() {
+        let dialogs = Rc::new(FakeFileDialogs::default());
+        let notifications = Rc::new(FakeNotifications::default());
+        let tray = Rc::new(FakeTray::default());
+        let services = Services::platform()
+            .with::<dyn FileDialogs>(dialogs.clone())
+            .with::<dyn Notifications>(notifications.clone())
+            .with::<dyn Tray>(tray.clone());
+
+        let dialog = services.file_dialogs().unwrap();
+        let command = dialog.save_file(FileDialogOptions::new());
+        assert_eq!(command.get(FAKE_DIALOG), Some(&"save"));
+        assert_eq!(*dialogs.0.borrow(), vec!["save"]);
+
+        let notify = services.notifications().unwrap();
+        notify.notify("Sync", "All files are up to date").unwrap();
+        let sent = notifications.0.borrow().clone();
+        let expected = ("Sync".to_string(), "All files are up to date".to_string());
+        assert_eq!(sent, vec![expected]);
+
+        services.tray().unwrap().show("Syncing").unwrap();
+        assert_eq!(tray.0.borrow().as_deref(), Some("Syncing"));
+        services.tray().unwrap().hide();
+        assert_eq!(*tray.0.borrow(), None);
+    }
+}
@@ -0,0 +1,179 @@
+use crate::{
+    state::store::{Store, StoreObject},
+    ui::Ui,
+    widgets::{
+        flex::{CrossAxisAlignment, Flex},
+        Button, Label, SizedBox,
+    },
+};
+use std::{collections::VecDeque, panic::Location};
+
+/// The number of states a debug store keeps.
+const MAX_SNAPSHOTS: usize = 1000;
+
+/// The recorded states of a store.
+struct History<T> {
+    snapshots: VecDeque<T>,
+    /// The snapshot that is shown instead of the latest state.
+    viewing: Option<usize>,
+}
+
+impl<T: Clone> History<T> {
+    fn new(state: &T) -> Self {
+        let mut snapshots = VecDeque::new();
+        snapshots.push_back(state.clone());
+        History {
+            snapshots,
+            viewing: None,
+        }
+    }
+
+    fn record(&mut self, state: &T) {
+        self.snapshots.push_back(state.clone());
+        if self.snapshots.len() > MAX_SNAPSHOTS {
+            self.snapshots.pop_front();
+        }
+    }
+
+    /// Drop the snapshots after the viewed one and return it.
+    fn branch(&mut self) -> Option<T> {
+        let index = self.viewing.take()?;
+        self.snapshots.truncate(index + 1);
+        self.snapshots.back().cloned()
+    }
+
+    fn position(&self) -> usize {
+        self.viewing.unwrap_or(self.snapshots.len() - 1)
+    }
+
+    fn step_back(&mut self) {
+        self.viewing = Some(self.position().saturating_sub(1));
+    }
+
+    fn step_forward(&mut self) {
+        let next = self.position() + 1;
+        self.viewing = if next + 1 < self.snapshots.len() {
+            Some(next)
+        } else {
+            None
+        };
+    }
+
+    fn shown(&self) -> Option<&T> {
+        self.snapshots.get(self.viewing?)
+    }
+}
+
+struct DebugStoreObject<T, M> {
+    store: StoreObject<T, M>,
+    history: History<T>,
+}
+
+/// A [`use_store`] which records every state, to step through them while debugging.
+///
+/// A panel above `content` steps backwards and forwards through the recorded
+/// states, `content` is then built from the selected state. Messages sent
+/// while looking at an older state continue from it, dropping the newer ones.
+///
+/// [`use_store`]: crate::state::use_store
+#[track_caller]
+pub fn use_debug_store<T: Clone + 'static, M: 'static>(
+    ui: &mut Ui,
+    init: impl FnOnce() -> T,
+    mut update: impl FnMut(&mut T, M),
+    content: impl FnOnce(&mut Ui, &mut Store<T, M>),
+) {
+    let caller = Location::caller().into();
+    ui.state_node(
+        caller,
+        || {
+            let store = StoreObject::new(init());
+            let history = History::new(&store.state);
+            DebugStoreObject { store, history }
+        },
+        |ui, object: &mut DebugStoreObject<T, M>| {
+            let DebugStoreObject { store, history } = object;
+            if !store.msg.queue.is_empty() {
+                if let Some(state) = history.branch() {
+                    store.state = state;
+                }
+                for msg in store.msg.queue.drain(..) {
+                    update(&mut store.state, msg);
+                }
+                history.record(&store.state);
+            }
+
+            Flex::column()
+                .cross_axis_alignment(CrossAxisAlignment::Start)
+                .build(ui, |ui| {
+                    history_panel(ui, history);
+                    SizedBox::new().height(10.0).empty(ui);
+
+                    let state = history.shown().unwrap_or(&store.state);
+                    content(
+                        ui,
+                        &mut Store {
+                            state,
+                            msg: &mut store.msg,
+                        },
+                    );
+                });
+        },
+    )
+}
+
+fn history_panel<T: Clone>(ui: &mut Ui, history: &mut History<T>) {
+    Flex::row().build(ui, |ui| {
+        let position = history.position();
+        let len = history.snapshots.len();
+
+        if Button::new().disabled(position == 0).labeled(ui, "Back") {
+            history.step_back();
+        }
+        SizedBox::new().width(5.0).empty(ui);
+        Label::new(format!("State {} of {}", position + 1, len)).build(ui);
+        SizedBox::new().width(5.0).empty(ui);
+        if Button::new()
+            .disabled(history.viewing.is_none())
+            .labeled(ui, "Forward")
+        {
+            history.step_forward();
+        }
+        SizedBox::new().width(5.0).empty(ui);
+        if Button::new()
+            .disabled(history.viewing.is_none())
+            .labeled(ui, "Latest")
+        {
+            history.viewing = None;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn step_and_branch() {
+        let mut history = History::new(&0);
+        history.record(&1);
+        history.record(&2);
+        assert_eq!(history.shown(), None);
+
+        history.step_back();
+        history.step_back();
+        assert_eq!(history.shown(), Some(&0));
+        history.step_back();
+        assert_eq!(history.shown(), Some(&0));
+
+        history.step_forward();
+        assert_eq!(history.shown(), Some(&1));
+        history.step_forward();
+        assert_eq!(history.shown(), None);
+
+        history.step_back();
+        assert_eq!(history.branch(), Some(1));
+        assert_eq!(history.snapshots.len(), 2);
+        assert_eq!(history.shown(), None);
+    }
+}
@@ -0,0 +1,53 @@
+use std::{
+    cell::{Ref, RefCell, RefMut},
+    rc::Rc,
+};
+
+/// A handle to state owned by the tree, created by [`Ui::use_state`].
+///
+/// The state lives as long as the call site that created it is built.
+/// Handles are cheap to clone and can be moved into closures.
+///
+/// [`Ui::use_state`]: crate::ui::Ui::use_state
+pub struct Local<T> {
+    value: Rc<RefCell<T>>,
+}
+
+impl<T> Clone for Local<T> {
+    fn clone(&self) -> Self {
+        Local {
+            value: self.value.clone(),
+        }
+    }
+}
+
+impl<T> Local<T> {
+    pub(crate) fn new(value: T) -> Self {
+        Local {
+            value: Rc::new(RefCell::new(value)),
+        }
+    }
+
+    pub fn borrow(&self) -> Ref<'_, T> {
+        self.value.borrow()
+    }
+
+    pub fn borrow_mut(&self) -> RefMut<'_, T> {
+        self.value.borrow_mut()
+    }
+
+    pub fn set(&self, value: T) {
+        *self.value.borrow_mut() = value;
+    }
+
+    /// Modify the state in place.
+    pub fn update(&self, f: impl FnOnce(&mut T)) {
+        f(&mut self.value.borrow_mut());
+    }
+}
+
+impl<T: Clone> Local<T> {
+    pub fn get(&self) -> T {
+        self.value.borrow().clone()
+    }
+}
@@ -1,5 +1,14 @@
 pub mod mutable;
 pub use mutable::Mutable;
 
+pub mod local;
+pub use local::Local;
+
 pub mod store;
 pub use store::use_store;
+
+pub mod history;
+pub use history::use_debug_store;
+
+pub mod session;
+pub use session::{List, Persistent, Session};
@@ -0,0 +1,441 @@
+use crate::ui::Ui;
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display},
+    fs, io,
+    panic::Location,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+/// The first line of a session document, followed by its version.
+const HEADER: &str = "coat-session";
+
+/// The version of the documents written by [`Session::save`].
+///
+/// Version 0 are the documents from before the header, with the same lines.
+const VERSION: u32 = 1;
+
+/// The persisted UI state of an application, stored in a single document.
+///
+/// Enable it with [`App::session`], then use [`Persistent`] for state
+/// that should survive a restart. The document is written after every
+/// event that changed it, so it also survives a crash.
+///
+/// The containers of this crate, like [`Tabs`] and [`Split`], leave their
+/// state to the caller, so it is persisted the same way: the selected tab
+/// and a split fraction as numbers, and a list of open tabs or a stack of
+/// navigated pages as a [`List`].
+///
+/// [`App::session`]: crate::app::App::session
+/// [`Tabs`]: crate::widgets::Tabs
+/// [`Split`]: crate::widgets::Split
+pub struct Session {
+    path: PathBuf,
+    values: BTreeMap<String, String>,
+    changed: bool,
+}
+
+impl Session {
+    /// Load the session stored at `path`.
+    ///
+    /// A missing or unreadable document results in an empty session.
+    /// Documents of older versions are migrated. Documents of an unknown
+    /// version, for example from a newer version of the app, are moved
+    /// aside to a `.rejected` file, so saving the new session keeps them.
+    pub fn load(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let mut session = Session {
+            path,
+            values: BTreeMap::new(),
+            changed: false,
+        };
+        let text = match fs::read_to_string(&session.path) {
+            Ok(text) => text,
+            Err(err) => {
+                if err.kind() != io::ErrorKind::NotFound {
+                    log::warn!("Failed to read session {}: {}", session.path.display(), err);
+                }
+                return session;
+            }
+        };
+        match read(&text) {
+            Ok((version, values)) => {
+                session.values = values;
+                // Written again in the current version.
+                session.changed = version != VERSION;
+            }
+            Err(version) => {
+                let rejected = session.path.with_extension("rejected");
+                log::warn!(
+                    "Session {} has the unknown version {:?}, starting a new one",
+                    session.path.display(),
+                    version
+                );
+                if let Err(err) = fs::rename(&session.path, &rejected) {
+                    log::warn!("Failed to move it to {}: {}", rejected.display(), err);
+                }
+            }
+        }
+        session
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.values.get(key).map(String::as_str)
+    }
+
+    pub fn set(&mut self, key: &str, value: String) {
+        if self.get(key) != Some(value.as_str()) {
+            self.values.insert(key.to_owned(), value);
+            self.changed = true;
+        }
+    }
+
+    pub fn remove(&mut self, key: &str) {
+        self.changed |= self.values.remove(key).is_some();
+    }
+
+    /// Forget all persisted state.
+    pub fn clear(&mut self) {
+        self.changed |= !self.values.is_empty();
+        self.values.clear();
+    }
+
+    /// Write the document, if it changed since it was last written.
+    ///
+    /// The document is replaced atomically, a crash while saving
+    /// leaves the previous version intact.
+    pub fn save(&mut self) -> io::Result<()> {
+        if !self.changed {
+            return Ok(());
+        }
+        let tmp_path = self.path.with_extension("tmp");
+        fs::write(&tmp_path, serialize(&self.values))?;
+        fs::rename(&tmp_path, &self.path)?;
+        self.changed = false;
+        Ok(())
+    }
+}
+
+/// A list of values that is persisted as one value, like a stack of pages
+/// or the open tabs.
+///
+/// ```ignore
+/// Persistent::<List<String>>::new("editor.tabs").use_in(ui, |ui, tabs| {
+///     for path in &tabs.0 {
+///         // ...
+///     }
+/// });
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct List<T>(pub Vec<T>);
+
+// Each item is followed by a line break, so an empty item is not the empty list.
+impl<T: Display> Display for List<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut text = String::new();
+        for item in &self.0 {
+            for c in item.to_string().chars() {
+                match c {
+                    '\\' => text.push_str("\\\\"),
+                    '\n' => text.push_str("\\n"),
+                    c => text.push(c),
+                }
+            }
+            text.push('\n');
+        }
+        f.write_str(&text)
+    }
+}
+
+impl<T: FromStr> FromStr for List<T> {
+    type Err = T::Err;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut items = Vec::new();
+        let mut item = String::new();
+        let mut chars = s.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => match chars.next() {
+                    Some('n') => item.push('\n'),
+                    Some(c) => item.push(c),
+                    None => break,
+                },
+                '\n' => items.push(std::mem::take(&mut item).parse()?),
+                c => item.push(c),
+            }
+        }
+        if !item.is_empty() {
+            items.push(item.parse()?);
+        }
+        Ok(List(items))
+    }
+}
+
+/// State that is restored from the [`Session`], if there is one.
+///
+/// Without a session, this behaves like [`Mutable`].
+///
+/// [`Mutable`]: crate::state::Mutable
+pub struct Persistent<T> {
+    key: String,
+    init: Box<dyn FnOnce() -> T>,
+}
+
+impl<T: FromStr + Display + Default + 'static> Persistent<T> {
+    pub fn new(key: impl Into<String>) -> Self {
+        Persistent {
+            key: key.into(),
+            init: Box::new(T::default),
+        }
+    }
+}
+
+impl<T: FromStr + Display + 'static> Persistent<T> {
+    pub fn with(key: impl Into<String>, init: impl FnOnce() -> T + 'static) -> Self {
+        Persistent {
+            key: key.into(),
+            init: Box::new(init),
+        }
+    }
+
+    #[track_caller]
+    pub fn use_in(self, cx: &mut Ui, content: impl FnOnce(&mut Ui, &mut T)) {
+        let caller = Location::caller().into();
+        let session = cx.session();
+        let key = self.key;
+        let init = self.init;
+
+        let restore = || {
+            let session = session.as_ref()?;
+            let session = session.borrow();
+            session.get(&key)?.parse().ok()
+        };
+        let init = || restore().unwrap_or_else(init);
+
+        cx.state_node(caller, init, |ui, value: &mut T| {
+            content(ui, value);
+            if let Some(session) = &session {
+                session.borrow_mut().set(&key, value.to_string());
+            }
+        });
+    }
+}
+
+/// The version and values of the document `text`, or the version
+/// if it is unknown.
+fn read(text: &str) -> Result<(u32, BTreeMap<String, String>), String> {
+    let (first, rest) = text.split_once('\n').unwrap_or((text, ""));
+    let version = match first.strip_prefix(HEADER) {
+        Some(version) if !first.contains('=') => version.trim(),
+        // Version 0 had no header.
+        _ => return Ok((0, parse(text))),
+    };
+    match version.parse() {
+        Ok(VERSION) => Ok((VERSION, parse(rest))),
+        _ => Err(version.to_owned()),
+    }
+}
+
+fn parse(text: &str) -> BTreeMap<String, String> {
+    let mut values = BTreeMap::new();
+    for line in text.lines() {
+        let mut key = String::new();
+        let mut value = String::new();
+        let mut in_value = false;
+        let mut chars = line.chars();
+        while let Some(c) = chars.next() {
+            let c = match c {
+                '\\' => match chars.next() {
+                    Some('n') => '\n',
+                    Some(c) => c,
+                    None => break,
+                },
+                '=' if !in_value => {
+                    in_value = true;
+                    continue;
+                }
+                c => c,
+            };
+            if in_value {
+                value.push(c);
+            } else {
+                key.push(c);
+            }
+        }
+        if in_value {
+            values.insert(key, value);
+        }
+    }
+    values
+}
+
+fn serialize(values: &BTreeMap<String, String>) -> String {
+    let mut text = format!("{} {}\n", HEADER, VERSION);
+    for (key, value) in values {
+        escape_into(&mut text, key);
+        text.push('=');
+        escape_into(&mut text, value);
+        text.push('\n');
+    }
+    text
+}
+
+fn escape_into(text: &mut String, s: &str) {
+    for c in s.chars() {
+        match c {
+            '\\' => text.push_str("\\\\"),
+            '\n' => text.push_str("\\n"),
+            '=' => text.push_str("\\="),
+            c => text.push(c),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        testing::{self, Harness},
+        widgets::{flex::Axis, Flex, Label, Split, Tab, Tabs, TabsAction},
+    };
+    use std::{cell::RefCell, rc::Rc};
+
+    #[test]
+    fn document_roundtrip() {
+        let mut values = BTreeMap::new();
+        values.insert("split.ratio".to_owned(), "0.25".to_owned());
+        values.insert("a=b".to_owned(), "line\nbreak \\ and = sign".to_owned());
+        values.insert("empty".to_owned(), String::new());
+
+        assert_eq!(read(&serialize(&values)), Ok((VERSION, values)));
+    }
+
+    #[test]
+    fn document_versions() {
+        let old = "split.ratio=0.25\n";
+        assert_eq!(read(old).unwrap().0, 0);
+        assert_eq!(read(old).unwrap().1["split.ratio"], "0.25");
+        assert_eq!(
+            read("coat-session 2\nsplit.ratio=0.25\n"),
+            Err("2".to_owned())
+        );
+        assert_eq!(read("coat-session x\n"), Err("x".to_owned()));
+    }
+
+    #[test]
+    fn reject_unknown_version() {
+        let dir = std::env::temp_dir().join(format!("coat-session-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("session");
+        let newer = "coat-session 2\nsplit=0.25\n";
+        fs::write(&path, newer).unwrap();
+
+        let mut session = Session::load(&path);
+        assert_eq!(session.get("split"), None);
+        session.set("split", "0.5".to_owned());
+        session.save().unwrap();
+        assert_eq!(
+            fs::read_to_string(dir.join("session.rejected")).unwrap(),
+            newer
+        );
+
+        // Older documents are migrated.
+        fs::write(&path, "split=0.25\n").unwrap();
+        let mut session = Session::load(&path);
+        assert_eq!(session.get("split"), Some("0.25"));
+        session.save().unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "coat-session 1\nsplit=0.25\n"
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn list_roundtrip() {
+        let list = List(vec![
+            "a".to_owned(),
+            String::new(),
+            "line\nbreak \\".to_owned(),
+        ]);
+        assert_eq!(list.to_string().parse(), Ok(list));
+        assert_eq!("".parse(), Ok(List::<String>(Vec::new())));
+        assert_eq!("\n".parse(), Ok(List(vec![String::new()])));
+        assert!("1\nx\n".parse::<List<u32>>().is_err());
+    }
+
+    #[test]
+    fn restore_containers() {
+        let mut values = BTreeMap::new();
+        let pages = List(vec!["Home".to_owned(), "Settings".to_owned()]);
+        let tabs = List(vec!["main.rs".to_owned(), "lib.rs".to_owned()]);
+        values.insert("pages".to_owned(), pages.to_string());
+        values.insert("tabs".to_owned(), tabs.to_string());
+        values.insert("tabs.selected".to_owned(), "1".to_owned());
+        values.insert("split".to_owned(), "0.25".to_owned());
+        let session = Rc::new(RefCell::new(Session {
+            path: PathBuf::new(),
+            values,
+            changed: false,
+        }));
+
+        let mut harness = Harness::with_session(
+            |ui| {
+                Flex::column().build(ui, |ui| {
+                    Persistent::<List<String>>::new("pages").use_in(ui, |ui, pages| {
+                        let page = pages.0.last().map_or("", String::as_str);
+                        Label::new(format!("Page {}", page)).build(ui);
+                    });
+                    Persistent::<f64>::with("split", || 0.5).use_in(ui, |ui, fraction| {
+                        let split = Split::new(Axis::Horizontal).fraction(*fraction);
+                        let changed = split.build(ui, |ui| Label::new("Sidebar").build(ui), editor);
+                        if let Some(changed) = changed {
+                            *fraction = changed;
+                        }
+                    });
+                });
+            },
+            session.clone(),
+        );
+
+        let texts = harness.label_texts();
+        assert!(texts.contains(&"Page Settings".to_owned()));
+        assert!(texts.contains(&"Editing lib.rs".to_owned()));
+        let first = testing::assert_exists::<Label>(harness.tree(), testing::with_label("main.rs"));
+        // The editor pane starts after a quarter of the window.
+        let x = harness.window_rect(first).x0;
+        assert!(x > 200.0 && x < 250.0, "The tabs start at {}", x);
+
+        harness.click_on(first);
+        harness.rebuild();
+        assert!(harness
+            .label_texts()
+            .contains(&"Editing main.rs".to_owned()));
+        assert_eq!(session.borrow().get("tabs.selected"), Some("0"));
+        assert_eq!(session.borrow().get("split"), Some("0.25"));
+    }
+
+    /// Tabs of the files persisted as `"tabs"`.
+    fn editor(ui: &mut Ui) {
+        Persistent::<List<String>>::new("tabs").use_in(ui, |ui, tabs| {
+            Persistent::<usize>::new("tabs.selected").use_in(ui, |ui, selected| {
+                let names = tabs.0.clone();
+                let strip = names.iter().fold(Tabs::new(*selected), |strip, name| {
+                    strip.tab(Tab::new(name.as_str()))
+                });
+                let action = strip.build(ui, |ui, index| {
+                    Label::new(format!("Editing {}", names[index])).build(ui);
+                });
+                if let Some(TabsAction::Select(index)) = action {
+                    *selected = index;
+                }
+            });
+        });
+    }
+}
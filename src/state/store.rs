@@ -2,8 +2,8 @@ use crate::ui::Ui;
 use std::{collections::VecDeque, panic::Location};
 
 pub(crate) struct StoreObject<T, M> {
-    state: T,
-    msg: MsgQueue<M>,
+    pub(crate) state: T,
+    pub(crate) msg: MsgQueue<M>,
 }
 
 impl<T, M> StoreObject<T, M> {
@@ -0,0 +1,433 @@
+//! Solid, gradient and image fills.
+
+use crate::{
+    context::PaintCtx,
+    kurbo::{Insets, Point, Rect, Shape, Size},
+    piet::{
+        Color, GradientStop, ImageBuf, InterpolationMode, LinearGradient, PaintBrush, PietImage,
+        RadialGradient, RenderContext, UnitPoint,
+    },
+    widgets::image::ImageSet,
+};
+
+/// How an image background covers the area.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImageRepeat {
+    /// The image is stretched to the size of the area.
+    Stretch,
+    /// The image is repeated in its own size, starting at the top left.
+    Tile,
+}
+
+/// What the area of a widget is filled with.
+///
+/// The points of gradients are relative to the bounding box of the filled
+/// shape, with `(0.0, 0.0)` at the top left and `(1.0, 1.0)` at the bottom
+/// right, so a gradient stretches with the widget. The stops are pairs of a
+/// position from `0.0` to `1.0` along the gradient and the color there.
+///
+/// Images are clipped to the filled shape. Of an [`ImageSet`], the image that
+/// matches the scale of the window is painted. They are uploaded to the
+/// render context on every paint, which is fine for the few widgets of a skin.
+///
+/// ```ignore
+/// let background = Background::linear(Point::new(0.5, 0.0), Point::new(0.5, 1.0), &[top, bottom]);
+/// let frame = Background::nine_patch(skin.frame.clone(), Insets::uniform(12.0));
+/// ```
+#[derive(Debug, Clone)]
+pub enum Background {
+    Color(Color),
+    /// Blends the colors along the line from `start` to `end`.
+    LinearGradient {
+        start: Point,
+        end: Point,
+        stops: Vec<(f64, Color)>,
+    },
+    /// Blends the colors outwards from `center`, the last stop is reached at
+    /// `radius` times the longer side of the area.
+    RadialGradient {
+        center: Point,
+        radius: f64,
+        stops: Vec<(f64, Color)>,
+    },
+    /// An image stretched or tiled over the area.
+    Image {
+        image: ImageSet,
+        repeat: ImageRepeat,
+    },
+    /// An image cut into a grid of three by three parts by `slices`, given in
+    /// logical pixels of the image.
+    ///
+    /// The corners keep their size, the edges are stretched along the sides
+    /// and the center fills the rest, so frames and buttons of a skin can
+    /// take any size. Corners shrink when the area is smaller than them.
+    NinePatch {
+        image: ImageSet,
+        slices: Insets,
+    },
+}
+
+impl Background {
+    /// A linear gradient with `colors` spread evenly from `start` to `end`.
+    pub fn linear(start: Point, end: Point, colors: &[Color]) -> Self {
+        Background::LinearGradient {
+            start,
+            end,
+            stops: even_stops(colors),
+        }
+    }
+
+    /// A radial gradient around the center with `colors` spread evenly up to `radius`.
+    pub fn radial(radius: f64, colors: &[Color]) -> Self {
+        Background::RadialGradient {
+            center: Point::new(0.5, 0.5),
+            radius,
+            stops: even_stops(colors),
+        }
+    }
+
+    /// An image stretched over the area.
+    pub fn image(image: impl Into<ImageSet>) -> Self {
+        Background::Image {
+            image: image.into(),
+            repeat: ImageRepeat::Stretch,
+        }
+    }
+
+    /// An image repeated over the area.
+    pub fn tiled(image: impl Into<ImageSet>) -> Self {
+        Background::Image {
+            image: image.into(),
+            repeat: ImageRepeat::Tile,
+        }
+    }
+
+    /// An image that keeps the size of its borders, see [`Background::NinePatch`].
+    pub fn nine_patch(image: impl Into<ImageSet>, slices: impl Into<Insets>) -> Self {
+        Background::NinePatch {
+            image: image.into(),
+            slices: slices.into(),
+        }
+    }
+
+    /// The same background with the alpha of all colors set to `alpha`.
+    ///
+    /// Images are not changed.
+    pub fn with_alpha(self, alpha: f64) -> Self {
+        let fade = |stops: Vec<(f64, Color)>| {
+            stops
+                .into_iter()
+                .map(|(pos, color)| (pos, color.with_alpha(alpha)))
+                .collect()
+        };
+        match self {
+            Background::Color(color) => Background::Color(color.with_alpha(alpha)),
+            Background::LinearGradient { start, end, stops } => Background::LinearGradient {
+                start,
+                end,
+                stops: fade(stops),
+            },
+            Background::RadialGradient {
+                center,
+                radius,
+                stops,
+            } => Background::RadialGradient {
+                center,
+                radius,
+                stops: fade(stops),
+            },
+            image => image,
+        }
+    }
+
+    /// The brush that paints this background, resolved against the shape it
+    /// fills, or `None` for images.
+    pub fn brush(&self) -> Option<PaintBrush> {
+        let stops = |stops: &[(f64, Color)]| -> Vec<GradientStop> {
+            stops
+                .iter()
+                .map(|(pos, color)| GradientStop {
+                    pos: *pos as f32,
+                    color: color.clone(),
+                })
+                .collect()
+        };
+        let brush = match self {
+            Background::Color(color) => color.clone().into(),
+            Background::LinearGradient {
+                start,
+                end,
+                stops: s,
+            } => LinearGradient::new(unit_point(*start), unit_point(*end), stops(s)).into(),
+            Background::RadialGradient {
+                center,
+                radius,
+                stops: s,
+            } => RadialGradient::new(*radius, stops(s))
+                .with_center(unit_point(*center))
+                .with_origin(unit_point(*center))
+                .into(),
+            Background::Image { .. } | Background::NinePatch { .. } => return None,
+        };
+        Some(brush)
+    }
+
+    /// Fill `shape` with this background.
+    pub fn paint(&self, ctx: &mut PaintCtx, shape: impl Shape) {
+        if let Some(brush) = self.brush() {
+            ctx.fill(shape, &brush);
+            return;
+        }
+        // The parts are computed in logical pixels of the image.
+        let (image, parts) = match self {
+            Background::Image { image, repeat } => {
+                let dest = shape.bounding_box();
+                let parts = match repeat {
+                    ImageRepeat::Stretch => vec![(image.size().to_rect(), dest)],
+                    ImageRepeat::Tile => tiles(image.size(), dest),
+                };
+                (image, parts)
+            }
+            Background::NinePatch { image, slices } => (
+                image,
+                nine_patch(image.size(), *slices, shape.bounding_box()),
+            ),
+            _ => return,
+        };
+        let (scale, image) = image.pick(ctx.scale().x());
+        let uploaded = match upload(ctx, image) {
+            Some(uploaded) => uploaded,
+            None => return,
+        };
+        ctx.with_save(|ctx| {
+            ctx.clip(shape);
+            for (src, dest) in parts {
+                let src = Rect::new(
+                    src.x0 * scale,
+                    src.y0 * scale,
+                    src.x1 * scale,
+                    src.y1 * scale,
+                );
+                ctx.render_ctx
+                    .draw_image_area(&uploaded, src, dest, InterpolationMode::Bilinear);
+            }
+        });
+    }
+}
+
+impl PartialEq for Background {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Background::Color(a), Background::Color(b)) => a == b,
+            (
+                Background::LinearGradient { start, end, stops },
+                Background::LinearGradient {
+                    start: other_start,
+                    end: other_end,
+                    stops: other_stops,
+                },
+            ) => start == other_start && end == other_end && stops == other_stops,
+            (
+                Background::RadialGradient {
+                    center,
+                    radius,
+                    stops,
+                },
+                Background::RadialGradient {
+                    center: other_center,
+                    radius: other_radius,
+                    stops: other_stops,
+                },
+            ) => center == other_center && radius == other_radius && stops == other_stops,
+            (
+                Background::Image { image, repeat },
+                Background::Image {
+                    image: other_image,
+                    repeat: other_repeat,
+                },
+            ) => image.same(other_image) && repeat == other_repeat,
+            (
+                Background::NinePatch { image, slices },
+                Background::NinePatch {
+                    image: other_image,
+                    slices: other_slices,
+                },
+            ) => image.same(other_image) && slices == other_slices,
+            _ => false,
+        }
+    }
+}
+
+impl From<Color> for Background {
+    fn from(color: Color) -> Self {
+        Background::Color(color)
+    }
+}
+
+fn unit_point(point: Point) -> UnitPoint {
+    UnitPoint::new(point.x, point.y)
+}
+
+fn upload(ctx: &mut PaintCtx, image: &ImageBuf) -> Option<PietImage> {
+    if image.size().is_empty() {
+        return None;
+    }
+    let uploaded = ctx.render_ctx.make_image(
+        image.width(),
+        image.height(),
+        image.raw_pixels(),
+        image.format(),
+    );
+    match uploaded {
+        Ok(uploaded) => Some(uploaded),
+        Err(err) => {
+            log::warn!("Failed to create background image: {}", err);
+            None
+        }
+    }
+}
+
+/// The source and destination rects of the tiles of an `image` sized image covering `dest`.
+fn tiles(image: Size, dest: Rect) -> Vec<(Rect, Rect)> {
+    if image.is_empty() {
+        return Vec::new();
+    }
+    let mut tiles = Vec::new();
+    let mut y = dest.y0;
+    while y < dest.y1 {
+        let mut x = dest.x0;
+        while x < dest.x1 {
+            tiles.push((image.to_rect(), Rect::from_origin_size((x, y), image)));
+            x += image.width;
+        }
+        y += image.height;
+    }
+    tiles
+}
+
+/// The source and destination rects of the nine parts of an `image` sized
+/// image sliced by `slices` and painted to `dest`, without empty parts.
+fn nine_patch(image: Size, slices: Insets, dest: Rect) -> Vec<(Rect, Rect)> {
+    // Shrink the corners when they do not fit.
+    let fit = |space: f64, a: f64, b: f64| {
+        if a + b > space && a + b > 0.0 {
+            space / (a + b)
+        } else {
+            1.0
+        }
+    };
+    let scale_x = fit(dest.width(), slices.x0, slices.x1);
+    let scale_y = fit(dest.height(), slices.y0, slices.y1);
+    let src_x = [0.0, slices.x0, image.width - slices.x1, image.width];
+    let src_y = [0.0, slices.y0, image.height - slices.y1, image.height];
+    let dest_x = [
+        dest.x0,
+        dest.x0 + slices.x0 * scale_x,
+        dest.x1 - slices.x1 * scale_x,
+        dest.x1,
+    ];
+    let dest_y = [
+        dest.y0,
+        dest.y0 + slices.y0 * scale_y,
+        dest.y1 - slices.y1 * scale_y,
+        dest.y1,
+    ];
+    let mut parts = Vec::new();
+    for row in 0..3 {
+        for column in 0..3 {
+            let src = Rect::new(src_x[column], src_y[row], src_x[column + 1], src_y[row + 1]);
+            let dest = Rect::new(
+                dest_x[column],
+                dest_y[row],
+                dest_x[column + 1],
+                dest_y[row + 1],
+            );
+            if src.area() > 0.0 && dest.area() > 0.0 {
+                parts.push((src, dest));
+            }
+        }
+    }
+    parts
+}
+
+fn even_stops(colors: &[Color]) -> Vec<(f64, Color)> {
+    let last = colors.len().saturating_sub(1).max(1) as f64;
+    colors
+        .iter()
+        .enumerate()
+        .map(|(i, color)| (i as f64 / last, color.clone()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evenly_spread_stops() {
+        let stops = even_stops(&[Color::BLACK, Color::WHITE, Color::BLACK]);
+        let positions: Vec<f64> = stops.iter().map(|(pos, _)| *pos).collect();
+        assert_eq!(positions, vec![0.0, 0.5, 1.0]);
+        assert_eq!(even_stops(&[Color::WHITE]), vec![(0.0, Color::WHITE)]);
+    }
+
+    #[test]
+    fn fade_gradient() {
+        let background = Background::radial(0.5, &[Color::BLACK, Color::WHITE]).with_alpha(0.5);
+        match background {
+            Background::RadialGradient { stops, .. } => {
+                assert_eq!(stops[1].1, Color::WHITE.with_alpha(0.5));
+            }
+            _ => panic!("expected a radial gradient"),
+        }
+    }
+
+    #[test]
+    fn tile_image() {
+        let tiles = tiles(Size::new(10.0, 10.0), Rect::new(0.0, 0.0, 25.0, 10.0));
+        let origins: Vec<Point> = tiles.iter().map(|(_, dest)| dest.origin()).collect();
+        assert_eq!(
+            origins,
+            vec![
+                Point::new(0.0, 0.0),
+                Point::new(10.0, 0.0),
+                Point::new(20.0, 0.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn slice_nine_patch() {
+        let image = Size::new(30.0, 30.0);
+        let parts = nine_patch(
+            image,
+            Insets::uniform(10.0),
+            Rect::new(0.0, 0.0, 100.0, 50.0),
+        );
+        assert_eq!(parts.len(), 9);
+        assert_eq!(
+            parts[0],
+            (
+                Rect::new(0.0, 0.0, 10.0, 10.0),
+                Rect::new(0.0, 0.0, 10.0, 10.0)
+            )
+        );
+        assert_eq!(
+            parts[4],
+            (
+                Rect::new(10.0, 10.0, 20.0, 20.0),
+                Rect::new(10.0, 10.0, 90.0, 40.0)
+            )
+        );
+        assert_eq!(parts[8].1, Rect::new(90.0, 40.0, 100.0, 50.0));
+
+        // Only the shrunk corners remain in an area smaller than them.
+        let parts = nine_patch(
+            image,
+            Insets::uniform(10.0),
+            Rect::new(0.0, 0.0, 10.0, 10.0),
+        );
+        assert_eq!(parts.len(), 4);
+        assert_eq!(parts[3].1, Rect::new(5.0, 5.0, 10.0, 10.0));
+    }
+}
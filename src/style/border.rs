@@ -0,0 +1,373 @@
+//! Borders with their own width and color on every side.
+
+use crate::{
+    context::PaintCtx,
+    kurbo::{Arc, BezPath, Insets, Point, Rect, Vec2},
+    piet::{Color, RenderContext},
+};
+use std::f64::consts::{FRAC_PI_2, PI};
+
+/// The accuracy of the paths of rounded corners.
+const TOLERANCE: f64 = 0.1;
+
+/// One side of a [`Border`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct BorderSide {
+    pub width: f64,
+    pub color: Color,
+}
+
+impl BorderSide {
+    /// A side that is not painted.
+    pub const NONE: BorderSide = BorderSide {
+        width: 0.0,
+        color: Color::rgba8(0, 0, 0, 0),
+    };
+
+    pub fn new(width: f64, color: Color) -> Self {
+        BorderSide { width, color }
+    }
+}
+
+/// The radii of the corners of a [`Border`].
+///
+/// Radii larger than half of the shorter side of the area are reduced to it.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CornerRadii {
+    pub top_left: f64,
+    pub top_right: f64,
+    pub bottom_right: f64,
+    pub bottom_left: f64,
+}
+
+impl CornerRadii {
+    pub fn uniform(radius: f64) -> Self {
+        CornerRadii {
+            top_left: radius,
+            top_right: radius,
+            bottom_right: radius,
+            bottom_left: radius,
+        }
+    }
+
+    /// The largest of the radii.
+    pub fn max(&self) -> f64 {
+        self.top_left
+            .max(self.top_right)
+            .max(self.bottom_right)
+            .max(self.bottom_left)
+    }
+}
+
+impl From<f64> for CornerRadii {
+    fn from(radius: f64) -> Self {
+        CornerRadii::uniform(radius)
+    }
+}
+
+/// The border of a widget, painted inside of its area.
+///
+/// Every side has its own width and color, so a text field can be underlined
+/// only, or a panel can get an accent bar on the left:
+///
+/// ```ignore
+/// let underline = Border {
+///     bottom: BorderSide::new(2.0, accent),
+///     ..Border::NONE
+/// };
+/// let accent_bar = Border::NONE
+///     .side_left(BorderSide::new(4.0, accent))
+///     .radius(CornerRadii { top_left: 4.0, bottom_left: 4.0, ..CornerRadii::default() });
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Border {
+    pub left: BorderSide,
+    pub top: BorderSide,
+    pub right: BorderSide,
+    pub bottom: BorderSide,
+    pub radii: CornerRadii,
+}
+
+impl Border {
+    /// No border and square corners.
+    pub const NONE: Border = Border {
+        left: BorderSide::NONE,
+        top: BorderSide::NONE,
+        right: BorderSide::NONE,
+        bottom: BorderSide::NONE,
+        radii: CornerRadii {
+            top_left: 0.0,
+            top_right: 0.0,
+            bottom_right: 0.0,
+            bottom_left: 0.0,
+        },
+    };
+
+    /// The same `width` and `color` on all sides, with square corners.
+    pub fn uniform(width: f64, color: Color) -> Self {
+        let side = BorderSide::new(width, color);
+        Border {
+            left: side.clone(),
+            top: side.clone(),
+            right: side.clone(),
+            bottom: side,
+            radii: CornerRadii::default(),
+        }
+    }
+
+    /// Round the corners.
+    pub fn radius(mut self, radii: impl Into<CornerRadii>) -> Self {
+        self.radii = radii.into();
+        self
+    }
+
+    pub fn side_left(mut self, side: BorderSide) -> Self {
+        self.left = side;
+        self
+    }
+
+    pub fn side_top(mut self, side: BorderSide) -> Self {
+        self.top = side;
+        self
+    }
+
+    pub fn side_right(mut self, side: BorderSide) -> Self {
+        self.right = side;
+        self
+    }
+
+    pub fn side_bottom(mut self, side: BorderSide) -> Self {
+        self.bottom = side;
+        self
+    }
+
+    /// The same border with all sides in `color`.
+    pub fn with_color(mut self, color: Color) -> Self {
+        for side in self.sides_mut() {
+            side.color = color.clone();
+        }
+        self
+    }
+
+    /// The same border with the alpha of all sides set to `alpha`.
+    pub fn with_alpha(mut self, alpha: f64) -> Self {
+        for side in self.sides_mut() {
+            side.color = side.color.clone().with_alpha(alpha);
+        }
+        self
+    }
+
+    /// The same border with all sides `width` wide.
+    pub fn with_width(mut self, width: f64) -> Self {
+        for side in self.sides_mut() {
+            side.width = width;
+        }
+        self
+    }
+
+    /// The same border with the widths rounded to whole physical pixels, so
+    /// that it is painted crisp on the screen of `ctx`.
+    ///
+    /// Painted into a rect from [`PaintCtx::snap_rect`], its edges line up
+    /// with the physical pixels.
+    pub fn snapped(&self, ctx: &PaintCtx) -> Border {
+        let mut border = self.clone();
+        for side in border.sides_mut() {
+            side.width = ctx.snap_width(side.width);
+        }
+        border
+    }
+
+    fn sides_mut(&mut self) -> [&mut BorderSide; 4] {
+        [
+            &mut self.left,
+            &mut self.top,
+            &mut self.right,
+            &mut self.bottom,
+        ]
+    }
+
+    /// The widths of the sides, as insets from the outside.
+    pub fn widths(&self) -> Insets {
+        Insets::new(
+            self.left.width,
+            self.top.width,
+            self.right.width,
+            self.bottom.width,
+        )
+    }
+
+    /// The outline of `rect` with the rounded corners, to fill a background.
+    pub fn outer_path(&self, rect: Rect) -> BezPath {
+        let max = rect.width().min(rect.height()) / 2.0;
+        let radius = |r: f64| {
+            let r = r.min(max).max(0.0);
+            Vec2::new(r, r)
+        };
+        rounded_path(
+            rect,
+            [
+                radius(self.radii.top_left),
+                radius(self.radii.top_right),
+                radius(self.radii.bottom_right),
+                radius(self.radii.bottom_left),
+            ],
+        )
+    }
+
+    /// The area of `rect` inside of the border, to clip content.
+    pub fn inner_path(&self, rect: Rect) -> BezPath {
+        let max = rect.width().min(rect.height()) / 2.0;
+        let widths = self.widths();
+        let inner = Rect::new(
+            rect.x0 + widths.x0,
+            rect.y0 + widths.y0,
+            (rect.x1 - widths.x1).max(rect.x0 + widths.x0),
+            (rect.y1 - widths.y1).max(rect.y0 + widths.y0),
+        );
+        // The inner corners follow the outer ones, less the width of the sides.
+        let radius = |r: f64, x: f64, y: f64| {
+            let r = r.min(max);
+            Vec2::new((r - x).max(0.0), (r - y).max(0.0))
+        };
+        rounded_path(
+            inner,
+            [
+                radius(self.radii.top_left, widths.x0, widths.y0),
+                radius(self.radii.top_right, widths.x1, widths.y0),
+                radius(self.radii.bottom_right, widths.x1, widths.y1),
+                radius(self.radii.bottom_left, widths.x0, widths.y1),
+            ],
+        )
+    }
+
+    /// Paint the border inside of `rect`.
+    pub fn paint(&self, ctx: &mut PaintCtx, rect: Rect) {
+        if self.widths() == Insets::ZERO {
+            return;
+        }
+        let mut ring = self.outer_path(rect);
+        ring.extend(self.inner_path(rect));
+
+        let uniform_color = self.top.color == self.left.color
+            && self.top.color == self.right.color
+            && self.top.color == self.bottom.color;
+        if uniform_color {
+            ctx.fill_even_odd(ring, &self.top.color);
+            return;
+        }
+
+        // Every side gets the part of the ring between the diagonals
+        // through its corners.
+        let inner = rect - self.widths();
+        let corners = [
+            (rect.origin(), inner.origin()),
+            (Point::new(rect.x1, rect.y0), Point::new(inner.x1, inner.y0)),
+            (Point::new(rect.x1, rect.y1), Point::new(inner.x1, inner.y1)),
+            (Point::new(rect.x0, rect.y1), Point::new(inner.x0, inner.y1)),
+        ];
+        let sides = [&self.top, &self.right, &self.bottom, &self.left];
+        for (i, side) in sides.iter().enumerate() {
+            if side.width <= 0.0 {
+                continue;
+            }
+            let (outer_start, inner_start) = corners[i];
+            let (outer_end, inner_end) = corners[(i + 1) % 4];
+            let mut part = BezPath::new();
+            part.move_to(outer_start);
+            part.line_to(outer_end);
+            part.line_to(inner_end);
+            part.line_to(inner_start);
+            part.close_path();
+            let ring = &ring;
+            ctx.with_save(|ctx| {
+                ctx.clip(part);
+                ctx.fill_even_odd(ring, &side.color);
+            });
+        }
+    }
+}
+
+impl Default for Border {
+    fn default() -> Self {
+        Border::NONE
+    }
+}
+
+/// A closed path around `rect` with elliptic corners, clockwise from the
+/// top left.
+fn rounded_path(rect: Rect, radii: [Vec2; 4]) -> BezPath {
+    let [top_left, top_right, bottom_right, bottom_left] = radii;
+    let mut path = BezPath::new();
+    path.move_to((rect.x0, rect.y0 + top_left.y));
+    let mut corner = |center: Point, radii: Vec2, start_angle: f64| {
+        if radii.x > 0.0 && radii.y > 0.0 {
+            let arc = Arc {
+                center,
+                radii,
+                start_angle,
+                sweep_angle: FRAC_PI_2,
+                x_rotation: 0.0,
+            };
+            let (sin, cos) = start_angle.sin_cos();
+            path.line_to(center + Vec2::new(radii.x * cos, radii.y * sin));
+            path.extend(arc.append_iter(TOLERANCE));
+        } else {
+            path.line_to(center);
+        }
+    };
+    corner(
+        Point::new(rect.x0 + top_left.x, rect.y0 + top_left.y),
+        top_left,
+        PI,
+    );
+    corner(
+        Point::new(rect.x1 - top_right.x, rect.y0 + top_right.y),
+        top_right,
+        -FRAC_PI_2,
+    );
+    corner(
+        Point::new(rect.x1 - bottom_right.x, rect.y1 - bottom_right.y),
+        bottom_right,
+        0.0,
+    );
+    corner(
+        Point::new(rect.x0 + bottom_left.x, rect.y1 - bottom_left.y),
+        bottom_left,
+        FRAC_PI_2,
+    );
+    path.close_path();
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kurbo::Shape;
+
+    fn assert_near(a: Rect, b: Rect) {
+        let near = (a.x0 - b.x0).abs() < 1e-9
+            && (a.y0 - b.y0).abs() < 1e-9
+            && (a.x1 - b.x1).abs() < 1e-9
+            && (a.y1 - b.y1).abs() < 1e-9;
+        assert!(near, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn border_paths() {
+        let rect = Rect::new(0.0, 0.0, 100.0, 20.0);
+        let border = Border::uniform(2.0, Color::BLACK).radius(50.0);
+        assert_near(border.outer_path(rect).bounding_box(), rect);
+        assert_near(
+            border.inner_path(rect).bounding_box(),
+            Rect::new(2.0, 2.0, 98.0, 18.0),
+        );
+
+        let accent_bar = Border::NONE.side_left(BorderSide::new(4.0, Color::WHITE));
+        assert_near(
+            accent_bar.inner_path(rect).bounding_box(),
+            Rect::new(4.0, 0.0, 100.0, 20.0),
+        );
+        assert_eq!(accent_bar.widths(), Insets::new(4.0, 0.0, 0.0, 0.0));
+    }
+}
@@ -0,0 +1,655 @@
+//! Styles loaded from a file at runtime.
+//!
+//! The format is a small subset of CSS:
+//!
+//! ```text
+//! /* Applies to the whole theme. */
+//! theme {
+//!     accent: #8080de;
+//!     corner-radius: 4;
+//! }
+//!
+//! button {
+//!     background: #404080;
+//!     border-radius: 6;
+//! }
+//!
+//! button:hovered {
+//!     background: #5050a0;
+//! }
+//! ```
+//!
+//! The selectors are `theme`, `button` and `text-box`, the widgets can be
+//! followed by one of the states `:hovered`, `:pressed`, `:focused` and
+//! `:disabled`. Values are numbers or colors written as `#rgb`, `#rrggbb`
+//! or `#rrggbbaa`.
+//!
+//! Besides `border-width`, `border-color` and `border-radius` for the whole
+//! border, the sides and corners of borders can be set on their own, as in
+//! `border-bottom-width`, `border-left-color` and `border-top-right-radius`.
+
+use crate::{
+    kurbo::Size,
+    piet::Color,
+    style::{Border, BorderSide, CornerRadii, Shadow, State},
+    theme::Theme,
+    widgets::{button, textbox},
+    BoxConstraints,
+};
+use std::{any::Any, error::Error, fmt, fs, io, path::Path, rc::Rc, str::Chars};
+
+/// Style rules parsed from a style file.
+///
+/// The rules are applied on top of a [`Theme`] with [`apply`].
+///
+/// [`apply`]: StyleFile::apply
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StyleFile {
+    rules: Vec<Rule>,
+}
+
+/// A syntax error in a style file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    /// The line of the error, starting at 1.
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for ParseError {}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Target {
+    Theme,
+    Button,
+    TextBox,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    Number,
+    Color,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Number(f64),
+    Color(Color),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Rule {
+    target: Target,
+    state: Option<State>,
+    declarations: Vec<(String, Value)>,
+}
+
+impl Target {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "theme" => Some(Target::Theme),
+            "button" => Some(Target::Button),
+            "text-box" => Some(Target::TextBox),
+            _ => None,
+        }
+    }
+
+    fn property(self, name: &str) -> Option<Kind> {
+        let properties: &[(&str, Kind)] = match self {
+            Target::Theme => &[
+                ("background", Kind::Color),
+                ("text", Kind::Color),
+                ("accent", Kind::Color),
+                ("on-accent", Kind::Color),
+                ("panel", Kind::Color),
+                ("panel-border", Kind::Color),
+                ("scrim", Kind::Color),
+                ("ghost", Kind::Color),
+                ("corner-radius", Kind::Number),
+                ("dialog-padding", Kind::Number),
+                ("menu-padding", Kind::Number),
+                ("text-insets", Kind::Number),
+                ("font-size", Kind::Number),
+            ],
+            Target::Button => &[
+                ("background", Kind::Color),
+                ("border-color", Kind::Color),
+                ("border-width", Kind::Number),
+                ("border-radius", Kind::Number),
+                ("text-color", Kind::Color),
+                ("elevation", Kind::Number),
+            ],
+            Target::TextBox => &[
+                ("background", Kind::Color),
+                ("border-color", Kind::Color),
+                ("border-width", Kind::Number),
+                ("border-radius", Kind::Number),
+                ("selection-color", Kind::Color),
+                ("cursor-color", Kind::Color),
+            ],
+        };
+        let kind = properties
+            .iter()
+            .find(|(property, _)| *property == name)
+            .map(|(_, kind)| *kind);
+        match self {
+            Target::Theme => kind,
+            Target::Button | Target::TextBox => kind.or_else(|| border_property(name)),
+        }
+    }
+}
+
+const SIDES: [&str; 4] = ["left", "top", "right", "bottom"];
+const CORNERS: [&str; 4] = ["top-left", "top-right", "bottom-right", "bottom-left"];
+
+/// The kind of the properties of single border sides and corners.
+fn border_property(name: &str) -> Option<Kind> {
+    let name = name.strip_prefix("border-")?;
+    if let Some(side) = name.strip_suffix("-width") {
+        if SIDES.contains(&side) {
+            return Some(Kind::Number);
+        }
+    }
+    if let Some(side) = name.strip_suffix("-color") {
+        if SIDES.contains(&side) {
+            return Some(Kind::Color);
+        }
+    }
+    if let Some(corner) = name.strip_suffix("-radius") {
+        if CORNERS.contains(&corner) {
+            return Some(Kind::Number);
+        }
+    }
+    None
+}
+
+/// Apply a border declaration to `border`, returns whether `name` was one.
+fn apply_border(border: &mut Border, name: &str, value: &Value) -> bool {
+    match (name, value) {
+        ("border-width", Value::Number(n)) => *border = border.clone().with_width(*n),
+        ("border-color", Value::Color(color)) => *border = border.clone().with_color(color.clone()),
+        ("border-radius", Value::Number(n)) => border.radii = CornerRadii::uniform(*n),
+        (name, Value::Number(n)) => {
+            let name = match name.strip_prefix("border-") {
+                Some(name) => name,
+                None => return false,
+            };
+            let target = if let Some(name) = name.strip_suffix("-width") {
+                border_side(border, name).map(|side| &mut side.width)
+            } else if let Some(name) = name.strip_suffix("-radius") {
+                match name {
+                    "top-left" => Some(&mut border.radii.top_left),
+                    "top-right" => Some(&mut border.radii.top_right),
+                    "bottom-right" => Some(&mut border.radii.bottom_right),
+                    "bottom-left" => Some(&mut border.radii.bottom_left),
+                    _ => None,
+                }
+            } else {
+                None
+            };
+            match target {
+                Some(target) => *target = *n,
+                None => return false,
+            }
+        }
+        (name, Value::Color(color)) => {
+            let side = name
+                .strip_prefix("border-")
+                .and_then(|name| name.strip_suffix("-color"))
+                .and_then(|name| border_side(border, name));
+            match side {
+                Some(side) => side.color = color.clone(),
+                None => return false,
+            }
+        }
+    }
+    true
+}
+
+fn border_side<'a>(border: &'a mut Border, side: &str) -> Option<&'a mut BorderSide> {
+    match side {
+        "left" => Some(&mut border.left),
+        "top" => Some(&mut border.top),
+        "right" => Some(&mut border.right),
+        "bottom" => Some(&mut border.bottom),
+        _ => None,
+    }
+}
+
+impl StyleFile {
+    pub fn parse(text: &str) -> Result<StyleFile, ParseError> {
+        Parser::new(text).parse()
+    }
+
+    /// Read and parse the style file at `path`.
+    ///
+    /// Syntax errors are reported as [`io::ErrorKind::InvalidData`].
+    pub fn load(path: impl AsRef<Path>) -> io::Result<StyleFile> {
+        let text = fs::read_to_string(path)?;
+        StyleFile::parse(&text).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    /// Apply the rules to `theme`.
+    ///
+    /// The style sheets of the theme are replaced by ones that start from them,
+    /// so properties missing in the file keep their value. Declarations without
+    /// a state apply to all states of a widget, the rules of a state override them.
+    pub fn apply(&self, theme: &mut Theme) {
+        for (name, value) in self.declarations(Target::Theme, None) {
+            match (name, value) {
+                ("background", Value::Color(color)) => theme.colors.background = color.clone(),
+                ("text", Value::Color(color)) => theme.colors.text = color.clone(),
+                ("accent", Value::Color(color)) => theme.colors.accent = color.clone(),
+                ("on-accent", Value::Color(color)) => theme.colors.on_accent = color.clone(),
+                ("panel", Value::Color(color)) => theme.colors.panel = color.clone(),
+                ("panel-border", Value::Color(color)) => theme.colors.panel_border = color.clone(),
+                ("scrim", Value::Color(color)) => theme.colors.scrim = color.clone(),
+                ("ghost", Value::Color(color)) => theme.colors.ghost = color.clone(),
+                ("corner-radius", Value::Number(n)) => theme.spacing.corner_radius = *n,
+                ("dialog-padding", Value::Number(n)) => theme.spacing.dialog_padding = *n,
+                ("menu-padding", Value::Number(n)) => theme.spacing.menu_padding = *n,
+                ("text-insets", Value::Number(n)) => theme.spacing.text_insets = *n,
+                ("font-size", Value::Number(n)) => theme.font = theme.font.clone().with_size(*n),
+                _ => {}
+            }
+        }
+
+        if self.targets(Target::Button) {
+            theme.button = Rc::new(self.button_sheet(theme.button.clone()));
+        }
+        if self.targets(Target::TextBox) {
+            theme.text_box = Rc::new(self.text_box_sheet(theme.text_box.clone()));
+        }
+    }
+
+    /// The button style sheet declared in the file, on top of `base`.
+    pub fn button_sheet(&self, base: Rc<dyn button::StyleSheet>) -> impl button::StyleSheet {
+        ButtonSheet {
+            base,
+            rules: self.rules_of(Target::Button),
+        }
+    }
+
+    /// The text box style sheet declared in the file, on top of `base`.
+    pub fn text_box_sheet(&self, base: Rc<dyn textbox::StyleSheet>) -> impl textbox::StyleSheet {
+        TextBoxSheet {
+            base,
+            rules: self.rules_of(Target::TextBox),
+        }
+    }
+
+    fn targets(&self, target: Target) -> bool {
+        self.rules.iter().any(|rule| rule.target == target)
+    }
+
+    fn rules_of(&self, target: Target) -> StyleFile {
+        let rules = self.rules.iter().filter(|rule| rule.target == target);
+        StyleFile {
+            rules: rules.cloned().collect(),
+        }
+    }
+
+    /// The declarations that apply to `target` in `state`, in the order they apply.
+    fn declarations(
+        &self,
+        target: Target,
+        state: Option<State>,
+    ) -> impl Iterator<Item = (&str, &Value)> {
+        let rules = self.rules.iter().filter(move |rule| rule.target == target);
+        let general = rules.clone().filter(|rule| rule.state.is_none());
+        let specific = rules.filter(move |rule| rule.state.is_some() && rule.state == state);
+        general
+            .chain(specific)
+            .flat_map(|rule| rule.declarations.iter())
+            .map(|(name, value)| (name.as_str(), value))
+    }
+}
+
+struct ButtonSheet {
+    base: Rc<dyn button::StyleSheet>,
+    rules: StyleFile,
+}
+
+impl ButtonSheet {
+    fn apply(&self, mut style: button::Style, state: State) -> button::Style {
+        for (name, value) in self.rules.declarations(Target::Button, Some(state)) {
+            if apply_border(&mut style.border, name, value) {
+                continue;
+            }
+            match (name, value) {
+                ("background", Value::Color(color)) => style.background = color.clone().into(),
+                ("text-color", Value::Color(color)) => style.text_color = color.clone(),
+                ("elevation", Value::Number(n)) => style.shadow = Shadow::elevation(*n),
+                _ => {}
+            }
+        }
+        style
+    }
+}
+
+impl button::StyleSheet for ButtonSheet {
+    fn eq(&self, other: &dyn button::StyleSheet) -> bool {
+        let other: &dyn Any = other;
+        match other.downcast_ref::<Self>() {
+            Some(other) => self.rules == other.rules && self.base.eq(other.base.as_ref()),
+            None => false,
+        }
+    }
+
+    fn enabled(&self) -> button::Style {
+        self.apply(self.base.enabled(), State::Enabled)
+    }
+
+    fn hovered(&self) -> button::Style {
+        self.apply(self.base.hovered(), State::Hovered)
+    }
+
+    fn pressed(&self) -> button::Style {
+        self.apply(self.base.pressed(), State::Pressed)
+    }
+
+    fn focused(&self) -> button::Style {
+        self.apply(self.base.focused(), State::Focused)
+    }
+
+    fn disabled(&self) -> button::Style {
+        self.apply(self.base.disabled(), State::Disabled)
+    }
+
+    fn pick_size(&self, bc: &BoxConstraints, required_size: Size) -> Size {
+        self.base.pick_size(bc, required_size)
+    }
+}
+
+struct TextBoxSheet {
+    base: Rc<dyn textbox::StyleSheet>,
+    rules: StyleFile,
+}
+
+impl TextBoxSheet {
+    fn apply(&self, mut style: textbox::Style, state: State) -> textbox::Style {
+        for (name, value) in self.rules.declarations(Target::TextBox, Some(state)) {
+            if apply_border(&mut style.border, name, value) {
+                continue;
+            }
+            match (name, value) {
+                ("background", Value::Color(color)) => style.background = color.clone(),
+                ("selection-color", Value::Color(color)) => style.selection_color = color.clone(),
+                ("cursor-color", Value::Color(color)) => style.cursor_color = color.clone(),
+                _ => {}
+            }
+        }
+        style
+    }
+}
+
+impl textbox::StyleSheet for TextBoxSheet {
+    fn eq(&self, other: &dyn textbox::StyleSheet) -> bool {
+        let other: &dyn Any = other;
+        match other.downcast_ref::<Self>() {
+            Some(other) => self.rules == other.rules && self.base.eq(other.base.as_ref()),
+            None => false,
+        }
+    }
+
+    fn enabled(&self) -> textbox::Style {
+        self.apply(self.base.enabled(), State::Enabled)
+    }
+
+    fn hovered(&self) -> textbox::Style {
+        self.apply(self.base.hovered(), State::Hovered)
+    }
+
+    fn pressed(&self) -> textbox::Style {
+        self.apply(self.base.pressed(), State::Pressed)
+    }
+
+    fn focused(&self) -> textbox::Style {
+        self.apply(self.base.focused(), State::Focused)
+    }
+
+    fn disabled(&self) -> textbox::Style {
+        self.apply(self.base.disabled(), State::Disabled)
+    }
+}
+
+struct Parser<'a> {
+    chars: std::iter::Peekable<Chars<'a>>,
+    line: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(text: &'a str) -> Self {
+        Parser {
+            chars: text.chars().peekable(),
+            line: 1,
+        }
+    }
+
+    fn error<T>(&self, message: impl Into<String>) -> Result<T, ParseError> {
+        Err(ParseError {
+            line: self.line,
+            message: message.into(),
+        })
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.chars.next()?;
+        if c == '\n' {
+            self.line += 1;
+        }
+        Some(c)
+    }
+
+    /// Skip whitespace and comments, and return the next character.
+    fn peek(&mut self) -> Result<Option<char>, ParseError> {
+        loop {
+            match self.chars.peek() {
+                Some(c) if c.is_whitespace() => {
+                    self.bump();
+                }
+                Some('/') => {
+                    self.bump();
+                    if self.bump() != Some('*') {
+                        return self.error("expected a comment after `/`");
+                    }
+                    let mut previous = ' ';
+                    loop {
+                        match self.bump() {
+                            Some('/') if previous == '*' => break,
+                            Some(c) => previous = c,
+                            None => return self.error("unterminated comment"),
+                        }
+                    }
+                }
+                c => return Ok(c.copied()),
+            }
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), ParseError> {
+        match self.peek()? {
+            Some(c) if c == expected => {
+                self.bump();
+                Ok(())
+            }
+            Some(c) => self.error(format!("expected `{}`, found `{}`", expected, c)),
+            None => self.error(format!(
+                "expected `{}`, found the end of the file",
+                expected
+            )),
+        }
+    }
+
+    /// A run of characters that can appear in names and values.
+    fn word(&mut self) -> Result<String, ParseError> {
+        self.peek()?;
+        let mut word = String::new();
+        while let Some(&c) = self.chars.peek() {
+            if !(c.is_alphanumeric() || matches!(c, '-' | '_' | '.' | '#')) {
+                break;
+            }
+            word.push(c);
+            self.bump();
+        }
+        if word.is_empty() {
+            return self.error("expected a name or value");
+        }
+        Ok(word)
+    }
+
+    fn parse(mut self) -> Result<StyleFile, ParseError> {
+        let mut rules = Vec::new();
+        while self.peek()?.is_some() {
+            rules.push(self.rule()?);
+        }
+        Ok(StyleFile { rules })
+    }
+
+    fn rule(&mut self) -> Result<Rule, ParseError> {
+        let name = self.word()?;
+        let target = match Target::from_name(&name) {
+            Some(target) => target,
+            None => return self.error(format!("unknown selector `{}`", name)),
+        };
+
+        let mut state = None;
+        if self.peek()? == Some(':') {
+            self.bump();
+            if target == Target::Theme {
+                return self.error("the theme has no states");
+            }
+            state = Some(match self.word()?.as_str() {
+                "hovered" => State::Hovered,
+                "pressed" => State::Pressed,
+                "focused" => State::Focused,
+                "disabled" => State::Disabled,
+                other => return self.error(format!("unknown state `{}`", other)),
+            });
+        }
+
+        self.expect('{')?;
+        let mut declarations = Vec::new();
+        while self.peek()? != Some('}') {
+            let property = self.word()?;
+            let kind = match target.property(&property) {
+                Some(kind) => kind,
+                None => {
+                    return self.error(format!("unknown property `{}` of `{}`", property, name))
+                }
+            };
+            self.expect(':')?;
+            let value = self.value(kind)?;
+            declarations.push((property, value));
+            match self.peek()? {
+                Some('}') => break,
+                _ => self.expect(';')?,
+            }
+        }
+        self.expect('}')?;
+
+        Ok(Rule {
+            target,
+            state,
+            declarations,
+        })
+    }
+
+    fn value(&mut self, kind: Kind) -> Result<Value, ParseError> {
+        let word = self.word()?;
+        let value = match kind {
+            Kind::Color => parse_color(&word).map(Value::Color),
+            Kind::Number => word.parse().ok().map(Value::Number),
+        };
+        match value {
+            Some(value) => Ok(value),
+            None => {
+                let expected = match kind {
+                    Kind::Color => "a color",
+                    Kind::Number => "a number",
+                };
+                self.error(format!("expected {}, found `{}`", expected, word))
+            }
+        }
+    }
+}
+
+fn parse_color(word: &str) -> Option<Color> {
+    let hex = word.strip_prefix('#')?;
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let digits: Vec<u8> = hex
+        .bytes()
+        .map(|c| (c as char).to_digit(16).unwrap() as u8)
+        .collect();
+    let channels: Vec<u8> = match digits.len() {
+        3 | 4 => digits.iter().map(|d| d * 17).collect(),
+        6 | 8 => digits
+            .chunks(2)
+            .map(|pair| pair[0] * 16 + pair[1])
+            .collect(),
+        _ => return None,
+    };
+    let alpha = channels.get(3).copied().unwrap_or(0xff);
+    Some(Color::rgba8(channels[0], channels[1], channels[2], alpha))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{kurbo::Insets, widgets::button::StyleSheet};
+
+    #[test]
+    fn parse_and_apply() {
+        let file = StyleFile::parse(
+            "/* A comment */
+            theme { accent: #f00; menu-padding: 6 }
+            button { border-radius: 5; border-bottom-width: 3 }
+            button:hovered { background: #00ff0080; }
+            text-box { border-width: 0; border-bottom-width: 2; border-left-color: #fff }",
+        )
+        .unwrap();
+
+        let mut theme = Theme::default();
+        file.apply(&mut theme);
+        assert_eq!(theme.colors.accent, Color::rgb8(0xff, 0, 0));
+        assert_eq!(theme.spacing.menu_padding, 6.0);
+        assert_eq!(
+            theme.button.enabled().border.radii,
+            CornerRadii::uniform(5.0)
+        );
+        assert_eq!(theme.button.hovered().border.radii.top_left, 5.0);
+        assert_eq!(theme.button.hovered().border.bottom.width, 3.0);
+        let text_box = theme.text_box.enabled().border;
+        assert_eq!(text_box.widths(), Insets::new(0.0, 0.0, 0.0, 2.0));
+        assert_eq!(text_box.left.color, Color::WHITE);
+        assert_eq!(
+            theme.button.hovered().background,
+            Color::rgba8(0, 0xff, 0, 0x80).into()
+        );
+        assert_eq!(
+            theme.button.enabled().background,
+            button::style::Default.enabled().background
+        );
+        assert!(theme != Theme::default());
+    }
+
+    #[test]
+    fn parse_errors() {
+        let error = StyleFile::parse("button {\n  margin: 4;\n}").unwrap_err();
+        assert_eq!(error.line, 2);
+        assert!(StyleFile::parse("theme:hovered {}").is_err());
+        assert!(StyleFile::parse("button { background: 4 }").is_err());
+        assert!(StyleFile::parse("button { border-width: 1").is_err());
+        assert!(StyleFile::parse("/* open").is_err());
+        assert!(StyleFile::parse("button { border-middle-width: 1 }").is_err());
+        assert!(StyleFile::parse("theme { border-left-width: 1 }").is_err());
+    }
+}
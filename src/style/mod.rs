@@ -0,0 +1,93 @@
+//! The styling of the built-in widgets.
+//!
+//! Every widget with a configurable look has a `style` module with a `Style`,
+//! which describes its look in one [`State`], and a `StyleSheet`, which picks
+//! the `Style` for each state. A style sheet set on a widget overrides the one
+//! of the [`Theme`].
+//!
+//! Style sheets can also be loaded from a [`StyleFile`], [`use_style_file`]
+//! applies it again whenever it changes.
+//!
+//! [`Theme`]: crate::theme::Theme
+
+pub mod file;
+pub use file::{ParseError, StyleFile};
+
+pub mod reload;
+pub use reload::use_style_file;
+
+pub mod background;
+pub use background::{Background, ImageRepeat};
+
+pub mod border;
+pub use border::{Border, BorderSide, CornerRadii};
+
+pub mod shadow;
+pub use shadow::Shadow;
+
+/// The interaction state a widget is drawn in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum State {
+    Enabled,
+    Hovered,
+    /// The pointer was pressed on the widget and is still above it.
+    Pressed,
+    /// The widget has the keyboard focus.
+    Focused,
+    Disabled,
+}
+
+impl State {
+    /// The state of a widget, from the flags of its context.
+    ///
+    /// When several apply, disabled wins over pressed, focused and hovered,
+    /// in that order. An active widget only looks pressed while it is hovered.
+    pub fn new(disabled: bool, hovered: bool, active: bool, focused: bool) -> Self {
+        match (disabled, hovered, active, focused) {
+            (true, _, _, _) => State::Disabled,
+            (false, true, true, _) => State::Pressed,
+            (false, _, _, true) => State::Focused,
+            (false, true, _, false) => State::Hovered,
+            (false, false, _, false) => State::Enabled,
+        }
+    }
+}
+
+/// Implement comparison and conversion for boxed style sheets.
+///
+/// Style sheets are compared with their `eq` method, so properties holding
+/// one can derive `PartialEq`.
+macro_rules! impl_style_sheet {
+    ($sheet:ident) => {
+        impl PartialEq for Box<dyn $sheet> {
+            fn eq(&self, other: &Self) -> bool {
+                self.as_ref().eq(other.as_ref())
+            }
+        }
+
+        impl<T> From<T> for Box<dyn $sheet>
+        where
+            T: 'static + $sheet,
+        {
+            fn from(style: T) -> Self {
+                Box::new(style)
+            }
+        }
+    };
+}
+
+pub(crate) use impl_style_sheet;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn state_precedence() {
+        assert_eq!(State::new(true, true, true, true), State::Disabled);
+        assert_eq!(State::new(false, true, true, true), State::Pressed);
+        assert_eq!(State::new(false, false, true, true), State::Focused);
+        assert_eq!(State::new(false, true, false, false), State::Hovered);
+        assert_eq!(State::new(false, false, true, false), State::Enabled);
+    }
+}
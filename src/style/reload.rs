@@ -0,0 +1,97 @@
+use crate::{style::StyleFile, task::Task, theme::Theme, ui::Ui};
+use std::{
+    fs, mem,
+    panic::Location,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+    time::{Duration, SystemTime},
+};
+
+/// How often the style file is checked for changes.
+const POLL_INTERVAL: Duration = Duration::from_millis(300);
+
+/// A loaded style file, and the task waiting for it to change.
+struct Watched {
+    path: PathBuf,
+    file: StyleFile,
+    changed: Task<()>,
+    stop: Arc<AtomicBool>,
+}
+
+impl Drop for Watched {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Provide `base` with the styles of the file at `path` applied to `content`.
+///
+/// The file is watched and applied again whenever it changes, which lays out
+/// and paints the affected widgets again. If the file can not be read or
+/// parsed, the error is logged and the previous styles are kept.
+///
+/// See [`StyleFile`] for the format.
+#[track_caller]
+pub fn use_style_file(
+    ui: &mut Ui,
+    path: impl Into<PathBuf>,
+    base: Theme,
+    content: impl FnOnce(&mut Ui),
+) {
+    let caller = Location::caller().into();
+    let path = path.into();
+    ui.state_node(
+        caller,
+        || None,
+        |ui, watched: &mut Option<Watched>| {
+            let reload = match watched {
+                Some(watched) => watched.path != path || watched.changed.take().is_some(),
+                None => true,
+            };
+            if reload {
+                let loaded = modified(&path);
+                let file = match StyleFile::load(&path) {
+                    Ok(file) => file,
+                    Err(err) => {
+                        log::warn!("Failed to load styles {}: {}", path.display(), err);
+                        match watched.take() {
+                            Some(mut watched) if watched.path == path => {
+                                mem::take(&mut watched.file)
+                            }
+                            _ => StyleFile::default(),
+                        }
+                    }
+                };
+                let stop = Arc::new(AtomicBool::new(false));
+                let changed = ui.spawn(wait_for_change(path.clone(), loaded, stop.clone()));
+                *watched = Some(Watched {
+                    path,
+                    file,
+                    changed,
+                    stop,
+                });
+            }
+
+            let mut theme = base;
+            if let Some(watched) = watched {
+                watched.file.apply(&mut theme);
+            }
+            ui.provide(theme, content);
+        },
+    )
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+/// Completes once the file at `path` was modified after `loaded`, or `stop` was set.
+async fn wait_for_change(path: PathBuf, loaded: Option<SystemTime>, stop: Arc<AtomicBool>) {
+    while modified(&path) == loaded && !stop.load(Ordering::Relaxed) {
+        thread::sleep(POLL_INTERVAL);
+    }
+}
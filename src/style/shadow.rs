@@ -0,0 +1,100 @@
+//! Blurred drop shadows.
+
+use crate::{
+    context::PaintCtx,
+    kurbo::{Insets, Rect, Vec2},
+    piet::{Color, RenderContext},
+};
+
+/// How far the blur reaches, in multiples of the blur radius.
+const BLUR_EXTENT: f64 = 2.5;
+
+/// A blurred drop shadow below a rectangular widget.
+///
+/// Most shadows are made with [`Shadow::elevation`], which makes widgets look
+/// like they float the given distance above the window.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Shadow {
+    /// How far the shadow is moved from the widget.
+    pub offset: Vec2,
+    /// The standard deviation of the blur.
+    pub blur_radius: f64,
+    pub color: Color,
+}
+
+impl Shadow {
+    /// No shadow at all.
+    pub const NONE: Shadow = Shadow {
+        offset: Vec2::ZERO,
+        blur_radius: 0.0,
+        color: Color::rgba8(0, 0, 0, 0),
+    };
+
+    /// The shadow of a widget floating `elevation` pixels above the window,
+    /// lit from above.
+    pub fn elevation(elevation: f64) -> Self {
+        if elevation <= 0.0 {
+            return Shadow::NONE;
+        }
+        Shadow {
+            offset: Vec2::new(0.0, elevation / 2.0),
+            blur_radius: elevation,
+            color: Color::rgba8(0, 0, 0, 0x60),
+        }
+    }
+
+    /// Whether painting the shadow has no effect.
+    pub fn is_none(&self) -> bool {
+        let (_, _, _, alpha) = self.color.as_rgba8();
+        alpha == 0
+    }
+
+    /// The area the shadow of `rect` covers.
+    pub fn bounds(&self, rect: Rect) -> Rect {
+        if self.is_none() {
+            return rect;
+        }
+        let blur = self.blur_radius * BLUR_EXTENT;
+        (rect + self.offset).inset(blur).union(rect)
+    }
+
+    /// The paint insets a widget needs to paint this shadow below its
+    /// layout rect `rect`.
+    pub fn insets(&self, rect: Rect) -> Insets {
+        self.bounds(rect) - rect
+    }
+
+    /// Paint the shadow of `rect`.
+    pub fn paint(&self, ctx: &mut PaintCtx, rect: Rect) {
+        if self.is_none() {
+            return;
+        }
+        ctx.blurred_rect(rect + self.offset, self.blur_radius, &self.color);
+    }
+}
+
+impl Default for Shadow {
+    fn default() -> Self {
+        Shadow::NONE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shadow_insets() {
+        let rect = Rect::new(0.0, 0.0, 10.0, 10.0);
+        assert_eq!(Shadow::NONE.insets(rect), Insets::ZERO);
+        assert_eq!(Shadow::elevation(0.0), Shadow::NONE);
+
+        let shadow = Shadow {
+            offset: Vec2::new(0.0, 4.0),
+            blur_radius: 2.0,
+            color: Color::BLACK,
+        };
+        // The blur reaches 5 pixels, the offset moves it down by 4.
+        assert_eq!(shadow.insets(rect), Insets::new(5.0, 1.0, 5.0, 9.0));
+    }
+}
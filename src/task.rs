@@ -0,0 +1,104 @@
+//! Running work off the UI thread.
+
+use druid::{ExtEventSink, Selector, Target, WindowId};
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Wake, Waker},
+    thread::{self, Thread},
+};
+
+/// Sent to the window when a task finished, to rebuild it.
+pub(crate) const TASK_FINISHED: Selector = Selector::new("coat.task.finished");
+
+/// A handle to a spawned task.
+///
+/// Created by [`Ui::spawn`].
+///
+/// [`Ui::spawn`]: crate::ui::Ui::spawn
+pub struct Task<T> {
+    output: Arc<Mutex<Option<T>>>,
+}
+
+impl<T: Send + 'static> Task<T> {
+    /// Run `future` to completion on a thread of its own.
+    ///
+    /// Blocking I/O in the future does not block the UI.
+    /// Once it finished, the window is rebuilt.
+    pub(crate) fn spawn<F>(future: F, sink: ExtEventSink, window_id: WindowId) -> Self
+    where
+        F: Future<Output = T> + Send + 'static,
+    {
+        let output = Arc::new(Mutex::new(None));
+        let task_output = output.clone();
+        thread::spawn(move || {
+            let result = block_on(future);
+            *task_output.lock().unwrap() = Some(result);
+            if sink
+                .submit_command(TASK_FINISHED, (), Target::Window(window_id))
+                .is_err()
+            {
+                log::warn!("Task finished after its window was closed.");
+            }
+        });
+        Task { output }
+    }
+}
+
+impl<T> Task<T> {
+    /// Take the output of the task, if it finished.
+    ///
+    /// Returns `None` again after the output was taken.
+    pub fn take(&self) -> Option<T> {
+        self.output.lock().unwrap().take()
+    }
+}
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Poll `future` on the current thread until it is ready.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = Box::pin(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        match Pin::as_mut(&mut future).poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Completes on the second poll, after waking from another thread.
+    struct WakeOnce(bool);
+
+    impl Future for WakeOnce {
+        type Output = u32;
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<u32> {
+            if self.0 {
+                return Poll::Ready(42);
+            }
+            self.0 = true;
+            let waker = cx.waker().clone();
+            thread::spawn(move || waker.wake());
+            Poll::Pending
+        }
+    }
+
+    #[test]
+    fn block_on_wakes_up() {
+        assert_eq!(block_on(WakeOnce(false)), 42);
+    }
+}
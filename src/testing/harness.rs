@@ -0,0 +1,445 @@
+use super::{find_id, Snapshot};
+use crate::{
+    environment::Environment,
+    event::{Event, MouseButton, MouseButtons, MouseEvent},
+    id::ChildId,
+    kurbo::{Point, Rect, Size, Vec2},
+    piet::{Device, ImageFormat, PietText, RenderContext},
+    pump::{Platform, Requests, WindowTree},
+    recording::Recording,
+    state::Session,
+    text::Journals,
+    theme::Theme,
+    tree::{Child, Children},
+    ui::Ui,
+    widgets::Label,
+    BoxConstraints,
+};
+use druid::{
+    keyboard_types::KeyState, widget::SizedBox, AppLauncher, Command, KbKey, KeyEvent, Modifiers,
+    Region, TimerToken, WindowDesc, WindowHandle, WindowId,
+};
+use std::{
+    cell::RefCell,
+    path::PathBuf,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+/// The window size of a new [`Harness`].
+const DEFAULT_WINDOW_SIZE: Size = Size::new(800.0, 600.0);
+
+/// Runs an app without opening a window, for tests.
+///
+/// The harness builds and lays out the tree like a window would, after
+/// each synthetic event and whenever the virtual clock is advanced,
+/// and paints it into a [`Snapshot`] on request. The tree reads the time
+/// of the virtual clock, so timers, animations and debouncing can be
+/// driven with [`Harness::advance`].
+///
+/// ```ignore
+/// let mut harness = Harness::new(counter);
+/// let button = testing::assert_exists::<ButtonObject>(harness.tree(), testing::with_label("Add"));
+/// harness.click_on(button);
+/// assert_eq!(harness.label_texts(), ["Add", "1"]);
+/// ```
+pub struct Harness {
+    tree: WindowTree,
+    platform: Platform,
+    env: druid::Env,
+    /// The requested timers and the time at which they fire.
+    timers: Vec<(TimerToken, Instant)>,
+    /// The next build pass requested by build code.
+    build_deadline: Option<Instant>,
+    /// Commands submitted by the tree, see [`Harness::take_commands`].
+    commands: Vec<Command>,
+    /// The parts of the window to repaint, see [`Harness::take_damage`].
+    damage: Region,
+}
+
+impl Harness {
+    /// Build and lay out `app` in a window of the default size.
+    pub fn new(app: impl FnMut(&mut Ui) + 'static) -> Self {
+        Harness::with_window(app, None, None)
+    }
+
+    /// Like [`Harness::new`], with [`Persistent`] state stored in `session`,
+    /// like [`App::session`] does.
+    ///
+    /// [`Persistent`]: crate::state::Persistent
+    /// [`App::session`]: crate::app::App::session
+    pub fn with_session(app: impl FnMut(&mut Ui) + 'static, session: Rc<RefCell<Session>>) -> Self {
+        Harness::with_window(app, Some(session), None)
+    }
+
+    /// Like [`Harness::new`], with the edit journals of text widgets stored
+    /// in the directory at `dir`, like [`App::journal_dir`] does.
+    ///
+    /// [`App::journal_dir`]: crate::app::App::journal_dir
+    pub fn with_journals(app: impl FnMut(&mut Ui) + 'static, dir: impl Into<PathBuf>) -> Self {
+        let journals = Rc::new(RefCell::new(Journals::new(dir)));
+        Harness::with_window(app, None, Some(journals))
+    }
+
+    fn with_window(
+        app: impl FnMut(&mut Ui) + 'static,
+        session: Option<Rc<RefCell<Session>>>,
+        journals: Option<Rc<RefCell<Journals>>>,
+    ) -> Self {
+        // Tasks can only send their results to a launcher, which is never launched here.
+        let launcher = AppLauncher::with_window(WindowDesc::new(SizedBox::<()>::empty));
+        let theme = Theme::default();
+        let env = druid::Env::default()
+            .adding(druid::theme::UI_FONT, theme.font.clone())
+            .adding(druid::theme::LABEL_COLOR, theme.colors.text.clone());
+        let environment = Environment::default().with(Box::new(theme));
+        let mut tree = WindowTree::new(app, environment, false, session, journals, false);
+        tree.window_size = DEFAULT_WINDOW_SIZE;
+        let mut harness = Harness {
+            tree,
+            platform: Platform::new(
+                launcher.get_external_handle(),
+                WindowId::next(),
+                WindowHandle::default(),
+                PietText::new(),
+            ),
+            env,
+            timers: Vec::new(),
+            build_deadline: None,
+            commands: Vec::new(),
+            damage: Region::EMPTY,
+        };
+        let requests = harness.tree.build(&harness.platform);
+        harness.finish_pass(requests);
+        harness.layout();
+        harness
+    }
+
+    /// The tree, to inspect it with the assertions in [`testing`](super).
+    pub fn tree(&mut self) -> &mut Children {
+        &mut self.tree.root
+    }
+
+    /// The layout rect of the widget with the given id, in window coordinates.
+    pub fn window_rect(&mut self, id: ChildId) -> Rect {
+        let child = find_id(&mut self.tree.root, id);
+        Rect::from_origin_size(child.state.window_origin(), child.state.size())
+    }
+
+    /// The texts of all labels, depth first.
+    pub fn label_texts(&mut self) -> Vec<String> {
+        fn collect(children: &mut Children, texts: &mut Vec<String>) {
+            for child in &mut children.renders {
+                if let Some(label) = child.object.as_any().downcast_mut::<Label>() {
+                    texts.push(label.text().to_string());
+                }
+                collect(&mut child.children, texts);
+            }
+        }
+        let mut texts = Vec::new();
+        collect(&mut self.tree.root, &mut texts);
+        texts
+    }
+
+    /// The id of the focused widget.
+    pub fn focused(&self) -> Option<ChildId> {
+        self.tree.focus_widget
+    }
+
+    /// Take the commands the tree submitted to the window or the app.
+    pub fn take_commands(&mut self) -> Vec<Command> {
+        std::mem::take(&mut self.commands)
+    }
+
+    /// Take the parts of the window the tree asked to repaint, in window
+    /// coordinates.
+    ///
+    /// A window only repaints these, unless it is laid out again.
+    pub fn take_damage(&mut self) -> Region {
+        std::mem::replace(&mut self.damage, Region::EMPTY)
+    }
+
+    /// Switch to touch input, which expands small hit targets.
+    pub fn set_touch_mode(&mut self, touch_mode: bool) {
+        self.tree.touch_mode = touch_mode;
+    }
+
+    /// Resize the window and lay it out again.
+    pub fn resize(&mut self, size: Size) {
+        self.tree.window_size = size;
+        self.root().state.needs_layout = true;
+        self.event(Event::WindowSize(size));
+    }
+
+    /// Build the tree again, like when a task finished.
+    pub fn rebuild(&mut self) {
+        self.root().state.request_update = true;
+        let requests = self.tree.build(&self.platform);
+        self.finish_pass(requests);
+        self.layout();
+    }
+
+    /// Move the virtual clock forward, firing the timers that are due in order.
+    ///
+    /// Afterwards a single animation frame is delivered if one was requested.
+    pub fn advance(&mut self, duration: Duration) {
+        let end = self.platform.now + duration;
+        loop {
+            let due = self
+                .timers
+                .iter()
+                .enumerate()
+                .filter(|(_, (_, deadline))| *deadline <= end)
+                .min_by_key(|(_, (_, deadline))| *deadline)
+                .map(|(ix, _)| ix);
+            match due {
+                Some(ix) => {
+                    let (token, deadline) = self.timers.remove(ix);
+                    self.platform.now = deadline;
+                    self.event(Event::Timer(token));
+                }
+                None => break,
+            }
+        }
+        self.platform.now = end;
+
+        if let Some(deadline) = self.build_deadline {
+            if deadline <= end {
+                self.build_deadline = None;
+                self.rebuild();
+            }
+        }
+        if self.tree.build_anim_frame || self.root().state.request_anim {
+            self.event(Event::AnimFrame(duration.as_nanos() as u64));
+        }
+    }
+
+    /// Deliver `event` to the tree, then build and lay it out if requested.
+    pub fn event(&mut self, event: Event) {
+        let requests = self.tree.pump(&self.platform, &event, Vec::new());
+        self.finish_pass(requests);
+        self.layout();
+    }
+
+    /// Move the mouse to `pos`, in window coordinates.
+    pub fn mouse_move(&mut self, pos: Point) {
+        let buttons = match self.root().has_active() {
+            true => MouseButtons::new().with(MouseButton::Left),
+            false => MouseButtons::new(),
+        };
+        self.event(Event::MouseMove(mouse_event(
+            pos,
+            buttons,
+            MouseButton::None,
+        )));
+    }
+    /// Press and release the left mouse button at `pos`, in window coordinates.
+    pub fn click(&mut self, pos: Point) {
+        self.mouse_down(pos);
+        self.mouse_up(pos);
+    }
+
+    /// Move the mouse to `pos` and press the left mouse button, to hold it down.
+    pub fn mouse_down(&mut self, pos: Point) {
+        self.mouse_move(pos);
+        let pressed = MouseButtons::new().with(MouseButton::Left);
+        self.event(Event::MouseDown(mouse_event(
+            pos,
+            pressed,
+            MouseButton::Left,
+        )));
+    }
+
+    /// Release the left mouse button at `pos`.
+    pub fn mouse_up(&mut self, pos: Point) {
+        let released = MouseButtons::new();
+        self.event(Event::MouseUp(mouse_event(
+            pos,
+            released,
+            MouseButton::Left,
+        )));
+    }
+
+    /// Click the center of the widget with the given id.
+    pub fn click_on(&mut self, id: ChildId) {
+        let center = self.window_rect(id).center();
+        self.click(center);
+    }
+
+    /// Scroll the mouse wheel at `pos` by `delta` pixels, like a [normalized] wheel event.
+    ///
+    /// [normalized]: crate::wheel
+    pub fn wheel(&mut self, pos: Point, delta: Vec2) {
+        let mut wheel = mouse_event(pos, MouseButtons::new(), MouseButton::None);
+        wheel.wheel_delta = delta;
+        self.event(Event::Wheel(wheel));
+    }
+
+    /// Press and release `key`.
+    pub fn key(&mut self, key: KbKey) {
+        self.key_with_mods(Modifiers::default(), key);
+    }
+
+    /// Press and release `key` while holding `mods`, for shortcuts.
+    pub fn key_with_mods(&mut self, mods: Modifiers, key: KbKey) {
+        let down = KeyEvent::for_test(mods, key);
+        let mut up = down.clone();
+        up.state = KeyState::Up;
+        self.event(Event::KeyDown(down));
+        self.event(Event::KeyUp(up));
+    }
+
+    /// Type `text` one character at a time.
+    pub fn type_text(&mut self, text: &str) {
+        for c in text.chars() {
+            self.key(KbKey::Character(c.to_string()));
+        }
+    }
+
+    /// Deliver the events of `recording`, advancing the virtual clock between them.
+    ///
+    /// Recorded window resizes resize the harness.
+    pub fn replay(&mut self, recording: &Recording) {
+        let start = self.platform.now;
+        for (time, event) in recording.events() {
+            let elapsed = self.platform.now - start;
+            if *time > elapsed {
+                self.advance(*time - elapsed);
+            }
+            match event {
+                Event::WindowSize(size) => self.resize(*size),
+                event => self.event(event.clone()),
+            }
+        }
+    }
+
+    /// Paint the whole window into an offscreen bitmap.
+    ///
+    /// Compare it to a reference image with [`assert_snapshot`](super::assert_snapshot).
+    pub fn snapshot(&mut self) -> Snapshot {
+        let mut device = Device::new().expect("Failed to create a device");
+        let window_size = self.tree.window_size;
+        let width = window_size.width.ceil() as usize;
+        let height = window_size.height.ceil() as usize;
+        let mut target = device
+            .bitmap_target(width, height, 1.0)
+            .expect("Failed to create a bitmap");
+        let mut render_ctx = target.render_context();
+
+        let viewport = window_size.to_rect();
+        self.tree.paint(
+            &self.platform,
+            &mut render_ctx,
+            viewport.into(),
+            0,
+            &self.env,
+        );
+        render_ctx.finish().expect("Failed to paint");
+        drop(render_ctx);
+
+        let mut pixels = vec![0; width * height * 4];
+        target
+            .copy_raw_pixels(ImageFormat::RgbaPremul, &mut pixels)
+            .expect("Failed to read the bitmap");
+        Snapshot::new(width, height, pixels)
+    }
+
+    fn root(&mut self) -> &mut Child {
+        self.tree.root()
+    }
+
+    fn layout(&mut self) {
+        if !self.root().state.needs_layout {
+            return;
+        }
+        let bc = BoxConstraints::tight(self.tree.window_size);
+        let (_, requests) = self.tree.layout(&self.platform, &bc, &self.env);
+        self.finish_pass(requests);
+    }
+
+    fn finish_pass(&mut self, requests: Requests) {
+        self.commands.extend(requests.commands);
+        let now = self.platform.now;
+        self.timers.extend(
+            requests
+                .timers
+                .into_iter()
+                .map(|(token, delay)| (token, now + delay)),
+        );
+        self.build_deadline = min_deadline(self.build_deadline, requests.build_deadline);
+        let invalid = std::mem::replace(&mut self.root().state.invalid, Region::EMPTY);
+        self.damage.union_with(&invalid);
+    }
+}
+
+fn mouse_event(pos: Point, buttons: MouseButtons, button: MouseButton) -> MouseEvent {
+    MouseEvent {
+        pos,
+        window_pos: pos,
+        buttons,
+        mods: Modifiers::default(),
+        count: if button == MouseButton::None { 0 } else { 1 },
+        focus: false,
+        button,
+        wheel_delta: Vec2::ZERO,
+    }
+}
+
+fn min_deadline(a: Option<Instant>, b: Option<Instant>) -> Option<Instant> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        animation::Easing,
+        state::Mutable,
+        testing,
+        widgets::{button::ButtonObject, Button},
+    };
+
+    #[test]
+    fn click_button() {
+        let mut harness = Harness::new(|ui| {
+            Mutable::new().use_in(ui, |ui, count: &mut usize| {
+                if Button::new().labeled(ui, "Add") {
+                    *count += 1;
+                }
+                Label::new(count.to_string()).build(ui);
+            });
+        });
+        assert_eq!(harness.label_texts(), ["Add", "0"]);
+
+        let button =
+            testing::assert_exists::<ButtonObject>(harness.tree(), testing::with_label("Add"));
+        harness.click_on(button);
+        assert_eq!(harness.label_texts(), ["Add", "1"]);
+        assert_eq!(harness.snapshot().width(), 800);
+    }
+
+    #[test]
+    fn advance_drives_interval_and_animate() {
+        let mut harness = Harness::new(|ui| {
+            Mutable::new().use_in(ui, |ui, ticks: &mut usize| {
+                if ui.interval(Duration::from_secs(10)) {
+                    *ticks += 1;
+                }
+                let target = if *ticks > 0 { 100.0 } else { 0.0 };
+                let value = ui.animate(target, Duration::from_secs(1), Easing::Linear);
+                Label::new(format!("{} {}", ticks, value)).build(ui);
+            });
+        });
+        assert_eq!(harness.label_texts(), ["0 0"]);
+
+        harness.advance(Duration::from_secs(10));
+        assert_eq!(harness.label_texts(), ["1 0"]);
+        harness.advance(Duration::from_millis(500));
+        assert_eq!(harness.label_texts(), ["1 50"]);
+        harness.advance(Duration::from_millis(500));
+        assert_eq!(harness.label_texts(), ["1 100"]);
+    }
+}
@@ -0,0 +1,148 @@
+//! Assertions on the widget tree, for tests.
+//!
+//! The assertions search the whole tree below the given children
+//! and panic with a description of the failure. A [`Harness`] runs
+//! an app without a window and provides its tree, and its painted
+//! [`Snapshot`]s can be compared to reference images with [`assert_snapshot`].
+
+use crate::{
+    id::ChildId,
+    kurbo::Rect,
+    tree::{Child, Children},
+    widgets::Label,
+};
+use std::any::{type_name, Any};
+
+mod harness;
+#[cfg(test)]
+mod probe;
+mod snapshot;
+
+pub use harness::Harness;
+#[cfg(test)]
+pub(crate) use probe::{Log, Probe, Record};
+pub use snapshot::{assert_snapshot, Difference, Snapshot, UPDATE_SNAPSHOTS_VAR};
+
+/// A condition on a widget, used to find it in the tree.
+pub struct Matcher {
+    description: String,
+    matches: Box<dyn Fn(&mut Child) -> bool>,
+}
+
+impl Matcher {
+    pub fn new(
+        description: impl Into<String>,
+        matches: impl Fn(&mut Child) -> bool + 'static,
+    ) -> Self {
+        Matcher {
+            description: description.into(),
+            matches: Box::new(matches),
+        }
+    }
+}
+
+/// Matches every widget.
+pub fn any() -> Matcher {
+    Matcher::new("any", |_| true)
+}
+
+/// Matches widgets that are, or contain, a [`Label`] with the given text.
+pub fn with_label(text: impl Into<String>) -> Matcher {
+    let text = text.into();
+    let description = format!("with label {:?}", text);
+    Matcher::new(description, move |child| has_label(child, &text))
+}
+
+fn has_label(child: &mut Child, text: &str) -> bool {
+    if let Some(label) = child.object.as_any().downcast_mut::<Label>() {
+        if label.text() == text {
+            return true;
+        }
+    }
+    child
+        .children
+        .renders
+        .iter_mut()
+        .any(|child| has_label(child, text))
+}
+
+/// Find the first widget of type `R` for which `matcher` matches, depth first.
+pub fn find<R: Any>(tree: &mut Children, matcher: &Matcher) -> Option<ChildId> {
+    find_child(tree, &mut |child| {
+        child.object.as_any().is::<R>() && (matcher.matches)(child)
+    })
+    .map(|child| child.state.id)
+}
+
+fn find_child<'a>(
+    tree: &'a mut Children,
+    predicate: &mut dyn FnMut(&mut Child) -> bool,
+) -> Option<&'a mut Child> {
+    for child in &mut tree.renders {
+        if predicate(child) {
+            return Some(child);
+        }
+        if let Some(found) = find_child(&mut child.children, predicate) {
+            return Some(found);
+        }
+    }
+    None
+}
+
+fn find_id(tree: &mut Children, id: ChildId) -> &mut Child {
+    match find_child(tree, &mut |child| child.state.id == id) {
+        Some(child) => child,
+        None => panic!("No widget with id {:?} in the tree", id),
+    }
+}
+
+/// Assert that a widget of type `R` for which `matcher` matches exists.
+///
+/// Returns the id of the first one.
+#[track_caller]
+pub fn assert_exists<R: Any>(tree: &mut Children, matcher: Matcher) -> ChildId {
+    match find::<R>(tree, &matcher) {
+        Some(id) => id,
+        None => panic!(
+            "No {} {} in the tree",
+            type_name::<R>(),
+            matcher.description
+        ),
+    }
+}
+
+/// Assert that no widget of type `R` for which `matcher` matches exists.
+#[track_caller]
+pub fn assert_missing<R: Any>(tree: &mut Children, matcher: Matcher) {
+    if let Some(id) = find::<R>(tree, &matcher) {
+        panic!(
+            "Unexpected {} {} in the tree, with id {:?}",
+            type_name::<R>(),
+            matcher.description,
+            id
+        );
+    }
+}
+
+/// Assert that the widget with the given id is focused.
+#[track_caller]
+pub fn assert_focused(tree: &mut Children, id: ChildId) {
+    let child = find_id(tree, id);
+    // The focused widget ends the focus path.
+    let is_focused =
+        child.state.has_focus && !child.children.renders.iter().any(|c| c.state.has_focus);
+    assert!(is_focused, "The widget with id {:?} is not focused", id);
+}
+
+/// Assert that the widget with the given id has the given layout rect,
+/// relative to its parent.
+#[track_caller]
+pub fn assert_layout_rect(tree: &mut Children, id: ChildId, rect: Rect) {
+    let child = find_id(tree, id);
+    assert_eq!(
+        child.layout_rect(),
+        rect,
+        "Unexpected layout rect of the widget with id {:?}",
+        id
+    );
+}
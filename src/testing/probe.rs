@@ -0,0 +1,185 @@
+//! A widget that records what the tree does to it, for the tests of the tree.
+
+use super::{find, Matcher};
+use crate::{
+    id::ChildId,
+    kurbo::{Point, Vec2},
+    object::prelude::*,
+    piet::Color,
+};
+use std::{cell::RefCell, rc::Rc};
+
+/// What happened to a [`Probe`].
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Record {
+    LifeCycle(LifeCycle),
+    Layout,
+    /// The probe was clicked, and none of its children handled the click.
+    Click,
+}
+
+/// The records of all probes sharing it, with the names of the probes.
+pub(crate) type Log = Rc<RefCell<Vec<(&'static str, Record)>>>;
+
+/// A widget of a fixed size, which stacks its children and records its
+/// life cycle events, layout passes and the clicks it handles in a [`Log`].
+pub(crate) struct Probe {
+    name: &'static str,
+    log: Log,
+    size: Size,
+    z_index: u32,
+    round: bool,
+    color: Option<Color>,
+}
+
+impl Properties for Probe {
+    type Object = ProbeObject;
+}
+
+impl Probe {
+    pub(crate) fn new(name: &'static str, log: &Log) -> Self {
+        Probe {
+            name,
+            log: log.clone(),
+            size: Size::new(100.0, 100.0),
+            z_index: 0,
+            round: false,
+            color: None,
+        }
+    }
+
+    /// The size of the probe, whatever the constraints are.
+    pub(crate) fn size(mut self, width: f64, height: f64) -> Self {
+        self.size = Size::new(width, height);
+        self
+    }
+
+    pub(crate) fn z_index(mut self, z_index: u32) -> Self {
+        self.z_index = z_index;
+        self
+    }
+
+    /// Only hit the circle within the layout rect.
+    pub(crate) fn round(mut self) -> Self {
+        self.round = true;
+        self
+    }
+
+    /// Fill the layout rect with `color`, below the children.
+    pub(crate) fn color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    #[track_caller]
+    pub(crate) fn build(self, ui: &mut Ui, content: impl FnOnce(&mut Ui)) {
+        let caller = Location::caller().into();
+        ui.render_object(caller, self, content);
+    }
+
+    /// The id of the probe named `name`.
+    pub(crate) fn find(tree: &mut Children, name: &'static str) -> ChildId {
+        let matcher = Matcher::new(name, move |child| {
+            match child.object.as_any().downcast_mut::<ProbeObject>() {
+                Some(probe) => probe.props.name == name,
+                None => false,
+            }
+        });
+        match find::<ProbeObject>(tree, &matcher) {
+            Some(id) => id,
+            None => panic!("No probe named {:?} in the tree", name),
+        }
+    }
+
+    /// Take the records of `log` for which `keep` returns `true`.
+    pub(crate) fn take(log: &Log, keep: impl Fn(&Record) -> bool) -> Vec<(&'static str, Record)> {
+        let records = std::mem::take(&mut *log.borrow_mut());
+        records
+            .into_iter()
+            .filter(|(_, record)| keep(record))
+            .collect()
+    }
+}
+
+pub(crate) struct ProbeObject {
+    props: Probe,
+}
+
+impl ProbeObject {
+    fn record(&self, record: Record) {
+        self.props.log.borrow_mut().push((self.props.name, record));
+    }
+}
+
+impl RenderObject<Probe> for ProbeObject {
+    type Action = ();
+
+    fn create(props: Probe) -> Self {
+        ProbeObject { props }
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, props: Probe) {
+        if props.size != self.props.size {
+            ctx.request_layout();
+        }
+        ctx.set_z_index(props.z_index);
+        if props.color != self.props.color {
+            ctx.request_paint();
+        }
+        self.props = props;
+    }
+}
+
+impl RenderObjectInterface for ProbeObject {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, children: &mut Children) {
+        for child in &mut *children {
+            child.event(ctx, event);
+        }
+        if let Event::MouseDown(_) = event {
+            if ctx.is_hot() && !ctx.is_handled() {
+                self.record(Record::Click);
+                ctx.request_paint();
+                ctx.set_handled();
+            }
+        }
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        match event {
+            LifeCycle::HotChanged(_) => return,
+            LifeCycle::WidgetAdded => ctx.set_z_index(self.props.z_index),
+            _ => {}
+        }
+        self.record(Record::LifeCycle(event.clone()));
+    }
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        _bc: &BoxConstraints,
+        children: &mut Children,
+    ) -> Size {
+        self.record(Record::Layout);
+        children.layout_stacked(ctx, &BoxConstraints::tight(self.props.size));
+        self.props.size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, children: &mut Children) {
+        if let Some(color) = &self.props.color {
+            let rect = ctx.size().to_rect();
+            ctx.fill(rect, color);
+        }
+        for child in children {
+            child.paint(ctx);
+        }
+    }
+
+    fn hit_test(&self, pos: Point) -> bool {
+        if !self.props.round {
+            return true;
+        }
+        let radius = self.props.size.width.min(self.props.size.height) / 2.0;
+        let center: Vec2 = self.props.size.to_vec2() / 2.0;
+        (pos.to_vec2() - center).hypot() <= radius
+    }
+}
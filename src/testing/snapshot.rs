@@ -0,0 +1,271 @@
+use std::{
+    env, fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+/// The environment variable that makes [`assert_snapshot`] write the reference images.
+pub const UPDATE_SNAPSHOTS_VAR: &str = "COAT_UPDATE_SNAPSHOTS";
+
+/// The pixels of a painted window, see [`Harness::snapshot`].
+///
+/// Snapshots are stored as [PAM] images with premultiplied alpha,
+/// which makes no difference for windows, as they are opaque.
+///
+/// [`Harness::snapshot`]: super::Harness::snapshot
+/// [PAM]: https://netpbm.sourceforge.net/doc/pam.html
+#[derive(Clone, PartialEq)]
+pub struct Snapshot {
+    width: usize,
+    height: usize,
+    /// Four bytes per pixel, RGBA, row by row.
+    pixels: Vec<u8>,
+}
+
+/// How a [`Snapshot`] differs from the expected one.
+pub struct Difference {
+    /// The number of pixels that differ by more than the tolerance.
+    pub pixels: usize,
+    /// The expected image dimmed, with the differing pixels in red.
+    pub image: Snapshot,
+}
+
+impl Snapshot {
+    pub fn new(width: usize, height: usize, pixels: Vec<u8>) -> Self {
+        assert_eq!(
+            pixels.len(),
+            width * height * 4,
+            "Expected four bytes per pixel"
+        );
+        Snapshot {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The RGBA value of the pixel at `x` and `y`.
+    pub fn pixel(&self, x: usize, y: usize) -> [u8; 4] {
+        let ix = (y * self.width + x) * 4;
+        let mut pixel = [0; 4];
+        pixel.copy_from_slice(&self.pixels[ix..ix + 4]);
+        pixel
+    }
+
+    /// Read a snapshot saved with [`Snapshot::save`].
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        parse_pam(&fs::read(path)?)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Not an RGBA PAM image"))
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let header = format!(
+            "P7\nWIDTH {}\nHEIGHT {}\nDEPTH 4\nMAXVAL 255\nTUPLTYPE RGB_ALPHA\nENDHDR\n",
+            self.width, self.height
+        );
+        let mut data = header.into_bytes();
+        data.extend_from_slice(&self.pixels);
+        fs::write(path, data)
+    }
+
+    /// Compare this snapshot with the `expected` one.
+    ///
+    /// Pixels match if no channel differs by more than `tolerance`.
+    /// Returns `None` if all pixels match and both have the same size.
+    pub fn compare(&self, expected: &Snapshot, tolerance: u8) -> Option<Difference> {
+        let width = self.width.max(expected.width);
+        let height = self.height.max(expected.height);
+        let mut pixels = Vec::with_capacity(width * height * 4);
+        let mut differing = 0;
+        for y in 0..height {
+            for x in 0..width {
+                let actual = self.get(x, y);
+                let wanted = expected.get(x, y);
+                let matches = match (actual, wanted) {
+                    (Some(a), Some(b)) => a
+                        .iter()
+                        .zip(b.iter())
+                        .all(|(a, b)| a.max(b) - a.min(b) <= tolerance),
+                    _ => false,
+                };
+                if matches {
+                    let [r, g, b, _] = wanted.unwrap_or_default();
+                    let gray = ((r as u32 + g as u32 + b as u32) / 3 / 4 + 0x40) as u8;
+                    pixels.extend_from_slice(&[gray, gray, gray, 0xff]);
+                } else {
+                    differing += 1;
+                    pixels.extend_from_slice(&[0xff, 0x00, 0x00, 0xff]);
+                }
+            }
+        }
+        if differing == 0 && self.width == expected.width && self.height == expected.height {
+            return None;
+        }
+        Some(Difference {
+            pixels: differing,
+            image: Snapshot::new(width, height, pixels),
+        })
+    }
+
+    fn get(&self, x: usize, y: usize) -> Option<[u8; 4]> {
+        if x < self.width && y < self.height {
+            Some(self.pixel(x, y))
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Debug for Snapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Snapshot({}x{})", self.width, self.height)
+    }
+}
+
+/// Assert that `snapshot` matches the reference image at `path`.
+///
+/// Pixels match if no channel differs by more than `tolerance`. On a mismatch
+/// the snapshot is saved next to the reference image with the extension
+/// `new.pam`, and an image highlighting the differences with `diff.pam`.
+///
+/// A missing reference image fails the assertion as well, the snapshot is
+/// then saved with the extension `new.pam`. If the `COAT_UPDATE_SNAPSHOTS`
+/// environment variable is set, the reference image is written instead.
+#[track_caller]
+pub fn assert_snapshot(snapshot: &Snapshot, path: impl AsRef<Path>, tolerance: u8) {
+    let path = path.as_ref();
+    if env::var_os(UPDATE_SNAPSHOTS_VAR).is_some() {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).expect("Failed to create the snapshot directory");
+        }
+        snapshot.save(path).expect("Failed to save the snapshot");
+        return;
+    }
+    if !path.exists() {
+        let new_path = with_extension(path, "new.pam");
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).expect("Failed to create the snapshot directory");
+        }
+        snapshot
+            .save(&new_path)
+            .expect("Failed to save the snapshot");
+        panic!(
+            "Snapshot {} does not exist, see {} or set {} to write it",
+            path.display(),
+            new_path.display(),
+            UPDATE_SNAPSHOTS_VAR
+        );
+    }
+
+    let expected = match Snapshot::load(path) {
+        Ok(expected) => expected,
+        Err(err) => panic!("Failed to load snapshot {}: {}", path.display(), err),
+    };
+    if let Some(difference) = snapshot.compare(&expected, tolerance) {
+        let new_path = with_extension(path, "new.pam");
+        let diff_path = with_extension(path, "diff.pam");
+        snapshot
+            .save(&new_path)
+            .expect("Failed to save the snapshot");
+        difference
+            .image
+            .save(&diff_path)
+            .expect("Failed to save the difference");
+        panic!(
+            "Snapshot {} differs in {} pixels ({}x{} instead of {}x{}), see {} and {}",
+            path.display(),
+            difference.pixels,
+            snapshot.width,
+            snapshot.height,
+            expected.width,
+            expected.height,
+            new_path.display(),
+            diff_path.display()
+        );
+    }
+}
+
+fn with_extension(path: &Path, extension: &str) -> PathBuf {
+    let mut path = path.to_path_buf();
+    path.set_extension(extension);
+    path
+}
+
+fn parse_pam(data: &[u8]) -> Option<Snapshot> {
+    const END: &[u8] = b"ENDHDR\n";
+    let header_len = data.windows(END.len()).position(|w| w == END)?;
+    let header = std::str::from_utf8(&data[..header_len]).ok()?;
+    let mut lines = header.lines();
+    if lines.next()? != "P7" {
+        return None;
+    }
+    let (mut width, mut height, mut depth, mut maxval) = (None, None, None, None);
+    for line in lines {
+        let mut parts = line.split_whitespace();
+        let (key, value) = (parts.next(), parts.next());
+        let value = || value?.parse::<usize>().ok();
+        match key {
+            Some("WIDTH") => width = value(),
+            Some("HEIGHT") => height = value(),
+            Some("DEPTH") => depth = value(),
+            Some("MAXVAL") => maxval = value(),
+            _ => {}
+        }
+    }
+    if depth? != 4 || maxval? != 255 {
+        return None;
+    }
+    let (width, height) = (width?, height?);
+    let pixels = data[header_len + END.len()..].to_vec();
+    if pixels.len() != width * height * 4 {
+        return None;
+    }
+    Some(Snapshot::new(width, height, pixels))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solid(width: usize, height: usize, rgba: [u8; 4]) -> Snapshot {
+        Snapshot::new(width, height, rgba.repeat(width * height))
+    }
+
+    #[test]
+    fn compare_with_tolerance() {
+        let a = solid(2, 2, [10, 20, 30, 255]);
+        let b = solid(2, 2, [12, 20, 30, 255]);
+        assert!(a.compare(&b, 2).is_none());
+
+        let difference = a.compare(&b, 1).unwrap();
+        assert_eq!(difference.pixels, 4);
+        assert_eq!(difference.image.pixel(0, 0), [0xff, 0x00, 0x00, 0xff]);
+
+        let larger = solid(3, 2, [10, 20, 30, 255]);
+        assert_eq!(a.compare(&larger, 0).unwrap().pixels, 2);
+    }
+
+    #[test]
+    fn pam_round_trip() {
+        let snapshot = Snapshot::new(2, 1, vec![1, 2, 3, 4, 5, 6, 7, 8]);
+        let path = env::temp_dir().join("coat-snapshot-round-trip.pam");
+        snapshot.save(&path).unwrap();
+        assert_eq!(Snapshot::load(&path).unwrap(), snapshot);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "does not exist")]
+    fn missing_reference_fails() {
+        let path = env::temp_dir().join("coat-snapshot-missing.pam");
+        let _ = fs::remove_file(&path);
+        assert_snapshot(&solid(1, 1, [0, 0, 0, 255]), &path, 0);
+    }
+}
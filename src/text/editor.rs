@@ -21,7 +21,8 @@ use super::{
 use crate::context::PaintCtx;
 use crate::kurbo::Line;
 use crate::piet::PietText;
-use druid::{Application, Env, MouseEvent, Point, Rect, UpdateCtx};
+use crate::services::Clipboard;
+use druid::{Env, MouseEvent, Point, Rect, UpdateCtx};
 
 /// A component for widgets that offer text editing.
 ///
@@ -151,16 +152,16 @@ impl<T: TextStorage + EditableText> Editor<T> {
     }
 
     /// Handle a copy command
-    pub fn copy(&self, data: &mut T) {
+    pub fn copy(&self, data: &mut T, clipboard: &dyn Clipboard) {
         if !self.data_is_stale(data) {
-            self.set_clipboard()
+            self.set_clipboard(clipboard)
         }
     }
 
     /// Handle a cut command
-    pub fn cut(&mut self, data: &mut T) {
+    pub fn cut(&mut self, data: &mut T, clipboard: &dyn Clipboard) {
         if !self.data_is_stale(data) {
-            self.set_clipboard();
+            self.set_clipboard(clipboard);
             self.delete_backward(data);
         }
     }
@@ -299,14 +300,14 @@ impl<T: TextStorage + EditableText> Editor<T> {
         self.selection = Selection::caret(self.selection.min());
     }
 
-    fn set_clipboard(&self) {
+    fn set_clipboard(&self, clipboard: &dyn Clipboard) {
         if let Some(text) = self
             .layout
             .text()
             .and_then(|txt| txt.slice(self.selection.range()))
         {
             if !text.is_empty() {
-                Application::global().clipboard().put_string(text);
+                clipboard.put_string(&text);
             }
         }
     }
@@ -0,0 +1,148 @@
+//! Fonts for the characters that the main font of a text does not have.
+
+use druid::FontFamily;
+use std::ops::{Range, RangeInclusive};
+
+/// Emoji and pictographs.
+pub const EMOJI: &[RangeInclusive<char>] = &['\u{2600}'..='\u{27BF}', '\u{1F000}'..='\u{1FAFF}'];
+
+/// Chinese, Japanese and Korean characters, and their punctuation.
+pub const CJK: &[RangeInclusive<char>] = &[
+    '\u{1100}'..='\u{11FF}',
+    '\u{2E80}'..='\u{9FFF}',
+    '\u{AC00}'..='\u{D7AF}',
+    '\u{F900}'..='\u{FAFF}',
+    '\u{FE30}'..='\u{FE4F}',
+    '\u{FF00}'..='\u{FFEF}',
+    '\u{20000}'..='\u{2FA1F}',
+];
+
+/// Arrows, mathematical operators, technical symbols and shapes.
+pub const SYMBOLS: &[RangeInclusive<char>] = &[
+    '\u{2190}'..='\u{23FF}',
+    '\u{25A0}'..='\u{25FF}',
+    '\u{2B00}'..='\u{2BFF}',
+];
+
+/// An ordered list of fonts for the characters that the main font of a text
+/// does not have, like emoji or CJK characters in a Latin font.
+///
+/// The text backends can not tell which characters a font has, so every
+/// font of the list is given the characters it is used for. A character is
+/// set in the first font of the list that is used for it, or in the main
+/// font if there is none.
+///
+/// Labels, buttons and text boxes take the fallback of the theme:
+///
+/// ```ignore
+/// let mut theme = Theme::default();
+/// theme.font_fallback = FontFallback::new()
+///     .font(FontFamily::new_unchecked("Noto Color Emoji"), fallback::EMOJI)
+///     .font(FontFamily::new_unchecked("Noto Sans CJK JP"), fallback::CJK);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FontFallback {
+    fonts: Vec<(FontFamily, Vec<RangeInclusive<char>>)>,
+}
+
+impl FontFallback {
+    /// No fallback, everything is set in the main font.
+    pub fn new() -> Self {
+        FontFallback::default()
+    }
+
+    /// The fonts that come with the current platform, for emoji, CJK
+    /// characters and symbols.
+    pub fn platform() -> Self {
+        let (emoji, cjk, symbols) = if cfg!(target_os = "windows") {
+            ("Segoe UI Emoji", "Microsoft YaHei", "Segoe UI Symbol")
+        } else if cfg!(target_os = "macos") {
+            ("Apple Color Emoji", "PingFang SC", "Apple Symbols")
+        } else {
+            ("Noto Color Emoji", "Noto Sans CJK SC", "DejaVu Sans")
+        };
+        FontFallback::new()
+            .font(FontFamily::new_unchecked(emoji), EMOJI)
+            .font(FontFamily::new_unchecked(cjk), CJK)
+            .font(FontFamily::new_unchecked(symbols), SYMBOLS)
+    }
+
+    /// Add `family` to the end of the list, for the characters in `ranges`.
+    pub fn font<'a>(
+        mut self,
+        family: FontFamily,
+        ranges: impl IntoIterator<Item = &'a RangeInclusive<char>>,
+    ) -> Self {
+        self.fonts
+            .push((family, ranges.into_iter().cloned().collect()));
+        self
+    }
+
+    /// Returns `true` if the list has no fonts.
+    pub fn is_empty(&self) -> bool {
+        self.fonts.is_empty()
+    }
+
+    /// The font for `c`, or `None` for the main font.
+    pub fn family_for(&self, c: char) -> Option<&FontFamily> {
+        self.fonts
+            .iter()
+            .find(|(_, ranges)| ranges.iter().any(|range| range.contains(&c)))
+            .map(|(family, _)| family)
+    }
+
+    /// The byte ranges of `text` to set in a fallback font, with that font.
+    ///
+    /// Joiners and variation selectors stay with the character before them,
+    /// so that composed emoji are set in one font.
+    pub fn runs(&self, text: &str) -> Vec<(Range<usize>, FontFamily)> {
+        let mut runs: Vec<(Range<usize>, FontFamily)> = Vec::new();
+        if self.is_empty() {
+            return runs;
+        }
+        let mut previous: Option<&FontFamily> = None;
+        for (index, c) in text.char_indices() {
+            let end = index + c.len_utf8();
+            let joins = matches!(c, '\u{200D}' | '\u{FE00}'..='\u{FE0F}');
+            let family = match (joins, previous) {
+                (true, Some(family)) => Some(family),
+                _ => self.family_for(c),
+            };
+            previous = family;
+            let family = match family {
+                Some(family) => family,
+                None => continue,
+            };
+            match runs.last_mut() {
+                Some((range, last)) if range.end == index && last == family => range.end = end,
+                _ => runs.push((index..end, family.clone())),
+            }
+        }
+        runs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fallback_runs() {
+        let emoji = FontFamily::new_unchecked("Emoji");
+        let cjk = FontFamily::new_unchecked("CJK");
+        let fallback = FontFallback::new()
+            .font(emoji.clone(), EMOJI)
+            .font(cjk.clone(), CJK);
+
+        assert!(FontFallback::new().runs("a😀").is_empty());
+        assert!(fallback.runs("plain text").is_empty());
+        assert_eq!(fallback.family_for('日'), Some(&cjk));
+        assert_eq!(fallback.family_for('a'), None);
+
+        // "a", the family emoji joined with zero width joiners, "日本".
+        let text = "a\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}\u{65E5}\u{672C}";
+        assert_eq!(fallback.runs(text), [(1..19, emoji), (19..25, cjk)]);
+        // A joiner after a character of the main font stays in it.
+        assert!(fallback.runs("a\u{200D}").is_empty());
+    }
+}
@@ -0,0 +1,87 @@
+//! Fonts that come with the app, see [`App::register_font`].
+//!
+//! [`App::register_font`]: crate::app::App::register_font
+
+use druid::piet::{PietText, Text};
+use std::{
+    borrow::Cow,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+/// Where a font registered with [`App::register_font`] is loaded from.
+///
+/// [`App::register_font`]: crate::app::App::register_font
+#[derive(Debug, Clone)]
+pub enum FontSource {
+    /// The data of a font file, often embedded with `include_bytes!`.
+    Bytes(Cow<'static, [u8]>),
+    /// The path of a font file.
+    Path(PathBuf),
+}
+
+impl From<&'static [u8]> for FontSource {
+    fn from(data: &'static [u8]) -> Self {
+        FontSource::Bytes(Cow::Borrowed(data))
+    }
+}
+
+impl From<Vec<u8>> for FontSource {
+    fn from(data: Vec<u8>) -> Self {
+        FontSource::Bytes(Cow::Owned(data))
+    }
+}
+
+impl From<PathBuf> for FontSource {
+    fn from(path: PathBuf) -> Self {
+        FontSource::Path(path)
+    }
+}
+
+impl From<&Path> for FontSource {
+    fn from(path: &Path) -> Self {
+        FontSource::Path(path.to_owned())
+    }
+}
+
+/// The fonts registered with the app, provided to every window so that it
+/// can load them when it opens.
+#[derive(Clone, Default)]
+pub(crate) struct Fonts {
+    data: Rc<Vec<Cow<'static, [u8]>>>,
+}
+
+impl Fonts {
+    /// Read the font files of `sources`, once for all windows.
+    pub(crate) fn read(sources: Vec<FontSource>) -> Fonts {
+        let data = sources
+            .into_iter()
+            .filter_map(|source| match source {
+                FontSource::Bytes(data) => Some(data),
+                FontSource::Path(path) => match std::fs::read(&path) {
+                    Ok(data) => Some(Cow::Owned(data)),
+                    Err(err) => {
+                        log::error!("Failed to read font {}: {}", path.display(), err);
+                        None
+                    }
+                },
+            })
+            .collect();
+        Fonts {
+            data: Rc::new(data),
+        }
+    }
+
+    /// Load the fonts into the text factory of a window.
+    ///
+    /// Fonts that fail to load are reported, text set in them falls back to
+    /// the default font.
+    pub(crate) fn load(&self, text: &mut PietText) {
+        for data in self.data.iter() {
+            match text.load_font(data) {
+                Ok(family) => log::debug!("Loaded the font family {}", family.name()),
+                Err(err) => log::error!("Failed to load a registered font: {}", err),
+            }
+        }
+    }
+}
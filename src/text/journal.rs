@@ -0,0 +1,277 @@
+//! Crash-safe journaling of text edits.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+/// A single edit, replacing a byte range of the text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Edit {
+    pub range: Range<usize>,
+    pub text: String,
+}
+
+impl Edit {
+    /// The edit that turns `old` into `new`, if they differ.
+    pub fn between(old: &str, new: &str) -> Option<Edit> {
+        if old == new {
+            return None;
+        }
+
+        let mut prefix = old
+            .bytes()
+            .zip(new.bytes())
+            .take_while(|(a, b)| a == b)
+            .count();
+        while !old.is_char_boundary(prefix) || !new.is_char_boundary(prefix) {
+            prefix -= 1;
+        }
+
+        let max_suffix = old.len().min(new.len()) - prefix;
+        let mut suffix = old
+            .bytes()
+            .rev()
+            .zip(new.bytes().rev())
+            .take(max_suffix)
+            .take_while(|(a, b)| a == b)
+            .count();
+        while !old.is_char_boundary(old.len() - suffix) || !new.is_char_boundary(new.len() - suffix)
+        {
+            suffix -= 1;
+        }
+
+        Some(Edit {
+            range: prefix..old.len() - suffix,
+            text: new[prefix..new.len() - suffix].to_owned(),
+        })
+    }
+
+    /// Apply the edit to `text`.
+    ///
+    /// Returns `false` if the edit does not fit the text.
+    pub fn apply(&self, text: &mut String) -> bool {
+        let fits = self.range.start <= self.range.end
+            && self.range.end <= text.len()
+            && text.is_char_boundary(self.range.start)
+            && text.is_char_boundary(self.range.end);
+        if fits {
+            text.replace_range(self.range.clone(), &self.text);
+        }
+        fits
+    }
+}
+
+/// An append-only log of the edits made to a text since it was last saved.
+///
+/// Every edit is flushed to disk before `append` returns. After a crash,
+/// [`replay`] applies the logged edits to the last saved text.
+/// The journal should be [`clear`]ed whenever the text itself is saved.
+///
+/// [`replay`]: Journal::replay
+/// [`clear`]: Journal::clear
+pub struct Journal {
+    path: PathBuf,
+    file: Option<File>,
+}
+
+impl Journal {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Journal {
+            path: path.into(),
+            file: None,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Log an edit.
+    pub fn append(&mut self, edit: &Edit) -> io::Result<()> {
+        let file = match &mut self.file {
+            Some(file) => file,
+            None => {
+                if let Some(dir) = self.path.parent() {
+                    fs::create_dir_all(dir)?;
+                }
+                let file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&self.path)?;
+                self.file.get_or_insert(file)
+            }
+        };
+
+        let mut line = format!("{} {} ", edit.range.start, edit.range.end);
+        for c in edit.text.chars() {
+            match c {
+                '\\' => line.push_str("\\\\"),
+                '\n' => line.push_str("\\n"),
+                '\r' => line.push_str("\\r"),
+                c => line.push(c),
+            }
+        }
+        line.push('\n');
+        file.write_all(line.as_bytes())?;
+        file.sync_data()
+    }
+
+    /// Read all logged edits.
+    ///
+    /// An edit that was only partially written is ignored.
+    pub fn edits(&self) -> io::Result<Vec<Edit>> {
+        let text = match fs::read_to_string(&self.path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err),
+        };
+
+        // Only complete lines end with a line break.
+        let complete = text.rfind('\n').map_or("", |end| &text[..end]);
+        Ok(complete.lines().filter_map(parse_edit).collect())
+    }
+
+    /// Apply all logged edits to `text`.
+    ///
+    /// Returns `true` if the text was changed.
+    pub fn replay(&self, text: &mut String) -> io::Result<bool> {
+        let mut changed = false;
+        for edit in self.edits()? {
+            if !edit.apply(text) {
+                log::warn!("Journal {} does not match the text.", self.path.display());
+                break;
+            }
+            changed = true;
+        }
+        Ok(changed)
+    }
+
+    /// Remove all logged edits.
+    pub fn clear(&mut self) -> io::Result<()> {
+        self.file = None;
+        match fs::remove_file(&self.path) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn parse_edit(line: &str) -> Option<Edit> {
+    let mut parts = line.splitn(3, ' ');
+    let start = parts.next()?.parse().ok()?;
+    let end = parts.next()?.parse().ok()?;
+
+    let mut text = String::new();
+    let mut chars = parts.next()?.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next()? {
+                'n' => text.push('\n'),
+                'r' => text.push('\r'),
+                c => text.push(c),
+            },
+            c => text.push(c),
+        }
+    }
+
+    Some(Edit {
+        range: start..end,
+        text,
+    })
+}
+
+/// The journals of an app, stored in one directory.
+///
+/// Enable it with [`App::journal_dir`] and access it through the contexts.
+///
+/// [`App::journal_dir`]: crate::app::App::journal_dir
+pub struct Journals {
+    dir: PathBuf,
+    journals: HashMap<String, Journal>,
+    /// The names of the journals replayed since the app started.
+    replayed: HashSet<String>,
+}
+
+impl Journals {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Journals {
+            dir: dir.into(),
+            journals: HashMap::new(),
+            replayed: HashSet::new(),
+        }
+    }
+
+    /// The journal with the given name.
+    pub fn journal(&mut self, name: &str) -> &mut Journal {
+        let dir = &self.dir;
+        self.journals
+            .entry(name.to_owned())
+            .or_insert_with(|| Journal::new(dir.join(format!("{}.journal", name))))
+    }
+
+    /// Replay the journal with the given name onto `text`, see [`Journal::replay`].
+    ///
+    /// Only the first replay of a journal applies its edits. Widgets created
+    /// again later edit the same text, which already contains them.
+    ///
+    /// Returns `true` if the text was changed.
+    pub fn replay(&mut self, name: &str, text: &mut String) -> io::Result<bool> {
+        if !self.replayed.insert(name.to_owned()) {
+            return Ok(false);
+        }
+        self.journal(name).replay(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edit_between() {
+        let edit = Edit::between("hello world", "hello brave world").unwrap();
+        assert_eq!(edit.range, 6..6);
+        assert_eq!(edit.text, "brave ");
+
+        let edit = Edit::between("aaa", "aa").unwrap();
+        let mut text = "aaa".to_owned();
+        assert!(edit.apply(&mut text));
+        assert_eq!(text, "aa");
+
+        let edit = Edit::between("aä", "aö").unwrap();
+        assert_eq!(edit.range, 1..3);
+        assert_eq!(edit.text, "ö");
+
+        assert_eq!(Edit::between("same", "same"), None);
+    }
+
+    #[test]
+    fn replay_once() {
+        let dir = std::env::temp_dir().join(format!("coat-journals-{}", std::process::id()));
+        let mut journals = Journals::new(&dir);
+        let edit = Edit::between("draft", "first draft").unwrap();
+        journals.journal("notes").append(&edit).unwrap();
+
+        let mut text = "draft".to_owned();
+        assert!(journals.replay("notes", &mut text).unwrap());
+        assert_eq!(text, "first draft");
+        // A text box created again for the same text.
+        assert!(!journals.replay("notes", &mut text).unwrap());
+        assert_eq!(text, "first draft");
+
+        journals.journal("notes").clear().unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn edit_parsing() {
+        let edit = parse_edit("2 4 a\\nb\\\\ c").unwrap();
+        assert_eq!(edit.range, 2..4);
+        assert_eq!(edit.text, "a\nb\\ c");
+        assert_eq!(parse_edit("2 x"), None);
+    }
+}
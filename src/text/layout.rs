@@ -23,7 +23,7 @@ use crate::piet::{
     Color, PietText, PietTextLayout, Text as _, TextAlignment, TextAttribute, TextLayout as _,
     TextLayoutBuilder as _,
 };
-use crate::text::FontDescriptor;
+use crate::text::{FontDescriptor, FontFallback};
 use druid::{Env, KeyOrValue, RenderContext, UpdateCtx};
 
 /// A component for displaying text on screen.
@@ -54,6 +54,9 @@ pub struct TextLayout<T> {
     // using a `FontDescriptor` in the `Env`.
     text_size_override: Option<KeyOrValue<f64>>,
     text_color: KeyOrValue<Color>,
+    fallback: FontFallback,
+    underline: bool,
+    strikethrough: bool,
     layout: Option<PietTextLayout>,
     wrap_width: f64,
     alignment: TextAlignment,
@@ -81,6 +84,9 @@ impl<T> TextLayout<T> {
             font: druid::theme::UI_FONT.into(),
             text_color: druid::theme::LABEL_COLOR.into(),
             text_size_override: None,
+            fallback: FontFallback::new(),
+            underline: false,
+            strikethrough: false,
             layout: None,
             wrap_width: f64::INFINITY,
             alignment: Default::default(),
@@ -123,6 +129,30 @@ impl<T> TextLayout<T> {
         }
     }
 
+    /// Set the fonts for the characters the default font does not have.
+    pub fn set_font_fallback(&mut self, fallback: FontFallback) {
+        if fallback != self.fallback {
+            self.fallback = fallback;
+            self.layout = None;
+        }
+    }
+
+    /// Set whether the text is underlined.
+    pub fn set_underline(&mut self, underline: bool) {
+        if underline != self.underline {
+            self.underline = underline;
+            self.layout = None;
+        }
+    }
+
+    /// Set whether the text is struck through.
+    pub fn set_strikethrough(&mut self, strikethrough: bool) {
+        if strikethrough != self.strikethrough {
+            self.strikethrough = strikethrough;
+            self.layout = None;
+        }
+    }
+
     /// Set the font size.
     ///
     /// This overrides the size in the [`FontDescriptor`] provided to [`set_font`].
@@ -328,14 +358,19 @@ impl<T: TextStorage> TextLayout<T> {
                     font
                 };
 
-                let builder = factory
+                let mut builder = factory
                     .new_text_layout(text.clone())
                     .max_width(self.wrap_width)
                     .alignment(self.alignment)
                     .font(descriptor.family.clone(), descriptor.size)
                     .default_attribute(descriptor.weight)
                     .default_attribute(descriptor.style)
-                    .default_attribute(TextAttribute::TextColor(color));
+                    .default_attribute(TextAttribute::TextColor(color))
+                    .default_attribute(TextAttribute::Underline(self.underline))
+                    .default_attribute(TextAttribute::Strikethrough(self.strikethrough));
+                for (range, family) in self.fallback.runs(text.as_str()) {
+                    builder = builder.range_attribute(range, TextAttribute::FontFamily(family));
+                }
                 let layout = text.add_attributes(builder, env).build().unwrap();
                 self.layout = Some(layout);
             }
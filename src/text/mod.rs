@@ -18,8 +18,11 @@ mod attribute;
 pub mod backspace;
 mod editable_text;
 mod editor;
+pub mod fallback;
 //mod font_descriptor;
+mod fonts;
 pub mod format;
+pub mod journal;
 mod layout;
 pub mod movement;
 mod rich_text;
@@ -30,12 +33,16 @@ mod text_input;
 pub use self::attribute::{Attribute, AttributeSpans};
 pub use self::backspace::offset_for_delete_backwards;
 pub use self::editable_text::{EditableText, EditableTextCursor, StringCursor};
+pub use self::fallback::FontFallback;
 //pub use self::font_descriptor::FontDescriptor;
 pub use self::layout::{LayoutMetrics, TextLayout};
 pub use self::movement::{movement, Movement};
 pub use self::selection::Selection;
 pub use self::text_input::{BasicTextInput, EditAction, MouseAction, TextInput};
-pub use druid::FontDescriptor;
+pub use druid::{FontDescriptor, FontFamily, FontStyle, FontWeight};
 pub use editor::Editor;
+pub use fonts::FontSource;
+pub(crate) use fonts::Fonts;
+pub use journal::{Edit, Journal, Journals};
 pub use rich_text::{AttributesAdder, RichText, RichTextBuilder};
 pub use storage::{ArcStr, TextStorage};
@@ -78,6 +78,22 @@ impl TextInput for BasicTextInput {
             k_e if (HotKey::new(SysMods::CmdShift, KbKey::ArrowRight)).matches(k_e) => {
                 EditAction::ModifySelection(Movement::RightWord)
             }
+            // Select to the start of the document (Shift+Ctrl+Home || Shift+Cmd+Home)
+            k_e if (HotKey::new(SysMods::CmdShift, KbKey::Home)).matches(k_e) => {
+                EditAction::ModifySelection(Movement::StartOfDocument)
+            }
+            // Select to the end of the document (Shift+Ctrl+End || Shift+Cmd+End)
+            k_e if (HotKey::new(SysMods::CmdShift, KbKey::End)).matches(k_e) => {
+                EditAction::ModifySelection(Movement::EndOfDocument)
+            }
+            // Start of the document (Ctrl+Home || Cmd+Home)
+            k_e if (HotKey::new(SysMods::Cmd, KbKey::Home)).matches(k_e) => {
+                EditAction::Move(Movement::StartOfDocument)
+            }
+            // End of the document (Ctrl+End || Cmd+End)
+            k_e if (HotKey::new(SysMods::Cmd, KbKey::End)).matches(k_e) => {
+                EditAction::Move(Movement::EndOfDocument)
+            }
             // Select to home (Shift+Home)
             k_e if (HotKey::new(SysMods::Shift, KbKey::Home)).matches(k_e) => {
                 EditAction::ModifySelection(Movement::PrecedingLineBreak)
@@ -0,0 +1,152 @@
+//! Colors, spacing and fonts shared by the built-in widgets.
+//!
+//! The theme of an app is set with [`App::theme`]. A different theme can be
+//! [`provide`]d to a subtree at any time, which lays it out and paints it again.
+//! Render objects read the theme with `ctx.theme()`.
+//!
+//! Widgets with a style sheet of their own, see [`style`], take it from the theme
+//! unless one is set on the widget itself.
+//!
+//! [`App::theme`]: crate::app::App::theme
+//! [`provide`]: crate::ui::Ui::provide
+//! [`style`]: crate::style
+
+use crate::{
+    piet::Color,
+    text::{FontDescriptor, FontFallback},
+    widgets::{button, textbox},
+};
+use druid::FontFamily;
+use std::rc::Rc;
+
+/// The look of the built-in widgets.
+#[derive(Clone)]
+pub struct Theme {
+    pub colors: Colors,
+    pub spacing: Spacing,
+    /// The font of labels and text boxes.
+    pub font: FontDescriptor,
+    /// The fonts for the characters that `font` does not have, like emoji.
+    pub font_fallback: FontFallback,
+    /// The style sheet of buttons without a style of their own.
+    pub button: Rc<dyn button::StyleSheet>,
+    /// The style sheet of text boxes without a style of their own.
+    pub text_box: Rc<dyn textbox::StyleSheet>,
+}
+
+/// The colors of a [`Theme`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Colors {
+    /// The background of windows.
+    pub background: Color,
+    pub text: Color,
+    /// Highlights, like the hovered menu item or drop indicators.
+    pub accent: Color,
+    /// Icons drawn on top of the accent color.
+    pub on_accent: Color,
+    /// The background of menus and dialogs.
+    pub panel: Color,
+    /// The border of menus and dialogs, and the lines between menu items.
+    pub panel_border: Color,
+    /// Dims the window behind modal dialogs.
+    pub scrim: Color,
+    /// The background of hovered items, like tabs or menu entries.
+    pub ghost: Color,
+}
+
+/// The spacing of a [`Theme`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spacing {
+    /// The corner radius of menus and dialogs.
+    pub corner_radius: f64,
+    /// The space between the border of a modal dialog and its content.
+    pub dialog_padding: f64,
+    /// The space between the border of a menu and its items.
+    pub menu_padding: f64,
+    /// The space between the border of a text box and its text.
+    pub text_insets: f64,
+}
+
+impl Theme {
+    /// Light text on dark backgrounds.
+    pub fn dark() -> Self {
+        Theme {
+            colors: Colors {
+                background: Color::rgb8(0x29, 0x29, 0x2c),
+                text: Color::rgb8(0xf0, 0xf0, 0xea),
+                accent: Color::rgb(0.5, 0.5, 0.87),
+                on_accent: Color::WHITE,
+                panel: Color::rgb8(0x3a, 0x3a, 0x3a),
+                panel_border: Color::rgb8(0x60, 0x60, 0x60),
+                scrim: Color::rgba8(0x00, 0x00, 0x00, 0x80),
+                ghost: Color::rgba8(0x80, 0x80, 0x80, 0x80),
+            },
+            spacing: Spacing::default(),
+            font: default_font(),
+            font_fallback: FontFallback::platform(),
+            button: Rc::new(button::style::Default),
+            text_box: Rc::new(textbox::style::Default),
+        }
+    }
+
+    /// Dark text on light backgrounds.
+    pub fn light() -> Self {
+        Theme {
+            colors: Colors {
+                background: Color::rgb8(0xf2, 0xf2, 0xf2),
+                text: Color::rgb8(0x20, 0x20, 0x20),
+                accent: Color::rgb(0.4, 0.4, 0.85),
+                on_accent: Color::WHITE,
+                panel: Color::WHITE,
+                panel_border: Color::rgb8(0xc0, 0xc0, 0xc0),
+                scrim: Color::rgba8(0x00, 0x00, 0x00, 0x40),
+                ghost: Color::rgba8(0xff, 0xff, 0xff, 0x80),
+            },
+            spacing: Spacing::default(),
+            font: default_font(),
+            font_fallback: FontFallback::platform(),
+            button: Rc::new(button::style::Light),
+            text_box: Rc::new(textbox::style::Light),
+        }
+    }
+
+    /// The theme used where none was provided.
+    pub(crate) fn fallback() -> &'static Theme {
+        thread_local! {
+            static FALLBACK: &'static Theme = Box::leak(Box::new(Theme::default()));
+        }
+        FALLBACK.with(|theme| *theme)
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::dark()
+    }
+}
+
+impl PartialEq for Theme {
+    fn eq(&self, other: &Self) -> bool {
+        self.colors == other.colors
+            && self.spacing == other.spacing
+            && self.font == other.font
+            && self.font_fallback == other.font_fallback
+            && self.button.eq(other.button.as_ref())
+            && self.text_box.eq(other.text_box.as_ref())
+    }
+}
+
+impl Default for Spacing {
+    fn default() -> Self {
+        Spacing {
+            corner_radius: 3.0,
+            dialog_padding: 12.0,
+            menu_padding: 4.0,
+            text_insets: 3.0,
+        }
+    }
+}
+
+fn default_font() -> FontDescriptor {
+    FontDescriptor::new(FontFamily::SYSTEM_UI).with_size(15.0)
+}
@@ -1,25 +1,39 @@
 use crate::{
     bloom::Bloom,
-    context::{ContextState, EventCtx, LayoutCtx, LifeCycleCtx, PaintCtx},
-    event::{Event, LifeCycle},
+    context::{ContextState, EventCtx, LayoutCtx, LifeCycleCtx, PaintCtx, RaisedPaint},
+    environment::Environment,
+    event::{Event, Gesture, LifeCycle, MouseEvent, ViewContext},
+    gesture::GESTURE,
     id::ChildId,
     key::Caller,
-    kurbo::{Affine, Insets, Point, Rect, Shape, Size, Vec2},
-    object::AnyRenderObject,
-    piet::RenderContext,
+    kurbo::{Affine, Insets, Line, Point, Rect, Shape, Size, Vec2},
+    object::{AnyRenderObject, Intrinsic},
+    piet::{Color, RenderContext},
+    profile::{self, Phase},
+    widgets::overlay::{DismissReason, DISMISS_OVERLAY},
     BoxConstraints,
 };
-use druid::{Cursor, InternalEvent, Region, TimerToken};
+use druid::{Command, Cursor, InternalEvent, KbKey, Region, Scale, Selector, TimerToken};
 use std::{
     any::Any,
+    cell::RefCell,
+    cmp::Reverse,
     collections::HashMap,
     ops::{Index, IndexMut},
 };
 
+/// Wraps a command that is only delivered to the widget with the given id.
+pub(crate) const ROUTE_COMMAND: Selector<(ChildId, Command)> = Selector::new("coat.route-command");
+
+/// The minimum size of hit targets while touch input is active.
+pub const MIN_TOUCH_TARGET: f64 = 44.0;
+
 #[derive(Default)]
 pub struct Children {
     pub(crate) states: Vec<State>,
     pub(crate) renders: Vec<Child>,
+    /// Removed children that keep their state until they are built again.
+    pub(crate) retained: Vec<Child>,
 }
 
 pub struct State {
@@ -34,6 +48,11 @@ pub struct Child {
     pub(crate) children: Children,
     pub(crate) state: ChildState,
     pub(crate) dead: bool,
+    /// Keep this child in [`Children::retained`] when it is removed, with at
+    /// most this many retained children in total, see [`Retain::limit`].
+    ///
+    /// [`Retain::limit`]: crate::widgets::Retain::limit
+    pub(crate) retain: Option<usize>,
 }
 
 pub struct ChildState {
@@ -50,6 +69,9 @@ pub struct ChildState {
     /// The origin of the parent in the window coordinate space;
     pub(crate) parent_window_origin: Point,
 
+    /// The window origin last reported with `LifeCycle::ViewContextChanged`.
+    pub(crate) reported_window_origin: Option<Point>,
+
     /// A flag used to track and debug missing calls to set_origin.
     pub(crate) is_expecting_set_origin_call: bool,
 
@@ -65,6 +87,12 @@ pub struct ChildState {
     /// laid out alongside text can set this as appropriate.
     pub(crate) baseline_offset: f64,
 
+    /// How far the size exceeded the maximum of the constraints in the last
+    /// layout pass. Only tracked with [`App::debug_layout`].
+    ///
+    /// [`App::debug_layout`]: crate::app::App::debug_layout
+    pub(crate) overflow: Size,
+
     // The region that needs to be repainted, relative to the widget's bounds.
     pub(crate) invalid: Region,
 
@@ -73,6 +101,9 @@ pub struct ChildState {
     // similar, and it is used for propagating invalid regions.
     pub(crate) viewport_offset: Vec2,
 
+    /// Applied to the widget in addition to its origin, see [`Child::set_transform`].
+    pub(crate) transform: Affine,
+
     // TODO: consider using bitflags for the booleans.
     pub(crate) is_hot: bool,
 
@@ -80,9 +111,30 @@ pub struct ChildState {
 
     pub(crate) needs_layout: bool,
 
+    /// The minimum and maximum of the constraints and the window size of the last layout.
+    ///
+    /// The widget is not laid out again with the same ones, unless it or
+    /// one of its descendants requested a layout.
+    pub(crate) layout_key: Option<(Size, Size, Size)>,
+
     /// Any descendant is active.
     pub(crate) has_active: bool,
 
+    /// This widget captured the pointer, see [`EventCtx::capture_pointer`].
+    pub(crate) captures_pointer: bool,
+    /// This widget or a descendant captured the pointer.
+    pub(crate) has_capture: bool,
+
+    /// The part of the widget that was not clipped away in the last paint
+    /// pass, pointer events outside of it do not make it hot.
+    pub(crate) visible: Option<Rect>,
+
+    /// Raises the widget above its siblings, see [`EventCtx::set_z_index`].
+    pub(crate) z_index: u32,
+    /// The parent painted this widget after its siblings in the last paint
+    /// pass, so it receives mouse events before them.
+    pub(crate) painted_raised: bool,
+
     /// In the focused path, starting from window and ending at the focused widget.
     /// Descendants of the focused widget are not in the focused path.
     pub(crate) has_focus: bool,
@@ -106,7 +158,40 @@ pub struct ChildState {
     pub(crate) cursor: Option<Cursor>,
 
     /// Associate timers with widgets that requested them.
+    ///
+    /// This includes the timers of all descendants.
     pub(crate) timers: HashMap<TimerToken, ChildId>,
+
+    /// This child is part of the overlay layer.
+    ///
+    /// Layers are skipped by their parent during event dispatch and painting,
+    /// they receive events before and are painted after the rest of the tree.
+    pub(crate) is_layer: bool,
+
+    /// This layer blocks input to everything below it.
+    pub(crate) is_modal: bool,
+
+    /// This widget reacts to pointer input and should be easy to hit.
+    pub(crate) is_hit_target: bool,
+
+    /// The layout rect, grown to the expanded touch targets of this widget
+    /// and its descendants. While touch input is active, the widget is hot
+    /// anywhere in it, so that events reach targets at the edge of their parent.
+    pub(crate) touch_bounds: Rect,
+
+    /// Notifications submitted by descendants, not yet handled by an ancestor.
+    pub(crate) notifications: Vec<Command>,
+
+    /// The area this widget or a descendant wants to see, in window coordinates.
+    ///
+    /// It bubbles up during layout, so scroll containers can bring it into view.
+    pub(crate) scroll_to_view: Option<Rect>,
+
+    /// The values provided by the ancestors, as of the last build pass.
+    pub(crate) environment: Environment,
+
+    /// The style the widget was last painted with, if it published one.
+    pub(crate) resolved_style: RefCell<Option<Box<dyn Any>>>,
 }
 
 /// Methods by which a widget can attempt to change focus state.
@@ -161,6 +246,53 @@ impl Children {
     pub fn iter(&mut self) -> ChildIter {
         self.into_iter()
     }
+
+    /// Lay out the children on top of each other at the origin, with `bc`.
+    ///
+    /// Returns the smallest size within `bc` that fits all of them. The
+    /// baseline of the first child becomes the baseline of the container.
+    pub fn layout_stacked(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let mut size = bc.min();
+        for child in &mut *self {
+            let child_size = child.layout(ctx, bc);
+            child.set_origin(ctx, Point::ORIGIN);
+            size.width = size.width.max(child_size.width);
+            size.height = size.height.max(child_size.height);
+        }
+        if let Some(child) = self.renders.first() {
+            // Measured from the bottom, which may be below the child.
+            let below = size.height - child.state.size.height;
+            ctx.set_baseline_offset(child.baseline_offset() + below);
+        }
+        size
+    }
+
+    /// The largest minimum intrinsic width of the children at `height`, see
+    /// [`Child::min_intrinsic_width`].
+    pub fn min_intrinsic_width(&mut self, ctx: &mut LayoutCtx, height: f64) -> f64 {
+        self.max_intrinsic(ctx, Intrinsic::MinWidth, height)
+    }
+
+    /// The largest maximum intrinsic width of the children at `height`.
+    pub fn max_intrinsic_width(&mut self, ctx: &mut LayoutCtx, height: f64) -> f64 {
+        self.max_intrinsic(ctx, Intrinsic::MaxWidth, height)
+    }
+
+    /// The largest minimum intrinsic height of the children at `width`.
+    pub fn min_intrinsic_height(&mut self, ctx: &mut LayoutCtx, width: f64) -> f64 {
+        self.max_intrinsic(ctx, Intrinsic::MinHeight, width)
+    }
+
+    /// The largest maximum intrinsic height of the children at `width`.
+    pub fn max_intrinsic_height(&mut self, ctx: &mut LayoutCtx, width: f64) -> f64 {
+        self.max_intrinsic(ctx, Intrinsic::MaxHeight, width)
+    }
+
+    fn max_intrinsic(&mut self, ctx: &mut LayoutCtx, intrinsic: Intrinsic, extent: f64) -> f64 {
+        self.iter()
+            .map(|child| child.intrinsic(ctx, intrinsic, extent))
+            .fold(0.0, f64::max)
+    }
 }
 
 impl Index<usize> for Children {
@@ -186,8 +318,27 @@ impl Child {
             // from other points in the library.
             return;
         }
+        if ctx.state.pointer_captured && !self.state.has_capture && is_pointer_event(event) {
+            // Another widget captured the pointer.
+            ctx.child_state.merge_up(&mut self.state);
+            return;
+        }
+        if self.state.environment.is_disabled() && !self.state.has_active && is_input_event(event) {
+            // Let a press that started before the widget was disabled finish.
+            ctx.child_state.merge_up(&mut self.state);
+            return;
+        }
+        if self.state.painted_raised
+            && ctx.raised_child != Some(self.state.id)
+            && is_pointer_event(event)
+        {
+            // Received the event before its siblings, see `Children::event_raised`.
+            ctx.child_state.merge_up(&mut self.state);
+            return;
+        }
         let had_active = self.state.has_active;
-        let rect = self.layout_rect();
+        let rect = self.state.size.to_rect();
+        let to_local = self.state.parent_to_local();
 
         // If we need to replace either the event or its data.
         let mut modified_event = None;
@@ -218,11 +369,11 @@ impl Child {
                     &mut self.state,
                     ctx.state,
                     rect,
-                    Some(mouse_event.pos),
+                    Some(to_local * mouse_event.pos),
                 );
                 if had_active || self.state.is_hot {
                     let mut mouse_event = mouse_event.clone();
-                    mouse_event.pos -= rect.origin().to_vec2();
+                    mouse_event.pos = to_local * mouse_event.pos;
                     modified_event = Some(Event::MouseDown(mouse_event));
                     true
                 } else {
@@ -235,11 +386,11 @@ impl Child {
                     &mut self.state,
                     ctx.state,
                     rect,
-                    Some(mouse_event.pos),
+                    Some(to_local * mouse_event.pos),
                 );
                 if had_active || self.state.is_hot {
                     let mut mouse_event = mouse_event.clone();
-                    mouse_event.pos -= rect.origin().to_vec2();
+                    mouse_event.pos = to_local * mouse_event.pos;
                     modified_event = Some(Event::MouseUp(mouse_event));
                     true
                 } else {
@@ -252,14 +403,14 @@ impl Child {
                     &mut self.state,
                     ctx.state,
                     rect,
-                    Some(mouse_event.pos),
+                    Some(to_local * mouse_event.pos),
                 );
                 // MouseMove is recursed even if the widget is not active and not hot,
                 // but was hot previously. This is to allow the widget to respond to the movement,
                 // e.g. drag functionality where the widget wants to follow the mouse.
                 if had_active || self.state.is_hot || hot_changed {
                     let mut mouse_event = mouse_event.clone();
-                    mouse_event.pos -= rect.origin().to_vec2();
+                    mouse_event.pos = to_local * mouse_event.pos;
                     modified_event = Some(Event::MouseMove(mouse_event));
                     true
                 } else {
@@ -272,11 +423,11 @@ impl Child {
                     &mut self.state,
                     ctx.state,
                     rect,
-                    Some(mouse_event.pos),
+                    Some(to_local * mouse_event.pos),
                 );
                 if had_active || self.state.is_hot {
                     let mut mouse_event = mouse_event.clone();
-                    mouse_event.pos -= rect.origin().to_vec2();
+                    mouse_event.pos = to_local * mouse_event.pos;
                     modified_event = Some(Event::Wheel(mouse_event));
                     true
                 } else {
@@ -292,8 +443,27 @@ impl Child {
             Event::KeyUp(_) => self.state.has_focus,
             Event::Paste(_) => self.state.has_focus,
             Event::Zoom(_) => had_active || self.state.is_hot,
-            Event::Timer(_) => false, // This event was targeted only to our parent
-            Event::Command(_) => true,
+            // Only the subtree that requested the timer receives it.
+            Event::Timer(token) => self.state.timers.remove(token).is_some(),
+            Event::Command(cmd) => match (cmd.get(ROUTE_COMMAND), cmd.get(GESTURE)) {
+                // Only the target receives routed commands, and only the path to it.
+                (Some((target, cmd)), _) if *target == self.state.id => {
+                    modified_event = Some(Event::Command(cmd.clone()));
+                    true
+                }
+                (Some((target, _)), _) => self.state.children.may_contain(target),
+                // Gestures go where the mouse events they were made of go.
+                (None, Some(gesture)) => {
+                    if had_active || self.state.is_hot {
+                        let gesture = gesture.transform(to_local);
+                        modified_event = Some(Event::Command(GESTURE.with(gesture)));
+                        true
+                    } else {
+                        false
+                    }
+                }
+                (None, None) => true,
+            },
             Event::Notification(_) => false,
         };
 
@@ -303,14 +473,24 @@ impl Child {
                 child_state: &mut self.state,
                 is_handled: false,
                 is_root: false,
+                raised_child: None,
             };
             let inner_event = modified_event.as_ref().unwrap_or(event);
             inner_ctx.child_state.has_active = false;
+            inner_ctx.child_state.has_capture = false;
 
+            self.children.event_raised(&mut inner_ctx, inner_event);
             self.object
                 .event(&mut inner_ctx, &inner_event, &mut self.children);
 
+            // Captures end with the release of the last button at the latest.
+            if let Event::MouseUp(mouse_event) = inner_event {
+                if mouse_event.buttons.is_empty() && inner_ctx.child_state.captures_pointer {
+                    inner_ctx.release_pointer();
+                }
+            }
             inner_ctx.child_state.has_active |= inner_ctx.child_state.is_active;
+            inner_ctx.child_state.has_capture |= inner_ctx.child_state.captures_pointer;
             ctx.is_handled |= inner_ctx.is_handled;
         }
 
@@ -329,11 +509,16 @@ impl Child {
     }
 
     pub fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let layout_key = Some((bc.min(), bc.max(), ctx.state.window_size));
+        if !self.state.needs_layout && self.state.layout_key == layout_key {
+            return self.state.size;
+        }
+        self.state.layout_key = layout_key;
         self.state.needs_layout = false;
         self.state.is_expecting_set_origin_call = true;
 
         let child_mouse_pos = match ctx.mouse_pos {
-            Some(pos) => Some(pos - self.layout_rect().origin().to_vec2() + self.viewport_offset()),
+            Some(pos) => Some(self.state.parent_to_local() * pos + self.viewport_offset()),
             None => None,
         };
         let prev_size = self.state.size;
@@ -345,7 +530,17 @@ impl Child {
             env: ctx.env,
         };
 
-        let new_size = self.object.layout(&mut child_ctx, bc, &mut self.children);
+        let layout_debug = child_ctx.state.layout_debug.clone();
+        if let Some(layout_debug) = &layout_debug {
+            layout_debug.borrow_mut().enter(self.object.name(), bc);
+        }
+        let new_size = {
+            let _span = profile::widget_span(self.object.name(), Phase::Layout);
+            let start = profile::enter();
+            let new_size = self.object.layout(&mut child_ctx, bc, &mut self.children);
+            profile::exit(start, self.object.name(), Phase::Layout);
+            new_size
+        };
         if new_size != prev_size {
             let mut child_ctx = LifeCycleCtx {
                 child_state: child_ctx.child_state,
@@ -356,12 +551,74 @@ impl Child {
         }
 
         ctx.child_state.merge_up(&mut child_ctx.child_state);
+        if let Some(rect) = self.state.scroll_to_view.take() {
+            ctx.child_state.scroll_to_view = Some(rect);
+        }
         self.state.size = new_size;
-        self.log_layout_issues(new_size);
+        self.update_touch_bounds();
+        match &layout_debug {
+            Some(layout_debug) => {
+                let mut layout_debug = layout_debug.borrow_mut();
+                layout_debug.report_infinite(new_size);
+                let overflow = overflow(new_size, bc.max());
+                if overflow != Size::ZERO {
+                    layout_debug.report(&format!(
+                        "Larger than its constraints by {}x{}, it overflows its parent",
+                        overflow.width, overflow.height
+                    ));
+                }
+                self.state.overflow = overflow;
+                layout_debug.exit();
+            }
+            None => self.log_layout_issues(new_size),
+        }
 
         new_size
     }
 
+    /// The smallest width the child can take at `height` without its content
+    /// being cut off or overflowing.
+    ///
+    /// Unlike [`layout`](Child::layout), this does not change the layout of
+    /// the child, so a container can ask before it decides on the
+    /// constraints of its children. It walks the whole subtree, so it is
+    /// best asked once per layout pass.
+    pub fn min_intrinsic_width(&mut self, ctx: &mut LayoutCtx, height: f64) -> f64 {
+        self.intrinsic(ctx, Intrinsic::MinWidth, height)
+    }
+
+    /// The width beyond which the child does not get any shorter at `height`.
+    pub fn max_intrinsic_width(&mut self, ctx: &mut LayoutCtx, height: f64) -> f64 {
+        self.intrinsic(ctx, Intrinsic::MaxWidth, height)
+    }
+
+    /// The smallest height the child can take at `width` without its content
+    /// being cut off or overflowing.
+    pub fn min_intrinsic_height(&mut self, ctx: &mut LayoutCtx, width: f64) -> f64 {
+        self.intrinsic(ctx, Intrinsic::MinHeight, width)
+    }
+
+    /// The height beyond which the child does not get any narrower at `width`.
+    pub fn max_intrinsic_height(&mut self, ctx: &mut LayoutCtx, width: f64) -> f64 {
+        self.intrinsic(ctx, Intrinsic::MaxHeight, width)
+    }
+
+    pub(crate) fn intrinsic(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        intrinsic: Intrinsic,
+        extent: f64,
+    ) -> f64 {
+        let mut child_ctx = LayoutCtx {
+            state: ctx.state,
+            child_state: &mut self.state,
+            mouse_pos: None,
+            env: ctx.env,
+        };
+        self.object
+            .compute_intrinsic(&mut child_ctx, intrinsic, extent, &mut self.children)
+    }
+
     fn log_layout_issues(&self, size: Size) {
         if size.width.is_infinite() {
             let name = self.object.name();
@@ -374,13 +631,50 @@ impl Child {
     }
 
     pub fn paint(&mut self, ctx: &mut PaintCtx) {
+        if self.state.z_index > 0 && ctx.raised_child != Some(self.state.id) {
+            // Painted after its siblings, see `Children::paint_raised`.
+            ctx.raised.push(RaisedPaint {
+                id: self.state.id,
+                z_index: self.state.z_index,
+                transform: ctx.render_ctx.current_transform(),
+                region: ctx.region.clone(),
+                viewport: ctx.viewport,
+            });
+            return;
+        }
+        if self.state.is_expecting_set_origin_call {
+            if let Some(layout_debug) = &ctx.state.layout_debug {
+                let mut layout_debug = layout_debug.borrow_mut();
+                layout_debug.path.push(self.object.name());
+                layout_debug.report("Painted before its parent called set_origin");
+                layout_debug.path.pop();
+            }
+        }
         ctx.with_save(|ctx| {
-            let layout_origin = self.layout_rect().origin().to_vec2();
-            ctx.transform(Affine::translate(layout_origin));
+            let to_parent = self.state.local_to_parent();
+            ctx.transform(to_parent);
+            let bounds = self.state.parent_paint_rect();
             let mut visible = ctx.region().clone();
-            visible.intersect_with(self.state.paint_rect());
-            visible -= layout_origin;
-            ctx.with_child_ctx(visible, |ctx| self.paint_raw(ctx));
+            visible.intersect_with(bounds);
+            let viewport = ctx.viewport().intersect(bounds);
+            let to_local = to_parent.inverse();
+            self.state.visible = Some(if viewport.area() == 0.0 {
+                Rect::ZERO
+            } else {
+                to_local.transform_rect_bbox(viewport)
+            });
+            // Only the damaged parts of the window are repainted, and nothing that is clipped away.
+            if visible.is_empty() || viewport.area() == 0.0 {
+                return;
+            }
+            let mut local = Region::EMPTY;
+            for rect in visible.rects() {
+                local.add_rect(to_local.transform_rect_bbox(*rect));
+            }
+            ctx.with_child_ctx(local, |ctx| {
+                ctx.viewport = to_local.transform_rect_bbox(viewport);
+                self.paint_raw(ctx)
+            });
         });
     }
 
@@ -406,11 +700,24 @@ impl Child {
             state: ctx.state,
             z_ops: Vec::new(),
             region: ctx.region.clone(),
+            viewport: ctx.viewport,
             child_state: &self.state,
             depth: ctx.depth,
             env: ctx.env,
+            raised: Vec::new(),
+            raised_child: None,
         };
-        self.object.paint(&mut inner_ctx, &mut self.children);
+        {
+            let _span = profile::widget_span(self.object.name(), Phase::Paint);
+            let start = profile::enter();
+            self.object.paint(&mut inner_ctx, &mut self.children);
+            self.children.paint_raised(&mut inner_ctx);
+            profile::exit(start, self.object.name(), Phase::Paint);
+        }
+
+        if self.state.overflow != Size::ZERO {
+            paint_overflow(&mut inner_ctx, self.state.size, self.state.overflow);
+        }
 
         // let debug_ids = inner_ctx.is_hot() && env.get(Env::DEBUG_WIDGET_ID);
         // if debug_ids {
@@ -450,22 +757,35 @@ impl Child {
         self.state.is_hot
     }
 
+    /// The style of type `S` this widget was last painted with.
+    ///
+    /// See [`PaintCtx::publish_style`] for more information.
+    pub fn resolved_style<S: Any + Clone>(&self) -> Option<S> {
+        self.state.resolved_style()
+    }
+
     /// Set the origin of this widget, in the parent's coordinate space.
     ///
-    /// A container widget should call the [`Widget::layout`] method on its children in
-    /// its own [`Widget::layout`] implementation, and then call `set_origin` to
-    /// position those children.
+    /// A container render object should call [`layout`] on its children in
+    /// its own [`RenderObjectInterface::layout`] implementation, and then call `set_origin`
+    /// to position those children, also when they are at the origin.
+    /// [`paint`] and hit testing use this position, so there is no need to translate
+    /// the paint context. With [`App::debug_layout`], painting a child whose origin
+    /// was not set after its last layout is reported.
     ///
     /// The child will receive the [`LifeCycle::Size`] event informing them of the final [`Size`].
     ///
-    /// [`Widget::layout`]: trait.Widget.html#tymethod.layout
-    /// [`Rect`]: struct.Rect.html
-    /// [`Size`]: struct.Size.html
-    /// [`LifeCycle::Size`]: enum.LifeCycle.html#variant.Size
+    /// [`layout`]: Child::layout
+    /// [`paint`]: Child::paint
+    /// [`RenderObjectInterface::layout`]: crate::object::RenderObjectInterface::layout
+    /// [`App::debug_layout`]: crate::app::App::debug_layout
+    /// [`Size`]: crate::kurbo::Size
+    /// [`LifeCycle::Size`]: crate::event::LifeCycle::Size
     pub fn set_origin(&mut self, ctx: &mut LayoutCtx, origin: Point) {
         self.state.origin = origin;
         self.state.is_expecting_set_origin_call = false;
-        let layout_rect = self.layout_rect();
+        let rect = self.state.size.to_rect();
+        let mouse_pos = ctx.mouse_pos.map(|pos| self.state.parent_to_local() * pos);
 
         // if the widget has moved, it may have moved under the mouse, in which
         // case we need to handle that.
@@ -473,8 +793,8 @@ impl Child {
             self.object.as_mut(),
             &mut self.state,
             ctx.state,
-            layout_rect,
-            ctx.mouse_pos,
+            rect,
+            mouse_pos,
         ) {
             ctx.child_state.merge_up(&mut self.state);
         }
@@ -485,10 +805,10 @@ impl Child {
     /// This will be a [`Rect`] with a [`Size`] determined by the child's [`layout`]
     /// method, and the origin that was set by [`set_origin`].
     ///
-    /// [`Rect`]: struct.Rect.html
-    /// [`Size`]: struct.Size.html
-    /// [`layout`]: trait.Widget.html#tymethod.layout
-    /// [`set_origin`]: WidgetPod::set_origin
+    /// [`Rect`]: crate::kurbo::Rect
+    /// [`Size`]: crate::kurbo::Size
+    /// [`layout`]: Child::layout
+    /// [`set_origin`]: Child::set_origin
     pub fn layout_rect(&self) -> Rect {
         self.state.layout_rect()
     }
@@ -521,6 +841,44 @@ impl Child {
         self.state.viewport_offset
     }
 
+    /// Transform the child in addition to its origin, like scaling or rotating it.
+    ///
+    /// The transform applies to painting and to the positions of pointer
+    /// events, it does not change the layout. The parent has to request a
+    /// paint when the transform changes. [`EventCtx::window_origin`] and the
+    /// positions derived from it ignore the transform.
+    ///
+    /// [`EventCtx::window_origin`]: crate::context::EventCtx::window_origin
+    pub fn set_transform(&mut self, transform: Affine) {
+        self.state.transform = transform;
+    }
+
+    /// The transform set with [`set_transform`].
+    ///
+    /// [`set_transform`]: Child::set_transform
+    pub fn transform(&self) -> Affine {
+        self.state.transform
+    }
+
+    /// Raise the child above its siblings, like [`EventCtx::set_z_index`] does.
+    ///
+    /// The parent has to request a paint when the z-index changes.
+    ///
+    /// [`EventCtx::set_z_index`]: crate::context::EventCtx::set_z_index
+    pub fn set_z_index(&mut self, z_index: u32) {
+        self.state.z_index = z_index;
+        if z_index == 0 {
+            self.state.painted_raised = false;
+        }
+    }
+
+    /// The z-index of the child, see [`set_z_index`].
+    ///
+    /// [`set_z_index`]: Child::set_z_index
+    pub fn z_index(&self) -> u32 {
+        self.state.z_index
+    }
+
     /// Get the widget's paint [`Rect`].
     ///
     /// This is the [`Rect`] that widget has indicated it needs to paint in.
@@ -573,9 +931,33 @@ impl Child {
         self.state.baseline_offset
     }
 
+    /// Grow the layout rect to the touch targets of the widget and its
+    /// descendants, which were laid out already.
+    fn update_touch_bounds(&mut self) {
+        let rect = self.state.size.to_rect();
+        let mut bounds = match self.state.is_hit_target {
+            true => expand_to_touch_target(rect),
+            false => rect,
+        };
+        for child in &mut self.children {
+            if !child.state.is_layer {
+                let child_bounds = child
+                    .state
+                    .local_to_parent()
+                    .transform_rect_bbox(child.state.touch_bounds);
+                bounds = bounds.union(child_bounds);
+            }
+        }
+        self.state.touch_bounds = bounds;
+    }
+
     /// Determines if the provided `mouse_pos` is inside `rect`
     /// and if so updates the hot state and sends `LifeCycle::HotChanged`.
     ///
+    /// While touch input is active, the touch bounds of the widget are used
+    /// instead of `rect`. Outside of `rect`, the point of it nearest to
+    /// `mouse_pos` has to be visible and part of the widget.
+    ///
     /// Returns `true` if the hot state changed.
     ///
     /// The provided `child_state` should be merged up if this returns `true`.
@@ -588,7 +970,15 @@ impl Child {
     ) -> bool {
         let had_hot = child_state.is_hot;
         child_state.is_hot = match mouse_pos {
-            Some(pos) => rect.winding(pos) != 0,
+            Some(pos) if state.touch_mode => {
+                let nearest = nearest_point(rect, pos);
+                child_state.touch_bounds.winding(pos) != 0
+                    && child_state.is_visible_at(nearest)
+                    && child.hit_test(nearest)
+            }
+            Some(pos) => {
+                rect.winding(pos) != 0 && child_state.is_visible_at(pos) && child.hit_test(pos)
+            }
             None => false,
         };
         if had_hot != child_state.is_hot {
@@ -609,11 +999,18 @@ impl Child {
     pub(crate) fn update_focus(&mut self, focus: Option<ChildId>) -> bool {
         if let Some(focus) = focus {
             if self.state.id == focus {
+                // Bring the newly focused widget into view.
+                if !self.state.has_focus {
+                    let rect = self.state.size.to_rect() + self.state.window_origin().to_vec2();
+                    self.state.scroll_to_view = Some(rect);
+                    self.state.needs_layout = true;
+                }
                 self.state.has_focus = true;
             } else if self.state.children.may_contain(&focus) {
                 self.state.has_focus = false;
                 for child in self.children.iter() {
                     self.state.has_focus |= child.update_focus(Some(focus));
+                    self.state.needs_layout |= child.state.needs_layout;
                 }
             }
         } else {
@@ -632,6 +1029,263 @@ impl Child {
     }
 }
 
+/// Grow `rect` around its center to at least [`MIN_TOUCH_TARGET`] in both directions.
+fn expand_to_touch_target(rect: Rect) -> Rect {
+    let dx = (MIN_TOUCH_TARGET - rect.width()).max(0.0) / 2.0;
+    let dy = (MIN_TOUCH_TARGET - rect.height()).max(0.0) / 2.0;
+    rect.inset(Insets::uniform_xy(dx, dy))
+}
+
+/// The point of `rect` nearest to `pos`, kept half a pixel inside of its
+/// edges, which are not part of it.
+fn nearest_point(rect: Rect, pos: Point) -> Point {
+    let dx = (rect.width() / 2.0).min(0.5);
+    let dy = (rect.height() / 2.0).min(0.5);
+    Point::new(
+        pos.x.max(rect.x0 + dx).min(rect.x1 - dx),
+        pos.y.max(rect.y0 + dy).min(rect.y1 - dy),
+    )
+}
+
+/// Overlay layers.
+impl Child {
+    /// Recompute the window origin of this subtree.
+    ///
+    /// This has to be called after each layout pass, once all origins are known.
+    /// Widgets that moved receive `LifeCycle::ViewContextChanged`.
+    pub(crate) fn update_window_origin(
+        &mut self,
+        state: &mut ContextState,
+        parent_window_origin: Point,
+    ) {
+        self.state.parent_window_origin = parent_window_origin;
+        let window_origin = self.state.window_origin();
+        if self.state.reported_window_origin != Some(window_origin) {
+            self.state.reported_window_origin = Some(window_origin);
+            let event = LifeCycle::ViewContextChanged(ViewContext { window_origin });
+            let mut ctx = LifeCycleCtx {
+                state,
+                child_state: &mut self.state,
+            };
+            self.object.lifecycle(&mut ctx, &event);
+        }
+        for child in self.children.iter() {
+            child.update_window_origin(state, window_origin);
+        }
+    }
+
+    /// Deliver `LifeCycle::ScaleChanged` to this subtree, and lay it out again.
+    pub(crate) fn change_scale(&mut self, state: &mut ContextState, scale: Scale) {
+        self.state.needs_layout = true;
+        let mut ctx = LifeCycleCtx {
+            state,
+            child_state: &mut self.state,
+        };
+        self.object
+            .lifecycle(&mut ctx, &LifeCycle::ScaleChanged(scale));
+        for child in self
+            .children
+            .renders
+            .iter_mut()
+            .chain(&mut self.children.retained)
+        {
+            child.change_scale(state, scale);
+        }
+    }
+
+    /// Deliver `LifeCycle::WidgetRemoved` to this subtree, before it is dropped.
+    ///
+    /// Parents receive it before their children, retained children included.
+    pub(crate) fn remove(&mut self, state: &mut ContextState) {
+        let mut ctx = LifeCycleCtx {
+            state,
+            child_state: &mut self.state,
+        };
+        self.object.lifecycle(&mut ctx, &LifeCycle::WidgetRemoved);
+        for child in self
+            .children
+            .renders
+            .iter_mut()
+            .chain(&mut self.children.retained)
+        {
+            child.remove(state);
+        }
+    }
+}
+
+impl Children {
+    /// Dispatch pointer events to the children painted above their siblings,
+    /// topmost first, before the container dispatches them to the others.
+    pub(crate) fn event_raised(&mut self, ctx: &mut EventCtx, event: &Event) {
+        if !is_pointer_event(event) {
+            return;
+        }
+        let mut raised: Vec<&mut Child> = self
+            .renders
+            .iter_mut()
+            .filter(|child| child.state.painted_raised)
+            .collect();
+        raised.sort_by_key(|child| Reverse(child.state.z_index));
+        for child in raised {
+            ctx.raised_child = Some(child.state.id);
+            child.event(ctx, event);
+        }
+        ctx.raised_child = None;
+    }
+
+    /// Paint the raised children the container skipped, above everything it
+    /// painted, lowest z-index first.
+    pub(crate) fn paint_raised(&mut self, ctx: &mut PaintCtx) {
+        for child in &mut self.renders {
+            child.state.painted_raised = false;
+        }
+        let mut raised = std::mem::take(&mut ctx.raised);
+        if raised.is_empty() {
+            return;
+        }
+        raised.sort_by_key(|paint| paint.z_index);
+        let to_current = ctx.render_ctx.current_transform().inverse();
+        let (region, viewport) = (ctx.region.clone(), ctx.viewport);
+        for paint in raised {
+            let child = match self.renders.iter_mut().find(|c| c.state.id == paint.id) {
+                Some(child) => child,
+                None => continue,
+            };
+            child.state.painted_raised = true;
+            let (id, transform) = (paint.id, paint.transform);
+            ctx.region = paint.region;
+            ctx.viewport = paint.viewport;
+            ctx.with_save(|ctx| {
+                ctx.transform(to_current * transform);
+                ctx.raised_child = Some(id);
+                child.paint(ctx);
+                ctx.raised_child = None;
+            });
+        }
+        ctx.region = region;
+        ctx.viewport = viewport;
+    }
+
+    /// Dispatch an event to all layers in these subtrees, topmost first.
+    ///
+    /// Mouse positions are expected in window coordinates.
+    ///
+    /// A click outside of a layer or the escape key will send [`DISMISS_OVERLAY`]
+    /// to the layer instead of the original event.
+    pub(crate) fn event_layers(&mut self, ctx: &mut EventCtx, event: &Event) {
+        for index in (0..self.len()).rev() {
+            let child = &mut self.renders[index];
+
+            let mut inner_ctx = EventCtx {
+                state: ctx.state,
+                child_state: &mut child.state,
+                is_handled: false,
+                is_root: false,
+                raised_child: None,
+            };
+            // Layers nested inside of another node are above that node.
+            child.children.event_layers(&mut inner_ctx, event);
+            ctx.is_handled |= inner_ctx.is_handled;
+            ctx.child_state.merge_up(&mut child.state);
+
+            if child.state.is_layer {
+                let offset = child.state.parent_window_origin.to_vec2();
+                let window_rect = child.layout_rect() + offset;
+                let layer_event = match event {
+                    Event::MouseDown(mouse) if window_rect.winding(mouse.pos) == 0 => {
+                        Event::Command(DISMISS_OVERLAY.with(DismissReason::ClickOutside))
+                    }
+                    Event::KeyDown(key) if key.key == KbKey::Escape => {
+                        Event::Command(DISMISS_OVERLAY.with(DismissReason::Escape))
+                    }
+                    _ => translate_mouse_event(event, -offset),
+                };
+                let blocks_input = child.state.is_modal && is_input_event(event);
+                if blocks_input && !child.state.has_focus {
+                    // Keep the focus inside of the modal layer.
+                    ctx.child_state.request_focus = Some(FocusChange::Focus(child.state.id));
+                }
+                child.event(ctx, &layer_event);
+                if blocks_input {
+                    ctx.set_handled();
+                }
+            }
+        }
+    }
+
+    /// Paint all layers in these subtrees.
+    ///
+    /// Returns `true` if any layer was painted.
+    pub(crate) fn paint_layers(&mut self, ctx: &mut PaintCtx) -> bool {
+        let mut painted = false;
+        for child in self.iter() {
+            if child.state.is_layer {
+                let offset = child.state.parent_window_origin.to_vec2();
+                let mut region = ctx.region().clone();
+                region -= offset;
+                ctx.with_save(|ctx| {
+                    ctx.transform(Affine::translate(offset));
+                    ctx.with_child_ctx(region, |ctx| {
+                        ctx.viewport = ctx.viewport - offset;
+                        child.paint(ctx)
+                    });
+                });
+                painted = true;
+            }
+            painted |= child.children.paint_layers(ctx);
+        }
+        painted
+    }
+}
+
+/// Whether the event originates from user input.
+pub(crate) fn is_input_event(event: &Event) -> bool {
+    matches!(
+        event,
+        Event::MouseDown(_)
+            | Event::MouseUp(_)
+            | Event::MouseMove(_)
+            | Event::Wheel(_)
+            | Event::KeyDown(_)
+            | Event::KeyUp(_)
+            | Event::Paste(_)
+            | Event::Zoom(_)
+    ) || Gesture::from_event(event).is_some()
+}
+
+/// Whether the event is delivered according to the pointer position.
+fn is_pointer_event(event: &Event) -> bool {
+    matches!(
+        event,
+        Event::MouseDown(_)
+            | Event::MouseUp(_)
+            | Event::MouseMove(_)
+            | Event::Wheel(_)
+            | Event::Zoom(_)
+    ) || Gesture::from_event(event).is_some()
+}
+
+/// Move the position of mouse events and gestures by `offset`.
+fn translate_mouse_event(event: &Event, offset: Vec2) -> Event {
+    let translate = |mouse_event: &MouseEvent| {
+        let mut mouse_event = mouse_event.clone();
+        mouse_event.pos += offset;
+        mouse_event
+    };
+    match event {
+        Event::MouseDown(mouse_event) => Event::MouseDown(translate(mouse_event)),
+        Event::MouseUp(mouse_event) => Event::MouseUp(translate(mouse_event)),
+        Event::MouseMove(mouse_event) => Event::MouseMove(translate(mouse_event)),
+        Event::Wheel(mouse_event) => Event::Wheel(translate(mouse_event)),
+        event => match Gesture::from_event(event) {
+            Some(gesture) => {
+                Event::Command(GESTURE.with(gesture.transform(Affine::translate(offset))))
+            }
+            None => event.clone(),
+        },
+    }
+}
+
 /// Allows iterating over a set of [`Children`].
 pub struct ChildIter<'a> {
     children: &'a mut Children,
@@ -671,16 +1325,25 @@ impl ChildState {
             id,
             origin: Point::ORIGIN,
             parent_window_origin: Point::ORIGIN,
+            reported_window_origin: None,
             size: size.unwrap_or_default(),
             is_expecting_set_origin_call: true,
             paint_insets: Insets::ZERO,
             invalid: Region::EMPTY,
             viewport_offset: Vec2::ZERO,
+            transform: Affine::default(),
             baseline_offset: 0.0,
+            overflow: Size::ZERO,
             is_hot: false,
             needs_layout: false,
+            layout_key: None,
             is_active: false,
             has_active: false,
+            captures_pointer: false,
+            has_capture: false,
+            visible: None,
+            z_index: 0,
+            painted_raised: false,
             has_focus: false,
             request_anim: false,
             request_update: false,
@@ -691,10 +1354,31 @@ impl ChildState {
             timers: HashMap::new(),
             cursor_change: CursorChange::Default,
             cursor: None,
+            is_layer: false,
+            is_modal: false,
+            is_hit_target: false,
+            touch_bounds: Rect::ZERO,
+            notifications: Vec::new(),
+            scroll_to_view: None,
+            environment: Environment::default(),
+            resolved_style: RefCell::new(None),
             //sub_window_hosts: Vec::new(),
         }
     }
 
+    /// Whether `pos` was visible in the last paint pass, or the widget was not painted yet.
+    fn is_visible_at(&self, pos: Point) -> bool {
+        match self.visible {
+            Some(visible) => visible.contains(pos),
+            None => true,
+        }
+    }
+
+    pub(crate) fn resolved_style<S: Any + Clone>(&self) -> Option<S> {
+        let style = self.resolved_style.borrow();
+        style.as_ref()?.downcast_ref().cloned()
+    }
+
     pub(crate) fn add_timer(&mut self, timer_token: TimerToken) {
         self.timers.insert(timer_token, self.id);
     }
@@ -705,31 +1389,18 @@ impl ChildState {
     ///
     /// This method is idempotent and can be called multiple times.
     fn merge_up(&mut self, child_state: &mut ChildState) {
-        let clip = self
-            .layout_rect()
-            .with_origin(Point::ORIGIN)
-            .inset(self.paint_insets);
-        let offset = child_state.layout_rect().origin().to_vec2() - child_state.viewport_offset;
-        for &r in child_state.invalid.rects() {
-            let r = (r + offset).intersect(clip);
-            if r.area() != 0.0 {
-                self.invalid.add_rect(r);
-            }
-        }
-        // Clearing the invalid rects here is less fragile than doing it while painting. The
-        // problem is that widgets (for example, Either) might choose not to paint certain
-        // invisible children, and we shouldn't allow these invisible children to accumulate
-        // invalid rects.
-        child_state.invalid.clear();
-
-        self.needs_layout |= child_state.needs_layout;
+        self.merge_damage(child_state);
         self.request_anim |= child_state.request_anim;
         self.has_active |= child_state.has_active;
+        self.has_capture |= child_state.has_capture;
         self.has_focus |= child_state.has_focus;
         //self.children_changed |= child_state.children_changed;
         self.request_update |= child_state.request_update;
         self.request_focus = child_state.request_focus.take().or(self.request_focus);
-        //self.timers.extend_drain(&mut child_state.timers);
+        self.notifications.append(&mut child_state.notifications);
+        // Ancestors keep track of timers as well, so they can be routed.
+        self.timers
+            .extend(child_state.timers.iter().map(|(token, id)| (*token, *id)));
 
         // We reset `child_state.cursor` no matter what, so that on the every pass through the tree,
         // things will be recalculated just from `cursor_change`.
@@ -747,10 +1418,32 @@ impl ChildState {
         }
     }
 
+    /// Take the areas a child needs to repaint, and whether it needs a layout.
+    pub(crate) fn merge_damage(&mut self, child_state: &mut ChildState) {
+        let clip = self
+            .layout_rect()
+            .with_origin(Point::ORIGIN)
+            .inset(self.paint_insets);
+        let to_parent =
+            Affine::translate(-child_state.viewport_offset) * child_state.local_to_parent();
+        for &r in child_state.invalid.rects() {
+            let r = to_parent.transform_rect_bbox(r).intersect(clip);
+            if r.area() != 0.0 {
+                self.invalid.add_rect(r);
+            }
+        }
+        // Clearing the invalid rects here is less fragile than doing it while painting. The
+        // problem is that widgets (for example, Either) might choose not to paint certain
+        // invisible children, and we shouldn't allow these invisible children to accumulate
+        // invalid rects.
+        child_state.invalid.clear();
+        self.needs_layout |= child_state.needs_layout;
+    }
+
     /// Because of how cursor merge logic works, we need to handle the leaf case;
     /// in that case there will be nothing in the `cursor` field (as merge_up
     /// is never called) and so we need to also check the `cursor_change` field.
-    fn take_cursor(&mut self) -> Option<Cursor> {
+    pub(crate) fn take_cursor(&mut self) -> Option<Cursor> {
         self.cursor.take().or_else(|| self.cursor_change.cursor())
     }
 
@@ -772,6 +1465,22 @@ impl ChildState {
         Rect::from_origin_size(self.origin, self.size)
     }
 
+    /// Maps the coordinate space of the widget to the one of its parent.
+    pub(crate) fn local_to_parent(&self) -> Affine {
+        Affine::translate(self.origin.to_vec2()) * self.transform
+    }
+
+    /// Maps the coordinate space of the parent to the one of the widget.
+    pub(crate) fn parent_to_local(&self) -> Affine {
+        self.local_to_parent().inverse()
+    }
+
+    /// The bounds of the paint rect in the coordinate space of the parent.
+    pub(crate) fn parent_paint_rect(&self) -> Rect {
+        let local = self.size.to_rect() + self.paint_insets;
+        self.local_to_parent().transform_rect_bbox(local)
+    }
+
     // pub(crate) fn add_sub_window_host(&mut self, window_id: WindowId, host_id: WidgetId) {
     //     self.sub_window_hosts.push((window_id, host_id))
     // }
@@ -781,6 +1490,43 @@ impl ChildState {
     }
 }
 
+/// How far `size` exceeds `max`, ignoring infinite sizes.
+fn overflow(size: Size, max: Size) -> Size {
+    let excess = |size: f64, max: f64| {
+        if size.is_finite() && size > max {
+            size - max
+        } else {
+            0.0
+        }
+    };
+    Size::new(
+        excess(size.width, max.width),
+        excess(size.height, max.height),
+    )
+}
+
+/// Mark the parts of a widget `size` large that exceed its constraints by
+/// `overflow` with yellow and black stripes.
+fn paint_overflow(ctx: &mut PaintCtx, size: Size, overflow: Size) {
+    const STRIPE_WIDTH: f64 = 6.0;
+    let yellow = Color::rgba8(0xff, 0xd6, 0x00, 0xc0);
+    let black = Color::rgba8(0x00, 0x00, 0x00, 0xc0);
+    let right = Rect::new(size.width - overflow.width, 0.0, size.width, size.height);
+    let bottom = Rect::new(0.0, size.height - overflow.height, size.width, size.height);
+    for rect in [right, bottom].iter().filter(|rect| rect.area() > 0.0) {
+        ctx.with_save(|ctx| {
+            ctx.clip(*rect);
+            ctx.fill(*rect, &yellow);
+            let mut x = rect.x0 - rect.height();
+            while x < rect.x1 {
+                let line = Line::new((x, rect.y1), (x + rect.height(), rect.y0));
+                ctx.stroke(line, &black, STRIPE_WIDTH);
+                x += STRIPE_WIDTH * 2.0;
+            }
+        });
+    }
+}
+
 impl CursorChange {
     fn cursor(&self) -> Option<Cursor> {
         match self {
@@ -789,3 +1535,276 @@ impl CursorChange {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        kurbo::Vec2,
+        state::Mutable,
+        testing::{self, Harness, Log, Probe, Record},
+        widgets::{link::LinkObject, Flex, Label, Link, SizedBox},
+    };
+    use std::{cell::Cell, rc::Rc};
+
+    fn clicks(log: &Log) -> Vec<(&'static str, Record)> {
+        Probe::take(log, |record| *record == Record::Click)
+    }
+
+    #[test]
+    fn touch_target_expansion() {
+        let small = Rect::new(10.0, 10.0, 30.0, 20.0);
+        let expanded = expand_to_touch_target(small);
+        assert_eq!(
+            expanded.size(),
+            Size::new(MIN_TOUCH_TARGET, MIN_TOUCH_TARGET)
+        );
+        assert_eq!(expanded.center(), small.center());
+
+        let large = Rect::new(0.0, 0.0, 100.0, 100.0);
+        assert_eq!(expand_to_touch_target(large), large);
+
+        assert_eq!(
+            nearest_point(small, Point::new(50.0, 15.0)),
+            Point::new(29.5, 15.0)
+        );
+        assert_eq!(
+            nearest_point(small, Point::new(20.0, 12.0)),
+            Point::new(20.0, 12.0)
+        );
+    }
+
+    #[test]
+    fn touch_targets_at_the_edge_of_their_parent() {
+        let mut harness = Harness::new(|ui| {
+            Mutable::new().use_in(ui, |ui, clicks: &mut usize| {
+                Flex::column().build(ui, |ui| {
+                    SizedBox::new().height(100.0).empty(ui);
+                    SizedBox::new().width(20.0).height(10.0).build(ui, |ui| {
+                        if Link::new().labeled(ui, "Link") {
+                            *clicks += 1;
+                        }
+                    });
+                    Label::new(clicks.to_string()).build(ui);
+                });
+            });
+        });
+        let link =
+            testing::assert_exists::<LinkObject>(harness.tree(), testing::with_label("Link"));
+        // Below the link, outside of the sized box around it.
+        let below = harness.window_rect(link).center() + Vec2::new(0.0, 14.0);
+
+        harness.click(below);
+        assert_eq!(harness.label_texts(), ["Link", "0"]);
+
+        harness.set_touch_mode(true);
+        harness.click(below);
+        assert_eq!(harness.label_texts(), ["Link", "1"]);
+        harness.click(below + Vec2::new(0.0, 20.0));
+        assert_eq!(harness.label_texts(), ["Link", "1"]);
+    }
+
+    #[test]
+    fn clicks_outside_the_shape_reach_the_widgets_below() {
+        let log = Log::default();
+        let probes = log.clone();
+        let mut harness = Harness::new(move |ui| {
+            Flex::column().build(ui, |ui| {
+                Probe::new("below", &probes).build(ui, |ui| {
+                    Probe::new("round", &probes).round().build(ui, |_| {});
+                });
+            });
+        });
+        let below = Probe::find(harness.tree(), "below");
+        let rect = harness.window_rect(below);
+
+        harness.click(rect.origin() + Vec2::new(5.0, 5.0));
+        assert_eq!(clicks(&log), [("below", Record::Click)]);
+        harness.click(rect.center());
+        assert_eq!(clicks(&log), [("round", Record::Click)]);
+    }
+
+    #[test]
+    fn raised_widgets_receive_clicks_first() {
+        let log = Log::default();
+        let z_index = Rc::new(Cell::new(0));
+        let (probes, raised) = (log.clone(), z_index.clone());
+        let mut harness = Harness::new(move |ui| {
+            Flex::column().build(ui, |ui| {
+                Probe::new("stack", &probes).build(ui, |ui| {
+                    Probe::new("a", &probes).build(ui, |_| {});
+                    Probe::new("b", &probes)
+                        .z_index(raised.get())
+                        .build(ui, |_| {});
+                });
+            });
+        });
+        let stack = Probe::find(harness.tree(), "stack");
+        let center = harness.window_rect(stack).center();
+        harness.click(center);
+        assert_eq!(clicks(&log), [("a", Record::Click)]);
+
+        // Raised widgets receive events once they were painted raised.
+        z_index.set(1);
+        harness.rebuild();
+        harness.snapshot();
+        harness.click(center);
+        assert_eq!(clicks(&log), [("b", Record::Click)]);
+
+        z_index.set(0);
+        harness.rebuild();
+        harness.snapshot();
+        harness.click(center);
+        assert_eq!(clicks(&log), [("a", Record::Click)]);
+    }
+
+    #[test]
+    fn layout_only_what_changed() {
+        let log = Log::default();
+        let text = Rc::new(RefCell::new(String::from("Short")));
+        let (probes, label) = (log.clone(), text.clone());
+        let mut harness = Harness::new(move |ui| {
+            Flex::column().build(ui, |ui| {
+                Probe::new("fixed", &probes).build(ui, |_| {});
+                Label::new(label.borrow().clone()).build(ui);
+            });
+        });
+        let layouts = |log: &Log| Probe::take(log, |record| *record == Record::Layout).len();
+        assert_eq!(layouts(&log), 1);
+
+        *text.borrow_mut() = String::from("A longer text");
+        harness.rebuild();
+        assert_eq!(harness.label_texts(), ["A longer text"]);
+        assert_eq!(layouts(&log), 0);
+
+        harness.resize(Size::new(400.0, 300.0));
+        assert_eq!(layouts(&log), 1);
+    }
+
+    fn layouts(log: &Log) -> Vec<&'static str> {
+        let layouts = Probe::take(log, |record| *record == Record::Layout);
+        layouts.into_iter().map(|(name, _)| name).collect()
+    }
+
+    #[test]
+    fn layout_cache_hit() {
+        let log = Log::default();
+        let probes = log.clone();
+        let mut harness = Harness::new(move |ui| {
+            Flex::column().build(ui, |ui| {
+                Probe::new("a", &probes).build(ui, |_| {});
+                Probe::new("b", &probes).build(ui, |_| {});
+            });
+        });
+        assert_eq!(layouts(&log), ["a", "b"]);
+
+        harness.rebuild();
+        assert_eq!(layouts(&log), [] as [&str; 0]);
+    }
+
+    #[test]
+    fn layout_cache_miss_on_constraints() {
+        let log = Log::default();
+        let width = Rc::new(Cell::new(200.0));
+        let (probes, box_width) = (log.clone(), width.clone());
+        let mut harness = Harness::new(move |ui| {
+            Flex::column().build(ui, |ui| {
+                SizedBox::new()
+                    .width(box_width.get())
+                    .build(ui, |ui| Probe::new("inner", &probes).build(ui, |_| {}));
+                Probe::new("sibling", &probes).build(ui, |_| {});
+            });
+        });
+        assert_eq!(layouts(&log), ["inner", "sibling"]);
+
+        width.set(300.0);
+        harness.rebuild();
+        assert_eq!(layouts(&log), ["inner"]);
+    }
+
+    #[test]
+    fn layout_cache_miss_on_child_change() {
+        let log = Log::default();
+        let size = Rc::new(Cell::new(100.0));
+        let (probes, probe_size) = (log.clone(), size.clone());
+        let mut harness = Harness::new(move |ui| {
+            Flex::column().build(ui, |ui| {
+                Probe::new("outer", &probes)
+                    .size(300.0, 300.0)
+                    .build(ui, |ui| {
+                        let size = probe_size.get();
+                        Probe::new("changed", &probes)
+                            .size(size, size)
+                            .build(ui, |_| {});
+                    });
+                Probe::new("sibling", &probes).build(ui, |_| {});
+            });
+        });
+        assert_eq!(layouts(&log), ["outer", "changed", "sibling"]);
+
+        size.set(50.0);
+        harness.rebuild();
+        // The ancestors are laid out again, their other children are not.
+        assert_eq!(layouts(&log), ["outer", "changed"]);
+    }
+
+    #[test]
+    fn layout_cache_miss_on_environment_change() {
+        let log = Log::default();
+        let disabled = Rc::new(Cell::new(false));
+        let (probes, is_disabled) = (log.clone(), disabled.clone());
+        let mut harness = Harness::new(move |ui| {
+            Flex::column().build(ui, |ui| {
+                ui.disabled(is_disabled.get(), |ui| {
+                    Probe::new("provided", &probes).build(ui, |_| {});
+                });
+                Probe::new("sibling", &probes).build(ui, |_| {});
+            });
+        });
+        assert_eq!(layouts(&log), ["provided", "sibling"]);
+
+        harness.rebuild();
+        assert_eq!(layouts(&log), [] as [&str; 0]);
+
+        disabled.set(true);
+        harness.rebuild();
+        assert_eq!(layouts(&log), ["provided"]);
+    }
+
+    #[test]
+    fn repaint_only_what_changed() {
+        let log = Log::default();
+        let probes = log.clone();
+        let mut harness = Harness::new(move |ui| {
+            Flex::column().build(ui, |ui| {
+                Probe::new("a", &probes).build(ui, |_| {});
+                Probe::new("b", &probes).build(ui, |_| {});
+            });
+        });
+        let a = Probe::find(harness.tree(), "a");
+        let a = harness.window_rect(a);
+        let b = Probe::find(harness.tree(), "b");
+        let b = harness.window_rect(b);
+        harness.take_damage();
+
+        harness.click(b.center());
+        assert_eq!(clicks(&log), [("b", Record::Click)]);
+        let damage = harness.take_damage();
+        assert!(damage.intersects(b));
+        assert!(!damage.intersects(a));
+    }
+
+    #[test]
+    fn overflow_of_constraints() {
+        let max = Size::new(100.0, 20.0);
+        assert_eq!(overflow(Size::new(80.0, 20.0), max), Size::ZERO);
+        assert_eq!(overflow(Size::new(130.0, 25.0), max), Size::new(30.0, 5.0));
+        assert_eq!(
+            overflow(
+                Size::new(f64::INFINITY, 10.0),
+                Size::new(f64::INFINITY, 20.0)
+            ),
+            Size::ZERO
+        );
+    }
+}
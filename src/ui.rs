@@ -1,13 +1,39 @@
 use crate::{
+    animation::{Easing, Interpolate, Transition},
+    app::{self, OPEN_WINDOW},
     bloom::Bloom,
-    context::{ContextState, UpdateCtx},
+    context::{ContextState, LifeCycleCtx, UpdateCtx},
+    environment::{Disabled, Environment, LayoutDirection},
+    event::LifeCycle,
     id::ChildCounter,
     key::Caller,
+    localization::{self, Args},
+    menu::WindowMenu,
     object::{AnyRenderObject, Properties, RenderObject},
+    profile::FrameStats,
+    services::Services,
+    state::{Local, Session},
+    task::Task,
     tree::{Child, ChildState, Children, State},
+    widgets::{
+        memo::Memo,
+        toasts::{Toast, ToastQueue},
+    },
+    window::{Window, CLOSE_WINDOW_NOW},
+    VisualEq,
 };
 use core::panic;
-use std::any::Any;
+use druid::{Command, Cursor, SingleUse, Target, WindowId};
+use std::{
+    any::Any,
+    cell::RefCell,
+    collections::hash_map::DefaultHasher,
+    future::Future,
+    hash::{Hash, Hasher},
+    panic::Location,
+    rc::Rc,
+    time::{Duration, Instant},
+};
 
 pub struct Ui<'a, 'b> {
     tree: &'a mut Children,
@@ -15,6 +41,12 @@ pub struct Ui<'a, 'b> {
     child_counter: &'a mut ChildCounter,
     state_index: usize,
     render_index: usize,
+    /// The hash of the keys of the enclosing [`Ui::keyed`] calls.
+    key: Option<u64>,
+    /// The values provided by the enclosing [`Ui::provide`] calls.
+    environment: Environment,
+    /// Render objects were added, removed or moved, the parent needs a layout.
+    changed: bool,
 }
 
 impl<'a, 'b> Ui<'a, 'b> {
@@ -22,6 +54,7 @@ impl<'a, 'b> Ui<'a, 'b> {
         tree: &'a mut Children,
         state: &'a mut ContextState<'b>,
         child_counter: &'a mut ChildCounter,
+        environment: Environment,
     ) -> Self {
         Ui {
             tree,
@@ -29,6 +62,9 @@ impl<'a, 'b> Ui<'a, 'b> {
             child_counter,
             state_index: 0,
             render_index: 0,
+            key: None,
+            environment,
+            changed: false,
         }
     }
 
@@ -38,6 +74,7 @@ impl<'a, 'b> Ui<'a, 'b> {
         I: FnOnce() -> T,
         N: FnOnce(&mut Ui, &mut T),
     {
+        let caller = caller.with_key(self.key);
         let index = self.find_state_node(caller);
         if index.is_none() {
             self.insert_state_node(caller, Box::new(init()));
@@ -69,6 +106,7 @@ impl<'a, 'b> Ui<'a, 'b> {
         R: RenderObject<P> + Any,
         N: FnOnce(&mut Ui),
     {
+        let caller = caller.with_key(self.key);
         let mut props = Some(props);
         let index = match self.find_render_object(caller) {
             Some(index) => index,
@@ -83,6 +121,22 @@ impl<'a, 'b> Ui<'a, 'b> {
         }
         let node = &mut self.tree.renders[index];
         self.render_index = index + 1;
+        let created = props.is_none();
+
+        if !node.state.environment.same(&self.environment) {
+            let was_disabled = node.state.environment.is_disabled();
+            node.state.environment = self.environment.clone();
+            node.state.needs_layout = true;
+            let disabled = self.environment.is_disabled();
+            if !created && disabled != was_disabled {
+                let mut ctx = LifeCycleCtx {
+                    state: self.state,
+                    child_state: &mut node.state,
+                };
+                node.object
+                    .lifecycle(&mut ctx, &LifeCycle::DisabledChanged(disabled));
+            }
+        }
 
         let mut action = R::Action::default();
         if let Some(props) = props {
@@ -99,13 +153,33 @@ impl<'a, 'b> Ui<'a, 'b> {
             }
         }
 
-        let mut object_cx = Ui::new(&mut node.children, self.state, self.child_counter);
+        let mut object_cx = Ui::new(
+            &mut node.children,
+            self.state,
+            self.child_counter,
+            self.environment.clone(),
+        );
         content(&mut object_cx);
+        if object_cx.finish() {
+            node.state.needs_layout = true;
+        }
 
-        object_cx.tree.states.truncate(object_cx.state_index);
-        object_cx.tree.states.retain(|s| !s.dead);
-        object_cx.tree.renders.truncate(object_cx.render_index);
-        object_cx.tree.renders.retain(|c| !c.dead);
+        if created {
+            let mut ctx = LifeCycleCtx {
+                state: self.state,
+                child_state: &mut node.state,
+            };
+            node.object.lifecycle(&mut ctx, &LifeCycle::WidgetAdded);
+        }
+
+        // Timers and animation frames requested while building are routed through the ancestors,
+        // as well as the areas to repaint and layout requests.
+        for child in &mut node.children.renders {
+            let timers = child.state.timers.iter().map(|(token, id)| (*token, *id));
+            node.state.timers.extend(timers);
+            node.state.request_anim |= child.state.request_anim;
+            node.state.merge_damage(&mut child.state);
+        }
 
         if true {
             // TODO: Only rebuild when children change.
@@ -121,6 +195,445 @@ impl<'a, 'b> Ui<'a, 'b> {
 
         action
     }
+
+    /// Provide `value` to `content`.
+    ///
+    /// Build code and render objects in `content` read it with `provided`,
+    /// unless it is overridden by another value of the same type.
+    #[track_caller]
+    pub fn provide<T: Any + PartialEq>(&mut self, value: T, content: impl FnOnce(&mut Ui)) {
+        let caller = Location::caller().into();
+        let parent = self.environment.clone();
+        self.state_node(
+            caller,
+            Environment::default,
+            |ui, provided: &mut Environment| {
+                // Keep the environment as long as nothing changed, so the render
+                // objects in `content` don't need to be laid out again.
+                let unchanged = match provided.split() {
+                    Some((provided_parent, provided_value)) => {
+                        provided_parent.same(&parent)
+                            && provided_value.downcast_ref::<T>() == Some(&value)
+                    }
+                    None => false,
+                };
+                if !unchanged {
+                    *provided = parent.with(Box::new(value));
+                }
+
+                let outer = std::mem::replace(&mut ui.environment, provided.clone());
+                content(ui);
+                ui.environment = outer;
+            },
+        );
+    }
+
+    /// Disable `content` if `disabled` is `true`.
+    ///
+    /// The widgets in `content` receive no input from the user and are
+    /// painted in their disabled style. They can check it with `is_disabled`
+    /// on their contexts. A subtree of a disabled one can not be enabled again.
+    ///
+    /// ```ignore
+    /// ui.disabled(saving, |ui| {
+    ///     TextBox::new(&mut name).build(ui);
+    ///     if Button::new().labeled(ui, "Save") {
+    ///         save(&name);
+    ///     }
+    /// });
+    /// ```
+    #[track_caller]
+    pub fn disabled(&mut self, disabled: bool, content: impl FnOnce(&mut Ui)) {
+        let disabled = disabled || self.is_disabled();
+        self.provide(Disabled(disabled), content);
+    }
+
+    /// Whether an enclosing [`Ui::disabled`] disabled the widgets built here.
+    pub fn is_disabled(&self) -> bool {
+        self.environment.is_disabled()
+    }
+
+    /// The [`LayoutDirection`] provided by an enclosing [`Ui::provide`],
+    /// left to right by default.
+    pub fn layout_direction(&self) -> LayoutDirection {
+        self.environment.layout_direction()
+    }
+
+    /// The message `id` of the [`Localization`] provided by an enclosing
+    /// [`Ui::provide`].
+    ///
+    /// Without a localization or such a message, the `id` itself is shown,
+    /// so that missing translations are easy to spot.
+    ///
+    /// [`Localization`]: crate::localization::Localization
+    pub fn localized(&self, id: &str) -> String {
+        self.localized_with(id, &Args::new())
+    }
+
+    /// The message `id` of the provided [`Localization`], with `args` inserted.
+    ///
+    /// ```ignore
+    /// let args = Args::new().with("count", inbox.len());
+    /// Label::new(ui.localized_with("emails", &args)).build(ui);
+    /// ```
+    ///
+    /// [`Localization`]: crate::localization::Localization
+    pub fn localized_with(&self, id: &str, args: &Args) -> String {
+        localization::localized(self.provided(), id, args)
+    }
+
+    /// The value of type `T` provided by an enclosing [`Ui::provide`].
+    pub fn provided<T: Any>(&self) -> Option<&T> {
+        self.environment.get()
+    }
+
+    /// The [`Services`] provided by an enclosing [`Ui::provide`], or the platform ones.
+    pub fn services(&self) -> &Services {
+        match self.provided() {
+            Some(services) => services,
+            None => Services::fallback(),
+        }
+    }
+
+    /// Replay the edit journal with the given name onto `text`, once per run
+    /// of the app, see [`Journals::replay`].
+    ///
+    /// Text widgets call this before they are created, so that they start
+    /// with the recovered text and journal their edits against it.
+    ///
+    /// [`Journals::replay`]: crate::text::Journals::replay
+    pub(crate) fn replay_journal(&mut self, name: &str, text: &mut String) {
+        if let Some(journals) = &self.state.journals {
+            if let Err(err) = journals.borrow_mut().replay(name, text) {
+                log::warn!("Failed to replay journal {}: {}", name, err);
+            }
+        }
+    }
+
+    /// Build `content` with an identity given by `key`, in addition to the call sites.
+    ///
+    /// Items built in a loop share their call sites, so their state is matched
+    /// by position. With a key, the state stays with the item when the order
+    /// of the items changes.
+    pub fn keyed(&mut self, key: impl Hash, content: impl FnOnce(&mut Ui)) {
+        let mut hasher = DefaultHasher::new();
+        self.key.hash(&mut hasher);
+        key.hash(&mut hasher);
+        let outer_key = self.key.replace(hasher.finish());
+        content(self);
+        self.key = outer_key;
+    }
+
+    /// Build `content` only if `key` changed since it was last built,
+    /// otherwise the previously built subtree is kept as it is.
+    ///
+    /// Content that requested an update is always rebuilt.
+    #[track_caller]
+    pub fn memoize<K: VisualEq + 'static>(&mut self, key: K, content: impl FnOnce(&mut Ui)) {
+        let caller = Location::caller().into();
+        if let Some(index) = self.find_render_object(Caller::with_key(caller, self.key)) {
+            let node = &mut self.tree.renders[index];
+            let unchanged = !node.state.request_update
+                && node.state.environment.same(&self.environment)
+                && match node.object.as_any().downcast_mut::<Memo<K>>() {
+                    Some(memo) => VisualEq::eq(&memo.key, &key),
+                    None => false,
+                };
+            if unchanged {
+                for node in &mut self.tree.renders[self.render_index..index] {
+                    node.dead = true;
+                }
+                self.render_index = index + 1;
+                return;
+            }
+        }
+        self.render_object(caller, Memo { key }, content);
+    }
+
+    /// State kept by the tree, identified by the call site.
+    ///
+    /// The state is created by `init` the first time the call site is built,
+    /// and dropped once it is no longer built.
+    #[track_caller]
+    pub fn use_state<T: 'static>(&mut self, init: impl FnOnce() -> T) -> Local<T> {
+        let caller = Location::caller().into();
+        let mut handle = None;
+        self.state_node(
+            caller,
+            || Local::new(init()),
+            |_, local: &mut Local<T>| handle = Some(local.clone()),
+        );
+        handle.unwrap()
+    }
+
+    /// Run `mount` the first time this call site is built.
+    ///
+    /// Together with [`Ui::on_unmount`], it starts work that lasts as long
+    /// as a part of the UI is shown, like a subscription of a panel.
+    ///
+    /// ```ignore
+    /// ui.on_mount(|| feed.subscribe(id));
+    /// ui.on_unmount(move || feed.unsubscribe(id));
+    /// ```
+    #[track_caller]
+    pub fn on_mount(&mut self, mount: impl FnOnce()) {
+        let caller = Location::caller().into();
+        self.state_node(
+            caller,
+            || {
+                mount();
+                Mounted
+            },
+            |_, _: &mut Mounted| {},
+        );
+    }
+
+    /// Run `unmount` once this call site is no longer built, or its window
+    /// is closed.
+    ///
+    /// The closure of the latest build pass is run, after the build pass
+    /// that no longer built the call site. Content kept by a [`Retain`]
+    /// is only unmounted once the retained state is dropped.
+    ///
+    /// [`Retain`]: crate::widgets::Retain
+    #[track_caller]
+    pub fn on_unmount(&mut self, unmount: impl FnOnce() + 'static) {
+        let caller = Location::caller().into();
+        self.state_node(
+            caller,
+            || Unmount(None),
+            |_, stored: &mut Unmount| stored.0 = Some(Box::new(unmount)),
+        );
+    }
+
+    /// Returns `true` once every `period`, for polling from build code.
+    ///
+    /// A build pass is scheduled for when the period has passed.
+    #[track_caller]
+    pub fn interval(&mut self, period: Duration) -> bool {
+        let caller = Location::caller().into();
+        let mut elapsed = false;
+        let now = self.now();
+        self.state_node(
+            caller,
+            || now + period,
+            |ui, next: &mut Instant| {
+                if now >= *next {
+                    elapsed = true;
+                    *next = now + period;
+                }
+                ui.state.request_build_at(*next);
+            },
+        );
+        elapsed
+    }
+
+    /// Schedule a build pass for `deadline`.
+    pub(crate) fn request_build_at(&mut self, deadline: Instant) {
+        self.state.request_build_at(deadline);
+    }
+
+    /// Show `toast` in the enclosing [`Toasts`](crate::widgets::Toasts).
+    ///
+    /// Without an enclosing `Toasts`, the toast is dropped and a warning is logged.
+    pub fn toast(&mut self, toast: impl Into<Toast>) {
+        let toast = toast.into();
+        match self.provided::<ToastQueue>() {
+            Some(queue) => queue.push(toast, self.now()),
+            None => log::warn!("No Toasts to show {:?}", toast),
+        }
+    }
+
+    /// Smoothly move towards `value`, returns the value for the current frame.
+    ///
+    /// Whenever `value` changes, a transition from the current value to
+    /// the new one starts, taking `duration`. Build passes are requested
+    /// in every animation frame until it is finished.
+    #[track_caller]
+    pub fn animate<T>(&mut self, value: T, duration: Duration, easing: Easing) -> T
+    where
+        T: Interpolate + 'static,
+    {
+        let caller = Location::caller().into();
+        let initial = value.clone();
+        let mut current = None;
+        let now = self.now();
+        self.state_node(
+            caller,
+            || Transition::new(initial, now),
+            |ui, transition: &mut Transition<T>| {
+                transition.retarget(value, duration, easing, now);
+                if !transition.is_finished(now) {
+                    ui.state.anim_frame = true;
+                }
+                current = Some(transition.value(now));
+            },
+        );
+        current.unwrap()
+    }
+
+    /// Run `future` off the UI thread, the window is rebuilt once it finished.
+    pub fn spawn<T, F>(&mut self, future: F) -> Task<T>
+    where
+        T: Send + 'static,
+        F: Future<Output = T> + Send + 'static,
+    {
+        let sink = self.state.ext_handle.clone();
+        Task::spawn(future, sink, self.state.window_id)
+    }
+
+    /// Run the future created by `make` once, and build `content` with its output.
+    ///
+    /// The output is `None` until the future finished.
+    #[track_caller]
+    pub fn use_future<T, F>(
+        &mut self,
+        make: impl FnOnce() -> F,
+        content: impl FnOnce(&mut Ui, Option<&mut T>),
+    ) where
+        T: Send + 'static,
+        F: Future<Output = T> + Send + 'static,
+    {
+        let caller = Location::caller().into();
+        let sink = self.state.ext_handle.clone();
+        let window_id = self.state.window_id;
+        self.state_node(
+            caller,
+            || (Task::spawn(make(), sink, window_id), None),
+            |ui, (task, output): &mut (Task<T>, Option<T>)| {
+                if output.is_none() {
+                    *output = task.take();
+                }
+                content(ui, output.as_mut());
+            },
+        );
+    }
+
+    /// The persisted UI state, if the app has a session.
+    pub(crate) fn session(&self) -> Option<Rc<RefCell<Session>>> {
+        self.state.session.clone()
+    }
+
+    /// How long the phases of the latest frame of the window took.
+    ///
+    /// Reading the stats does not cause further build passes, poll
+    /// them with [`Ui::interval`] to display them live.
+    pub fn frame_stats(&self) -> FrameStats {
+        self.state.frame_stats
+    }
+
+    /// The time of the build pass, see [`EventCtx::now`].
+    ///
+    /// [`EventCtx::now`]: crate::context::EventCtx::now
+    pub fn now(&self) -> Instant {
+        self.state.now
+    }
+
+    /// The id of the window that is being built.
+    pub fn window_id(&self) -> WindowId {
+        self.state.window_id
+    }
+
+    /// Open a new window with its own tree, built by `app`.
+    ///
+    /// The values provided around this call, like the theme, are provided to the new window.
+    ///
+    /// The window stays open until the user closes it or [`close_window`] is called.
+    ///
+    /// [`close_window`]: Ui::close_window
+    pub fn open_window(
+        &mut self,
+        title: impl Into<String>,
+        app: impl FnMut(&mut Ui) + 'static,
+    ) -> WindowId {
+        let session = self.session();
+        let journals = self.state.journals.clone();
+        let debug_layout = self.state.layout_debug.is_some();
+        let window = app::window_desc(
+            title.into(),
+            app,
+            self.state.touch_mode,
+            session,
+            journals,
+            debug_layout,
+            None,
+            self.environment.clone(),
+        );
+        let id = window.id;
+        let command = OPEN_WINDOW.with(SingleUse::new(window));
+        self.state.commands.push(command);
+        id
+    }
+
+    /// Submit a command to all widgets of the window, after the current build pass.
+    pub fn submit_command(&mut self, cmd: impl Into<Command>) {
+        self.state.commands.push(cmd.into());
+    }
+
+    /// Close the window with the given id.
+    ///
+    /// The close request handler of the window is not asked, see [`on_close_request`].
+    ///
+    /// [`on_close_request`]: Ui::on_close_request
+    pub fn close_window(&mut self, id: WindowId) {
+        let command = CLOSE_WINDOW_NOW.to(Target::Window(id));
+        self.state.commands.push(command);
+    }
+
+    /// Returns `true` if the window was closed since the last build pass.
+    pub fn window_closed(&self, id: WindowId) -> bool {
+        self.state.closed_windows.contains(&id)
+    }
+
+    /// Declare the native menu of the window.
+    ///
+    /// Selections are reported by the menu items in the next build pass.
+    /// The menu is only replaced when its description changes.
+    pub fn window_menu(&mut self, items: impl FnOnce(&mut WindowMenu)) {
+        let mut entries = Vec::new();
+        let mut next_id = 0;
+        let selected = self.state.menu_selection;
+        items(&mut WindowMenu::new(&mut entries, &mut next_id, selected));
+        self.state.window_menu = Some(entries);
+    }
+
+    /// Show `cursor` over the whole window, regardless of the widgets under the pointer.
+    ///
+    /// Useful to show that the app is busy or during a drag. The override
+    /// only lasts as long as this is called in every build pass.
+    pub fn override_cursor(&mut self, cursor: Cursor) {
+        self.state.cursor_override = Some(cursor);
+    }
+
+    /// Configure the window that is being built, like its title or size.
+    pub fn window(&mut self) -> Window<'_> {
+        let id = self.state.window_id;
+        let size = self.state.window_size;
+        Window::new(id, size, &mut self.state.window_config)
+    }
+
+    /// Intercept the user closing the window, for example to ask about unsaved changes.
+    ///
+    /// While this is called in every build pass, closing the window only
+    /// calls `handler` in the next build pass, which returns `true` if the
+    /// window should close. A window kept open can be closed later with
+    /// [`close_window`].
+    ///
+    /// ```ignore
+    /// ui.on_close_request(|| {
+    ///     show_save_dialog = document.modified;
+    ///     !document.modified
+    /// });
+    /// ```
+    ///
+    /// [`close_window`]: Ui::close_window
+    pub fn on_close_request(&mut self, handler: impl FnOnce() -> bool) {
+        self.state.window_config.intercept_close = true;
+        if std::mem::take(&mut self.state.close_requested) && handler() {
+            let command = CLOSE_WINDOW_NOW.to(Target::Window(self.state.window_id));
+            self.state.commands.push(command);
+        }
+    }
 }
 
 impl Ui<'_, '_> {
@@ -128,6 +641,12 @@ impl Ui<'_, '_> {
         let mut ix = self.state_index;
         for node in &mut self.tree.states[ix..] {
             if node.key == caller {
+                if caller.is_keyed() {
+                    // Keyed nodes move instead of replacing the nodes in between.
+                    let node = self.tree.states.remove(ix);
+                    self.tree.states.insert(self.state_index, node);
+                    return Some(self.state_index);
+                }
                 return Some(ix);
             }
             ix += 1;
@@ -147,13 +666,86 @@ impl Ui<'_, '_> {
         let mut ix = self.render_index;
         for node in &mut self.tree.renders[ix..] {
             if node.key == caller {
+                if caller.is_keyed() {
+                    // Keyed nodes move instead of replacing the nodes in between.
+                    if ix != self.render_index {
+                        let node = self.tree.renders.remove(ix);
+                        self.tree.renders.insert(self.render_index, node);
+                        self.changed = true;
+                    }
+                    return Some(self.render_index);
+                }
                 return Some(ix);
             }
             ix += 1;
         }
+
+        let retained = &mut self.tree.retained;
+        if let Some(ix) = retained.iter().position(|node| node.key == caller) {
+            let node = retained.remove(ix);
+            self.tree.renders.insert(self.render_index, node);
+            self.changed = true;
+            return Some(self.render_index);
+        }
         None
     }
 
+    /// Keep the state of the last built render object after it was removed.
+    ///
+    /// It will be restored when it is built again with the same key. Once
+    /// more than `limit` render objects are retained, the ones removed first
+    /// are dropped.
+    pub(crate) fn retain_last_render_object(&mut self, limit: usize) {
+        if let Some(index) = self.render_index.checked_sub(1) {
+            self.tree.renders[index].retain = Some(limit);
+        }
+    }
+
+    /// Drop the retained render objects for which `keep` returns `false`.
+    pub(crate) fn drop_retained(&mut self, mut keep: impl FnMut(&mut Child) -> bool) {
+        let state = &mut *self.state;
+        self.tree.retained.retain_mut(|node| {
+            let keep = keep(node);
+            if !keep {
+                node.remove(state);
+            }
+            keep
+        });
+    }
+
+    /// Drop the state and render objects that were not built.
+    ///
+    /// Returns whether render objects were added, removed or moved.
+    pub(crate) fn finish(&mut self) -> bool {
+        self.tree.states.truncate(self.state_index);
+        self.tree.states.retain(|s| !s.dead);
+        self.remove_dead_render_objects();
+        self.changed
+    }
+
+    fn remove_dead_render_objects(&mut self) {
+        let removed = self.tree.renders.split_off(self.render_index);
+        let (dead, alive): (Vec<_>, Vec<_>) = self.tree.renders.drain(..).partition(|c| c.dead);
+        self.tree.renders = alive;
+        self.changed |= !removed.is_empty() || !dead.is_empty();
+
+        for mut node in removed.into_iter().chain(dead) {
+            if let Some(limit) = node.retain {
+                node.dead = false;
+                node.state.is_hot = false;
+                node.state.is_active = false;
+                node.state.has_active = false;
+                self.tree.retained.push(node);
+                let excess = self.tree.retained.len().saturating_sub(limit);
+                for mut evicted in self.tree.retained.drain(..excess) {
+                    evicted.remove(self.state);
+                }
+            } else {
+                node.remove(self.state);
+            }
+        }
+    }
+
     fn insert_render_object(&mut self, caller: Caller, object: Box<dyn AnyRenderObject>) {
         self.tree.renders.insert(
             self.render_index,
@@ -163,7 +755,194 @@ impl Ui<'_, '_> {
                 children: Children::new(),
                 state: ChildState::new(self.child_counter.generate_id(), None),
                 dead: false,
+                retain: None,
             },
         );
+        self.changed = true;
+    }
+}
+
+/// The state of [`Ui::on_mount`].
+struct Mounted;
+
+/// The state of [`Ui::on_unmount`], runs the closure when it is dropped with the state.
+struct Unmount(Option<Box<dyn FnOnce()>>);
+
+impl Drop for Unmount {
+    fn drop(&mut self) {
+        if let Some(unmount) = self.0.take() {
+            unmount();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        kurbo::Point,
+        lifecycle::ViewContext,
+        state::Mutable,
+        testing::{self, Harness, Log, Probe, Record},
+        widgets::{button::ButtonObject, Button, Flex, Label},
+    };
+    use std::cell::Cell;
+
+    #[test]
+    fn keyed_items_keep_their_state() {
+        let items = Rc::new(RefCell::new(vec!["a", "b", "c"]));
+        let built = items.clone();
+        let mut harness = Harness::new(move |ui| {
+            Flex::column().build(ui, |ui| {
+                for &item in built.borrow().iter() {
+                    let state = ui.use_state(|| item);
+                    Label::new(state.get()).build(ui);
+                }
+                for &item in built.borrow().iter() {
+                    ui.keyed(item, |ui| {
+                        let state = ui.use_state(|| item);
+                        Label::new(format!("{}'", state.get())).build(ui);
+                    });
+                }
+            });
+        });
+        assert_eq!(harness.label_texts(), ["a", "b", "c", "a'", "b'", "c'"]);
+
+        *items.borrow_mut() = vec!["c", "a", "b"];
+        harness.rebuild();
+        assert_eq!(harness.label_texts(), ["a", "b", "c", "c'", "a'", "b'"]);
+
+        *items.borrow_mut() = vec!["c", "b"];
+        harness.rebuild();
+        assert_eq!(harness.label_texts(), ["a", "b", "c'", "b'"]);
+    }
+
+    #[test]
+    fn memoize_skips_unchanged_content() {
+        let key = Rc::new(Cell::new(1));
+        let builds = Rc::new(Cell::new(0));
+        let (memo_key, memo_builds) = (key.clone(), builds.clone());
+        let mut harness = Harness::new(move |ui| {
+            ui.memoize(memo_key.get(), |ui| {
+                memo_builds.set(memo_builds.get() + 1);
+                Flex::column().build(ui, |ui| {
+                    Mutable::new().use_in(ui, |ui, clicks: &mut usize| {
+                        if Button::new().labeled(ui, "Add") {
+                            *clicks += 1;
+                        }
+                        Label::new(format!("{} {}", memo_key.get(), clicks)).build(ui);
+                    });
+                });
+            });
+        });
+        assert_eq!(builds.get(), 1);
+        harness.rebuild();
+        assert_eq!(builds.get(), 1);
+
+        // Content that requested an update is built even with the same key.
+        let add =
+            testing::assert_exists::<ButtonObject>(harness.tree(), testing::with_label("Add"));
+        harness.click_on(add);
+        assert_eq!(harness.label_texts(), ["Add", "1 1"]);
+        let clicked_builds = builds.get();
+        harness.rebuild();
+        assert_eq!(builds.get(), clicked_builds);
+
+        key.set(2);
+        harness.rebuild();
+        assert_eq!(builds.get(), clicked_builds + 1);
+        assert_eq!(harness.label_texts(), ["Add", "2 1"]);
+    }
+
+    #[test]
+    fn mount_and_unmount() {
+        let shown = Rc::new(Cell::new(true));
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let (show, hooks) = (shown.clone(), log.clone());
+        let mut harness = Harness::new(move |ui| {
+            Flex::column().build(ui, |ui| {
+                if show.get() {
+                    ui.on_mount(|| hooks.borrow_mut().push("mount"));
+                    let hooks = hooks.clone();
+                    ui.on_unmount(move || hooks.borrow_mut().push("unmount"));
+                }
+            });
+        });
+        assert_eq!(*log.borrow(), ["mount"]);
+        harness.rebuild();
+        assert_eq!(*log.borrow(), ["mount"]);
+
+        shown.set(false);
+        harness.rebuild();
+        assert_eq!(*log.borrow(), ["mount", "unmount"]);
+        shown.set(true);
+        harness.rebuild();
+        assert_eq!(*log.borrow(), ["mount", "unmount", "mount"]);
+    }
+
+    #[test]
+    fn life_cycle_events_in_tree_order() {
+        let log = Log::default();
+        let shown = Rc::new(Cell::new(true));
+        let disabled = Rc::new(Cell::new(false));
+        let (probes, show, disable) = (log.clone(), shown.clone(), disabled.clone());
+        let mut harness = Harness::new(move |ui| {
+            ui.disabled(disable.get(), |ui| {
+                Probe::new("outer", &probes).build(ui, |ui| {
+                    if show.get() {
+                        Probe::new("middle", &probes)
+                            .size(50.0, 50.0)
+                            .build(ui, |ui| {
+                                Probe::new("inner", &probes)
+                                    .size(20.0, 20.0)
+                                    .build(ui, |_| {});
+                            });
+                    }
+                });
+            });
+        });
+        // Sizes are left out, the root is sized by the window.
+        let events = |log: &Log| {
+            Probe::take(log, |record| match record {
+                Record::LifeCycle(LifeCycle::Size(_)) => false,
+                record => matches!(record, Record::LifeCycle(_)),
+            })
+        };
+        let event = |name, event| (name, Record::LifeCycle(event));
+        let moved = LifeCycle::ViewContextChanged(ViewContext {
+            window_origin: Point::ORIGIN,
+        });
+        assert_eq!(
+            events(&log),
+            [
+                event("inner", LifeCycle::WidgetAdded),
+                event("middle", LifeCycle::WidgetAdded),
+                event("outer", LifeCycle::WidgetAdded),
+                event("outer", moved.clone()),
+                event("middle", moved.clone()),
+                event("inner", moved),
+            ]
+        );
+
+        disabled.set(true);
+        harness.rebuild();
+        assert_eq!(
+            events(&log),
+            [
+                event("outer", LifeCycle::DisabledChanged(true)),
+                event("middle", LifeCycle::DisabledChanged(true)),
+                event("inner", LifeCycle::DisabledChanged(true)),
+            ]
+        );
+
+        shown.set(false);
+        harness.rebuild();
+        assert_eq!(
+            events(&log),
+            [
+                event("middle", LifeCycle::WidgetRemoved),
+                event("inner", LifeCycle::WidgetRemoved),
+            ]
+        );
     }
 }
@@ -0,0 +1,140 @@
+//! Mouse wheel deltas that mean the same on every platform, and smooth scrolling.
+//!
+//! The wheel events received by widgets carry their delta in pixels, with
+//! vertical wheels scrolling sideways while shift is held. Scroll containers
+//! can add the delta to their offset without looking at the platform.
+
+use crate::{
+    animation::{Easing, Interpolate, Transition},
+    context::EventCtx,
+    event::Event,
+    kurbo::Vec2,
+};
+use std::time::Duration;
+
+/// How far one notch of a mouse wheel scrolls.
+pub const NOTCH_DISTANCE: f64 = 48.0;
+
+/// The delta of one notch as reported on Windows and Linux.
+const RAW_NOTCH: f64 = 120.0;
+
+/// Convert the wheel delta reported by the platform to pixels.
+///
+/// Windows and Linux report lines, in units of [`RAW_NOTCH`] per notch,
+/// macOS and the web already report pixels.
+pub(crate) fn normalize(delta: Vec2, shift: bool) -> Vec2 {
+    let delta = if cfg!(any(target_os = "macos", target_arch = "wasm32")) {
+        delta
+    } else {
+        delta * (NOTCH_DISTANCE / RAW_NOTCH)
+    };
+    if shift && delta.x == 0.0 {
+        Vec2::new(delta.y, 0.0)
+    } else {
+        delta
+    }
+}
+
+/// Makes the scroll containers below it glide to the new offset on wheel
+/// movements, instead of jumping there, when provided with [`Ui::provide`].
+///
+/// The built-in scroll containers are [`Table`], [`TextArea`] and [`CodeView`].
+///
+/// ```ignore
+/// ui.provide(SmoothScrolling::default(), |ui| editor(ui, &mut document));
+/// ```
+///
+/// [`Ui::provide`]: crate::ui::Ui::provide
+/// [`Table`]: crate::widgets::Table
+/// [`TextArea`]: crate::widgets::TextArea
+/// [`CodeView`]: crate::widgets::CodeView
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SmoothScrolling {
+    /// How long it takes to reach the new offset.
+    pub duration: Duration,
+}
+
+impl Default for SmoothScrolling {
+    fn default() -> Self {
+        SmoothScrolling {
+            duration: Duration::from_millis(150),
+        }
+    }
+}
+
+/// The wheel scrolling of a scroll container, smooth if [`SmoothScrolling`] is provided.
+pub(crate) struct WheelScroll<T> {
+    transition: Option<Transition<T>>,
+}
+
+impl<T: Interpolate> WheelScroll<T> {
+    pub(crate) fn new() -> Self {
+        WheelScroll { transition: None }
+    }
+
+    /// The offset that wheel movements add to, the one being scrolled to if any.
+    pub(crate) fn target(&self, offset: T) -> T {
+        match &self.transition {
+            Some(transition) => transition.target().clone(),
+            None => offset,
+        }
+    }
+
+    /// Scroll from `offset` to `target`, returns the offset to show now.
+    pub(crate) fn scroll(&mut self, ctx: &mut EventCtx, offset: T, target: T) -> T {
+        let smooth = match ctx.provided::<SmoothScrolling>() {
+            Some(smooth) => *smooth,
+            None => {
+                self.transition = None;
+                return target;
+            }
+        };
+        let now = ctx.now();
+        let transition = self
+            .transition
+            .get_or_insert_with(|| Transition::new(offset, now));
+        transition.retarget(target, smooth.duration, Easing::EaseOut, now);
+        ctx.request_anim_frame();
+        transition.value(now)
+    }
+
+    /// The offset to show on an animation frame, while scrolling smoothly.
+    pub(crate) fn anim_frame(&mut self, ctx: &mut EventCtx, event: &Event) -> Option<T> {
+        if !matches!(event, Event::AnimFrame(_)) {
+            return None;
+        }
+        let transition = self.transition.as_ref()?;
+        let now = ctx.now();
+        let offset = transition.value(now);
+        if transition.is_finished(now) {
+            self.transition = None;
+        } else {
+            ctx.request_anim_frame();
+        }
+        Some(offset)
+    }
+
+    /// Stop scrolling smoothly, because the offset was changed otherwise.
+    pub(crate) fn stop(&mut self) {
+        self.transition = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_deltas() {
+        let notch = if cfg!(any(target_os = "macos", target_arch = "wasm32")) {
+            Vec2::new(0.0, NOTCH_DISTANCE)
+        } else {
+            Vec2::new(0.0, RAW_NOTCH)
+        };
+        assert_eq!(normalize(notch, false), Vec2::new(0.0, NOTCH_DISTANCE));
+        assert_eq!(normalize(-notch, true), Vec2::new(-NOTCH_DISTANCE, 0.0));
+        // Horizontal wheels are not turned by shift.
+        let sideways = Vec2::new(notch.y, 0.0);
+        assert_eq!(normalize(sideways, true), Vec2::new(NOTCH_DISTANCE, 0.0));
+    }
+}
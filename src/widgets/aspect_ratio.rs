@@ -0,0 +1,139 @@
+//! A container that keeps the proportions of its content.
+
+use crate::{kurbo::Point, object::prelude::*};
+
+/// Sizes its content to the largest size with a fixed ratio of width to
+/// height that fits the constraints, like a video thumbnail:
+///
+/// ```ignore
+/// AspectRatio::new(16.0 / 9.0).build(ui, |ui| {
+///     Image::new(thumbnail).fit(ImageFit::Cover).build(ui);
+/// });
+/// ```
+///
+/// Without a bound on the width or the height, the content is as small as
+/// the minimum constraints allow.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AspectRatio {
+    ratio: f64,
+}
+
+impl Properties for AspectRatio {
+    type Object = AspectRatio;
+}
+
+impl AspectRatio {
+    /// Keep `width / height` at `ratio`.
+    pub fn new(ratio: f64) -> Self {
+        AspectRatio { ratio }
+    }
+
+    #[track_caller]
+    pub fn build(self, ui: &mut Ui, content: impl FnOnce(&mut Ui)) {
+        let caller = Location::caller().into();
+        ui.render_object(caller, self, content);
+    }
+}
+
+impl RenderObject<AspectRatio> for AspectRatio {
+    type Action = ();
+
+    fn create(props: AspectRatio) -> Self {
+        props
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, props: AspectRatio) {
+        if *self != props {
+            *self = props;
+            ctx.request_layout();
+        }
+    }
+}
+
+impl RenderObjectInterface for AspectRatio {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, children: &mut Children) {
+        for child in children {
+            child.event(ctx, event);
+        }
+    }
+
+    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle) {}
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        children: &mut Children,
+    ) -> Size {
+        bc.debug_check("AspectRatio");
+        if !bc.max().width.is_finite() && !bc.max().height.is_finite() {
+            ctx.report_layout_issue("AspectRatio without a max width or height has no size");
+        }
+        let size = bc.fit_aspect_ratio(self.ratio);
+        let child_bc = BoxConstraints::tight(size);
+        for child in &mut *children {
+            child.layout(ctx, &child_bc);
+            child.set_origin(ctx, Point::ORIGIN);
+            ctx.set_baseline_offset(child.baseline_offset());
+        }
+        size
+    }
+
+    fn compute_min_intrinsic_width(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        height: f64,
+        children: &mut Children,
+    ) -> f64 {
+        if height.is_finite() {
+            height * self.ratio
+        } else {
+            children.min_intrinsic_width(ctx, height)
+        }
+    }
+
+    fn compute_max_intrinsic_width(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        height: f64,
+        children: &mut Children,
+    ) -> f64 {
+        if height.is_finite() {
+            height * self.ratio
+        } else {
+            children.max_intrinsic_width(ctx, height)
+        }
+    }
+
+    fn compute_min_intrinsic_height(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        width: f64,
+        children: &mut Children,
+    ) -> f64 {
+        if width.is_finite() {
+            width / self.ratio
+        } else {
+            children.min_intrinsic_height(ctx, width)
+        }
+    }
+
+    fn compute_max_intrinsic_height(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        width: f64,
+        children: &mut Children,
+    ) -> f64 {
+        if width.is_finite() {
+            width / self.ratio
+        } else {
+            children.max_intrinsic_height(ctx, width)
+        }
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, children: &mut Children) {
+        for child in children {
+            child.paint(ctx);
+        }
+    }
+}
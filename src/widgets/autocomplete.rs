@@ -0,0 +1,480 @@
+//! Completions for text inputs, shown in a popup below them.
+
+use crate::{
+    event::MouseButton,
+    kurbo::Point,
+    object::prelude::*,
+    piet::FontWeight,
+    text::{Attribute, RichText},
+    widgets::{
+        overlay::{Overlay, Placement},
+        RichLabel,
+    },
+};
+use druid::KbKey;
+use std::{cell::Cell, ops::Range, rc::Rc};
+
+const COMPLETION_PADDING: Size = Size::new(6.0, 3.0);
+
+/// Offers completions for the text of any text input.
+///
+/// The candidates are passed in every build. While the text is edited, the
+/// ones containing it are shown in a popup below the input, with the matched
+/// part in bold. The arrow keys move through them, enter or a click replaces
+/// the text with the highlighted completion.
+///
+/// ```ignore
+/// let committed = Autocomplete::new(&countries).build(ui, &mut country, |ui, text| {
+///     TextBox::new(text).placeholder("Country").build(ui);
+/// });
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Autocomplete {
+    candidates: Vec<String>,
+    filter: bool,
+    max_items: usize,
+}
+
+/// An input from the keys or the popup, applied in the next build pass.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Command {
+    /// Move the highlight by the given number of completions.
+    Move(isize),
+    /// Commit the completion at the index.
+    Commit(usize),
+    Close,
+}
+
+struct AutocompleteState {
+    commands: Rc<Cell<Option<Command>>>,
+    /// The text after the last build, to notice edits.
+    seen: String,
+    open: bool,
+    highlighted: Option<usize>,
+}
+
+impl Autocomplete {
+    pub fn new<S: Into<String>>(candidates: impl IntoIterator<Item = S>) -> Self {
+        Autocomplete {
+            candidates: candidates.into_iter().map(Into::into).collect(),
+            filter: true,
+            max_items: 8,
+        }
+    }
+
+    /// Whether only candidates containing the text are shown.
+    ///
+    /// Disable it when the candidates are already picked for the text,
+    /// for example by a search. The default value is `true`.
+    pub fn filter(mut self, filter: bool) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// The most completions shown at once.
+    ///
+    /// The default value is `8`.
+    pub fn max_items(mut self, max_items: usize) -> Self {
+        self.max_items = max_items;
+        self
+    }
+
+    /// Build the `input` editing `text`, with completions for it.
+    ///
+    /// Returns the committed completion, which already replaced the text.
+    #[must_use]
+    #[track_caller]
+    pub fn build(
+        self,
+        ui: &mut Ui,
+        text: &mut String,
+        input: impl FnOnce(&mut Ui, &mut String),
+    ) -> Option<String> {
+        let caller = Location::caller().into();
+        let Autocomplete {
+            candidates,
+            filter,
+            max_items,
+        } = self;
+        let initial = text.clone();
+        let mut committed = None;
+        ui.state_node(
+            caller,
+            || AutocompleteState {
+                commands: Rc::new(Cell::new(None)),
+                seen: initial,
+                open: false,
+                highlighted: None,
+            },
+            |ui, state: &mut AutocompleteState| {
+                let completions = completions(&candidates, text, filter, max_items);
+                let count = completions.len();
+                match state.commands.take() {
+                    Some(Command::Move(delta)) if count > 0 => {
+                        state.open = true;
+                        state.highlighted = Some(move_highlight(state.highlighted, delta, count));
+                    }
+                    Some(Command::Commit(index)) if index < count => {
+                        let candidate = &candidates[completions[index].0];
+                        text.clone_from(candidate);
+                        committed = Some(candidate.clone());
+                        state.open = false;
+                    }
+                    Some(Command::Close) => state.open = false,
+                    _ => {}
+                }
+
+                let open = state.open && count > 0;
+                let highlighted = state.highlighted.filter(|_| open);
+                let commands = state.commands.clone();
+                let dismissed = Overlay::new(Placement::Below).build(
+                    ui,
+                    open,
+                    |ui| {
+                        let caller = Location::caller().into();
+                        let keys = CompletionKeys {
+                            open,
+                            highlighted,
+                            has_completions: count > 0,
+                            commands: commands.clone(),
+                        };
+                        ui.render_object(caller, keys, |ui| input(ui, text));
+                    },
+                    |ui| {
+                        let caller = Location::caller().into();
+                        ui.render_object(caller, CompletionList, |ui| {
+                            for (index, (candidate, range)) in completions.iter().enumerate() {
+                                let candidate = candidates[*candidate].as_str();
+                                let mut rich_text = RichText::new(candidate.into());
+                                if let Some(range) = range {
+                                    let bold = Attribute::Weight(FontWeight::BOLD);
+                                    rich_text = rich_text.with_attribute(range.clone(), bold);
+                                }
+                                let caller = Location::caller().into();
+                                let item = Completion {
+                                    index,
+                                    highlighted: highlighted == Some(index),
+                                    commands: commands.clone(),
+                                };
+                                ui.render_object(caller, item, |ui| {
+                                    RichLabel::new(rich_text).build(ui);
+                                });
+                            }
+                        });
+                    },
+                );
+
+                if *text != state.seen {
+                    state.seen.clone_from(text);
+                    state.open = committed.is_none();
+                    state.highlighted = None;
+                }
+                if dismissed || !state.open {
+                    state.open = false;
+                    state.highlighted = None;
+                }
+            },
+        );
+        committed
+    }
+}
+
+/// The indices of the candidates shown for `text`, with the matched ranges.
+fn completions(
+    candidates: &[String],
+    text: &str,
+    filter: bool,
+    max_items: usize,
+) -> Vec<(usize, Option<Range<usize>>)> {
+    let matches = candidates
+        .iter()
+        .enumerate()
+        .filter_map(|(index, candidate)| {
+            if !filter {
+                return Some((index, None));
+            }
+            let range = match_range(candidate, text)?;
+            Some((index, Some(range).filter(|range| !range.is_empty())))
+        });
+    matches.take(max_items).collect()
+}
+
+/// The first byte range of `candidate` that equals `query`, ignoring case.
+fn match_range(candidate: &str, query: &str) -> Option<Range<usize>> {
+    if query.is_empty() {
+        return Some(0..0);
+    }
+    candidate.char_indices().find_map(|(start, _)| {
+        let mut rest = candidate[start..].char_indices();
+        for expected in query.chars() {
+            let (_, found) = rest.next()?;
+            if !found.to_lowercase().eq(expected.to_lowercase()) {
+                return None;
+            }
+        }
+        let end = rest
+            .next()
+            .map_or(candidate.len(), |(offset, _)| start + offset);
+        Some(start..end)
+    })
+}
+
+/// The completion highlighted after moving by `delta`, wrapping around at the ends.
+///
+/// Without a highlighted completion, moving down starts at the first one
+/// and moving up at the last one. `count` must not be zero.
+fn move_highlight(highlighted: Option<usize>, delta: isize, count: usize) -> usize {
+    match highlighted {
+        Some(index) => (index as isize + delta).rem_euclid(count as isize) as usize,
+        None if delta < 0 => count - 1,
+        None => 0,
+    }
+}
+
+/// Turns keys pressed in the input into commands for the popup.
+struct CompletionKeys {
+    open: bool,
+    highlighted: Option<usize>,
+    has_completions: bool,
+    commands: Rc<Cell<Option<Command>>>,
+}
+
+impl Properties for CompletionKeys {
+    type Object = CompletionKeys;
+}
+
+impl RenderObject<CompletionKeys> for CompletionKeys {
+    type Action = ();
+
+    fn create(props: CompletionKeys) -> Self {
+        props
+    }
+
+    fn update(&mut self, _ctx: &mut UpdateCtx, props: CompletionKeys) {
+        *self = props;
+    }
+}
+
+impl RenderObjectInterface for CompletionKeys {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, children: &mut Children) {
+        // Key events only reach this while the input has focus.
+        if let Event::KeyDown(key) = event {
+            let command = match (&key.key, self.highlighted) {
+                (KbKey::ArrowDown, _) if self.has_completions => Some(Command::Move(1)),
+                (KbKey::ArrowUp, _) if self.open => Some(Command::Move(-1)),
+                (KbKey::Enter, Some(index)) => Some(Command::Commit(index)),
+                _ => None,
+            };
+            if let Some(command) = command {
+                self.commands.set(Some(command));
+                ctx.request_update();
+                ctx.set_handled();
+                return;
+            }
+            // Enter without a highlighted completion is left to the input.
+            if key.key == KbKey::Enter && self.open {
+                self.commands.set(Some(Command::Close));
+                ctx.request_update();
+            }
+        }
+        for child in children {
+            child.event(ctx, event);
+        }
+    }
+
+    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle) {}
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        children: &mut Children,
+    ) -> Size {
+        children.layout_stacked(ctx, bc)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, children: &mut Children) {
+        for child in children {
+            child.paint(ctx);
+        }
+    }
+}
+
+/// Stacks the completions vertically, with equal widths.
+struct CompletionList;
+
+impl Properties for CompletionList {
+    type Object = CompletionList;
+}
+
+impl RenderObject<CompletionList> for CompletionList {
+    type Action = ();
+
+    fn create(props: CompletionList) -> Self {
+        props
+    }
+
+    fn update(&mut self, _ctx: &mut UpdateCtx, _props: CompletionList) {}
+}
+
+impl RenderObjectInterface for CompletionList {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, children: &mut Children) {
+        for child in children {
+            child.event(ctx, event);
+        }
+    }
+
+    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle) {}
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        children: &mut Children,
+    ) -> Size {
+        let menu_padding = ctx.theme().spacing.menu_padding;
+        let padding = Size::new(2.0 * menu_padding, 2.0 * menu_padding);
+        let child_bc = bc.loosen().shrink(padding);
+
+        let mut width: f64 = 0.0;
+        for child in children.iter() {
+            width = width.max(child.layout(ctx, &child_bc).width);
+        }
+        let child_bc = BoxConstraints::new(
+            Size::new(width, 0.0),
+            Size::new(width, child_bc.max().height),
+        );
+        let mut y = menu_padding;
+        for child in children.iter() {
+            let child_size = child.layout(ctx, &child_bc);
+            child.set_origin(ctx, Point::new(menu_padding, y));
+            y += child_size.height;
+        }
+        bc.constrain(Size::new(width, y - menu_padding) + padding)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, children: &mut Children) {
+        let theme = ctx.theme().clone();
+        let rect = ctx
+            .size()
+            .to_rect()
+            .inset(-0.5)
+            .to_rounded_rect(theme.spacing.corner_radius);
+        ctx.fill(rect, &theme.colors.panel);
+        ctx.stroke(rect, &theme.colors.panel_border, 1.0);
+        for child in children {
+            child.paint(ctx);
+        }
+    }
+}
+
+/// A completion, which is committed by clicking it.
+struct Completion {
+    index: usize,
+    highlighted: bool,
+    commands: Rc<Cell<Option<Command>>>,
+}
+
+impl Properties for Completion {
+    type Object = Completion;
+}
+
+impl RenderObject<Completion> for Completion {
+    type Action = ();
+
+    fn create(props: Completion) -> Self {
+        props
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, props: Completion) {
+        if self.highlighted != props.highlighted {
+            ctx.request_paint();
+        }
+        *self = props;
+    }
+}
+
+impl RenderObjectInterface for Completion {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, _children: &mut Children) {
+        match event {
+            Event::MouseDown(mouse) if mouse.button == MouseButton::Left => {
+                ctx.set_active(true);
+                ctx.set_handled();
+            }
+            Event::MouseUp(mouse) if mouse.button == MouseButton::Left => {
+                if ctx.is_active() && ctx.is_hot() {
+                    self.commands.set(Some(Command::Commit(self.index)));
+                    ctx.request_update();
+                    ctx.set_handled();
+                }
+                ctx.set_active(false);
+            }
+            _ => {}
+        }
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        if let LifeCycle::HotChanged(_) = event {
+            ctx.request_paint();
+        }
+    }
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        children: &mut Children,
+    ) -> Size {
+        ctx.set_hit_target(true);
+        let padding = COMPLETION_PADDING * 2.0;
+        let label = children[0].layout(ctx, &bc.loosen().shrink(padding));
+        children[0].set_origin(ctx, COMPLETION_PADDING.to_vec2().to_point());
+        bc.constrain(label + padding)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, children: &mut Children) {
+        let colors = ctx.theme().colors.clone();
+        let rect = ctx.size().to_rect().to_rounded_rect(2.0);
+        if self.highlighted {
+            ctx.fill(rect, &colors.accent);
+        } else if ctx.is_hot() {
+            ctx.fill(rect, &colors.ghost);
+        }
+        children[0].paint(ctx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wrap_highlight() {
+        assert_eq!(move_highlight(None, 1, 3), 0);
+        assert_eq!(move_highlight(None, -1, 3), 2);
+        assert_eq!(move_highlight(Some(2), 1, 3), 0);
+        assert_eq!(move_highlight(Some(0), -1, 3), 2);
+        assert_eq!(move_highlight(Some(1), 1, 3), 2);
+    }
+
+    #[test]
+    fn match_ignoring_case() {
+        assert_eq!(match_range("Germany", "man"), Some(3..6));
+        assert_eq!(match_range("Germany", "GER"), Some(0..3));
+        assert_eq!(match_range("Germany", "France"), None);
+        assert_eq!(match_range("Österreich", "öst"), Some(0..4));
+        assert_eq!(match_range("abc", ""), Some(0..0));
+    }
+
+    #[test]
+    fn filter_candidates() {
+        let candidates = ["Austria", "Germany", "Australia"].map(String::from);
+        assert_eq!(
+            completions(&candidates, "aus", true, 8),
+            [(0, Some(0..3)), (2, Some(0..3))]
+        );
+        assert_eq!(completions(&candidates, "aus", true, 1).len(), 1);
+        assert_eq!(completions(&candidates, "x", false, 8).len(), 3);
+        assert_eq!(completions(&candidates, "", true, 8)[1], (1, None));
+    }
+}
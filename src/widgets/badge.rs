@@ -0,0 +1,224 @@
+//! A count or dot painted on the corner of a widget.
+
+use crate::{
+    kurbo::{Point, Rect},
+    object::prelude::*,
+    piet::{
+        FontFamily, FontWeight, PietTextLayout, Text, TextAttribute, TextLayout, TextLayoutBuilder,
+    },
+};
+
+const BADGE_TEXT_SIZE: f64 = 10.0;
+const BADGE_HEIGHT: f64 = 16.0;
+const BADGE_X_PADDING: f64 = 4.0;
+const DOT_SIZE: f64 = 8.0;
+
+/// The corner of its content a [`Badge`] is centered on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BadgeCorner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+/// Paints a count or a dot on a corner of its content, like unread
+/// notifications on a button or a tab.
+///
+/// The badge does not take part in the layout, the content keeps its size
+/// and the badge is painted over its corner. A count of zero hides the badge.
+///
+/// ```ignore
+/// Badge::count(unread).build(ui, |ui| {
+///     if Button::new().labeled(ui, "Inbox") {
+///         open_inbox();
+///     }
+/// });
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Badge {
+    count: Option<u32>,
+    max: u32,
+    corner: BadgeCorner,
+    visible: bool,
+}
+
+impl Properties for Badge {
+    type Object = BadgeObject;
+}
+
+impl Badge {
+    /// A badge showing `count`, hidden when the count is zero.
+    pub fn count(count: u32) -> Self {
+        Badge {
+            count: Some(count),
+            max: 99,
+            corner: BadgeCorner::TopRight,
+            visible: true,
+        }
+    }
+
+    /// A badge without text, shown as a small dot.
+    pub fn dot() -> Self {
+        Badge {
+            count: None,
+            ..Badge::count(0)
+        }
+    }
+
+    /// The highest count shown, larger counts are shown as `max+`.
+    ///
+    /// The default value is `99`.
+    pub fn max(mut self, max: u32) -> Self {
+        self.max = max;
+        self
+    }
+
+    /// The default value is [`BadgeCorner::TopRight`].
+    pub fn corner(mut self, corner: BadgeCorner) -> Self {
+        self.corner = corner;
+        self
+    }
+
+    /// Whether the badge is shown at all, for example to show a dot only when needed.
+    ///
+    /// The default value is `true`.
+    pub fn visible(mut self, visible: bool) -> Self {
+        self.visible = visible;
+        self
+    }
+
+    #[track_caller]
+    pub fn build(self, ui: &mut Ui, content: impl FnOnce(&mut Ui)) {
+        let caller = Location::caller().into();
+        ui.render_object(caller, self, content);
+    }
+}
+
+/// What a badge shows: `None` hides it, an empty string is a dot.
+fn badge_text(count: Option<u32>, max: u32) -> Option<String> {
+    match count {
+        None => Some(String::new()),
+        Some(0) => None,
+        Some(count) if count > max => Some(format!("{}+", max)),
+        Some(count) => Some(count.to_string()),
+    }
+}
+
+pub struct BadgeObject {
+    props: Badge,
+    layout: Option<PietTextLayout>,
+    /// Where the badge is painted, relative to the content.
+    rect: Option<Rect>,
+}
+
+impl RenderObject<Badge> for BadgeObject {
+    type Action = ();
+
+    fn create(props: Badge) -> Self {
+        BadgeObject {
+            props,
+            layout: None,
+            rect: None,
+        }
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, props: Badge) {
+        if self.props != props {
+            self.props = props;
+            self.layout = None;
+            ctx.request_layout();
+        }
+    }
+}
+
+impl RenderObjectInterface for BadgeObject {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, children: &mut Children) {
+        children[0].event(ctx, event);
+    }
+
+    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle) {}
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        children: &mut Children,
+    ) -> Size {
+        bc.debug_check("Badge");
+        let child = &mut children[0];
+        let size = child.layout(ctx, bc);
+        child.set_origin(ctx, Point::ORIGIN);
+        ctx.set_baseline_offset(child.baseline_offset());
+
+        let text = self
+            .props
+            .visible
+            .then(|| badge_text(self.props.count, self.props.max))
+            .flatten();
+        self.rect = text.map(|text| {
+            let badge_size = if text.is_empty() {
+                self.layout = None;
+                Size::new(DOT_SIZE, DOT_SIZE)
+            } else {
+                let color = ctx.theme().colors.on_accent.clone();
+                let layout = ctx
+                    .text()
+                    .new_text_layout(text)
+                    .font(FontFamily::SYSTEM_UI, BADGE_TEXT_SIZE)
+                    .default_attribute(FontWeight::BOLD)
+                    .default_attribute(TextAttribute::TextColor(color))
+                    .build()
+                    .unwrap();
+                let width = layout.size().width + 2.0 * BADGE_X_PADDING;
+                self.layout = Some(layout);
+                Size::new(width.max(BADGE_HEIGHT), BADGE_HEIGHT)
+            };
+            let center = match self.props.corner {
+                BadgeCorner::TopLeft => Point::ORIGIN,
+                BadgeCorner::TopRight => Point::new(size.width, 0.0),
+                BadgeCorner::BottomLeft => Point::new(0.0, size.height),
+                BadgeCorner::BottomRight => Point::new(size.width, size.height),
+            };
+            Rect::from_center_size(center, badge_size)
+        });
+
+        let bounds = size.to_rect();
+        let mut paint_rect = bounds + child.compute_parent_paint_insets(size);
+        if let Some(rect) = self.rect {
+            paint_rect = paint_rect.union(rect);
+        }
+        ctx.set_paint_insets(paint_rect - bounds);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, children: &mut Children) {
+        children[0].paint(ctx);
+        if let Some(rect) = self.rect {
+            let theme = ctx.theme();
+            let accent = theme.colors.accent.clone();
+            let background = theme.colors.background.clone();
+            let shape = rect.to_rounded_rect(rect.height() / 2.0);
+            ctx.fill(shape, &accent);
+            ctx.stroke(shape, &background, 1.0);
+            if let Some(layout) = &self.layout {
+                let text_size = layout.size();
+                let origin = rect.center() - text_size.to_vec2() / 2.0;
+                ctx.draw_text(layout, origin);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_of_count() {
+        assert_eq!(badge_text(Some(0), 99), None);
+        assert_eq!(badge_text(Some(7), 99).as_deref(), Some("7"));
+        assert_eq!(badge_text(Some(100), 99).as_deref(), Some("99+"));
+        assert_eq!(badge_text(None, 99).as_deref(), Some(""));
+    }
+}
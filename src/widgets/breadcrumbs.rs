@@ -0,0 +1,355 @@
+//! A path of clickable segments, like the location of a file.
+
+use crate::{
+    event::MouseButton,
+    kurbo::Point,
+    object::prelude::*,
+    piet::{PietTextLayout, Text, TextAttribute, TextLayout, TextLayoutBuilder},
+    widgets::Label,
+};
+use std::ops::Range;
+
+const CRUMB_PADDING: Size = Size::new(4.0, 2.0);
+const SEPARATOR_PADDING: f64 = 4.0;
+const ELLIPSIS: &str = "…";
+
+/// Shows a path as segments with separators between them, for example
+/// the folders leading to a file.
+///
+/// When the path does not fit, segments in the middle are replaced by an
+/// ellipsis, the first and last segments stay visible. The last segment
+/// is the current location and can not be clicked.
+///
+/// ```ignore
+/// if let Some(index) = Breadcrumbs::new(&folders).build(ui) {
+///     folders.truncate(index + 1);
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Breadcrumbs {
+    segments: Vec<String>,
+    separator: String,
+}
+
+impl Breadcrumbs {
+    pub fn new<S: Into<String>>(segments: impl IntoIterator<Item = S>) -> Self {
+        Breadcrumbs {
+            segments: segments.into_iter().map(Into::into).collect(),
+            separator: "›".to_string(),
+        }
+    }
+
+    /// The text between segments.
+    ///
+    /// The default value is `"›"`.
+    pub fn separator(mut self, separator: impl Into<String>) -> Self {
+        self.separator = separator.into();
+        self
+    }
+
+    /// Returns the index of the clicked segment.
+    #[must_use]
+    #[track_caller]
+    pub fn build(self, ui: &mut Ui) -> Option<usize> {
+        let caller = Location::caller().into();
+        let Breadcrumbs {
+            segments,
+            separator,
+        } = self;
+        let mut clicked = None;
+        ui.render_object(caller, Trail { separator }, |ui| {
+            let last = segments.len().saturating_sub(1);
+            for (index, segment) in segments.into_iter().enumerate() {
+                let caller = Location::caller().into();
+                let crumb = Crumb {
+                    current: index == last,
+                };
+                if ui.render_object(caller, crumb, |ui| Label::new(segment).build(ui)) {
+                    clicked = Some(index);
+                }
+            }
+        });
+        clicked
+    }
+}
+
+/// The segments to hide so the path fits into `max` width.
+///
+/// Segments are hidden from the second one on, until the remaining ones
+/// and an ellipsis fit. The first and last segment are never hidden.
+fn hidden_segments(widths: &[f64], separator: f64, ellipsis: f64, max: f64) -> Range<usize> {
+    let count = widths.len();
+    let total = widths.iter().sum::<f64>() + separator * count.saturating_sub(1) as f64;
+    if total <= max || count <= 2 {
+        return 0..0;
+    }
+    for first_shown in 2..count {
+        let shown = widths[0] + widths[first_shown..].iter().sum::<f64>();
+        let separators = (count - first_shown + 1) as f64 * separator;
+        if shown + ellipsis + separators <= max {
+            return 1..first_shown;
+        }
+    }
+    1..count - 1
+}
+
+/// Lays out the segments in a row, with separators between them.
+#[derive(PartialEq)]
+struct Trail {
+    separator: String,
+}
+
+impl Properties for Trail {
+    type Object = TrailObject;
+}
+
+struct TrailObject {
+    separator: String,
+    separator_layout: Option<PietTextLayout>,
+    ellipsis_layout: Option<PietTextLayout>,
+    /// The segments replaced by the ellipsis.
+    hidden: Range<usize>,
+    /// Where separators and the ellipsis are painted.
+    separators: Vec<Point>,
+    ellipsis: Option<Point>,
+}
+
+impl RenderObject<Trail> for TrailObject {
+    type Action = ();
+
+    fn create(props: Trail) -> Self {
+        TrailObject {
+            separator: props.separator,
+            separator_layout: None,
+            ellipsis_layout: None,
+            hidden: 0..0,
+            separators: Vec::new(),
+            ellipsis: None,
+        }
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, props: Trail) {
+        if self.separator != props.separator {
+            self.separator = props.separator;
+            self.separator_layout = None;
+            ctx.request_layout();
+        }
+    }
+}
+
+impl RenderObjectInterface for TrailObject {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, children: &mut Children) {
+        for (index, child) in children.iter().enumerate() {
+            if !self.hidden.contains(&index) {
+                child.event(ctx, event);
+            }
+        }
+    }
+
+    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle) {}
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        children: &mut Children,
+    ) -> Size {
+        bc.debug_check("Breadcrumbs");
+
+        let color = ctx.theme().colors.panel_border.clone();
+        let font = ctx.theme().font.clone();
+        let mut text_layout = |text: &str| {
+            ctx.text()
+                .new_text_layout(text.to_string())
+                .font(font.family.clone(), font.size)
+                .default_attribute(TextAttribute::TextColor(color.clone()))
+                .build()
+                .unwrap()
+        };
+        if self.separator_layout.is_none() {
+            self.separator_layout = Some(text_layout(&self.separator));
+        }
+        if self.ellipsis_layout.is_none() {
+            self.ellipsis_layout = Some(text_layout(ELLIPSIS));
+        }
+        let separator = self
+            .separator_layout
+            .as_ref()
+            .map(|l| l.size())
+            .unwrap_or_default();
+        let ellipsis = self
+            .ellipsis_layout
+            .as_ref()
+            .map(|l| l.size())
+            .unwrap_or_default();
+        let separator_width = separator.width + 2.0 * SEPARATOR_PADDING;
+
+        let child_bc = BoxConstraints::new(Size::ZERO, Size::new(f64::INFINITY, bc.max().height));
+        let mut widths = Vec::with_capacity(children.len());
+        let mut height = separator.height.max(ellipsis.height);
+        for child in children.iter() {
+            let size = child.layout(ctx, &child_bc);
+            widths.push(size.width);
+            height = height.max(size.height);
+        }
+        self.hidden = hidden_segments(&widths, separator_width, ellipsis.width, bc.max().width);
+
+        self.separators.clear();
+        self.ellipsis = None;
+        let mut x = 0.0;
+        let mut baseline = None;
+        for (index, child) in children.iter().enumerate() {
+            if self.hidden.contains(&index) {
+                if self.ellipsis.is_none() {
+                    self.ellipsis = Some(Point::new(x, (height - ellipsis.height) / 2.0));
+                    x += ellipsis.width;
+                    self.separators.push(Point::new(
+                        x + SEPARATOR_PADDING,
+                        (height - separator.height) / 2.0,
+                    ));
+                    x += separator_width;
+                }
+                continue;
+            }
+            let size = child.layout_rect().size();
+            let y = (height - size.height) / 2.0;
+            child.set_origin(ctx, Point::new(x, y));
+            baseline.get_or_insert(child.baseline_offset() + y);
+            x += size.width;
+            if index + 1 < widths.len() {
+                self.separators.push(Point::new(
+                    x + SEPARATOR_PADDING,
+                    (height - separator.height) / 2.0,
+                ));
+                x += separator_width;
+            }
+        }
+        if x > bc.max().width {
+            ctx.report_layout_issue("Breadcrumbs do not fit, even with segments hidden");
+        }
+        ctx.set_baseline_offset(baseline.unwrap_or(0.0));
+        bc.constrain(Size::new(x, height))
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, children: &mut Children) {
+        if let Some(layout) = &self.separator_layout {
+            for origin in &self.separators {
+                ctx.draw_text(layout, *origin);
+            }
+        }
+        if let (Some(layout), Some(origin)) = (&self.ellipsis_layout, self.ellipsis) {
+            ctx.draw_text(layout, origin);
+        }
+        for (index, child) in children.iter().enumerate() {
+            if !self.hidden.contains(&index) {
+                child.paint(ctx);
+            }
+        }
+    }
+}
+
+/// A segment, which is clickable unless it is the current location.
+#[derive(PartialEq)]
+struct Crumb {
+    current: bool,
+}
+
+impl Properties for Crumb {
+    type Object = CrumbObject;
+}
+
+struct CrumbObject {
+    current: bool,
+    clicked: bool,
+}
+
+impl RenderObject<Crumb> for CrumbObject {
+    type Action = bool;
+
+    fn create(props: Crumb) -> Self {
+        CrumbObject {
+            current: props.current,
+            clicked: false,
+        }
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, props: Crumb) -> bool {
+        if self.current != props.current {
+            self.current = props.current;
+            ctx.request_paint();
+        }
+        std::mem::take(&mut self.clicked)
+    }
+}
+
+impl RenderObjectInterface for CrumbObject {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, _children: &mut Children) {
+        if self.current {
+            return;
+        }
+        match event {
+            Event::MouseDown(mouse) if mouse.button == MouseButton::Left => {
+                ctx.set_active(true);
+                ctx.request_paint();
+            }
+            Event::MouseUp(mouse) if ctx.is_active() && mouse.button == MouseButton::Left => {
+                ctx.set_active(false);
+                if ctx.is_hot() {
+                    self.clicked = true;
+                    ctx.request_update();
+                    ctx.set_handled();
+                }
+                ctx.request_paint();
+            }
+            _ => {}
+        }
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        if let LifeCycle::HotChanged(_) = event {
+            ctx.request_paint();
+        }
+    }
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        children: &mut Children,
+    ) -> Size {
+        ctx.set_hit_target(true);
+        let padding = CRUMB_PADDING * 2.0;
+        let label = children[0].layout(ctx, &bc.loosen().shrink(padding));
+        children[0].set_origin(ctx, CRUMB_PADDING.to_vec2().to_point());
+        let baseline = children[0].baseline_offset();
+        ctx.set_baseline_offset(baseline + CRUMB_PADDING.height);
+        bc.constrain(label + padding)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, children: &mut Children) {
+        if !self.current && ctx.is_hot() {
+            let theme = ctx.theme();
+            let ghost = theme.colors.ghost.clone();
+            let radius = theme.spacing.corner_radius;
+            let rect = ctx.size().to_rect().to_rounded_rect(radius);
+            ctx.fill(rect, &ghost);
+        }
+        children[0].paint(ctx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hide_middle_segments() {
+        let widths = [10.0, 20.0, 20.0, 10.0];
+        assert_eq!(hidden_segments(&widths, 5.0, 8.0, 100.0), 0..0);
+        // All segments take 75, without the second one 10 + 8 + 20 + 10 + 3 * 5 = 63.
+        assert_eq!(hidden_segments(&widths, 5.0, 8.0, 70.0), 1..2);
+        assert_eq!(hidden_segments(&widths, 5.0, 8.0, 40.0), 1..3);
+        assert_eq!(hidden_segments(&widths, 5.0, 8.0, 10.0), 1..3);
+        assert_eq!(hidden_segments(&[50.0, 50.0], 5.0, 8.0, 10.0), 0..0);
+    }
+}
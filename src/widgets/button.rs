@@ -3,12 +3,13 @@ use std::panic::Location;
 use crate::{
     context::{EventCtx, LayoutCtx, LifeCycleCtx, PaintCtx, UpdateCtx},
     event::{Event, LifeCycle, MouseButton},
-    kurbo::Size,
+    kurbo::{BezPath, Shape, Size},
     object::{Properties, RenderObject, RenderObjectInterface},
-    piet::RenderContext,
+    style::State,
+    theme::Theme,
     tree::Children,
     ui::Ui,
-    widgets::label::Label,
+    widgets::{label::Label, RepeatBehavior, Repeatable},
     BoxConstraints,
 };
 use druid::Point;
@@ -18,6 +19,7 @@ pub use style::{Style, StyleSheet};
 #[derive(Default, PartialEq)]
 pub struct Button {
     disabled: bool,
+    repeat: Option<RepeatBehavior>,
     style: Option<Box<dyn StyleSheet>>,
 }
 
@@ -35,6 +37,14 @@ impl Button {
         self
     }
 
+    /// Click repeatedly while the button is held down.
+    ///
+    /// The first click happens when the button is pressed, instead of released.
+    pub fn repeat(mut self, behavior: RepeatBehavior) -> Self {
+        self.repeat = Some(behavior);
+        self
+    }
+
     pub fn style(mut self, style: impl Into<Box<dyn StyleSheet>>) -> Self {
         self.style = Some(style.into());
         self
@@ -49,6 +59,23 @@ impl Button {
         })
     }
 
+    /// A button with a `label` of its own, to emphasize its text.
+    ///
+    /// ```ignore
+    /// let label = Label::new("Delete").weight(FontWeight::BOLD);
+    /// if Button::new().label(ui, label) {
+    ///     delete(&item);
+    /// }
+    /// ```
+    #[must_use]
+    #[track_caller]
+    pub fn label(self, ui: &mut Ui, label: Label) -> bool {
+        let caller = Location::caller().into();
+        ui.render_object(caller, self, |ui| {
+            label.build(ui);
+        })
+    }
+
     #[must_use]
     #[track_caller]
     pub fn custom(self, ui: &mut Ui, content: impl FnOnce(&mut Ui)) -> bool {
@@ -66,25 +93,22 @@ pub struct ButtonObject {
     props: Button,
     label_size: Size,
     clicked: bool,
+    repeatable: Repeatable,
+    /// The painted shape, clicks outside of it do not count.
+    shape: Option<BezPath>,
 }
 
 impl ButtonObject {
-    fn style_sheet(&self) -> &dyn style::StyleSheet {
+    fn style_sheet<'a>(&'a self, theme: &'a Theme) -> &'a dyn style::StyleSheet {
         match self.props.style {
             Some(ref sheet) => sheet.as_ref(),
-            None => &style::Default,
+            None => theme.button.as_ref(),
         }
     }
 
-    fn style(&self, hovered: bool, pressed: bool) -> Style {
-        let sheet = self.style_sheet();
-        let disabled = self.props.disabled;
-        match (disabled, hovered, pressed) {
-            (true, _, _) => sheet.disabled(),
-            (false, true, true) => sheet.pressed(),
-            (false, true, false) => sheet.hovered(),
-            (false, false, _) => sheet.enabled(),
-        }
+    fn style(&self, theme: &Theme, disabled: bool, hovered: bool, pressed: bool) -> Style {
+        let state = State::new(self.props.disabled || disabled, hovered, pressed, false);
+        self.style_sheet(theme).style(state)
     }
 }
 
@@ -93,15 +117,19 @@ impl RenderObject<Button> for ButtonObject {
 
     fn create(props: Button) -> Self {
         ButtonObject {
+            repeatable: Repeatable::new(props.repeat.unwrap_or_default()),
             props,
             label_size: Size::ZERO,
             clicked: false,
+            shape: None,
         }
     }
 
     fn update(&mut self, ctx: &mut UpdateCtx, props: Button) -> Self::Action {
         if self.props != props {
             ctx.request_layout();
+            self.repeatable
+                .set_behavior(props.repeat.unwrap_or_default());
             self.props = props;
         }
 
@@ -113,17 +141,29 @@ impl RenderObject<Button> for ButtonObject {
 
 impl RenderObjectInterface for ButtonObject {
     fn event(&mut self, ctx: &mut EventCtx, event: &Event, children: &mut Children) {
+        if self.repeatable.is_repeat(ctx, event) && ctx.is_hot() {
+            self.clicked = true;
+            ctx.request_update();
+        }
+
         match event {
             Event::MouseDown(mouse_event) => {
                 if mouse_event.button == MouseButton::Left {
                     ctx.set_active(true);
+                    if self.props.repeat.is_some() {
+                        self.repeatable.press(ctx);
+                        self.clicked = true;
+                        ctx.request_update();
+                    }
                     ctx.request_paint();
                 }
             }
             Event::MouseUp(mouse_event) => {
                 if ctx.is_active() && mouse_event.button == MouseButton::Left {
                     ctx.set_active(false);
-                    if ctx.is_hot() {
+                    if self.repeatable.is_pressed() {
+                        self.repeatable.release();
+                    } else if ctx.is_hot() {
                         self.clicked = true;
                         ctx.request_update();
                         ctx.set_handled();
@@ -152,48 +192,81 @@ impl RenderObjectInterface for ButtonObject {
         children: &mut Children,
     ) -> Size {
         bc.debug_check("Button");
-        let style = self.style(ctx.is_hot(), ctx.is_active());
-
-        let padding = Size::new(2.0 * style.border_radius, 2.0 * style.border_radius);
+        ctx.set_hit_target(true);
+        let style = self.style(
+            ctx.theme(),
+            ctx.is_disabled(),
+            ctx.is_hot(),
+            ctx.is_active(),
+        );
+
+        let radius = style.border.radii.max();
+        let padding = Size::new(2.0 * radius, 2.0 * radius);
         let label_bc = bc.loosen().shrink(padding);
         self.label_size = children[0].layout(ctx, &label_bc);
 
         let baseline = children[0].baseline_offset();
-        ctx.set_baseline_offset(baseline + style.border_radius);
+        ctx.set_baseline_offset(baseline + radius);
 
         let required_size = self.label_size + padding;
-        let size = bc.constrain(self.style_sheet().pick_size(bc, required_size));
+        let size = bc.constrain(self.style_sheet(ctx.theme()).pick_size(bc, required_size));
 
         let h_offset = (size.width - self.label_size.width) / 2.0;
         let v_offset = (size.height - self.label_size.height) / 2.0;
         children[0].set_origin(ctx, Point::new(h_offset, v_offset));
 
+        // The shadow changes with the hover state, which does not cause a
+        // layout pass, so make room for the largest one.
+        let sheet = self.style_sheet(ctx.theme());
+        let rect = size.to_rect();
+        let painted = [
+            sheet.enabled(),
+            sheet.hovered(),
+            sheet.pressed(),
+            sheet.focused(),
+        ]
+        .iter()
+        .fold(rect, |painted, style| {
+            painted.union(style.shadow.bounds(rect))
+        });
+        ctx.set_paint_insets(painted - rect);
+
         size
     }
 
     fn paint(&mut self, ctx: &mut PaintCtx, children: &mut Children) {
         let size = ctx.size();
-        let style = self.style(ctx.is_hot(), ctx.is_active());
-        let stroke_width = style.border_width;
-
-        let rounded_rect = size
-            .to_rect()
-            .inset(-stroke_width / 2.0)
-            .to_rounded_rect(style.border_radius);
-
-        let border_color = style.border_color;
-
-        ctx.stroke(rounded_rect, &border_color, stroke_width);
-
-        ctx.fill(rounded_rect, &style.background);
+        let style = self.style(
+            ctx.theme(),
+            ctx.is_disabled(),
+            ctx.is_hot(),
+            ctx.is_active(),
+        );
+        let rect = ctx.snap_rect(size.to_rect());
+        let border = style.border.snapped(ctx);
+        let shape = border.outer_path(rect);
+
+        style.shadow.paint(ctx, rect);
+        style.background.paint(ctx, shape.clone());
+        border.paint(ctx, rect);
         children[0].paint(ctx);
+        ctx.publish_style(style);
+        self.shape = Some(shape);
+    }
+
+    fn hit_test(&self, pos: Point) -> bool {
+        match &self.shape {
+            Some(shape) => shape.winding(pos) != 0,
+            None => true,
+        }
     }
 }
 
 pub mod style {
     use crate::{
-        kurbo::{Size, Vec2},
+        kurbo::Size,
         piet::Color,
+        style::{impl_style_sheet, Background, Border, Shadow, State},
         BoxConstraints,
     };
     use std::any::Any;
@@ -203,34 +276,27 @@ pub mod style {
     /// The appearance of a button.
     #[derive(Debug, Clone)]
     pub struct Style {
-        pub border_width: f64,
-        pub border_radius: f64,
-        pub border_color: Color,
-        pub background: Color,
+        pub border: Border,
+        pub background: Background,
 
-        pub shadow_offset: Vec2,
+        /// The shadow below the button, it has to fit in the paint insets,
+        /// which the button takes from the enabled, hovered, pressed and
+        /// focused styles.
+        pub shadow: Shadow,
         pub text_color: Color,
     }
 
     impl std::default::Default for Style {
         fn default() -> Self {
             Self {
-                shadow_offset: Vec2::default(),
-                background: TRANSPARENT,
-                border_radius: 0.0,
-                border_width: 0.0,
-                border_color: TRANSPARENT,
+                shadow: Shadow::NONE,
+                background: TRANSPARENT.into(),
+                border: Border::NONE,
                 text_color: Color::BLACK,
             }
         }
     }
 
-    impl PartialEq for Box<dyn StyleSheet> {
-        fn eq(&self, other: &Self) -> bool {
-            self.as_ref().eq(other.as_ref())
-        }
-    }
-
     /// A set of rules that dictate the style of a button.
     pub trait StyleSheet: Any {
         fn eq(&self, other: &dyn StyleSheet) -> bool;
@@ -238,62 +304,76 @@ pub mod style {
         fn enabled(&self) -> Style;
 
         fn hovered(&self) -> Style {
-            let active = self.enabled();
-
             Style {
-                shadow_offset: active.shadow_offset + Vec2::new(0.0, 1.0),
-                ..active
+                shadow: Shadow::elevation(2.0),
+                ..self.enabled()
             }
         }
 
         fn pressed(&self) -> Style {
             Style {
-                shadow_offset: Vec2::default(),
+                shadow: Shadow::elevation(1.0),
                 ..self.hovered()
             }
         }
 
+        fn focused(&self) -> Style {
+            self.enabled()
+        }
+
         fn disabled(&self) -> Style {
             let active = self.enabled();
 
             Style {
-                shadow_offset: Vec2::default(),
+                shadow: Shadow::NONE,
                 background: active.background.with_alpha(0.5),
                 text_color: active.text_color.with_alpha(0.5),
                 ..active
             }
         }
 
+        /// The style in `state`.
+        fn style(&self, state: State) -> Style {
+            match state {
+                State::Enabled => self.enabled(),
+                State::Hovered => self.hovered(),
+                State::Pressed => self.pressed(),
+                State::Focused => self.focused(),
+                State::Disabled => self.disabled(),
+            }
+        }
+
         fn pick_size(&self, _bc: &BoxConstraints, required_size: Size) -> Size {
             Size::new(required_size.width, f64::max(required_size.height, 24.0))
         }
     }
 
+    /// The button style of the dark theme.
     #[derive(Debug, PartialEq)]
     pub struct Default;
 
     impl StyleSheet for Default {
         fn enabled(&self) -> Style {
             Style {
-                shadow_offset: Vec2::new(0.0, 0.0),
-                background: Color::rgb(0.5, 0.5, 0.87),
-                border_radius: 2.0,
-                border_width: 1.0,
-                border_color: Color::rgb(0.7, 0.7, 0.7),
+                shadow: Shadow::NONE,
+                background: Color::rgb(0.5, 0.5, 0.87).into(),
+                border: Border::uniform(1.0, Color::rgb(0.7, 0.7, 0.7)).radius(2.0),
                 text_color: Color::WHITE,
             }
         }
 
         fn hovered(&self) -> Style {
             Style {
-                background: Color::rgb(0.6, 0.6, 0.87),
+                background: Color::rgb(0.6, 0.6, 0.87).into(),
+                shadow: Shadow::elevation(2.0),
                 ..self.enabled()
             }
         }
 
         fn pressed(&self) -> Style {
             Style {
-                background: Color::rgb(0.6, 0.6, 0.95),
+                background: Color::rgb(0.6, 0.6, 0.95).into(),
+                shadow: Shadow::elevation(1.0),
                 ..self.enabled()
             }
         }
@@ -303,18 +383,46 @@ pub mod style {
         }
     }
 
-    impl std::default::Default for Box<dyn StyleSheet> {
-        fn default() -> Self {
-            Box::new(Default)
+    /// The button style of the light theme.
+    #[derive(Debug, PartialEq)]
+    pub struct Light;
+
+    impl StyleSheet for Light {
+        fn enabled(&self) -> Style {
+            Style {
+                shadow: Shadow::NONE,
+                background: Color::rgb(0.4, 0.4, 0.85).into(),
+                border: Border::uniform(1.0, Color::rgb(0.5, 0.5, 0.5)).radius(2.0),
+                text_color: Color::WHITE,
+            }
+        }
+
+        fn hovered(&self) -> Style {
+            Style {
+                background: Color::rgb(0.45, 0.45, 0.9).into(),
+                shadow: Shadow::elevation(2.0),
+                ..self.enabled()
+            }
+        }
+
+        fn pressed(&self) -> Style {
+            Style {
+                background: Color::rgb(0.35, 0.35, 0.8).into(),
+                shadow: Shadow::elevation(1.0),
+                ..self.enabled()
+            }
+        }
+
+        fn eq(&self, other: &dyn StyleSheet) -> bool {
+            Any::type_id(other) == std::any::TypeId::of::<Self>()
         }
     }
 
-    impl<T> From<T> for Box<dyn StyleSheet>
-    where
-        T: 'static + StyleSheet,
-    {
-        fn from(style: T) -> Self {
-            Box::new(style)
+    impl_style_sheet!(StyleSheet);
+
+    impl std::default::Default for Box<dyn StyleSheet> {
+        fn default() -> Self {
+            Box::new(Default)
         }
     }
 }
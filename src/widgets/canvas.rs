@@ -0,0 +1,209 @@
+//! A widget painted by a closure.
+
+use crate::{object::prelude::*, piet::Piet};
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+};
+
+type PaintFn = dyn Fn(&mut Piet, Size);
+type EventFn = dyn FnMut(&mut EventCtx, &Event);
+
+/// A widget for custom painting, without implementing a render object.
+///
+/// The canvas takes all the space its parent allows, or the
+/// [`size`](Canvas::size) if the constraints are unbounded.
+///
+/// The closures are replaced in every build pass. Without a
+/// [`key`](Canvas::key), the canvas is repainted after every build pass,
+/// because its closures can not be compared.
+///
+/// ```ignore
+/// Canvas::new(move |piet, size| {
+///     let circle = Circle::new(size.to_rect().center(), size.min_side() / 2.0);
+///     piet.fill(circle, &color);
+/// })
+/// .key(color.as_rgba_u32())
+/// .build(ui);
+/// ```
+pub struct Canvas {
+    paint: Box<PaintFn>,
+    event: Option<Box<EventFn>>,
+    key: Option<u64>,
+    size: Size,
+}
+
+impl Properties for Canvas {
+    type Object = CanvasObject;
+}
+
+impl Canvas {
+    /// Paint with `paint`, which gets the size of the canvas.
+    pub fn new(paint: impl Fn(&mut Piet, Size) + 'static) -> Self {
+        Canvas {
+            paint: Box::new(paint),
+            event: None,
+            key: None,
+            size: Size::ZERO,
+        }
+    }
+
+    /// Handle the events the canvas receives with `event`, in canvas coordinates.
+    pub fn on_event(mut self, event: impl FnMut(&mut EventCtx, &Event) + 'static) -> Self {
+        self.event = Some(Box::new(event));
+        self
+    }
+
+    /// Only repaint when `key` changed, it should cover everything `paint` depends on.
+    pub fn key(mut self, key: impl Hash) -> Self {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        self.key = Some(hasher.finish());
+        self
+    }
+
+    /// The size of the canvas in unbounded directions.
+    ///
+    /// The default value is [`Size::ZERO`].
+    pub fn size(mut self, size: Size) -> Self {
+        self.size = size;
+        self
+    }
+
+    #[track_caller]
+    pub fn build(self, ui: &mut Ui) {
+        let caller = Location::caller().into();
+        ui.render_object(caller, self, |_| {});
+    }
+}
+
+pub struct CanvasObject {
+    props: Canvas,
+}
+
+impl RenderObject<Canvas> for CanvasObject {
+    type Action = ();
+
+    fn create(props: Canvas) -> Self {
+        CanvasObject { props }
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, props: Canvas) {
+        if props.key.is_none() || props.key != self.props.key {
+            ctx.request_paint();
+        }
+        if props.size != self.props.size {
+            ctx.request_layout();
+        }
+        self.props = props;
+    }
+}
+
+impl RenderObjectInterface for CanvasObject {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, _children: &mut Children) {
+        if let Some(handler) = &mut self.props.event {
+            handler(ctx, event);
+        }
+    }
+
+    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle) {}
+
+    fn layout(
+        &mut self,
+        _ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        _children: &mut Children,
+    ) -> Size {
+        let max = bc.max();
+        let preferred = Size::new(
+            if max.width.is_finite() {
+                max.width
+            } else {
+                self.props.size.width
+            },
+            if max.height.is_finite() {
+                max.height
+            } else {
+                self.props.size.height
+            },
+        );
+        bc.constrain(preferred)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, _children: &mut Children) {
+        let size = ctx.size();
+        let paint = &self.props.paint;
+        ctx.with_save(|ctx| {
+            ctx.clip(size.to_rect());
+            paint(ctx.render_ctx, size);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        kurbo::Point,
+        piet::Color,
+        testing::{self, Harness},
+        widgets::{Flex, SizedBox},
+    };
+    use std::{cell::Cell, rc::Rc};
+
+    #[test]
+    fn paint_and_handle_events() {
+        let pressed = Rc::new(Cell::new(None));
+        let pressed_at = pressed.clone();
+        let mut harness = Harness::new(move |ui| {
+            let pressed_at = pressed_at.clone();
+            Flex::column().build(ui, |ui| {
+                SizedBox::new().height(100.0).empty(ui);
+                Canvas::new(|piet, size| piet.fill(size.to_rect(), &Color::rgb8(0, 0, 255)))
+                    .on_event(move |_ctx, event| {
+                        if let Event::MouseDown(mouse) = event {
+                            pressed_at.set(Some(mouse.pos));
+                        }
+                    })
+                    .size(Size::new(200.0, 50.0))
+                    .build(ui);
+            });
+        });
+        let canvas = testing::assert_exists::<CanvasObject>(harness.tree(), testing::any());
+        let rect = harness.window_rect(canvas);
+        // Unbounded vertically, bounded horizontally.
+        assert_eq!(rect.height(), 50.0);
+        assert_eq!(rect.width(), 800.0);
+
+        let snapshot = harness.snapshot();
+        assert_eq!(snapshot.pixel(10, 110), [0, 0, 255, 255]);
+        assert_ne!(snapshot.pixel(10, 160), [0, 0, 255, 255]);
+
+        harness.click(Point::new(30.0, 120.0));
+        assert_eq!(pressed.get(), Some(Point::new(30.0, 20.0)));
+    }
+
+    #[test]
+    fn repaint_when_the_key_changes() {
+        let key = Rc::new(Cell::new(0));
+        let canvas_key = key.clone();
+        let mut harness = Harness::new(move |ui| {
+            Flex::column().build(ui, |ui| {
+                Canvas::new(|_, _| {})
+                    .key(canvas_key.get())
+                    .size(Size::new(200.0, 50.0))
+                    .build(ui);
+            });
+        });
+        harness.take_damage();
+
+        harness.rebuild();
+        assert!(harness.take_damage().is_empty());
+
+        key.set(1);
+        harness.rebuild();
+        let canvas = testing::assert_exists::<CanvasObject>(harness.tree(), testing::any());
+        let rect = harness.window_rect(canvas);
+        assert!(harness.take_damage().intersects(rect));
+    }
+}
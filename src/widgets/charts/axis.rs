@@ -0,0 +1,143 @@
+//! Ticks and labels of chart axes.
+
+use crate::{
+    kurbo::Size,
+    piet::{
+        Color, FontFamily, PietText, PietTextLayout, Text, TextAttribute, TextLayout,
+        TextLayoutBuilder,
+    },
+};
+
+const LABEL_TEXT_SIZE: f64 = 11.0;
+
+/// A value axis, with ticks at round numbers.
+pub(super) struct Axis {
+    pub min: f64,
+    pub max: f64,
+    pub ticks: Vec<(f64, PietTextLayout)>,
+}
+
+impl Axis {
+    /// An axis covering `min` to `max` with about `count` ticks.
+    pub fn new(text: &mut PietText, min: f64, max: f64, count: usize, color: &Color) -> Self {
+        let (values, step) = nice_ticks(min, max, count);
+        let ticks = values
+            .iter()
+            .map(|&value| (value, label(text, format_tick(value, step), color)))
+            .collect();
+        Axis {
+            min: values[0],
+            max: values[values.len() - 1],
+            ticks,
+        }
+    }
+
+    /// Map `value` onto the range from `start` to `end`.
+    pub fn map(&self, value: f64, start: f64, end: f64) -> f64 {
+        start + (value - self.min) / (self.max - self.min) * (end - start)
+    }
+
+    /// The size of the largest label.
+    pub fn label_size(&self) -> Size {
+        max_size(self.ticks.iter().map(|(_, layout)| layout))
+    }
+}
+
+/// Lay out an axis label or tooltip text.
+pub(super) fn label(text: &mut PietText, label: String, color: &Color) -> PietTextLayout {
+    text.new_text_layout(label)
+        .font(FontFamily::SYSTEM_UI, LABEL_TEXT_SIZE)
+        .default_attribute(TextAttribute::TextColor(color.clone()))
+        .build()
+        .unwrap()
+}
+
+/// The size of the largest of `layouts`.
+pub(super) fn max_size<'a>(layouts: impl Iterator<Item = &'a PietTextLayout>) -> Size {
+    layouts.fold(Size::ZERO, |max, layout| {
+        let size = layout.size();
+        Size::new(max.width.max(size.width), max.height.max(size.height))
+    })
+}
+
+/// A step of 1, 2 or 5 times a power of ten that splits `range` into about `count` steps.
+fn nice_step(range: f64, count: usize) -> f64 {
+    let raw = range / count.max(1) as f64;
+    let magnitude = 10f64.powi(raw.log10().floor() as i32);
+    let nice = match raw / magnitude {
+        n if n <= 1.0 => 1.0,
+        n if n <= 2.0 => 2.0,
+        n if n <= 5.0 => 5.0,
+        _ => 10.0,
+    };
+    nice * magnitude
+}
+
+/// Round ticks covering `min` to `max`, and the step between them.
+///
+/// The first and last tick are at or beyond the bounds, so the data fits
+/// between them. Empty ranges are widened, so there are always two ticks.
+fn nice_ticks(min: f64, max: f64, count: usize) -> (Vec<f64>, f64) {
+    let (min, max) = if !min.is_finite() || !max.is_finite() {
+        (0.0, 1.0)
+    } else if max > min {
+        (min, max)
+    } else if min == 0.0 {
+        (0.0, 1.0)
+    } else {
+        let padding = min.abs() / 2.0;
+        (min - padding, max + padding)
+    };
+    let step = nice_step(max - min, count);
+    let first = (min / step).floor() as i64;
+    let last = (max / step).ceil() as i64;
+    let ticks = (first..=last)
+        .map(|index| {
+            let value = index as f64 * step;
+            // Avoid labels like "-0".
+            if value == 0.0 {
+                0.0
+            } else {
+                value
+            }
+        })
+        .collect();
+    (ticks, step)
+}
+
+/// The label of a tick, with as many decimals as the step needs.
+fn format_tick(value: f64, step: f64) -> String {
+    let decimals = (-step.log10().floor()).max(0.0) as usize;
+    format!("{:.*}", decimals, value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn steps() {
+        assert_eq!(nice_step(10.0, 5), 2.0);
+        assert_eq!(nice_step(100.0, 4), 50.0);
+        assert_eq!(nice_step(1.0, 10), 0.1);
+        assert_eq!(nice_step(7.0, 2), 5.0);
+    }
+
+    #[test]
+    fn ticks() {
+        assert_eq!(
+            nice_ticks(0.0, 10.0, 5),
+            (vec![0.0, 2.0, 4.0, 6.0, 8.0, 10.0], 2.0)
+        );
+        assert_eq!(nice_ticks(-3.0, 7.0, 2), (vec![-5.0, 0.0, 5.0, 10.0], 5.0));
+        assert_eq!(nice_ticks(0.0, 0.0, 5).0.len(), 6);
+        assert_eq!(nice_ticks(4.0, 4.0, 2), (vec![2.0, 4.0, 6.0], 2.0));
+    }
+
+    #[test]
+    fn tick_labels() {
+        assert_eq!(format_tick(10.0, 5.0), "10");
+        assert_eq!(format_tick(0.5, 0.5), "0.5");
+        assert_eq!(format_tick(0.1, 0.05), "0.10");
+    }
+}
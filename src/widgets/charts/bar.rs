@@ -0,0 +1,289 @@
+//! A chart of series as groups of bars.
+
+use super::{
+    axis::{label, max_size, Axis},
+    chart_size, paint_tooltip, tick_count, Series, PLOT_PADDING,
+};
+use crate::{
+    kurbo::{Line, Point, Rect},
+    object::prelude::*,
+    piet::{PietTextLayout, TextLayout},
+};
+
+/// The part of a category's width taken by its bars.
+const GROUP_FILL: f64 = 0.8;
+
+/// Draws the values of series as bars, grouped by category.
+///
+/// The n-th value of every series belongs to the n-th category, the
+/// value axis always includes zero. Hovering a bar shows its value.
+///
+/// ```ignore
+/// BarChart::new()
+///     .categories(["Q1", "Q2", "Q3", "Q4"])
+///     .series(Series::values("2023", [12.0, 15.0, 9.0, 20.0]))
+///     .series(Series::values("2024", [14.0, 18.0, 11.0, 24.0]))
+///     .build(ui);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct BarChart {
+    categories: Vec<String>,
+    series: Vec<Series>,
+}
+
+impl Properties for BarChart {
+    type Object = BarChartObject;
+}
+
+impl Default for BarChart {
+    fn default() -> Self {
+        BarChart::new()
+    }
+}
+
+impl BarChart {
+    pub fn new() -> Self {
+        BarChart {
+            categories: Vec::new(),
+            series: Vec::new(),
+        }
+    }
+
+    /// The labels below the groups of bars.
+    pub fn categories<S: Into<String>>(mut self, categories: impl IntoIterator<Item = S>) -> Self {
+        self.categories = categories.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Add a series, only the y values of its points are used.
+    pub fn series(mut self, series: Series) -> Self {
+        self.series.push(series);
+        self
+    }
+
+    #[track_caller]
+    pub fn build(self, ui: &mut Ui) {
+        let caller = Location::caller().into();
+        ui.render_object(caller, self, |_| {});
+    }
+
+    fn category_count(&self) -> usize {
+        let values = self.series.iter().map(|s| s.points.len()).max();
+        values.unwrap_or(0).max(self.categories.len())
+    }
+}
+
+/// The horizontal extent of a bar, in a plot from `x` that is `width` wide.
+fn bar_span(
+    x: f64,
+    width: f64,
+    (categories, series_count): (usize, usize),
+    (category, series): (usize, usize),
+) -> (f64, f64) {
+    let group = width / categories.max(1) as f64;
+    let bars = group * GROUP_FILL;
+    let bar = bars / series_count.max(1) as f64;
+    let start = x + category as f64 * group + (group - bars) / 2.0 + series as f64 * bar;
+    (start, start + bar)
+}
+
+pub struct BarChartObject {
+    props: BarChart,
+    /// The value axis, from the last layout.
+    axis: Option<Axis>,
+    labels: Vec<PietTextLayout>,
+    plot: Rect,
+    /// The category and series of the bar under the pointer.
+    hovered: Option<(usize, usize)>,
+    tooltip: Option<PietTextLayout>,
+}
+
+impl BarChartObject {
+    fn bar_rect(&self, category: usize, series: usize) -> Option<Rect> {
+        let axis = self.axis.as_ref()?;
+        let value = self.props.series[series].points.get(category)?.y;
+        let counts = (self.props.category_count(), self.props.series.len());
+        let (x0, x1) = bar_span(self.plot.x0, self.plot.width(), counts, (category, series));
+        let zero = axis.map(0.0, self.plot.y1, self.plot.y0);
+        let top = axis.map(value, self.plot.y1, self.plot.y0);
+        Some(Rect::new(x0, zero, x1, top).abs())
+    }
+
+    fn bar_at(&self, pos: Point) -> Option<(usize, usize)> {
+        let categories = self.props.category_count();
+        let series = self.props.series.len();
+        (0..categories)
+            .flat_map(|category| (0..series).map(move |series| (category, series)))
+            .find(|&(category, series)| {
+                self.bar_rect(category, series)
+                    .is_some_and(|rect| rect.contains(pos))
+            })
+    }
+
+    fn set_hovered(&mut self, hovered: Option<(usize, usize)>) -> bool {
+        let changed = self.hovered != hovered;
+        if changed {
+            self.hovered = hovered;
+            self.tooltip = None;
+        }
+        changed
+    }
+}
+
+impl RenderObject<BarChart> for BarChartObject {
+    type Action = ();
+
+    fn create(props: BarChart) -> Self {
+        BarChartObject {
+            props,
+            axis: None,
+            labels: Vec::new(),
+            plot: Rect::ZERO,
+            hovered: None,
+            tooltip: None,
+        }
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, props: BarChart) {
+        if self.props != props {
+            self.props = props;
+            self.set_hovered(None);
+            ctx.request_layout();
+        }
+    }
+}
+
+impl RenderObjectInterface for BarChartObject {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, _children: &mut Children) {
+        if let Event::MouseMove(mouse) = event {
+            let hovered = if ctx.is_hot() {
+                self.bar_at(mouse.pos)
+            } else {
+                None
+            };
+            if self.set_hovered(hovered) {
+                ctx.request_paint();
+            }
+        }
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        if let LifeCycle::HotChanged(false) = event {
+            if self.set_hovered(None) {
+                ctx.request_paint();
+            }
+        }
+    }
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        _children: &mut Children,
+    ) -> Size {
+        bc.debug_check("BarChart");
+        let size = chart_size(bc);
+
+        let values = self.props.series.iter().flat_map(|s| s.points.iter());
+        let (min, max) = values.fold((0.0f64, 0.0f64), |(min, max), p| {
+            (min.min(p.y), max.max(p.y))
+        });
+        let color = ctx.theme().colors.text.clone();
+        let axis = Axis::new(ctx.text(), min, max, tick_count(size.height), &color);
+        self.labels = self
+            .props
+            .categories
+            .iter()
+            .map(|category| label(ctx.text(), category.clone(), &color))
+            .collect();
+
+        let labels = max_size(self.labels.iter());
+        let axis_labels = axis.label_size();
+        self.plot = Rect::new(
+            axis_labels.width + 2.0 * PLOT_PADDING,
+            PLOT_PADDING + axis_labels.height / 2.0,
+            size.width - PLOT_PADDING,
+            size.height - labels.height - 2.0 * PLOT_PADDING,
+        );
+        if self.plot.width() <= 0.0 || self.plot.height() <= 0.0 {
+            ctx.report_layout_issue("BarChart is too small to show its axes");
+        }
+        self.axis = Some(axis);
+        self.tooltip = None;
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, _children: &mut Children) {
+        let size = ctx.size();
+        let theme = ctx.theme();
+        let grid = theme.colors.panel_border.clone();
+        let accent = theme.colors.accent.clone();
+        let text = theme.colors.text.clone();
+        let plot = self.plot;
+
+        if let Some(axis) = &self.axis {
+            for (value, layout) in &axis.ticks {
+                let y = axis.map(*value, plot.y1, plot.y0);
+                ctx.stroke(Line::new((plot.x0, y), (plot.x1, y)), &grid, 1.0);
+                let label_size = layout.size();
+                let origin = Point::new(
+                    plot.x0 - PLOT_PADDING - label_size.width,
+                    y - label_size.height / 2.0,
+                );
+                ctx.draw_text(layout, origin);
+            }
+        }
+        let categories = self.props.category_count();
+        for (category, layout) in self.labels.iter().enumerate() {
+            let (x0, x1) = bar_span(plot.x0, plot.width(), (categories, 1), (category, 0));
+            let origin = Point::new(
+                (x0 + x1 - layout.size().width) / 2.0,
+                plot.y1 + PLOT_PADDING,
+            );
+            ctx.draw_text(layout, origin);
+        }
+
+        for (index, series) in self.props.series.iter().enumerate() {
+            let color = series.color_or_palette(index);
+            for category in 0..series.points.len() {
+                if let Some(rect) = self.bar_rect(category, index) {
+                    ctx.fill(rect, &color);
+                }
+            }
+        }
+
+        if let Some((category, series_index)) = self.hovered {
+            let rect = match self.bar_rect(category, series_index) {
+                Some(rect) => rect,
+                None => return,
+            };
+            ctx.stroke(rect.inset(-1.0), &accent, 2.0);
+
+            if self.tooltip.is_none() {
+                let series = &self.props.series[series_index];
+                let value = series.points[category].y;
+                let tooltip = match self.props.categories.get(category) {
+                    Some(name) => format!("{}\n{}: {}", name, series.name, value),
+                    None => format!("{}: {}", series.name, value),
+                };
+                self.tooltip = Some(label(ctx.text(), tooltip, &text));
+            }
+            if let Some(tooltip) = &self.tooltip {
+                let anchor = Point::new(rect.center().x, rect.y0);
+                paint_tooltip(ctx, anchor, tooltip, size);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bars_of_groups() {
+        assert_eq!(bar_span(0.0, 100.0, (1, 1), (0, 0)), (10.0, 90.0));
+        assert_eq!(bar_span(0.0, 100.0, (2, 2), (1, 0)), (55.0, 75.0));
+        assert_eq!(bar_span(10.0, 100.0, (2, 2), (1, 1)), (85.0, 105.0));
+    }
+}
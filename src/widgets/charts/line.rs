@@ -0,0 +1,266 @@
+//! A chart of series as lines.
+
+use super::{
+    axis::{label, Axis},
+    chart_size, paint_tooltip, tick_count, Series, PLOT_PADDING,
+};
+use crate::{
+    kurbo::{BezPath, Circle, Line, Point, Rect},
+    object::prelude::*,
+    piet::{PietTextLayout, TextLayout},
+};
+
+/// How far from a point the pointer can be to show its tooltip.
+const HOVER_RADIUS: f64 = 10.0;
+
+/// Draws series of points as lines, with labeled axes.
+///
+/// The axes cover all points, their ticks are placed at round numbers.
+/// Hovering a point shows its series and value.
+///
+/// ```ignore
+/// LineChart::new()
+///     .series(Series::values("Requests", requests.iter().copied()))
+///     .series(Series::values("Errors", errors.iter().copied()))
+///     .build(ui);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineChart {
+    series: Vec<Series>,
+    markers: bool,
+}
+
+impl Properties for LineChart {
+    type Object = LineChartObject;
+}
+
+impl Default for LineChart {
+    fn default() -> Self {
+        LineChart::new()
+    }
+}
+
+impl LineChart {
+    pub fn new() -> Self {
+        LineChart {
+            series: Vec::new(),
+            markers: true,
+        }
+    }
+
+    pub fn series(mut self, series: Series) -> Self {
+        self.series.push(series);
+        self
+    }
+
+    /// Whether a dot is drawn at every point.
+    ///
+    /// The default value is `true`.
+    pub fn markers(mut self, markers: bool) -> Self {
+        self.markers = markers;
+        self
+    }
+
+    #[track_caller]
+    pub fn build(self, ui: &mut Ui) {
+        let caller = Location::caller().into();
+        ui.render_object(caller, self, |_| {});
+    }
+}
+
+pub struct LineChartObject {
+    props: LineChart,
+    /// The x and y axis, from the last layout.
+    axes: Option<(Axis, Axis)>,
+    plot: Rect,
+    /// The series and point under the pointer.
+    hovered: Option<(usize, usize)>,
+    tooltip: Option<PietTextLayout>,
+}
+
+impl LineChartObject {
+    fn position(&self, point: Point) -> Point {
+        match &self.axes {
+            Some((x_axis, y_axis)) => Point::new(
+                x_axis.map(point.x, self.plot.x0, self.plot.x1),
+                y_axis.map(point.y, self.plot.y1, self.plot.y0),
+            ),
+            None => Point::ORIGIN,
+        }
+    }
+
+    fn point_at(&self, pos: Point) -> Option<(usize, usize)> {
+        let mut nearest = None;
+        let mut nearest_distance = HOVER_RADIUS;
+        for (series_index, series) in self.props.series.iter().enumerate() {
+            for (point_index, point) in series.points.iter().enumerate() {
+                let distance = self.position(*point).distance(pos);
+                if distance <= nearest_distance {
+                    nearest = Some((series_index, point_index));
+                    nearest_distance = distance;
+                }
+            }
+        }
+        nearest
+    }
+
+    fn set_hovered(&mut self, hovered: Option<(usize, usize)>) -> bool {
+        let changed = self.hovered != hovered;
+        if changed {
+            self.hovered = hovered;
+            self.tooltip = None;
+        }
+        changed
+    }
+}
+
+impl RenderObject<LineChart> for LineChartObject {
+    type Action = ();
+
+    fn create(props: LineChart) -> Self {
+        LineChartObject {
+            props,
+            axes: None,
+            plot: Rect::ZERO,
+            hovered: None,
+            tooltip: None,
+        }
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, props: LineChart) {
+        if self.props != props {
+            self.props = props;
+            self.set_hovered(None);
+            ctx.request_layout();
+        }
+    }
+}
+
+impl RenderObjectInterface for LineChartObject {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, _children: &mut Children) {
+        if let Event::MouseMove(mouse) = event {
+            let hovered = if ctx.is_hot() {
+                self.point_at(mouse.pos)
+            } else {
+                None
+            };
+            if self.set_hovered(hovered) {
+                ctx.request_paint();
+            }
+        }
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        if let LifeCycle::HotChanged(false) = event {
+            if self.set_hovered(None) {
+                ctx.request_paint();
+            }
+        }
+    }
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        _children: &mut Children,
+    ) -> Size {
+        bc.debug_check("LineChart");
+        let size = chart_size(bc);
+
+        let points = self.props.series.iter().flat_map(|s| s.points.iter());
+        let (min, max) = points.fold(
+            (
+                Point::new(f64::INFINITY, f64::INFINITY),
+                Point::new(f64::NEG_INFINITY, f64::NEG_INFINITY),
+            ),
+            |(min, max), p| {
+                (
+                    Point::new(min.x.min(p.x), min.y.min(p.y)),
+                    Point::new(max.x.max(p.x), max.y.max(p.y)),
+                )
+            },
+        );
+        let color = ctx.theme().colors.text.clone();
+        let x_axis = Axis::new(ctx.text(), min.x, max.x, tick_count(size.width), &color);
+        let y_axis = Axis::new(ctx.text(), min.y, max.y, tick_count(size.height), &color);
+
+        let x_labels = x_axis.label_size();
+        let y_labels = y_axis.label_size();
+        self.plot = Rect::new(
+            y_labels.width + 2.0 * PLOT_PADDING,
+            PLOT_PADDING + y_labels.height / 2.0,
+            size.width - PLOT_PADDING - x_labels.width / 2.0,
+            size.height - x_labels.height - 2.0 * PLOT_PADDING,
+        );
+        if self.plot.width() <= 0.0 || self.plot.height() <= 0.0 {
+            ctx.report_layout_issue("LineChart is too small to show its axes");
+        }
+        self.axes = Some((x_axis, y_axis));
+        self.tooltip = None;
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, _children: &mut Children) {
+        let size = ctx.size();
+        let theme = ctx.theme();
+        let grid = theme.colors.panel_border.clone();
+        let background = theme.colors.background.clone();
+        let text = theme.colors.text.clone();
+        let plot = self.plot;
+
+        if let Some((x_axis, y_axis)) = &self.axes {
+            for (value, layout) in &y_axis.ticks {
+                let y = y_axis.map(*value, plot.y1, plot.y0);
+                ctx.stroke(Line::new((plot.x0, y), (plot.x1, y)), &grid, 1.0);
+                let label_size = layout.size();
+                let origin = Point::new(
+                    plot.x0 - PLOT_PADDING - label_size.width,
+                    y - label_size.height / 2.0,
+                );
+                ctx.draw_text(layout, origin);
+            }
+            for (value, layout) in &x_axis.ticks {
+                let x = x_axis.map(*value, plot.x0, plot.x1);
+                ctx.stroke(Line::new((x, plot.y1), (x, plot.y1 + 4.0)), &grid, 1.0);
+                let origin = Point::new(x - layout.size().width / 2.0, plot.y1 + PLOT_PADDING);
+                ctx.draw_text(layout, origin);
+            }
+        }
+
+        for (index, series) in self.props.series.iter().enumerate() {
+            let color = series.color_or_palette(index);
+            let mut path = BezPath::new();
+            for (point_index, point) in series.points.iter().enumerate() {
+                let position = self.position(*point);
+                if point_index == 0 {
+                    path.move_to(position);
+                } else {
+                    path.line_to(position);
+                }
+            }
+            ctx.stroke(&path, &color, 2.0);
+            if self.props.markers {
+                for point in &series.points {
+                    ctx.fill(Circle::new(self.position(*point), 3.0), &color);
+                }
+            }
+        }
+
+        if let Some((series_index, point_index)) = self.hovered {
+            let series = &self.props.series[series_index];
+            let point = series.points[point_index];
+            let position = self.position(point);
+            let circle = Circle::new(position, 5.0);
+            ctx.fill(circle, &series.color_or_palette(series_index));
+            ctx.stroke(circle, &background, 1.5);
+
+            if self.tooltip.is_none() {
+                let tooltip = format!("{}: {}, {}", series.name, point.x, point.y);
+                self.tooltip = Some(label(ctx.text(), tooltip, &text));
+            }
+            if let Some(tooltip) = &self.tooltip {
+                paint_tooltip(ctx, position, tooltip, size);
+            }
+        }
+    }
+}
@@ -0,0 +1,116 @@
+//! Line and bar charts of data series.
+
+mod axis;
+mod bar;
+mod line;
+
+pub use bar::{BarChart, BarChartObject};
+pub use line::{LineChart, LineChartObject};
+
+use crate::{
+    context::PaintCtx,
+    kurbo::{Point, Rect, Size, Vec2},
+    piet::{Color, PietTextLayout, RenderContext, TextLayout},
+    BoxConstraints,
+};
+
+const PLOT_PADDING: f64 = 8.0;
+const TOOLTIP_PADDING: Size = Size::new(6.0, 4.0);
+/// The pixels per tick an axis aims for.
+const TICK_SPACING: f64 = 50.0;
+/// The size of a chart without size constraints.
+const DEFAULT_SIZE: Size = Size::new(300.0, 200.0);
+
+/// Colors for series without their own color, repeated for more series.
+const PALETTE: [Color; 6] = [
+    Color::rgb8(0x3b, 0x82, 0xf6),
+    Color::rgb8(0xf5, 0x9e, 0x0b),
+    Color::rgb8(0x10, 0xb9, 0x81),
+    Color::rgb8(0xef, 0x44, 0x44),
+    Color::rgb8(0x8b, 0x5c, 0xf6),
+    Color::rgb8(0x06, 0xb6, 0xd4),
+];
+
+/// A named sequence of values, one line of a [`LineChart`] or
+/// one bar in every category of a [`BarChart`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Series {
+    name: String,
+    points: Vec<Point>,
+    color: Option<Color>,
+}
+
+impl Series {
+    /// A series of `(x, y)` points.
+    pub fn new(name: impl Into<String>, points: impl IntoIterator<Item = (f64, f64)>) -> Self {
+        Series {
+            name: name.into(),
+            points: points.into_iter().map(Point::from).collect(),
+            color: None,
+        }
+    }
+
+    /// A series of values, at the x positions `0.0`, `1.0`, `2.0` and so on.
+    pub fn values(name: impl Into<String>, values: impl IntoIterator<Item = f64>) -> Self {
+        let points = values
+            .into_iter()
+            .enumerate()
+            .map(|(index, value)| (index as f64, value));
+        Series::new(name, points)
+    }
+
+    /// By default, series are colored from a fixed palette in the order they are added.
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    fn color_or_palette(&self, index: usize) -> Color {
+        match &self.color {
+            Some(color) => color.clone(),
+            None => PALETTE[index % PALETTE.len()].clone(),
+        }
+    }
+}
+
+/// The size of a chart under `bc`, which fills the available space.
+fn chart_size(bc: &BoxConstraints) -> Size {
+    let max = bc.max();
+    bc.constrain(Size::new(
+        if max.width.is_finite() {
+            max.width
+        } else {
+            DEFAULT_SIZE.width
+        },
+        if max.height.is_finite() {
+            max.height
+        } else {
+            DEFAULT_SIZE.height
+        },
+    ))
+}
+
+/// The number of ticks that fit along `length` pixels.
+fn tick_count(length: f64) -> usize {
+    ((length / TICK_SPACING) as usize).max(2)
+}
+
+/// Paint `layout` in a box above `anchor`, kept inside `bounds`.
+fn paint_tooltip(ctx: &mut PaintCtx, anchor: Point, layout: &PietTextLayout, bounds: Size) {
+    let theme = ctx.theme();
+    let panel = theme.colors.panel.clone();
+    let border = theme.colors.panel_border.clone();
+    let radius = theme.spacing.corner_radius;
+
+    let size = layout.size() + TOOLTIP_PADDING * 2.0;
+    let mut origin = anchor - Vec2::new(size.width / 2.0, size.height + PLOT_PADDING);
+    origin.x = origin.x.min(bounds.width - size.width).max(0.0);
+    if origin.y < 0.0 {
+        origin.y = anchor.y + PLOT_PADDING;
+    }
+    let rect = Rect::from_origin_size(origin, size);
+    let shape = rect.inset(-0.5).to_rounded_rect(radius);
+    ctx.fill(shape, &panel);
+    ctx.stroke(shape, &border, 1.0);
+    ctx.draw_text(layout, origin + TOOLTIP_PADDING.to_vec2());
+}
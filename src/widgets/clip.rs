@@ -0,0 +1,181 @@
+//! A container that clips its content to a shape.
+
+use crate::{
+    kurbo::{BezPath, Point, Shape},
+    object::prelude::*,
+};
+use std::rc::Rc;
+
+/// The accuracy of the paths of rounded corners.
+const TOLERANCE: f64 = 0.1;
+
+type PathFn = dyn Fn(Size) -> BezPath;
+
+#[derive(Clone)]
+enum ClipShape {
+    Rect,
+    Rounded(f64),
+    Path(Rc<PathFn>),
+}
+
+impl PartialEq for ClipShape {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (ClipShape::Rect, ClipShape::Rect) => true,
+            (ClipShape::Rounded(a), ClipShape::Rounded(b)) => a == b,
+            // Closures can not be compared.
+            _ => false,
+        }
+    }
+}
+
+/// Clips its content to its bounds, to a rounded rect or to a path.
+///
+/// Nothing of the content is painted outside of the shape, and pointer
+/// events outside of it do not reach the content, for example for cards
+/// with rounded corners or for viewports that scroll their content.
+///
+/// ```ignore
+/// Clip::rounded(8.0).build(ui, |ui| {
+///     Image::new(cover.clone()).build(ui);
+/// });
+/// ```
+#[derive(Clone, PartialEq)]
+pub struct Clip {
+    shape: ClipShape,
+}
+
+impl Properties for Clip {
+    type Object = ClipObject;
+}
+
+impl Clip {
+    /// Clip to the bounds of the content.
+    pub fn rect() -> Self {
+        Clip {
+            shape: ClipShape::Rect,
+        }
+    }
+
+    /// Clip to the bounds of the content, with corners rounded by `radius`.
+    pub fn rounded(radius: f64) -> Self {
+        Clip {
+            shape: ClipShape::Rounded(radius),
+        }
+    }
+
+    /// Clip to the path returned by `path` for the size of the content.
+    ///
+    /// The path is created again in every layout pass after a build pass,
+    /// because the closures can not be compared.
+    pub fn path(path: impl Fn(Size) -> BezPath + 'static) -> Self {
+        Clip {
+            shape: ClipShape::Path(Rc::new(path)),
+        }
+    }
+
+    #[track_caller]
+    pub fn build(self, ui: &mut Ui, content: impl FnOnce(&mut Ui)) {
+        let caller = Location::caller().into();
+        ui.render_object(caller, self, content);
+    }
+}
+
+pub struct ClipObject {
+    props: Clip,
+    /// The clip path for the current size.
+    path: BezPath,
+}
+
+impl RenderObject<Clip> for ClipObject {
+    type Action = ();
+
+    fn create(props: Clip) -> Self {
+        ClipObject {
+            props,
+            path: BezPath::new(),
+        }
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, props: Clip) {
+        if self.props != props {
+            self.props = props;
+            ctx.request_layout();
+            ctx.request_paint();
+        }
+    }
+}
+
+impl RenderObjectInterface for ClipObject {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, children: &mut Children) {
+        for child in children {
+            child.event(ctx, event);
+        }
+    }
+
+    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle) {}
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        children: &mut Children,
+    ) -> Size {
+        bc.debug_check("Clip");
+        let size = children.layout_stacked(ctx, bc);
+        let rect = size.to_rect();
+        self.path = match &self.props.shape {
+            ClipShape::Rect => rect.into_path(TOLERANCE),
+            ClipShape::Rounded(radius) => rect.to_rounded_rect(*radius).into_path(TOLERANCE),
+            ClipShape::Path(path) => path(size),
+        };
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, children: &mut Children) {
+        let path = &self.path;
+        ctx.with_save(|ctx| {
+            ctx.clip(path.clone());
+            for child in children {
+                child.paint(ctx);
+            }
+        });
+    }
+
+    fn hit_test(&self, pos: Point) -> bool {
+        self.path.winding(pos) != 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        kurbo::Vec2,
+        testing::{Harness, Log, Probe, Record},
+        widgets::Flex,
+    };
+
+    #[test]
+    fn clicks_outside_the_clip_reach_the_widgets_below() {
+        let log = Log::default();
+        let probes = log.clone();
+        let mut harness = Harness::new(move |ui| {
+            Flex::column().build(ui, |ui| {
+                Probe::new("below", &probes).build(ui, |ui| {
+                    Clip::rounded(50.0).build(ui, |ui| {
+                        Probe::new("card", &probes).build(ui, |_| {});
+                    });
+                });
+            });
+        });
+        let clicks = |log: &Log| Probe::take(log, |record| *record == Record::Click);
+        let below = Probe::find(harness.tree(), "below");
+        let rect = harness.window_rect(below);
+
+        harness.click(rect.origin() + Vec2::new(5.0, 5.0));
+        assert_eq!(clicks(&log), [("below", Record::Click)]);
+        harness.click(rect.center());
+        assert_eq!(clicks(&log), [("card", Record::Click)]);
+    }
+}
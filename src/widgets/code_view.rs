@@ -0,0 +1,477 @@
+//! A read-only view of source code.
+
+use crate::{
+    kurbo::{Point, Rect, Vec2},
+    object::prelude::*,
+    piet::{Color, FontFamily, PietTextLayout, Text, TextAttribute, TextLayout, TextLayoutBuilder},
+    wheel::WheelScroll,
+};
+use std::{collections::HashMap, ops::Range, sync::Arc};
+#[cfg(feature = "syntect")]
+use syntect::{
+    highlighting::{
+        HighlightState, Highlighter as ThemeHighlighter, RangedHighlightIterator, Theme,
+    },
+    parsing::{ParseState, ScopeStack, SyntaxReference, SyntaxSet},
+};
+
+const GUTTER_PADDING: f64 = 8.0;
+const TEXT_PADDING: f64 = 4.0;
+
+/// Colors the lines of a [`CodeView`].
+///
+/// Highlighters can keep state between lines, for example to know
+/// whether a line starts inside of a block comment.
+pub trait Highlighter {
+    /// The colored ranges of `line`, whose byte ranges are relative to the line.
+    ///
+    /// Lines are passed in order, starting with the first one. Text outside
+    /// of the returned ranges has the default text color.
+    fn highlight_line(&mut self, line: &str) -> Vec<(Range<usize>, Color)>;
+}
+
+/// Shows code in a monospaced font, next to line numbers.
+///
+/// The view scrolls in both directions with the mouse wheel, only the
+/// visible lines are laid out, so long files are cheap to show. The
+/// highlighter runs once over all lines whenever the text changes.
+///
+/// ```ignore
+/// CodeView::new(source.clone())
+///     .highlighter(RustHighlighter::new())
+///     .build(ui);
+/// ```
+pub struct CodeView {
+    text: Arc<str>,
+    line_numbers: bool,
+    text_size: f64,
+    highlighter: Option<Box<dyn Highlighter>>,
+}
+
+impl Properties for CodeView {
+    type Object = CodeViewObject;
+}
+
+impl CodeView {
+    pub fn new(text: impl Into<Arc<str>>) -> Self {
+        CodeView {
+            text: text.into(),
+            line_numbers: true,
+            text_size: 13.0,
+            highlighter: None,
+        }
+    }
+
+    /// Whether line numbers are shown in a gutter on the left.
+    ///
+    /// The default value is `true`.
+    pub fn line_numbers(mut self, line_numbers: bool) -> Self {
+        self.line_numbers = line_numbers;
+        self
+    }
+
+    /// The default value is `13.0`.
+    pub fn text_size(mut self, size: f64) -> Self {
+        self.text_size = size;
+        self
+    }
+
+    /// Color the code with `highlighter`.
+    ///
+    /// It is only used when the text changed, so it can be created in every build pass.
+    pub fn highlighter(mut self, highlighter: impl Highlighter + 'static) -> Self {
+        self.highlighter = Some(Box::new(highlighter));
+        self
+    }
+
+    #[track_caller]
+    pub fn build(self, ui: &mut Ui) {
+        let caller = Location::caller().into();
+        ui.render_object(caller, self, |_| {});
+    }
+}
+
+/// A [`Highlighter`] for the syntaxes and themes of [syntect].
+///
+/// Only available with the `syntect` feature. Loading syntaxes and themes
+/// is slow, so they are loaded once and shared, while the highlighter is
+/// cheap to create in every build pass. The syntaxes must be loaded for lines
+/// without line breaks:
+///
+/// ```ignore
+/// let syntaxes = Arc::new(SyntaxSet::load_defaults_nonewlines());
+/// let themes = ThemeSet::load_defaults();
+/// let theme = Arc::new(themes.themes["base16-ocean.dark"].clone());
+/// // In the build pass:
+/// let rust = syntaxes.find_syntax_by_extension("rs").unwrap();
+/// CodeView::new(source.clone())
+///     .highlighter(SyntectHighlighter::new(&syntaxes, rust, &theme))
+///     .build(ui);
+/// ```
+///
+/// Text in the foreground color of the theme keeps the text color of the view.
+///
+/// [syntect]: https://docs.rs/syntect
+#[cfg(feature = "syntect")]
+pub struct SyntectHighlighter {
+    syntaxes: Arc<SyntaxSet>,
+    theme: Arc<Theme>,
+    parse: ParseState,
+    highlight: HighlightState,
+}
+
+#[cfg(feature = "syntect")]
+impl SyntectHighlighter {
+    /// Highlight `syntax`, one of `syntaxes`, with the colors of `theme`.
+    pub fn new(syntaxes: &Arc<SyntaxSet>, syntax: &SyntaxReference, theme: &Arc<Theme>) -> Self {
+        let highlighter = ThemeHighlighter::new(theme);
+        SyntectHighlighter {
+            syntaxes: syntaxes.clone(),
+            theme: theme.clone(),
+            parse: ParseState::new(syntax),
+            highlight: HighlightState::new(&highlighter, ScopeStack::new()),
+        }
+    }
+}
+
+#[cfg(feature = "syntect")]
+impl Highlighter for SyntectHighlighter {
+    fn highlight_line(&mut self, line: &str) -> Vec<(Range<usize>, Color)> {
+        let ops = match self.parse.parse_line(line, &self.syntaxes) {
+            Ok(ops) => ops,
+            Err(err) => {
+                log::warn!("Failed to parse a line for highlighting: {}", err);
+                return Vec::new();
+            }
+        };
+        let highlighter = ThemeHighlighter::new(&self.theme);
+        let foreground = self.theme.settings.foreground;
+        RangedHighlightIterator::new(&mut self.highlight, &ops, line, &highlighter)
+            .filter(|(style, _, _)| Some(style.foreground) != foreground)
+            .map(|(style, _, range)| {
+                let c = style.foreground;
+                (range, Color::rgba8(c.r, c.g, c.b, c.a))
+            })
+            .collect()
+    }
+}
+
+/// The byte ranges of the lines in `text`, without their line breaks.
+fn line_ranges(text: &str) -> Vec<Range<usize>> {
+    let mut lines = Vec::new();
+    let mut start = 0;
+    for (index, _) in text.match_indices('\n') {
+        let end = if text[..index].ends_with('\r') {
+            index - 1
+        } else {
+            index
+        };
+        lines.push(start..end);
+        start = index + 1;
+    }
+    lines.push(start..text.len());
+    lines
+}
+
+/// The lines that intersect the viewport from `top` to `top + height`.
+fn visible_lines(top: f64, height: f64, line_height: f64, lines: usize) -> Range<usize> {
+    if line_height <= 0.0 {
+        return 0..0;
+    }
+    let start = (top / line_height).floor().max(0.0) as usize;
+    let end = ((top + height) / line_height).ceil().max(0.0) as usize;
+    start.min(lines)..end.min(lines)
+}
+
+/// The laid out text of a line and its number.
+struct LineLayout {
+    number: Option<PietTextLayout>,
+    code: PietTextLayout,
+}
+
+pub struct CodeViewObject {
+    props: CodeView,
+    lines: Vec<Range<usize>>,
+    colors: Vec<Vec<(Range<usize>, Color)>>,
+    /// The layouts of the visible lines.
+    layouts: HashMap<usize, LineLayout>,
+    /// The width of a character and the height of a line.
+    metrics: Option<Size>,
+    text_color: Option<Color>,
+    offset: Vec2,
+    scroll: WheelScroll<Vec2>,
+    /// The visible lines, from the last layout.
+    visible: Range<usize>,
+}
+
+impl CodeViewObject {
+    fn highlight(&mut self) {
+        self.lines = line_ranges(&self.props.text);
+        let text = &self.props.text;
+        self.colors = match &mut self.props.highlighter {
+            Some(highlighter) => self
+                .lines
+                .iter()
+                .map(|line| highlighter.highlight_line(&text[line.clone()]))
+                .collect(),
+            None => Vec::new(),
+        };
+        self.layouts.clear();
+    }
+
+    fn gutter_width(&self) -> f64 {
+        match (self.props.line_numbers, self.metrics) {
+            (true, Some(metrics)) => {
+                let digits = self.lines.len().to_string().len() as f64;
+                digits * metrics.width + 2.0 * GUTTER_PADDING
+            }
+            _ => 0.0,
+        }
+    }
+
+    /// The size of all lines, assuming every character is equally wide.
+    fn content_size(&self) -> Size {
+        let metrics = self.metrics.unwrap_or_default();
+        let text = &self.props.text;
+        let columns = self
+            .lines
+            .iter()
+            .map(|line| text[line.clone()].chars().count())
+            .max()
+            .unwrap_or(0);
+        Size::new(
+            self.gutter_width() + 2.0 * TEXT_PADDING + columns as f64 * metrics.width,
+            self.lines.len() as f64 * metrics.height,
+        )
+    }
+
+    /// `offset` limited to the content, for a view of `size`.
+    fn clamped(&self, offset: Vec2, size: Size) -> Vec2 {
+        let content = self.content_size();
+        let max = Vec2::new(
+            (content.width - size.width).max(0.0),
+            (content.height - size.height).max(0.0),
+        );
+        Vec2::new(offset.x.clamp(0.0, max.x), offset.y.clamp(0.0, max.y))
+    }
+
+    fn clamp_offset(&mut self, size: Size) {
+        self.offset = self.clamped(self.offset, size);
+    }
+
+    fn set_offset(&mut self, ctx: &mut EventCtx, offset: Vec2) {
+        let offset = self.clamped(offset, ctx.size());
+        if offset != self.offset {
+            self.offset = offset;
+            ctx.request_layout();
+            ctx.request_paint();
+        }
+    }
+}
+
+impl RenderObject<CodeView> for CodeViewObject {
+    type Action = ();
+
+    fn create(props: CodeView) -> Self {
+        let mut object = CodeViewObject {
+            props,
+            lines: Vec::new(),
+            colors: Vec::new(),
+            layouts: HashMap::new(),
+            metrics: None,
+            text_color: None,
+            offset: Vec2::ZERO,
+            scroll: WheelScroll::new(),
+            visible: 0..0,
+        };
+        object.highlight();
+        object
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, props: CodeView) {
+        let text_changed =
+            !Arc::ptr_eq(&self.props.text, &props.text) && self.props.text != props.text;
+        let style_changed = (self.props.line_numbers, self.props.text_size)
+            != (props.line_numbers, props.text_size);
+        self.props = props;
+        if text_changed {
+            self.highlight();
+            ctx.request_layout();
+        }
+        if style_changed {
+            self.metrics = None;
+            self.layouts.clear();
+            ctx.request_layout();
+        }
+    }
+}
+
+impl RenderObjectInterface for CodeViewObject {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, _children: &mut Children) {
+        match event {
+            Event::Wheel(wheel) if ctx.is_hot() => {
+                let target = self.scroll.target(self.offset) + wheel.wheel_delta;
+                let target = self.clamped(target, ctx.size());
+                let offset = self.scroll.scroll(ctx, self.offset, target);
+                self.set_offset(ctx, offset);
+                ctx.set_handled();
+            }
+            Event::AnimFrame(_) => {
+                if let Some(offset) = self.scroll.anim_frame(ctx, event) {
+                    self.set_offset(ctx, offset);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle) {}
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        _children: &mut Children,
+    ) -> Size {
+        bc.debug_check("CodeView");
+
+        let text_color = ctx.theme().colors.text.clone();
+        if self.text_color.as_ref().map(Color::as_rgba_u32) != Some(text_color.as_rgba_u32()) {
+            self.text_color = Some(text_color.clone());
+            self.layouts.clear();
+        }
+        let font = FontFamily::MONOSPACE;
+        let text_size = self.props.text_size;
+        if self.metrics.is_none() {
+            let sample = ctx
+                .text()
+                .new_text_layout("0")
+                .font(font.clone(), text_size)
+                .build()
+                .unwrap();
+            self.metrics = Some(sample.size());
+        }
+        let metrics = self.metrics.unwrap_or_default();
+
+        let content = self.content_size();
+        let size = bc.constrain(Size::new(
+            if bc.max().width.is_finite() {
+                bc.max().width
+            } else {
+                content.width
+            },
+            if bc.max().height.is_finite() {
+                bc.max().height
+            } else {
+                content.height
+            },
+        ));
+        self.clamp_offset(size);
+
+        self.visible = visible_lines(self.offset.y, size.height, metrics.height, self.lines.len());
+        let visible = self.visible.clone();
+        self.layouts.retain(|line, _| visible.contains(line));
+        let number_color = ctx.theme().colors.panel_border.clone();
+        for line in visible {
+            if self.layouts.contains_key(&line) {
+                continue;
+            }
+            let range = self.lines[line].clone();
+            let mut builder = ctx
+                .text()
+                .new_text_layout(self.props.text[range].to_string())
+                .font(font.clone(), text_size)
+                .default_attribute(TextAttribute::TextColor(text_color.clone()));
+            for (range, color) in self.colors.get(line).into_iter().flatten() {
+                builder =
+                    builder.range_attribute(range.clone(), TextAttribute::TextColor(color.clone()));
+            }
+            let code = builder.build().unwrap();
+            let number = self.props.line_numbers.then(|| {
+                ctx.text()
+                    .new_text_layout((line + 1).to_string())
+                    .font(font.clone(), text_size)
+                    .default_attribute(TextAttribute::TextColor(number_color.clone()))
+                    .build()
+                    .unwrap()
+            });
+            self.layouts.insert(line, LineLayout { number, code });
+        }
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, _children: &mut Children) {
+        let size = ctx.size();
+        let line_height = self.metrics.unwrap_or_default().height;
+        let gutter = self.gutter_width();
+        let panel = ctx.theme().colors.panel.clone();
+
+        ctx.with_save(|ctx| {
+            ctx.clip(Rect::new(gutter, 0.0, size.width, size.height));
+            for line in self.visible.clone() {
+                if let Some(layout) = self.layouts.get(&line) {
+                    let y = line as f64 * line_height - self.offset.y;
+                    let x = gutter + TEXT_PADDING - self.offset.x;
+                    ctx.draw_text(&layout.code, Point::new(x, y));
+                }
+            }
+        });
+
+        if gutter > 0.0 {
+            ctx.with_save(|ctx| {
+                let rect = Rect::new(0.0, 0.0, gutter, size.height);
+                ctx.clip(rect);
+                ctx.fill(rect, &panel);
+                for line in self.visible.clone() {
+                    let number = self.layouts.get(&line).and_then(|l| l.number.as_ref());
+                    if let Some(number) = number {
+                        let y = line as f64 * line_height - self.offset.y;
+                        let x = gutter - GUTTER_PADDING - number.size().width;
+                        ctx.draw_text(number, Point::new(x, y));
+                    }
+                }
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_lines() {
+        assert_eq!(line_ranges("").len(), 1);
+        assert!(line_ranges("")[0].is_empty());
+        assert_eq!(line_ranges("a\nbc\r\n"), [0..1, 2..4, 6..6]);
+        assert_eq!(line_ranges("\n\nx"), [0..0, 1..1, 2..3]);
+    }
+
+    #[test]
+    fn visible_range() {
+        assert_eq!(visible_lines(0.0, 100.0, 20.0, 100), 0..5);
+        assert_eq!(visible_lines(30.0, 100.0, 20.0, 100), 1..7);
+        assert_eq!(visible_lines(30.0, 100.0, 20.0, 3), 1..3);
+        assert_eq!(visible_lines(0.0, 100.0, 0.0, 3), 0..0);
+    }
+
+    #[cfg(feature = "syntect")]
+    #[test]
+    fn syntect_keeps_state_between_lines() {
+        use syntect::highlighting::ThemeSet;
+
+        let syntaxes = Arc::new(SyntaxSet::load_defaults_nonewlines());
+        let themes = ThemeSet::load_defaults();
+        let theme = Arc::new(themes.themes["InspiredGitHub"].clone());
+        let rust = syntaxes.find_syntax_by_extension("rs").unwrap();
+        let mut highlighter = SyntectHighlighter::new(&syntaxes, rust, &theme);
+
+        let keyword = highlighter.highlight_line("fn main() { /* start");
+        assert!(keyword.iter().any(|(range, _)| *range == (0..2)));
+        // Still inside of the comment, which has one color.
+        let comment = highlighter.highlight_line("end */");
+        assert_eq!(comment.first().map(|(range, _)| range.start), Some(0));
+        assert_eq!(comment.last().map(|(range, _)| range.end), Some(6));
+        assert!(comment.iter().all(|(_, color)| *color == comment[0].1));
+    }
+}
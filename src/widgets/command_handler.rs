@@ -0,0 +1,86 @@
+//! A widget that handles the notifications of its content.
+
+use crate::object::prelude::*;
+use druid::Selector;
+use std::any::Any;
+
+/// Handles the notifications its content submits with a selector.
+///
+/// Render objects submit notifications with [`EventCtx::submit_notification`],
+/// they bubble up to the nearest `CommandHandler` for their selector.
+/// Its payloads are returned in the next build pass.
+///
+/// [`EventCtx::submit_notification`]: crate::context::EventCtx::submit_notification
+pub struct CommandHandler<T> {
+    selector: Selector<T>,
+}
+
+impl<T: Any + Clone> Properties for CommandHandler<T> {
+    type Object = CommandHandlerObject<T>;
+}
+
+impl<T: Any + Clone> CommandHandler<T> {
+    pub fn new(selector: Selector<T>) -> Self {
+        CommandHandler { selector }
+    }
+
+    /// Build `content`, returns the payloads received since the last build pass.
+    #[must_use]
+    #[track_caller]
+    pub fn build(self, ui: &mut Ui, content: impl FnOnce(&mut Ui)) -> Vec<T> {
+        let caller = Location::caller().into();
+        ui.render_object(caller, self, content)
+    }
+}
+
+pub struct CommandHandlerObject<T> {
+    selector: Selector<T>,
+    received: Vec<T>,
+}
+
+impl<T: Any + Clone> RenderObject<CommandHandler<T>> for CommandHandlerObject<T> {
+    type Action = Vec<T>;
+
+    fn create(props: CommandHandler<T>) -> Self {
+        CommandHandlerObject {
+            selector: props.selector,
+            received: Vec::new(),
+        }
+    }
+
+    fn update(&mut self, _ctx: &mut UpdateCtx, props: CommandHandler<T>) -> Vec<T> {
+        self.selector = props.selector;
+        std::mem::take(&mut self.received)
+    }
+}
+
+impl<T: Any + Clone> RenderObjectInterface for CommandHandlerObject<T> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, children: &mut Children) {
+        for child in children {
+            child.event(ctx, event);
+        }
+
+        let received = ctx.take_notifications(self.selector);
+        if !received.is_empty() {
+            self.received.extend(received);
+            ctx.request_update();
+        }
+    }
+
+    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle) {}
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        children: &mut Children,
+    ) -> Size {
+        children.layout_stacked(ctx, bc)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, children: &mut Children) {
+        for child in children {
+            child.paint(ctx);
+        }
+    }
+}
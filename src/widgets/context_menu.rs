@@ -0,0 +1,429 @@
+//! A menu that opens on right-click.
+
+use crate::{
+    event::MouseButton,
+    kurbo::{BezPath, Line, Point},
+    object::prelude::*,
+    widgets::{
+        overlay::{Overlay, Placement},
+        Label,
+    },
+};
+
+const ITEM_PADDING: Size = Size::new(8.0, 4.0);
+const SUBMENU_ARROW_WIDTH: f64 = 12.0;
+const SEPARATOR_HEIGHT: f64 = 9.0;
+
+/// Opens a menu at the pointer position when its content is right-clicked.
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn build(ui: &mut coat::ui::Ui) {
+/// use coat::widgets::{ContextMenu, Label};
+///
+/// ContextMenu::new().build(
+///     ui,
+///     |ui| Label::new("Right-click me").build(ui),
+///     |menu| {
+///         if menu.item("Copy") {
+///             println!("Copy");
+///         }
+///         menu.separator();
+///         menu.submenu("Share", |menu| {
+///             if menu.item("Mail") {
+///                 println!("Mail");
+///             }
+///         });
+///     },
+/// );
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct ContextMenu;
+
+#[derive(Debug, Default)]
+struct ContextMenuState {
+    /// Where the menu was opened, in window coordinates.
+    position: Option<Point>,
+}
+
+impl ContextMenu {
+    pub fn new() -> Self {
+        ContextMenu
+    }
+
+    #[track_caller]
+    pub fn build(self, ui: &mut Ui, content: impl FnOnce(&mut Ui), items: impl FnOnce(&mut Menu)) {
+        let caller = Location::caller().into();
+        ui.state_node(caller, ContextMenuState::default, |ui, state| {
+            let position = state.position;
+            let placement = Placement::At(position.unwrap_or_default());
+            let mut requested = None;
+            let mut closed = false;
+
+            let dismissed = Overlay::new(placement).build(
+                ui,
+                position.is_some(),
+                |ui| {
+                    let caller = Location::caller().into();
+                    requested = ui.render_object(caller, ContextArea, content);
+                },
+                |ui| {
+                    let caller = Location::caller().into();
+                    ui.render_object(caller, MenuPanel, |ui| {
+                        items(&mut Menu {
+                            ui,
+                            closed: &mut closed,
+                        });
+                    });
+                },
+            );
+
+            if dismissed || closed {
+                state.position = None;
+            }
+            if requested.is_some() {
+                state.position = requested;
+            }
+        });
+    }
+}
+
+/// Builds the entries of a menu.
+pub struct Menu<'m, 'a, 'b> {
+    ui: &'m mut Ui<'a, 'b>,
+    closed: &'m mut bool,
+}
+
+impl Menu<'_, '_, '_> {
+    /// Add an item, returns `true` if it was selected.
+    ///
+    /// Selecting an item closes the menu.
+    #[must_use]
+    #[track_caller]
+    pub fn item(&mut self, label: impl Into<String>) -> bool {
+        let caller = Location::caller().into();
+        let item = MenuItem { submenu: false };
+        let clicked = self.ui.render_object(caller, item, |ui| {
+            Label::new(label).build(ui);
+        });
+        *self.closed |= clicked;
+        clicked
+    }
+
+    /// Add a separator line.
+    #[track_caller]
+    pub fn separator(&mut self) {
+        let caller = Location::caller().into();
+        self.ui.render_object(caller, MenuSeparator, |_| {});
+    }
+
+    /// Add an item that opens a nested menu when clicked.
+    #[track_caller]
+    pub fn submenu(&mut self, label: impl Into<String>, items: impl FnOnce(&mut Menu)) {
+        let caller = Location::caller().into();
+        let closed = &mut *self.closed;
+        self.ui.state_node(
+            caller,
+            || false,
+            |ui, open: &mut bool| {
+                let mut clicked = false;
+                let dismissed = Overlay::new(Placement::Right).build(
+                    ui,
+                    *open,
+                    |ui| {
+                        let caller = Location::caller().into();
+                        let item = MenuItem { submenu: true };
+                        clicked = ui.render_object(caller, item, |ui| {
+                            Label::new(label).build(ui);
+                        });
+                    },
+                    |ui| {
+                        let caller = Location::caller().into();
+                        ui.render_object(caller, MenuPanel, |ui| {
+                            items(&mut Menu { ui, closed });
+                        });
+                    },
+                );
+
+                if clicked {
+                    *open = !*open;
+                }
+                if dismissed {
+                    *open = false;
+                }
+            },
+        );
+    }
+}
+
+/// Reports right-clicks on its content.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct ContextArea;
+
+impl Properties for ContextArea {
+    type Object = ContextAreaObject;
+}
+
+struct ContextAreaObject {
+    requested: Option<Point>,
+}
+
+impl RenderObject<ContextArea> for ContextAreaObject {
+    type Action = Option<Point>;
+
+    fn create(_props: ContextArea) -> Self {
+        ContextAreaObject { requested: None }
+    }
+
+    fn update(&mut self, _ctx: &mut UpdateCtx, _props: ContextArea) -> Option<Point> {
+        self.requested.take()
+    }
+}
+
+impl RenderObjectInterface for ContextAreaObject {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, children: &mut Children) {
+        for child in children.iter() {
+            child.event(ctx, event);
+        }
+
+        if let Event::MouseDown(mouse_event) = event {
+            if mouse_event.button == MouseButton::Right && !ctx.is_handled() {
+                self.requested = Some(ctx.to_window(mouse_event.pos));
+                ctx.request_update();
+                ctx.set_handled();
+            }
+        }
+    }
+
+    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle) {}
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        children: &mut Children,
+    ) -> Size {
+        children.layout_stacked(ctx, bc)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, children: &mut Children) {
+        for child in children {
+            child.paint(ctx);
+        }
+    }
+}
+
+/// Stacks menu entries vertically, with equal widths.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct MenuPanel;
+
+impl Properties for MenuPanel {
+    type Object = MenuPanel;
+}
+
+impl RenderObject<MenuPanel> for MenuPanel {
+    type Action = ();
+
+    fn create(props: MenuPanel) -> Self {
+        props
+    }
+
+    fn update(&mut self, _ctx: &mut UpdateCtx, _props: MenuPanel) {}
+}
+
+impl RenderObjectInterface for MenuPanel {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, children: &mut Children) {
+        for child in children {
+            child.event(ctx, event);
+        }
+    }
+
+    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle) {}
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        children: &mut Children,
+    ) -> Size {
+        bc.debug_check("MenuPanel");
+        let menu_padding = ctx.theme().spacing.menu_padding;
+        let padding = Size::new(2.0 * menu_padding, 2.0 * menu_padding);
+        let child_bc = bc.loosen().shrink(padding);
+
+        // Measure the widest entry first, so all entries can share its width.
+        let mut width: f64 = 0.0;
+        for child in children.iter() {
+            width = width.max(child.layout(ctx, &child_bc).width);
+        }
+
+        let child_bc = BoxConstraints::new(
+            Size::new(width, 0.0),
+            Size::new(width, child_bc.max().height),
+        );
+        let mut y = menu_padding;
+        for child in children.iter() {
+            let child_size = child.layout(ctx, &child_bc);
+            child.set_origin(ctx, Point::new(menu_padding, y));
+            y += child_size.height;
+        }
+
+        bc.constrain(Size::new(width, y - menu_padding) + padding)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, children: &mut Children) {
+        let theme = ctx.theme().clone();
+        let rect = ctx
+            .size()
+            .to_rect()
+            .inset(-0.5)
+            .to_rounded_rect(theme.spacing.corner_radius);
+        ctx.fill(rect, &theme.colors.panel);
+        ctx.stroke(rect, &theme.colors.panel_border, 1.0);
+        for child in children {
+            child.paint(ctx);
+        }
+    }
+}
+
+/// A single selectable entry of a menu.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct MenuItem {
+    submenu: bool,
+}
+
+impl Properties for MenuItem {
+    type Object = MenuItemObject;
+}
+
+struct MenuItemObject {
+    props: MenuItem,
+    clicked: bool,
+}
+
+impl RenderObject<MenuItem> for MenuItemObject {
+    type Action = bool;
+
+    fn create(props: MenuItem) -> Self {
+        MenuItemObject {
+            props,
+            clicked: false,
+        }
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, props: MenuItem) -> bool {
+        if self.props != props {
+            self.props = props;
+            ctx.request_layout();
+        }
+        let was_clicked = self.clicked;
+        self.clicked = false;
+        was_clicked
+    }
+}
+
+impl RenderObjectInterface for MenuItemObject {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, _children: &mut Children) {
+        match event {
+            Event::MouseDown(mouse_event) if mouse_event.button == MouseButton::Left => {
+                ctx.set_active(true);
+                ctx.set_handled();
+            }
+            Event::MouseUp(mouse_event) if mouse_event.button == MouseButton::Left => {
+                if ctx.is_active() && ctx.is_hot() {
+                    self.clicked = true;
+                    ctx.request_update();
+                    ctx.set_handled();
+                }
+                ctx.set_active(false);
+            }
+            _ => {}
+        }
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        if let LifeCycle::HotChanged(_) = event {
+            ctx.request_paint();
+        }
+    }
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        children: &mut Children,
+    ) -> Size {
+        ctx.set_hit_target(true);
+        let mut padding = ITEM_PADDING + ITEM_PADDING;
+        if self.props.submenu {
+            padding.width += SUBMENU_ARROW_WIDTH;
+        }
+
+        let label_size = children[0].layout(ctx, &bc.loosen().shrink(padding));
+        children[0].set_origin(ctx, ITEM_PADDING.to_vec2().to_point());
+        bc.constrain(label_size + padding)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, children: &mut Children) {
+        let size = ctx.size();
+        let colors = ctx.theme().colors.clone();
+        if ctx.is_hot() {
+            let rect = size.to_rect().to_rounded_rect(2.0);
+            ctx.fill(rect, &colors.accent);
+        }
+        children[0].paint(ctx);
+
+        if self.props.submenu {
+            let x = size.width - ITEM_PADDING.width - SUBMENU_ARROW_WIDTH / 2.0;
+            let y = size.height / 2.0;
+            let mut arrow = BezPath::new();
+            arrow.move_to((x - 2.0, y - 4.0));
+            arrow.line_to((x + 2.0, y));
+            arrow.line_to((x - 2.0, y + 4.0));
+            arrow.close_path();
+            ctx.fill(arrow, &colors.on_accent);
+        }
+    }
+}
+
+/// A horizontal line between menu entries.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct MenuSeparator;
+
+impl Properties for MenuSeparator {
+    type Object = MenuSeparator;
+}
+
+impl RenderObject<MenuSeparator> for MenuSeparator {
+    type Action = ();
+
+    fn create(props: MenuSeparator) -> Self {
+        props
+    }
+
+    fn update(&mut self, _ctx: &mut UpdateCtx, _props: MenuSeparator) {}
+}
+
+impl RenderObjectInterface for MenuSeparator {
+    fn event(&mut self, _ctx: &mut EventCtx, _event: &Event, _children: &mut Children) {}
+
+    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle) {}
+
+    fn layout(
+        &mut self,
+        _ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        _children: &mut Children,
+    ) -> Size {
+        bc.constrain(Size::new(0.0, SEPARATOR_HEIGHT))
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, _children: &mut Children) {
+        let y = (SEPARATOR_HEIGHT / 2.0).floor() + 0.5;
+        let line = Line::new((0.0, y), (ctx.size().width, y));
+        let color = ctx.theme().colors.panel_border.clone();
+        ctx.stroke(line, &color, 1.0);
+    }
+}
@@ -0,0 +1,465 @@
+//! Visuals for drag and drop.
+
+use crate::{
+    event::{Gesture, MouseButton},
+    kurbo::{Line, Point, Rect, Vec2},
+    object::prelude::*,
+    widgets::{
+        flex::Axis,
+        overlay::{Overlay, Placement},
+    },
+};
+
+const INDICATOR_WIDTH: f64 = 2.0;
+
+/// The opacity of the copy of the dragged content that follows the pointer.
+const GHOST_OPACITY: f64 = 0.6;
+
+/// The state of a [`DragSource`], positions are in window coordinates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DragStatus {
+    Idle,
+    /// The content is being dragged, the pointer is at the given position.
+    Dragging(Point),
+    /// The content was released at the given position.
+    Dropped(Point),
+}
+
+impl DragStatus {
+    pub fn is_dragging(&self) -> bool {
+        matches!(self, DragStatus::Dragging(_))
+    }
+}
+
+/// Content that can be dragged with the left mouse button.
+///
+/// While dragging, a faded copy of the content follows the pointer.
+/// The content is built a second time for the copy, so `content`
+/// should not have side effects.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct DragSource;
+
+impl DragSource {
+    pub fn new() -> Self {
+        DragSource
+    }
+
+    #[track_caller]
+    pub fn build(self, ui: &mut Ui, content: impl Fn(&mut Ui)) -> DragStatus {
+        let caller = Location::caller().into();
+        let mut status = DragStatus::Idle;
+        ui.state_node(caller, DragAction::default, |ui, last: &mut DragAction| {
+            let (dragging, origin) = match last.status {
+                DragStatus::Dragging(pointer) => (true, pointer - last.grab),
+                _ => (false, Point::ORIGIN),
+            };
+            let ghost = Ghost { size: last.size };
+
+            let _ = Overlay::new(Placement::At(origin))
+                .interactive(false)
+                .build(
+                    ui,
+                    dragging,
+                    |ui| {
+                        let caller = Location::caller().into();
+                        *last = ui.render_object(caller, self, &content);
+                    },
+                    |ui| {
+                        let caller = Location::caller().into();
+                        ui.render_object(caller, ghost, &content);
+                    },
+                );
+
+            status = last.status;
+        });
+        status
+    }
+}
+
+impl Properties for DragSource {
+    type Object = DragSourceObject;
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct DragAction {
+    status: DragStatus,
+    /// Where the content was grabbed, relative to its origin.
+    grab: Vec2,
+    size: Size,
+}
+
+impl Default for DragAction {
+    fn default() -> Self {
+        DragAction {
+            status: DragStatus::Idle,
+            grab: Vec2::ZERO,
+            size: Size::ZERO,
+        }
+    }
+}
+
+pub struct DragSourceObject {
+    press: Option<Point>,
+    pointer: Option<Point>,
+    dropped: Option<Point>,
+}
+
+impl RenderObject<DragSource> for DragSourceObject {
+    type Action = DragAction;
+
+    fn create(_props: DragSource) -> Self {
+        DragSourceObject {
+            press: None,
+            pointer: None,
+            dropped: None,
+        }
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, _props: DragSource) -> DragAction {
+        let status = match (self.dropped.take(), self.pointer) {
+            (Some(point), _) => DragStatus::Dropped(point),
+            (None, Some(point)) => DragStatus::Dragging(point),
+            (None, None) => DragStatus::Idle,
+        };
+        DragAction {
+            status,
+            grab: self.press.unwrap_or_default().to_vec2(),
+            size: ctx.size(),
+        }
+    }
+}
+
+impl RenderObjectInterface for DragSourceObject {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, children: &mut Children) {
+        for child in children.iter() {
+            child.event(ctx, event);
+        }
+
+        match event {
+            Event::MouseDown(mouse_event)
+                if mouse_event.button == MouseButton::Left && !ctx.is_handled() =>
+            {
+                self.press = Some(mouse_event.pos);
+                ctx.set_active(true);
+                ctx.set_handled();
+            }
+            Event::MouseUp(mouse_event)
+                if ctx.is_active() && mouse_event.button == MouseButton::Left =>
+            {
+                ctx.set_active(false);
+                self.press = None;
+            }
+            _ => {}
+        }
+        if !ctx.is_active() {
+            return;
+        }
+        match Gesture::from_event(event) {
+            Some(Gesture::DragStart { pos, .. }) | Some(Gesture::DragMove { pos, .. }) => {
+                self.pointer = Some(ctx.to_window(*pos));
+                ctx.request_update();
+            }
+            Some(Gesture::DragEnd { pos, .. }) if self.pointer.take().is_some() => {
+                self.dropped = Some(ctx.to_window(*pos));
+                ctx.request_update();
+            }
+            _ => {}
+        }
+    }
+
+    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle) {}
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        children: &mut Children,
+    ) -> Size {
+        children.layout_stacked(ctx, bc)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, children: &mut Children) {
+        for child in children {
+            child.paint(ctx);
+        }
+    }
+}
+
+/// The copy of the dragged content.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Ghost {
+    size: Size,
+}
+
+impl Properties for Ghost {
+    type Object = Ghost;
+}
+
+impl RenderObject<Ghost> for Ghost {
+    type Action = ();
+
+    fn create(props: Ghost) -> Self {
+        props
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, props: Ghost) {
+        if self != &props {
+            *self = props;
+            ctx.request_layout();
+        }
+    }
+}
+
+impl RenderObjectInterface for Ghost {
+    fn event(&mut self, _ctx: &mut EventCtx, _event: &Event, _children: &mut Children) {}
+
+    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle) {}
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        _bc: &BoxConstraints,
+        children: &mut Children,
+    ) -> Size {
+        let child_bc = BoxConstraints::tight(self.size);
+        for child in children.iter() {
+            child.layout(ctx, &child_bc);
+            child.set_origin(ctx, Point::ORIGIN);
+        }
+        self.size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, children: &mut Children) {
+        let rect = ctx.size().to_rect();
+        ctx.with_opacity(GHOST_OPACITY, |ctx| {
+            ctx.with_save(|ctx| {
+                ctx.clip(rect);
+                for child in children {
+                    child.paint(ctx);
+                }
+            });
+        });
+    }
+}
+
+/// Which drop positions a [`DropTarget`] offers.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DropMode {
+    /// Insert before or after the target along the axis, as in lists and tabs.
+    Insert(Axis),
+    /// Insert before or after the target, or drop into it, as in trees.
+    InsertOrInside(Axis),
+    /// Drop into the target.
+    Inside,
+}
+
+/// Where a drop onto a [`DropTarget`] would end up.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DropIndicator {
+    Before,
+    After,
+    Inside,
+}
+
+impl DropMode {
+    fn indicator(self, pos: Point, size: Size) -> DropIndicator {
+        let (axis, inside_span) = match self {
+            DropMode::Insert(axis) => (axis, 0.0),
+            DropMode::InsertOrInside(axis) => (axis, 1.0 / 3.0),
+            DropMode::Inside => return DropIndicator::Inside,
+        };
+
+        let major = match axis {
+            Axis::Horizontal => pos.x,
+            Axis::Vertical => pos.y,
+        };
+        let fraction = major / axis.major(size);
+        if fraction < 0.5 - inside_span / 2.0 {
+            DropIndicator::Before
+        } else if fraction > 0.5 + inside_span / 2.0 {
+            DropIndicator::After
+        } else {
+            DropIndicator::Inside
+        }
+    }
+}
+
+/// Content that shows where dragged content would be dropped.
+///
+/// While `dragging` and the pointer is over the content, an insertion line
+/// or highlight is painted above it, and the matching [`DropIndicator`] is returned.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DropTarget {
+    mode: DropMode,
+    dragging: bool,
+}
+
+impl Properties for DropTarget {
+    type Object = DropTargetObject;
+}
+
+impl DropTarget {
+    pub fn new(mode: DropMode) -> Self {
+        DropTarget {
+            mode,
+            dragging: false,
+        }
+    }
+
+    #[track_caller]
+    pub fn build(
+        mut self,
+        ui: &mut Ui,
+        dragging: bool,
+        content: impl FnOnce(&mut Ui),
+    ) -> Option<DropIndicator> {
+        self.dragging = dragging;
+        let caller = Location::caller().into();
+        ui.render_object(caller, self, content)
+    }
+}
+
+pub struct DropTargetObject {
+    props: DropTarget,
+    indicator: Option<DropIndicator>,
+}
+
+impl RenderObject<DropTarget> for DropTargetObject {
+    type Action = Option<DropIndicator>;
+
+    fn create(props: DropTarget) -> Self {
+        DropTargetObject {
+            props,
+            indicator: None,
+        }
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, props: DropTarget) -> Option<DropIndicator> {
+        if self.props != props {
+            self.props = props;
+            ctx.request_paint();
+        }
+        self.indicator.filter(|_| self.props.dragging)
+    }
+}
+
+impl RenderObjectInterface for DropTargetObject {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, children: &mut Children) {
+        for child in children.iter() {
+            child.event(ctx, event);
+        }
+
+        if let Event::MouseMove(mouse_event) = event {
+            let indicator = if ctx.is_hot() {
+                Some(self.props.mode.indicator(mouse_event.pos, ctx.size()))
+            } else {
+                None
+            };
+            if indicator != self.indicator {
+                self.indicator = indicator;
+                if self.props.dragging {
+                    ctx.request_update();
+                    ctx.request_paint();
+                }
+            }
+        }
+    }
+
+    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle) {}
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        children: &mut Children,
+    ) -> Size {
+        children.layout_stacked(ctx, bc)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, children: &mut Children) {
+        for child in children {
+            child.paint(ctx);
+        }
+
+        if !self.props.dragging {
+            return;
+        }
+        let rect = ctx.size().to_rect();
+        let axis = match self.props.mode {
+            DropMode::Insert(axis) | DropMode::InsertOrInside(axis) => axis,
+            DropMode::Inside => Axis::Vertical,
+        };
+        match self.indicator {
+            Some(DropIndicator::Before) => paint_insertion_line(ctx, rect, axis, false),
+            Some(DropIndicator::After) => paint_insertion_line(ctx, rect, axis, true),
+            Some(DropIndicator::Inside) => paint_drop_highlight(ctx, rect),
+            None => {}
+        }
+    }
+}
+
+/// Paint an insertion line along the leading or trailing edge of `rect`.
+pub fn paint_insertion_line(ctx: &mut PaintCtx, rect: Rect, axis: Axis, trailing: bool) {
+    let inset = INDICATOR_WIDTH / 2.0;
+    let line = match (axis, trailing) {
+        (Axis::Vertical, false) => {
+            Line::new((rect.x0, rect.y0 + inset), (rect.x1, rect.y0 + inset))
+        }
+        (Axis::Vertical, true) => Line::new((rect.x0, rect.y1 - inset), (rect.x1, rect.y1 - inset)),
+        (Axis::Horizontal, false) => {
+            Line::new((rect.x0 + inset, rect.y0), (rect.x0 + inset, rect.y1))
+        }
+        (Axis::Horizontal, true) => {
+            Line::new((rect.x1 - inset, rect.y0), (rect.x1 - inset, rect.y1))
+        }
+    };
+    let color = ctx.theme().colors.accent.clone();
+    ctx.stroke(line, &color, INDICATOR_WIDTH);
+}
+
+/// Paint a highlight over `rect`, marking it as the drop destination.
+pub fn paint_drop_highlight(ctx: &mut PaintCtx, rect: Rect) {
+    let rect = rect.inset(-INDICATOR_WIDTH / 2.0).to_rounded_rect(2.0);
+    let color = ctx.theme().colors.accent.clone();
+    ctx.fill(rect, &color.clone().with_alpha(0.25));
+    ctx.stroke(rect, &color, INDICATOR_WIDTH);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drop_indicator_from_position() {
+        let size = Size::new(100.0, 30.0);
+        let list = DropMode::Insert(Axis::Vertical);
+        assert_eq!(
+            list.indicator(Point::new(50.0, 5.0), size),
+            DropIndicator::Before
+        );
+        assert_eq!(
+            list.indicator(Point::new(50.0, 25.0), size),
+            DropIndicator::After
+        );
+
+        let tree = DropMode::InsertOrInside(Axis::Vertical);
+        assert_eq!(
+            tree.indicator(Point::new(50.0, 5.0), size),
+            DropIndicator::Before
+        );
+        assert_eq!(
+            tree.indicator(Point::new(50.0, 15.0), size),
+            DropIndicator::Inside
+        );
+        assert_eq!(
+            tree.indicator(Point::new(50.0, 25.0), size),
+            DropIndicator::After
+        );
+
+        let tabs = DropMode::Insert(Axis::Horizontal);
+        assert_eq!(
+            tabs.indicator(Point::new(80.0, 5.0), size),
+            DropIndicator::After
+        );
+    }
+}
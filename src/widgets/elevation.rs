@@ -0,0 +1,111 @@
+//! A container that floats its content above the window with a drop shadow.
+
+use crate::{
+    object::prelude::*,
+    piet::{Color, RenderContext},
+    style::Shadow,
+};
+
+/// Paints a blurred drop shadow below its content, and optionally a
+/// background, to lift it above what is around it.
+///
+/// The shadow is as large as the content, which should fill it with an
+/// opaque background, either its own or the one set here, as the shadow
+/// would otherwise show through.
+///
+/// ```ignore
+/// Elevation::new(4.0)
+///     .background(ui.theme().colors.panel.clone())
+///     .build(ui, |ui| card(ui, &item));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Elevation {
+    shadow: Shadow,
+    background: Option<Color>,
+}
+
+impl Properties for Elevation {
+    type Object = Elevation;
+}
+
+impl Elevation {
+    /// Float the content `elevation` pixels above the window.
+    pub fn new(elevation: f64) -> Self {
+        Self::shadow(Shadow::elevation(elevation))
+    }
+
+    /// Paint `shadow` below the content.
+    pub fn shadow(shadow: Shadow) -> Self {
+        Elevation {
+            shadow,
+            background: None,
+        }
+    }
+
+    /// Fill the area of the content with `color` before painting it.
+    pub fn background(mut self, color: Color) -> Self {
+        self.background = Some(color);
+        self
+    }
+
+    #[track_caller]
+    pub fn build(self, ui: &mut Ui, content: impl FnOnce(&mut Ui)) {
+        let caller = Location::caller().into();
+        ui.render_object(caller, self, content);
+    }
+}
+
+impl RenderObject<Elevation> for Elevation {
+    type Action = ();
+
+    fn create(props: Elevation) -> Self {
+        props
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, props: Elevation) {
+        if *self != props {
+            *self = props;
+            // The paint insets depend on the shadow.
+            ctx.request_layout();
+            ctx.request_paint();
+        }
+    }
+}
+
+impl RenderObjectInterface for Elevation {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, children: &mut Children) {
+        for child in children {
+            child.event(ctx, event);
+        }
+    }
+
+    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle) {}
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        children: &mut Children,
+    ) -> Size {
+        bc.debug_check("Elevation");
+        let size = children.layout_stacked(ctx, bc);
+        let rect = size.to_rect();
+        let mut painted = self.shadow.bounds(rect);
+        for child in &mut *children {
+            painted = painted.union(rect + child.compute_parent_paint_insets(size));
+        }
+        ctx.set_paint_insets(painted - rect);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, children: &mut Children) {
+        let rect = ctx.size().to_rect();
+        self.shadow.paint(ctx, rect);
+        if let Some(background) = &self.background {
+            ctx.fill(rect, background);
+        }
+        for child in children {
+            child.paint(ctx);
+        }
+    }
+}
@@ -0,0 +1,319 @@
+//! A section whose body can be collapsed.
+
+use crate::{
+    animation::Easing,
+    event::MouseButton,
+    kurbo::{BezPath, Point},
+    object::prelude::*,
+    widgets::{Label, Retain},
+};
+use std::time::Duration;
+
+const HEADER_PADDING: Size = Size::new(6.0, 4.0);
+const ARROW_SIZE: f64 = 10.0;
+
+/// A clickable header that shows or hides the body below it.
+///
+/// The body keeps its state while it is collapsed, so scroll offsets and
+/// text input survive collapsing and expanding the section again.
+///
+/// ```ignore
+/// Expander::new("Advanced")
+///     .animated(Duration::from_millis(150))
+///     .build(ui, |ui| advanced_settings(ui));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expander {
+    title: String,
+    expanded: bool,
+    animation: Option<Duration>,
+}
+
+struct ExpanderState {
+    expanded: bool,
+}
+
+impl Expander {
+    pub fn new(title: impl Into<String>) -> Self {
+        Expander {
+            title: title.into(),
+            expanded: false,
+            animation: None,
+        }
+    }
+
+    /// Whether the section starts expanded.
+    ///
+    /// The default value is `false`.
+    pub fn expanded(mut self, expanded: bool) -> Self {
+        self.expanded = expanded;
+        self
+    }
+
+    /// Slide the body in and out over `duration`, instead of showing it at once.
+    pub fn animated(mut self, duration: Duration) -> Self {
+        self.animation = Some(duration);
+        self
+    }
+
+    /// Build the header and, while expanded, the `body`.
+    ///
+    /// Returns whether the section is expanded.
+    #[track_caller]
+    pub fn build(self, ui: &mut Ui, body: impl FnOnce(&mut Ui)) -> bool {
+        let caller = Location::caller().into();
+        let Expander {
+            title,
+            expanded,
+            animation,
+        } = self;
+        let mut result = expanded;
+        ui.state_node(
+            caller,
+            || ExpanderState { expanded },
+            |ui, state: &mut ExpanderState| {
+                let caller = Location::caller().into();
+                ui.render_object(caller, Section, |ui| {
+                    let caller = Location::caller().into();
+                    let header = Header {
+                        expanded: state.expanded,
+                    };
+                    if ui.render_object(caller, header, |ui| Label::new(title).build(ui)) {
+                        state.expanded = !state.expanded;
+                    }
+
+                    let mut progress = if state.expanded { 1.0 } else { 0.0 };
+                    if let Some(duration) = animation {
+                        progress = ui.animate(progress, duration, Easing::EaseInOut);
+                    }
+                    let caller = Location::caller().into();
+                    ui.render_object(caller, Body { progress }, |ui| {
+                        if progress > 0.0 {
+                            Retain::new().build(ui, body);
+                        }
+                    });
+                });
+                result = state.expanded;
+            },
+        );
+        result
+    }
+}
+
+/// Stacks the header above the body.
+struct Section;
+
+impl Properties for Section {
+    type Object = Section;
+}
+
+impl RenderObject<Section> for Section {
+    type Action = ();
+
+    fn create(props: Section) -> Self {
+        props
+    }
+
+    fn update(&mut self, _ctx: &mut UpdateCtx, _props: Section) {}
+}
+
+impl RenderObjectInterface for Section {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, children: &mut Children) {
+        for child in children {
+            child.event(ctx, event);
+        }
+    }
+
+    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle) {}
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        children: &mut Children,
+    ) -> Size {
+        bc.debug_check("Expander");
+        let mut size = Size::new(bc.min().width, 0.0);
+        for child in children {
+            let child_bc = BoxConstraints::new(
+                Size::new(bc.min().width, 0.0),
+                Size::new(bc.max().width, f64::INFINITY),
+            );
+            let child_size = child.layout(ctx, &child_bc);
+            child.set_origin(ctx, Point::new(0.0, size.height));
+            size.width = size.width.max(child_size.width);
+            size.height += child_size.height;
+        }
+        bc.constrain(size)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, children: &mut Children) {
+        for child in children {
+            child.paint(ctx);
+        }
+    }
+}
+
+/// The clickable header with an arrow pointing at the body.
+#[derive(PartialEq)]
+struct Header {
+    expanded: bool,
+}
+
+impl Properties for Header {
+    type Object = HeaderObject;
+}
+
+struct HeaderObject {
+    expanded: bool,
+    clicked: bool,
+}
+
+impl RenderObject<Header> for HeaderObject {
+    type Action = bool;
+
+    fn create(props: Header) -> Self {
+        HeaderObject {
+            expanded: props.expanded,
+            clicked: false,
+        }
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, props: Header) -> bool {
+        if self.expanded != props.expanded {
+            self.expanded = props.expanded;
+            ctx.request_paint();
+        }
+        std::mem::take(&mut self.clicked)
+    }
+}
+
+impl RenderObjectInterface for HeaderObject {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, _children: &mut Children) {
+        match event {
+            Event::MouseDown(mouse) if mouse.button == MouseButton::Left => {
+                ctx.set_active(true);
+                ctx.request_paint();
+            }
+            Event::MouseUp(mouse) if ctx.is_active() && mouse.button == MouseButton::Left => {
+                ctx.set_active(false);
+                if ctx.is_hot() {
+                    self.clicked = true;
+                    ctx.request_update();
+                    ctx.set_handled();
+                }
+                ctx.request_paint();
+            }
+            _ => {}
+        }
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        if let LifeCycle::HotChanged(_) = event {
+            ctx.request_paint();
+        }
+    }
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        children: &mut Children,
+    ) -> Size {
+        ctx.set_hit_target(true);
+        let left = ARROW_SIZE + 2.0 * HEADER_PADDING.width;
+        let padding = Size::new(left + HEADER_PADDING.width, 2.0 * HEADER_PADDING.height);
+        let label = children[0].layout(ctx, &bc.loosen().shrink(padding));
+        children[0].set_origin(ctx, Point::new(left, HEADER_PADDING.height));
+        let baseline = children[0].baseline_offset();
+        ctx.set_baseline_offset(baseline + HEADER_PADDING.height);
+        // The header spans the whole width, so all of it can be clicked.
+        let width = if bc.max().width.is_finite() {
+            bc.max().width
+        } else {
+            label.width + padding.width
+        };
+        bc.constrain(Size::new(width, label.height + padding.height))
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, children: &mut Children) {
+        let size = ctx.size();
+        let theme = ctx.theme();
+        let text = theme.colors.text.clone();
+        if ctx.is_hot() {
+            let ghost = theme.colors.ghost.clone();
+            ctx.fill(size.to_rect(), &ghost);
+        }
+
+        let center = Point::new(HEADER_PADDING.width + ARROW_SIZE / 2.0, size.height / 2.0);
+        let half = ARROW_SIZE / 2.0;
+        let mut arrow = BezPath::new();
+        if self.expanded {
+            arrow.move_to((center.x - half, center.y - half / 2.0));
+            arrow.line_to((center.x + half, center.y - half / 2.0));
+            arrow.line_to((center.x, center.y + half / 2.0));
+        } else {
+            arrow.move_to((center.x - half / 2.0, center.y - half));
+            arrow.line_to((center.x + half / 2.0, center.y));
+            arrow.line_to((center.x - half / 2.0, center.y + half));
+        }
+        arrow.close_path();
+        ctx.fill(arrow, &text);
+
+        children[0].paint(ctx);
+    }
+}
+
+/// Shows the `progress` share of the body, cut off at the bottom.
+struct Body {
+    progress: f64,
+}
+
+impl Properties for Body {
+    type Object = Body;
+}
+
+impl RenderObject<Body> for Body {
+    type Action = ();
+
+    fn create(props: Body) -> Self {
+        props
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, props: Body) {
+        if self.progress != props.progress {
+            self.progress = props.progress;
+            ctx.request_layout();
+        }
+    }
+}
+
+impl RenderObjectInterface for Body {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, children: &mut Children) {
+        for child in children {
+            child.event(ctx, event);
+        }
+    }
+
+    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle) {}
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        children: &mut Children,
+    ) -> Size {
+        let size = children.layout_stacked(ctx, bc);
+        Size::new(size.width, (size.height * self.progress).round())
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, children: &mut Children) {
+        let size = ctx.size();
+        ctx.with_save(|ctx| {
+            ctx.clip(size.to_rect());
+            for child in children {
+                child.paint(ctx);
+            }
+        });
+    }
+}
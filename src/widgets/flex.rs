@@ -1,8 +1,9 @@
 //! A widget that arranges its children in a one-dimensional array.
 
 use crate::{
+    environment::LayoutDirection,
     kurbo::{common::FloatExt, Point, Rect},
-    object::prelude::*,
+    object::{prelude::*, Intrinsic},
 };
 
 #[derive(PartialEq)]
@@ -128,22 +129,28 @@ impl Axis {
 pub enum CrossAxisAlignment {
     /// Top or leading.
     ///
-    /// In a vertical container, widgets are top aligned. In a horiziontal
-    /// container, their leading edges are aligned.
+    /// In a horizontal container, widgets are top aligned. In a vertical
+    /// container, their leading edges are aligned, the left ones or, right
+    /// to left, the right ones.
     Start,
     /// Widgets are centered in the container.
     Center,
     /// Bottom or trailing.
     ///
-    /// In a vertical container, widgets are bottom aligned. In a horiziontal
+    /// In a horizontal container, widgets are bottom aligned. In a vertical
     /// container, their trailing edges are aligned.
     End,
     /// Align on the baseline.
     ///
     /// In a horizontal container, widgets are aligned along the calculated
-    /// baseline. In a vertical container, this is equivalent to `End`.
+    /// baseline, so the text of a label, a text box and a button lines up
+    /// even though they differ in height. In a vertical container, this is
+    /// equivalent to `Center`.
     ///
     /// The calculated baseline is the maximum baseline offset of the children.
+    /// Single children of a row can be baseline aligned with
+    /// [`Flexible::alignment`], the others are then aligned with each other
+    /// at the bottom of the row.
     Baseline,
 }
 
@@ -193,7 +200,8 @@ impl Flex {
 
     /// Create a new horizontal stack.
     ///
-    /// The child widgets are laid out horizontally, from left to right.
+    /// The child widgets are laid out horizontally, from left to right, or
+    /// from right to left in a [`LayoutDirection::RightToLeft`] environment.
     ///
     pub fn row() -> Self {
         Self::for_axis(Axis::Horizontal)
@@ -262,6 +270,72 @@ impl Flex {
     }
 }
 
+impl Flex {
+    /// An intrinsic size of the children, where `extent` is the size on the
+    /// other axis.
+    fn intrinsic(
+        &self,
+        ctx: &mut LayoutCtx,
+        children: &mut Children,
+        intrinsic: Intrinsic,
+        extent: f64,
+    ) -> f64 {
+        let axis = match intrinsic {
+            Intrinsic::MinWidth | Intrinsic::MaxWidth => Axis::Horizontal,
+            Intrinsic::MinHeight | Intrinsic::MaxHeight => Axis::Vertical,
+        };
+        // On the main axis, the sizes of the children add up.
+        if axis == self.direction {
+            return children
+                .iter()
+                .map(|child| child.intrinsic(ctx, intrinsic, extent))
+                .sum();
+        }
+
+        // On the cross axis, every child is measured at the size it gets on
+        // the main axis: its natural size, or its share of the rest.
+        let natural_major = match self.direction {
+            Axis::Horizontal => Intrinsic::MaxWidth,
+            Axis::Vertical => Intrinsic::MaxHeight,
+        };
+        let majors: Vec<Option<f64>> = children
+            .iter()
+            .map(|child| {
+                if get_flex(child) == 0.0 {
+                    Some(child.intrinsic(ctx, natural_major, f64::INFINITY))
+                } else {
+                    None
+                }
+            })
+            .collect();
+        let flex_sum: f64 = children.iter().map(get_flex).sum();
+        let remaining = (extent - majors.iter().flatten().sum::<f64>()).max(0.0);
+        children
+            .iter()
+            .zip(majors)
+            .map(|(child, major)| {
+                let major = major.unwrap_or_else(|| remaining * get_flex(child) / flex_sum);
+                child.intrinsic(ctx, intrinsic, major)
+            })
+            .fold(0.0, f64::max)
+    }
+}
+
+fn get_flex(child: &mut Child) -> f64 {
+    child
+        .as_any()
+        .downcast_ref::<Flexible>()
+        .map(|f| f.flex)
+        .unwrap_or(0.0)
+}
+
+fn get_alignment(child: &mut Child) -> Option<CrossAxisAlignment> {
+    child
+        .as_any()
+        .downcast_ref::<Flexible>()
+        .and_then(|f| f.alignment)
+}
+
 impl Properties for Flex {
     type Object = Flex;
 }
@@ -298,20 +372,6 @@ impl RenderObjectInterface for Flex {
     ) -> Size {
         bc.debug_check("Flex");
 
-        fn get_flex(child: &mut Child) -> f64 {
-            child
-                .as_any()
-                .downcast_ref::<Flexible>()
-                .map(|f| f.flex)
-                .unwrap_or(0.0)
-        }
-        fn get_alignment(child: &mut Child) -> Option<CrossAxisAlignment> {
-            child
-                .as_any()
-                .downcast_ref::<Flexible>()
-                .and_then(|f| f.alignment)
-        }
-
         // we loosen our constraints when passing to children.
         let loosened_bc = bc.loosen();
 
@@ -320,13 +380,21 @@ impl RenderObjectInterface for Flex {
         // these two are calculated but only used if we're baseline aligned
         let mut max_above_baseline = 0f64;
         let mut max_below_baseline = 0f64;
-        let mut any_use_baseline = self.cross_alignment == CrossAxisAlignment::Baseline;
+        // Children only share a baseline in a row, either because it is the
+        // default of the container or because they ask for it themselves.
+        let cross_alignment = self.cross_alignment;
+        let uses_baseline = |child: &mut Child| {
+            get_alignment(child).unwrap_or(cross_alignment) == CrossAxisAlignment::Baseline
+        };
+        let any_use_baseline =
+            self.direction == Axis::Horizontal && children.iter().any(&uses_baseline);
+        // Without baseline aligned children, our own baseline is the lowest
+        // one of all children.
+        let in_baseline = |child: &mut Child| !any_use_baseline || uses_baseline(child);
 
         // Measure non-flex children.
         let mut major_non_flex = 0.0;
         for child in children.iter() {
-            any_use_baseline &= get_alignment(child) == Some(CrossAxisAlignment::Baseline);
-
             if get_flex(child) == 0.0 {
                 let child_bc = self
                     .direction
@@ -344,8 +412,11 @@ impl RenderObjectInterface for Flex {
 
                 major_non_flex += self.direction.major(child_size).expand();
                 minor = minor.max(self.direction.minor(child_size).expand());
-                max_above_baseline = max_above_baseline.max(child_size.height - baseline_offset);
-                max_below_baseline = max_below_baseline.max(baseline_offset);
+                if in_baseline(child) {
+                    max_above_baseline =
+                        max_above_baseline.max(child_size.height - baseline_offset);
+                    max_below_baseline = max_below_baseline.max(baseline_offset);
+                }
             }
         }
 
@@ -377,8 +448,11 @@ impl RenderObjectInterface for Flex {
 
                 major_flex += self.direction.major(child_size).expand();
                 minor = minor.max(self.direction.minor(child_size).expand());
-                max_above_baseline = max_above_baseline.max(child_size.height - baseline_offset);
-                max_below_baseline = max_below_baseline.max(baseline_offset);
+                if in_baseline(child) {
+                    max_above_baseline =
+                        max_above_baseline.max(child_size.height - baseline_offset);
+                    max_below_baseline = max_below_baseline.max(baseline_offset);
+                }
             }
         }
 
@@ -393,23 +467,24 @@ impl RenderObjectInterface for Flex {
 
         let mut spacing = Spacing::new(self.main_alignment, extra, children.len());
 
-        // the actual size needed to tightly fit the children on the minor axis.
-        // Unlike the 'minor' var, this ignores the incoming constraints.
-        let minor_dim = match self.direction {
-            Axis::Horizontal if any_use_baseline => max_below_baseline + max_above_baseline,
-            _ => minor,
+        // The baseline aligned children are stacked above and below their
+        // shared baseline, which may need more room than the tallest child.
+        let baseline_dim = max_below_baseline + max_above_baseline;
+        let minor_dim = if any_use_baseline {
+            minor.max(baseline_dim)
+        } else {
+            minor
         };
 
         let mut major = spacing.next().unwrap_or(0.);
-        let mut child_paint_rect = Rect::ZERO;
+        let mut positions = Vec::with_capacity(children.len());
         for child in children.iter() {
             let child_size = child.layout_rect().size();
             let alignment = get_alignment(child).unwrap_or(self.cross_alignment);
             let child_minor_offset = match alignment {
-                // This will ignore baseline alignment if it is overridden on children,
-                // but is not the default for the container. Is this okay?
-                CrossAxisAlignment::Baseline if matches!(self.direction, Axis::Horizontal) => {
-                    let extra_height = minor - minor_dim.min(minor);
+                // The baseline aligned children sit at the bottom of the row.
+                CrossAxisAlignment::Baseline if any_use_baseline => {
+                    let extra_height = minor_dim - baseline_dim;
                     let child_baseline = child.baseline_offset();
                     let child_above_baseline = child_size.height - child_baseline;
                     extra_height + (max_above_baseline - child_above_baseline)
@@ -420,9 +495,7 @@ impl RenderObjectInterface for Flex {
                 }
             };
 
-            let child_pos: Point = self.direction.pack(major, child_minor_offset).into();
-            child.set_origin(ctx, child_pos);
-            child_paint_rect = child_paint_rect.union(child.paint_rect());
+            positions.push(Point::from(self.direction.pack(major, child_minor_offset)));
             major += self.direction.major(child_size).expand();
             major += spacing.next().unwrap_or(0.);
         }
@@ -447,6 +520,18 @@ impl RenderObjectInterface for Flex {
             bc.constrain(my_size)
         };
 
+        // Right to left, rows start at the right and the leading edge of
+        // columns is the right one.
+        let right_to_left = ctx.layout_direction() == LayoutDirection::RightToLeft;
+        let mut child_paint_rect = Rect::ZERO;
+        for (child, mut child_pos) in children.iter().zip(positions) {
+            if right_to_left {
+                child_pos.x = my_size.width - child_pos.x - child.layout_rect().width();
+            }
+            child.set_origin(ctx, child_pos);
+            child_paint_rect = child_paint_rect.union(child.paint_rect());
+        }
+
         let my_bounds = Rect::ZERO.with_size(my_size);
         let insets = child_paint_rect - my_bounds;
         ctx.set_paint_insets(insets);
@@ -469,6 +554,42 @@ impl RenderObjectInterface for Flex {
         my_size
     }
 
+    fn compute_min_intrinsic_width(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        height: f64,
+        children: &mut Children,
+    ) -> f64 {
+        self.intrinsic(ctx, children, Intrinsic::MinWidth, height)
+    }
+
+    fn compute_max_intrinsic_width(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        height: f64,
+        children: &mut Children,
+    ) -> f64 {
+        self.intrinsic(ctx, children, Intrinsic::MaxWidth, height)
+    }
+
+    fn compute_min_intrinsic_height(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        width: f64,
+        children: &mut Children,
+    ) -> f64 {
+        self.intrinsic(ctx, children, Intrinsic::MinHeight, width)
+    }
+
+    fn compute_max_intrinsic_height(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        width: f64,
+        children: &mut Children,
+    ) -> f64 {
+        self.intrinsic(ctx, children, Intrinsic::MaxHeight, width)
+    }
+
     fn paint(&mut self, ctx: &mut PaintCtx, children: &mut Children) {
         for child in children {
             child.paint(ctx);
@@ -485,6 +606,28 @@ impl RenderObjectInterface for Flex {
     }
 }
 
+impl Flexible {
+    /// A child of a [`Flex`] that takes `flex` parts of the space left by
+    /// the other children on the main axis, or no space from it if `flex`
+    /// is zero.
+    pub fn new(flex: f64) -> Self {
+        flex.into()
+    }
+
+    /// Align this child on the cross axis with `alignment` instead of the
+    /// alignment of the container.
+    pub fn alignment(mut self, alignment: CrossAxisAlignment) -> Self {
+        self.alignment = Some(alignment);
+        self
+    }
+
+    #[track_caller]
+    pub fn build(self, ui: &mut Ui, content: impl FnOnce(&mut Ui)) {
+        let caller = Location::caller().into();
+        ui.render_object(caller, self, content);
+    }
+}
+
 impl Properties for Flexible {
     type Object = Flexible;
 }
@@ -515,7 +658,9 @@ impl RenderObjectInterface for Flexible {
         bc: &BoxConstraints,
         children: &mut Children,
     ) -> Size {
-        children[0].layout(ctx, bc)
+        let size = children[0].layout(ctx, bc);
+        ctx.set_baseline_offset(children[0].baseline_offset());
+        size
     }
 
     fn paint(&mut self, ctx: &mut PaintCtx, children: &mut Children) {
@@ -644,6 +789,11 @@ impl From<f64> for Flexible {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{
+        testing::{self, Harness},
+        widgets::{CommandHandler, Label, Retain},
+    };
+    use druid::Selector;
 
     #[test]
     #[allow(clippy::cognitive_complexity)]
@@ -714,4 +864,39 @@ mod tests {
         assert_eq!(vec(a, 38., 5), vec![4., 7., 8., 8., 7., 4.]);
         assert_eq!(vec(a, 39., 5), vec![4., 8., 7., 8., 8., 4.]);
     }
+
+    #[test]
+    fn wrappers_forward_the_baseline() {
+        const SELECTOR: Selector<()> = Selector::new("coat.test.baseline");
+        let mut harness = Harness::new(|ui| {
+            Flex::column().build(ui, |ui| {
+                Flex::row()
+                    .cross_axis_alignment(CrossAxisAlignment::Baseline)
+                    .build(ui, |ui| {
+                        Label::new("a").build(ui);
+                        Label::new("A").text_size(40.0).build(ui);
+                    });
+                Flex::row()
+                    .cross_axis_alignment(CrossAxisAlignment::Baseline)
+                    .build(ui, |ui| {
+                        Label::new("b").build(ui);
+                        Retain::new().build(ui, |ui| {
+                            ui.memoize(0, |ui| {
+                                let _ = CommandHandler::new(SELECTOR).build(ui, |ui| {
+                                    Label::new("B").text_size(40.0).build(ui);
+                                });
+                            });
+                        });
+                    });
+            });
+        });
+        let mut offset = |small: &str, large: &str| {
+            let small = testing::assert_exists::<Label>(harness.tree(), testing::with_label(small));
+            let large = testing::assert_exists::<Label>(harness.tree(), testing::with_label(large));
+            harness.window_rect(small).y0 - harness.window_rect(large).y0
+        };
+        let plain = offset("a", "A");
+        assert!(plain > 0.0);
+        assert_eq!(offset("b", "B"), plain);
+    }
 }
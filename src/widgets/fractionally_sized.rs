@@ -0,0 +1,88 @@
+//! A container that sizes its content relative to the available space.
+
+use crate::object::prelude::*;
+
+/// Sizes its content to a fraction of the maximum width or height its
+/// parent allows, like a side panel that takes a third of the window:
+///
+/// ```ignore
+/// FractionallySized::new().width(1.0 / 3.0).build(ui, |ui| side_panel(ui));
+/// ```
+///
+/// Axes without a fraction, or without a bound, are passed on unchanged.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FractionallySized {
+    width: Option<f64>,
+    height: Option<f64>,
+}
+
+impl Properties for FractionallySized {
+    type Object = FractionallySized;
+}
+
+impl FractionallySized {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the width to `fraction` of the maximum width, `0.5` for half.
+    pub fn width(mut self, fraction: f64) -> Self {
+        self.width = Some(fraction);
+        self
+    }
+
+    /// Set the height to `fraction` of the maximum height.
+    pub fn height(mut self, fraction: f64) -> Self {
+        self.height = Some(fraction);
+        self
+    }
+
+    #[track_caller]
+    pub fn build(self, ui: &mut Ui, content: impl FnOnce(&mut Ui)) {
+        let caller = Location::caller().into();
+        ui.render_object(caller, self, content);
+    }
+}
+
+impl RenderObject<FractionallySized> for FractionallySized {
+    type Action = ();
+
+    fn create(props: FractionallySized) -> Self {
+        props
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, props: FractionallySized) {
+        if *self != props {
+            *self = props;
+            ctx.request_layout();
+        }
+    }
+}
+
+impl RenderObjectInterface for FractionallySized {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, children: &mut Children) {
+        for child in children {
+            child.event(ctx, event);
+        }
+    }
+
+    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle) {}
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        children: &mut Children,
+    ) -> Size {
+        bc.debug_check("FractionallySized");
+        let child_bc = bc.fraction(self.width, self.height);
+        let size = children.layout_stacked(ctx, &child_bc);
+        bc.constrain(size)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, children: &mut Children) {
+        for child in children {
+            child.paint(ctx);
+        }
+    }
+}
@@ -0,0 +1,286 @@
+//! A dial showing a value within a range.
+
+use crate::{
+    animation::Easing,
+    kurbo::{Arc, Circle, Line, Point, Vec2},
+    object::prelude::*,
+    piet::{Color, FontFamily, PietTextLayout, Text, TextAttribute, TextLayout, TextLayoutBuilder},
+};
+use std::{f64::consts::PI, ops::RangeInclusive, time::Duration};
+
+/// The angle of the start of the range, at the bottom left.
+const START_ANGLE: f64 = 0.75 * PI;
+/// The angle the whole range spans, clockwise over the top.
+const SWEEP_ANGLE: f64 = 1.5 * PI;
+const TRACK_WIDTH: f64 = 10.0;
+const BAND_WIDTH: f64 = 4.0;
+const BAND_GAP: f64 = 2.0;
+/// The size of a gauge without size constraints.
+const DEFAULT_SIZE: f64 = 120.0;
+
+/// A circular dial with an arc and a needle at the value, for monitoring
+/// values like load or temperature.
+///
+/// Color bands mark parts of the range, the arc takes the color of the
+/// band the value is in.
+///
+/// ```ignore
+/// Gauge::new(cpu_load)
+///     .range(0.0..=100.0)
+///     .band(70.0..=90.0, Color::rgb8(0xf5, 0x9e, 0x0b))
+///     .band(90.0..=100.0, Color::rgb8(0xef, 0x44, 0x44))
+///     .label("CPU %")
+///     .animated(Duration::from_millis(300))
+///     .build(ui);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gauge {
+    value: f64,
+    range: RangeInclusive<f64>,
+    bands: Vec<(RangeInclusive<f64>, Color)>,
+    label: Option<String>,
+    decimals: usize,
+    animation: Option<Duration>,
+}
+
+impl Properties for Gauge {
+    type Object = GaugeObject;
+}
+
+impl Gauge {
+    pub fn new(value: f64) -> Self {
+        Gauge {
+            value,
+            range: 0.0..=1.0,
+            bands: Vec::new(),
+            label: None,
+            decimals: 0,
+            animation: None,
+        }
+    }
+
+    /// The values covered by the dial, values outside are shown at its ends.
+    ///
+    /// The default value is `0.0..=1.0`.
+    pub fn range(mut self, range: RangeInclusive<f64>) -> Self {
+        self.range = range;
+        self
+    }
+
+    /// Mark `range` with `color` around the dial.
+    ///
+    /// Later bands are painted over earlier ones.
+    pub fn band(mut self, range: RangeInclusive<f64>, color: Color) -> Self {
+        self.bands.push((range, color));
+        self
+    }
+
+    /// A caption shown below the value.
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// The number of decimals the value is shown with.
+    ///
+    /// The default value is `0`.
+    pub fn decimals(mut self, decimals: usize) -> Self {
+        self.decimals = decimals;
+        self
+    }
+
+    /// Move the needle to a new value over `duration`, instead of jumping to it.
+    pub fn animated(mut self, duration: Duration) -> Self {
+        self.animation = Some(duration);
+        self
+    }
+
+    #[track_caller]
+    pub fn build(mut self, ui: &mut Ui) {
+        let caller = Location::caller().into();
+        if let Some(duration) = self.animation {
+            self.value = ui.animate(self.value, duration, Easing::EaseOut);
+        }
+        ui.render_object(caller, self, |_| {});
+    }
+}
+
+/// The position of `value` in `range`, from `0.0` to `1.0`.
+fn fraction(value: f64, range: &RangeInclusive<f64>) -> f64 {
+    let span = range.end() - range.start();
+    if span <= 0.0 || value.is_nan() {
+        return 0.0;
+    }
+    ((value - range.start()) / span).clamp(0.0, 1.0)
+}
+
+pub struct GaugeObject {
+    props: Gauge,
+    value_layout: Option<PietTextLayout>,
+    label_layout: Option<PietTextLayout>,
+}
+
+impl GaugeObject {
+    fn angle(&self, value: f64) -> f64 {
+        START_ANGLE + fraction(value, &self.props.range) * SWEEP_ANGLE
+    }
+
+    /// The color of the last band containing the value.
+    fn value_color(&self) -> Option<Color> {
+        let value = self.props.value;
+        self.props
+            .bands
+            .iter()
+            .rev()
+            .find(|(range, _)| range.contains(&value))
+            .map(|(_, color)| color.clone())
+    }
+}
+
+impl RenderObject<Gauge> for GaugeObject {
+    type Action = ();
+
+    fn create(props: Gauge) -> Self {
+        GaugeObject {
+            props,
+            value_layout: None,
+            label_layout: None,
+        }
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, props: Gauge) {
+        if self.props != props {
+            if self.props.label != props.label {
+                self.label_layout = None;
+            }
+            self.props = props;
+            self.value_layout = None;
+            ctx.request_layout();
+        }
+    }
+}
+
+impl RenderObjectInterface for GaugeObject {
+    fn event(&mut self, _ctx: &mut EventCtx, _event: &Event, _children: &mut Children) {}
+
+    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle) {}
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        _children: &mut Children,
+    ) -> Size {
+        bc.debug_check("Gauge");
+
+        let color = ctx.theme().colors.text.clone();
+        if self.value_layout.is_none() {
+            let text = format!("{:.*}", self.props.decimals, self.props.value);
+            let layout = ctx
+                .text()
+                .new_text_layout(text)
+                .font(FontFamily::SYSTEM_UI, 18.0)
+                .default_attribute(TextAttribute::TextColor(color.clone()))
+                .build()
+                .unwrap();
+            self.value_layout = Some(layout);
+        }
+        if self.label_layout.is_none() {
+            if let Some(label) = &self.props.label {
+                let layout = ctx
+                    .text()
+                    .new_text_layout(label.clone())
+                    .font(FontFamily::SYSTEM_UI, 11.0)
+                    .default_attribute(TextAttribute::TextColor(color))
+                    .build()
+                    .unwrap();
+                self.label_layout = Some(layout);
+            }
+        }
+
+        let max = bc.max();
+        let side = match (max.width.is_finite(), max.height.is_finite()) {
+            (true, true) => max.width.min(max.height),
+            (true, false) => max.width,
+            (false, true) => max.height,
+            (false, false) => DEFAULT_SIZE,
+        };
+        bc.constrain(Size::new(side, side))
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, _children: &mut Children) {
+        let size = ctx.size();
+        let theme = ctx.theme();
+        let track = theme.colors.panel.clone();
+        let needle = theme.colors.text.clone();
+        let arc_color = self
+            .value_color()
+            .unwrap_or_else(|| theme.colors.accent.clone());
+
+        let center = size.to_rect().center();
+        let outer = size.width.min(size.height) / 2.0;
+        let band_radius = outer - BAND_WIDTH / 2.0;
+        let track_radius = outer - BAND_WIDTH - BAND_GAP - TRACK_WIDTH / 2.0;
+        if track_radius <= 0.0 {
+            return;
+        }
+        let arc = |radius: f64, start: f64, end: f64| Arc {
+            center,
+            radii: Vec2::new(radius, radius),
+            start_angle: start,
+            sweep_angle: end - start,
+            x_rotation: 0.0,
+        };
+
+        ctx.stroke(
+            arc(track_radius, START_ANGLE, START_ANGLE + SWEEP_ANGLE),
+            &track,
+            TRACK_WIDTH,
+        );
+        for (range, color) in &self.props.bands {
+            let band = arc(
+                band_radius,
+                self.angle(*range.start()),
+                self.angle(*range.end()),
+            );
+            ctx.stroke(band, color, BAND_WIDTH);
+        }
+        let angle = self.angle(self.props.value);
+        if angle > START_ANGLE {
+            ctx.stroke(
+                arc(track_radius, START_ANGLE, angle),
+                &arc_color,
+                TRACK_WIDTH,
+            );
+        }
+
+        let tip = center + Vec2::from_angle(angle) * (track_radius - TRACK_WIDTH / 2.0);
+        ctx.stroke(Line::new(center, tip), &needle, 2.0);
+        ctx.fill(Circle::new(center, 4.0), &needle);
+
+        // The value and label sit in the open bottom of the dial.
+        let mut y = center.y + track_radius * 0.35;
+        if let Some(layout) = &self.value_layout {
+            let text_size = layout.size();
+            ctx.draw_text(layout, Point::new(center.x - text_size.width / 2.0, y));
+            y += text_size.height;
+        }
+        if let Some(layout) = &self.label_layout {
+            let text_size = layout.size();
+            ctx.draw_text(layout, Point::new(center.x - text_size.width / 2.0, y));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fraction_of_range() {
+        assert_eq!(fraction(50.0, &(0.0..=100.0)), 0.5);
+        assert_eq!(fraction(-10.0, &(0.0..=100.0)), 0.0);
+        assert_eq!(fraction(150.0, &(0.0..=100.0)), 1.0);
+        assert_eq!(fraction(1.0, &(1.0..=1.0)), 0.0);
+    }
+}
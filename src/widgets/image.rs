@@ -0,0 +1,499 @@
+//! A widget that displays a bitmap, and loading bitmaps off the UI thread.
+
+use crate::{
+    kurbo::Rect,
+    object::prelude::*,
+    piet::{ImageBuf, ImageFormat, InterpolationMode, PietImage},
+    task::Task,
+};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fs,
+    hash::{Hash, Hasher},
+    io,
+    path::PathBuf,
+    sync::Arc,
+};
+
+/// How an image is scaled into the size of its widget.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImageFit {
+    /// Scaled as large as possible while staying within the widget, keeping the aspect ratio.
+    Contain,
+    /// Scaled as small as possible while covering the widget, keeping the aspect ratio.
+    /// The image is clipped.
+    Cover,
+    /// Stretched to the size of the widget.
+    Fill,
+}
+
+impl ImageFit {
+    /// The rect an image of `image` size is painted to in a widget of `size`.
+    fn rect(self, image: Size, size: Size) -> Rect {
+        if image.is_empty() {
+            return Rect::ZERO;
+        }
+        let scale_x = size.width / image.width;
+        let scale_y = size.height / image.height;
+        let scale = match self {
+            ImageFit::Contain => scale_x.min(scale_y),
+            ImageFit::Cover => scale_x.max(scale_y),
+            ImageFit::Fill => return size.to_rect(),
+        };
+        let scaled = image * scale;
+        let origin = (
+            (size.width - scaled.width) / 2.0,
+            (size.height - scaled.height) / 2.0,
+        );
+        Rect::from_origin_size(origin, scaled)
+    }
+}
+
+/// One image in several pixel densities, so that it stays sharp on high
+/// density screens.
+///
+/// Every image is made for a scale, the number of physical pixels per
+/// logical pixel, like `2.0` for a `@2x` asset. It is shown at its size in
+/// pixels divided by its scale, which should be the same for all images.
+///
+/// ```ignore
+/// let icon = ImageSet::new(icon_1x).with(2.0, icon_2x);
+/// Image::new(icon).build(ui);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ImageSet {
+    /// The images with their scales, ordered by scale.
+    images: Vec<(f64, ImageBuf)>,
+}
+
+impl ImageSet {
+    /// A set with `image` for screens with one physical pixel per logical pixel.
+    pub fn new(image: ImageBuf) -> Self {
+        Self::at_scale(1.0, image)
+    }
+
+    /// A set with `image` made for `scale`.
+    pub fn at_scale(scale: f64, image: ImageBuf) -> Self {
+        ImageSet {
+            images: vec![(scale, image)],
+        }
+    }
+
+    /// Add `image` made for `scale`.
+    pub fn with(mut self, scale: f64, image: ImageBuf) -> Self {
+        let index = self.images.partition_point(|(other, _)| *other < scale);
+        self.images.insert(index, (scale, image));
+        self
+    }
+
+    /// The size in logical pixels.
+    pub fn size(&self) -> Size {
+        let (scale, image) = self.pick(f64::INFINITY);
+        image.size() / scale
+    }
+
+    /// The image to show on a screen with `scale`, and the scale it was made
+    /// for: the least dense one that is at least as dense as the screen, or
+    /// the densest one.
+    pub fn pick(&self, scale: f64) -> (f64, &ImageBuf) {
+        let (image_scale, image) = self
+            .images
+            .iter()
+            .find(|(image_scale, _)| *image_scale >= scale - 1e-6)
+            .or_else(|| self.images.last())
+            .expect("an image set has at least one image");
+        (*image_scale, image)
+    }
+
+    pub(crate) fn same(&self, other: &ImageSet) -> bool {
+        self.images.len() == other.images.len()
+            && self.images.iter().zip(&other.images).all(
+                |((scale, image), (other_scale, other_image))| {
+                    scale == other_scale && same_image(image, other_image)
+                },
+            )
+    }
+}
+
+impl From<ImageBuf> for ImageSet {
+    fn from(image: ImageBuf) -> Self {
+        ImageSet::new(image)
+    }
+}
+
+/// A widget that displays a decoded bitmap.
+///
+/// The widget prefers the size of the image in logical pixels. Of an
+/// [`ImageSet`], it shows the image that matches the scale of the window.
+/// Use [`load`] to decode images from files or bytes.
+pub struct Image {
+    image: ImageSet,
+    fit: ImageFit,
+}
+
+impl Properties for Image {
+    type Object = ImageObject;
+}
+
+impl Image {
+    pub fn new(image: impl Into<ImageSet>) -> Self {
+        Image {
+            image: image.into(),
+            fit: ImageFit::Contain,
+        }
+    }
+
+    /// How the image is scaled into the size of the widget.
+    ///
+    /// The default value is [`ImageFit::Contain`].
+    pub fn fit(mut self, fit: ImageFit) -> Self {
+        self.fit = fit;
+        self
+    }
+
+    #[track_caller]
+    pub fn build(self, ui: &mut Ui) {
+        let caller = Location::caller().into();
+        ui.render_object(caller, self, |_| {});
+    }
+}
+
+pub struct ImageObject {
+    image: ImageSet,
+    fit: ImageFit,
+    /// The image uploaded to the render context with the scale it was
+    /// picked for, created when painting.
+    cached: Option<(f64, PietImage)>,
+}
+
+impl RenderObject<Image> for ImageObject {
+    type Action = ();
+
+    fn create(props: Image) -> Self {
+        ImageObject {
+            image: props.image,
+            fit: props.fit,
+            cached: None,
+        }
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, props: Image) {
+        if !self.image.same(&props.image) {
+            if self.image.size() != props.image.size() {
+                ctx.request_layout();
+            }
+            self.image = props.image;
+            self.cached = None;
+            ctx.request_paint();
+        }
+        if self.fit != props.fit {
+            self.fit = props.fit;
+            ctx.request_paint();
+        }
+    }
+}
+
+impl RenderObjectInterface for ImageObject {
+    fn event(&mut self, _ctx: &mut EventCtx, _event: &Event, _children: &mut Children) {}
+
+    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle) {}
+
+    fn layout(
+        &mut self,
+        _ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        _children: &mut Children,
+    ) -> Size {
+        bc.debug_check("Image");
+        bc.constrain(self.image.size())
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, _children: &mut Children) {
+        let size = ctx.size();
+        let rect = self.fit.rect(self.image.size(), size);
+        if rect.area() == 0.0 {
+            return;
+        }
+        let (scale, image) = self.image.pick(ctx.scale().x());
+        if self.cached.as_ref().map(|(cached, _)| *cached) != Some(scale) {
+            match ctx.render_ctx.make_image(
+                image.width(),
+                image.height(),
+                image.raw_pixels(),
+                image.format(),
+            ) {
+                Ok(image) => self.cached = Some((scale, image)),
+                Err(err) => {
+                    log::warn!("Failed to create image: {}", err);
+                    return;
+                }
+            }
+        }
+        if let Some((_, image)) = &self.cached {
+            ctx.with_save(|ctx| {
+                ctx.clip(size.to_rect());
+                ctx.render_ctx
+                    .draw_image(image, rect, InterpolationMode::Bilinear);
+            });
+        }
+    }
+}
+
+pub(crate) fn same_image(a: &ImageBuf, b: &ImageBuf) -> bool {
+    Arc::ptr_eq(&a.raw_pixels_shared(), &b.raw_pixels_shared())
+        && a.size() == b.size()
+        && a.format() == b.format()
+}
+
+/// Where an image is loaded from, see [`load`].
+///
+/// Bytes are identified by their address, so the same bytes should be
+/// passed to every build pass, like the ones from `include_bytes!`.
+#[derive(Debug, Clone)]
+pub enum ImageSource {
+    Path(PathBuf),
+    Bytes(Arc<[u8]>),
+    Static(&'static [u8]),
+}
+
+impl ImageSource {
+    fn key(&self) -> Result<&PathBuf, (*const u8, usize)> {
+        match self {
+            ImageSource::Path(path) => Ok(path),
+            ImageSource::Bytes(bytes) => Err((bytes.as_ptr(), bytes.len())),
+            ImageSource::Static(bytes) => Err((bytes.as_ptr(), bytes.len())),
+        }
+    }
+
+    fn read(&self) -> io::Result<Vec<u8>> {
+        match self {
+            ImageSource::Path(path) => fs::read(path),
+            ImageSource::Bytes(bytes) => Ok(bytes.to_vec()),
+            ImageSource::Static(bytes) => Ok(bytes.to_vec()),
+        }
+    }
+}
+
+impl PartialEq for ImageSource {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for ImageSource {}
+
+impl Hash for ImageSource {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key().hash(state);
+    }
+}
+
+impl From<PathBuf> for ImageSource {
+    fn from(path: PathBuf) -> Self {
+        ImageSource::Path(path)
+    }
+}
+
+impl From<&'static [u8]> for ImageSource {
+    fn from(bytes: &'static [u8]) -> Self {
+        ImageSource::Static(bytes)
+    }
+}
+
+enum Loaded {
+    Loading(Task<Result<ImageBuf, (io::ErrorKind, String)>>),
+    Done(Result<ImageBuf, (io::ErrorKind, String)>),
+}
+
+thread_local! {
+    /// The images loaded on this thread, they stay decoded until [`forget`] is called.
+    static IMAGES: RefCell<HashMap<ImageSource, Loaded>> = RefCell::new(HashMap::new());
+}
+
+/// Decode the image from `source` off the UI thread.
+///
+/// Returns `None` while the image is loading, the window is rebuilt once it
+/// is done. Decoded images are cached by their source, so loading the same
+/// source again, also in later build passes, does not decode it again.
+///
+/// The binary [Netpbm] formats PPM (`P6`) and PAM (`P7`) can be decoded.
+/// Images in other formats can be decoded by the app and shown with [`Image::new`].
+///
+/// ```ignore
+/// match image::load(ui, &ImageSource::Static(include_bytes!("logo.ppm"))) {
+///     Some(Ok(logo)) => Image::new(logo).build(ui),
+///     Some(Err(err)) => Label::new(err.to_string()).build(ui),
+///     None => Label::new("Loading").build(ui),
+/// }
+/// ```
+///
+/// [Netpbm]: https://netpbm.sourceforge.net/doc/
+pub fn load(ui: &mut Ui, source: &ImageSource) -> Option<io::Result<ImageBuf>> {
+    IMAGES.with(|images| {
+        let mut images = images.borrow_mut();
+        let loaded = images.entry(source.clone()).or_insert_with(|| {
+            let source = source.clone();
+            Loaded::Loading(ui.spawn(async move {
+                source
+                    .read()
+                    .and_then(|bytes| decode(&bytes))
+                    .map_err(|err| (err.kind(), err.to_string()))
+            }))
+        });
+        if let Loaded::Loading(task) = loaded {
+            *loaded = Loaded::Done(task.take()?);
+        }
+        match loaded {
+            Loaded::Done(Ok(image)) => Some(Ok(image.clone())),
+            Loaded::Done(Err((kind, message))) => Some(Err(io::Error::new(*kind, message.clone()))),
+            Loaded::Loading(_) => None,
+        }
+    })
+}
+
+/// Drop the cached image of `source`, it is decoded again the next time it is loaded.
+pub fn forget(source: &ImageSource) {
+    IMAGES.with(|images| images.borrow_mut().remove(source));
+}
+
+/// Decode a binary PPM or PAM image.
+pub fn decode(data: &[u8]) -> io::Result<ImageBuf> {
+    decode_netpbm(data).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            "Not a binary PPM or PAM image with 8 bits per channel",
+        )
+    })
+}
+
+fn decode_netpbm(data: &[u8]) -> Option<ImageBuf> {
+    match data.get(..2)? {
+        b"P6" => decode_ppm(data),
+        b"P7" => decode_pam(data),
+        _ => None,
+    }
+}
+
+fn decode_ppm(data: &[u8]) -> Option<ImageBuf> {
+    // The header is the magic number and three numbers, separated by whitespace
+    // and comments, followed by a single whitespace character.
+    let mut numbers = [0; 3];
+    let mut pos = 2;
+    for number in &mut numbers {
+        loop {
+            match data.get(pos)? {
+                b'#' => {
+                    while *data.get(pos)? != b'\n' {
+                        pos += 1;
+                    }
+                }
+                c if c.is_ascii_whitespace() => pos += 1,
+                _ => break,
+            }
+        }
+        let start = pos;
+        while data.get(pos)?.is_ascii_digit() {
+            pos += 1;
+        }
+        *number = std::str::from_utf8(&data[start..pos]).ok()?.parse().ok()?;
+    }
+    let [width, height, maxval] = numbers;
+    if maxval != 255 {
+        return None;
+    }
+    let pixels = data.get(pos + 1..)?.get(..width * height * 3)?;
+    Some(ImageBuf::from_raw(
+        pixels.to_vec(),
+        ImageFormat::Rgb,
+        width,
+        height,
+    ))
+}
+
+fn decode_pam(data: &[u8]) -> Option<ImageBuf> {
+    const END: &[u8] = b"ENDHDR\n";
+    let header_len = data.windows(END.len()).position(|w| w == END)?;
+    let header = std::str::from_utf8(&data[..header_len]).ok()?;
+    let (mut width, mut height, mut depth, mut maxval) = (None, None, None, None);
+    for line in header.lines().skip(1) {
+        let mut parts = line.split_whitespace();
+        let (key, value) = (parts.next(), parts.next());
+        let value = || value?.parse::<usize>().ok();
+        match key {
+            Some("WIDTH") => width = value(),
+            Some("HEIGHT") => height = value(),
+            Some("DEPTH") => depth = value(),
+            Some("MAXVAL") => maxval = value(),
+            _ => {}
+        }
+    }
+    if maxval? != 255 {
+        return None;
+    }
+    let format = match depth? {
+        1 => ImageFormat::Grayscale,
+        3 => ImageFormat::Rgb,
+        4 => ImageFormat::RgbaSeparate,
+        _ => return None,
+    };
+    let (width, height) = (width?, height?);
+    let len = width * height * format.bytes_per_pixel();
+    let pixels = data[header_len + END.len()..].get(..len)?;
+    Some(ImageBuf::from_raw(pixels.to_vec(), format, width, height))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fit_rect() {
+        let image = Size::new(20.0, 10.0);
+        let size = Size::new(40.0, 40.0);
+        assert_eq!(
+            ImageFit::Contain.rect(image, size),
+            Rect::new(0.0, 10.0, 40.0, 30.0)
+        );
+        assert_eq!(
+            ImageFit::Cover.rect(image, size),
+            Rect::new(-20.0, 0.0, 60.0, 40.0)
+        );
+        assert_eq!(ImageFit::Fill.rect(image, size), size.to_rect());
+    }
+
+    #[test]
+    fn decode_formats() {
+        let ppm = b"P6\n# comment\n2 1\n255\n\x01\x02\x03\x04\x05\x06";
+        let image = decode(ppm).unwrap();
+        assert_eq!((image.width(), image.height()), (2, 1));
+        assert_eq!(image.format(), ImageFormat::Rgb);
+        assert_eq!(image.raw_pixels(), [1, 2, 3, 4, 5, 6]);
+
+        let pam = b"P7\nWIDTH 1\nHEIGHT 1\nDEPTH 4\nMAXVAL 255\nTUPLTYPE RGB_ALPHA\nENDHDR\n\x01\x02\x03\x04";
+        let image = decode(pam).unwrap();
+        assert_eq!(image.format(), ImageFormat::RgbaSeparate);
+        assert_eq!(image.raw_pixels(), [1, 2, 3, 4]);
+
+        assert!(decode(b"P6\n2 1\n255\n\x01").is_err());
+        assert!(decode(b"\x89PNG").is_err());
+    }
+
+    #[test]
+    fn pick_from_set() {
+        let image =
+            |size| ImageBuf::from_raw(vec![0; size * size * 3], ImageFormat::Rgb, size, size);
+        let set = ImageSet::new(image(10))
+            .with(3.0, image(30))
+            .with(2.0, image(20));
+        assert_eq!(set.size(), Size::new(10.0, 10.0));
+        assert_eq!(set.pick(1.0).0, 1.0);
+        assert_eq!(set.pick(1.25).0, 2.0);
+        assert_eq!(set.pick(2.0).0, 2.0);
+        assert_eq!(set.pick(4.0).0, 3.0);
+        assert_eq!(
+            ImageSet::at_scale(2.0, image(20)).size(),
+            Size::new(10.0, 10.0)
+        );
+    }
+}
@@ -1,10 +1,13 @@
 //! A label widget.
 
 use crate::{
+    environment::LayoutDirection,
     kurbo::Point,
     object::prelude::*,
     piet::{Color, TextAlignment},
-    text::{FontDescriptor, TextLayout},
+    text::{FontDescriptor, FontStyle, FontWeight, TextLayout},
+    theme::Theme,
+    widgets::SelectableLabel,
 };
 
 // added padding between the edges of the widget and the text.
@@ -20,6 +23,15 @@ const LABEL_X_PADDING: f64 = 2.0;
 pub struct Label {
     layout: TextLayout<String>,
     line_break_mode: LineBreaking,
+    /// Overrides the theme.
+    text_color: Option<Color>,
+    text_size: Option<f64>,
+    font: Option<FontDescriptor>,
+    /// Override the weight and style of the font.
+    weight: Option<FontWeight>,
+    style: Option<FontStyle>,
+    underline: bool,
+    strikethrough: bool,
 }
 
 impl Properties for Label {
@@ -43,21 +55,51 @@ impl Label {
         Self {
             layout: TextLayout::from_text(text),
             line_break_mode: LineBreaking::Overflow,
+            text_color: None,
+            text_size: None,
+            font: None,
+            weight: None,
+            style: None,
+            underline: false,
+            strikethrough: false,
         }
     }
 
     pub fn text_color(mut self, color: Color) -> Self {
-        self.layout.set_text_color(color);
+        self.text_color = Some(color);
         self
     }
 
     pub fn text_size(mut self, size: f64) -> Self {
-        self.layout.set_text_size(size);
+        self.text_size = Some(size);
         self
     }
 
     pub fn font(mut self, font: FontDescriptor) -> Self {
-        self.layout.set_font(font);
+        self.font = Some(font);
+        self
+    }
+
+    /// Set the text in `weight`, like [`FontWeight::BOLD`], instead of the
+    /// weight of the font.
+    pub fn weight(mut self, weight: FontWeight) -> Self {
+        self.weight = Some(weight);
+        self
+    }
+
+    /// Set the text in italics.
+    pub fn italic(mut self) -> Self {
+        self.style = Some(FontStyle::Italic);
+        self
+    }
+
+    pub fn underline(mut self, underline: bool) -> Self {
+        self.underline = underline;
+        self
+    }
+
+    pub fn strikethrough(mut self, strikethrough: bool) -> Self {
+        self.strikethrough = strikethrough;
         self
     }
 
@@ -79,6 +121,36 @@ impl Label {
 }
 
 impl Label {
+    /// The text of the label.
+    pub(crate) fn text(&self) -> &str {
+        self.layout.text().map_or("", |text| text.as_str())
+    }
+
+    /// Make the text of the label selectable, see [`SelectableLabel`].
+    ///
+    /// [`SelectableLabel`]: crate::widgets::SelectableLabel
+    pub fn selectable(self) -> SelectableLabel {
+        SelectableLabel::new(self)
+    }
+
+    /// The laid out text of the label.
+    pub(crate) fn text_layout(&self) -> &TextLayout<String> {
+        &self.layout
+    }
+
+    /// Where the text starts in a label of `size`.
+    pub(crate) fn text_origin(&self, direction: LayoutDirection, size: Size) -> Point {
+        // In a label wider than its text, the text starts at the leading edge.
+        let x = match direction {
+            LayoutDirection::LeftToRight => LABEL_X_PADDING,
+            LayoutDirection::RightToLeft => {
+                let text_width = self.layout.layout_metrics().size.width;
+                size.width - text_width - LABEL_X_PADDING
+            }
+        };
+        Point::new(x, 0.0)
+    }
+
     /// Draw this label's text at the provided `Point`, without internal padding.
     ///
     /// This is a convenience for widgets that want to use Label as a way
@@ -99,6 +171,46 @@ impl Label {
         }
     }
 
+    /// The width to wrap the text at in a label `width` wide.
+    fn wrap_width(&self, width: f64) -> f64 {
+        match self.line_break_mode {
+            LineBreaking::WordWrap => width - LABEL_X_PADDING * 2.0,
+            _ => f64::INFINITY,
+        }
+    }
+
+    /// Apply the text color and font of the label, or of `theme`, to `layout`.
+    fn apply_style(&self, layout: &mut TextLayout<String>, theme: &Theme) {
+        let color = self.text_color.as_ref().unwrap_or(&theme.colors.text);
+        layout.set_text_color(color.clone());
+        let mut font = self.font.as_ref().unwrap_or(&theme.font).clone();
+        if let Some(weight) = self.weight {
+            font = font.with_weight(weight);
+        }
+        if let Some(style) = self.style {
+            font = font.with_style(style);
+        }
+        // Setting the font resets the size.
+        layout.set_font(font);
+        layout.set_underline(self.underline);
+        layout.set_strikethrough(self.strikethrough);
+        layout.set_font_fallback(theme.font_fallback.clone());
+        if let Some(size) = self.text_size {
+            layout.set_text_size(size);
+        }
+    }
+
+    /// The size of the text wrapped at `wrap_width`, leaving the layout of
+    /// the label as it is.
+    fn measure(&self, ctx: &mut LayoutCtx, wrap_width: f64) -> Size {
+        let mut layout = self.layout.clone();
+        self.apply_style(&mut layout, ctx.theme());
+        layout.set_wrap_width(wrap_width);
+        let env = ctx.env().clone();
+        layout.rebuild_if_needed(ctx.text(), &env);
+        layout.layout_metrics().size
+    }
+
     #[allow(dead_code)]
     // TODO: Find out what this was good for.
     /// Return the offset of the first baseline relative to the bottom of the widget.
@@ -120,6 +232,25 @@ impl RenderObject<Label> for Label {
             ctx.request_layout();
             self.layout = props.layout;
         }
+        if (&self.text_color, self.text_size, &self.font)
+            != (&props.text_color, props.text_size, &props.font)
+            || (self.weight, self.style, self.underline, self.strikethrough)
+                != (
+                    props.weight,
+                    props.style,
+                    props.underline,
+                    props.strikethrough,
+                )
+        {
+            ctx.request_layout();
+            self.text_color = props.text_color;
+            self.text_size = props.text_size;
+            self.font = props.font;
+            self.weight = props.weight;
+            self.style = props.style;
+            self.underline = props.underline;
+            self.strikethrough = props.strikethrough;
+        }
         if self.layout.layout().is_none() {
             ctx.request_layout();
         }
@@ -139,14 +270,17 @@ impl RenderObjectInterface for Label {
     ) -> Size {
         bc.debug_check("Label");
 
-        let width = match self.line_break_mode {
-            LineBreaking::WordWrap => bc.max().width - LABEL_X_PADDING * 2.0,
-            _ => f64::INFINITY,
-        };
+        if self.line_break_mode == LineBreaking::WordWrap && !bc.max().width.is_finite() {
+            ctx.report_layout_issue("Word wrapping label without a max width never wraps");
+        }
+        let width = self.wrap_width(bc.max().width);
 
-        self.layout.set_wrap_width(width);
+        let mut layout = std::mem::take(&mut self.layout);
+        self.apply_style(&mut layout, ctx.theme());
+        layout.set_wrap_width(width);
         let env = ctx.env().clone();
-        self.layout.rebuild_if_needed(ctx.text(), &env);
+        layout.rebuild_if_needed(ctx.text(), &env);
+        self.layout = layout;
 
         let text_metrics = self.layout.layout_metrics();
         ctx.set_baseline_offset(text_metrics.size.height - text_metrics.first_baseline);
@@ -156,9 +290,48 @@ impl RenderObjectInterface for Label {
         ))
     }
 
+    fn compute_min_intrinsic_width(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        _height: f64,
+        _children: &mut Children,
+    ) -> f64 {
+        // A wrapping label can break every word onto its own line.
+        let width = self.wrap_width(0.0);
+        self.measure(ctx, width).width + 2. * LABEL_X_PADDING
+    }
+
+    fn compute_max_intrinsic_width(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        _height: f64,
+        _children: &mut Children,
+    ) -> f64 {
+        self.measure(ctx, f64::INFINITY).width + 2. * LABEL_X_PADDING
+    }
+
+    fn compute_min_intrinsic_height(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        width: f64,
+        _children: &mut Children,
+    ) -> f64 {
+        let width = self.wrap_width(width);
+        self.measure(ctx, width).height
+    }
+
+    fn compute_max_intrinsic_height(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        width: f64,
+        children: &mut Children,
+    ) -> f64 {
+        self.compute_min_intrinsic_height(ctx, width, children)
+    }
+
     fn paint(&mut self, ctx: &mut PaintCtx, _children: &mut Children) {
-        let origin = Point::new(LABEL_X_PADDING, 0.0);
         let label_size = ctx.size();
+        let origin = self.text_origin(ctx.layout_direction(), label_size);
 
         if self.line_break_mode == LineBreaking::Clip {
             ctx.clip(label_size.to_rect());
@@ -166,3 +339,152 @@ impl RenderObjectInterface for Label {
         self.draw_at(ctx, origin)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        testing::{self, Harness},
+        widgets::Flex,
+    };
+    use std::{cell::Cell, rc::Rc};
+
+    /// The intrinsic sizes of the content of a [`Measure`].
+    #[derive(Clone, Copy, Default)]
+    struct Intrinsics {
+        min_width: f64,
+        max_width: f64,
+        /// The height at the minimum width.
+        narrow_height: f64,
+        /// The height at an unbounded width.
+        wide_height: f64,
+    }
+
+    /// Lays out its content as it is, after querying its intrinsic sizes.
+    struct Measure(Rc<Cell<Intrinsics>>);
+
+    impl Properties for Measure {
+        type Object = Measure;
+    }
+
+    impl RenderObject<Measure> for Measure {
+        type Action = ();
+
+        fn create(props: Measure) -> Self {
+            props
+        }
+
+        fn update(&mut self, _ctx: &mut UpdateCtx, props: Measure) {
+            *self = props;
+        }
+    }
+
+    impl RenderObjectInterface for Measure {
+        fn event(&mut self, _ctx: &mut EventCtx, _event: &Event, _children: &mut Children) {}
+
+        fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle) {}
+
+        fn layout(
+            &mut self,
+            ctx: &mut LayoutCtx,
+            bc: &BoxConstraints,
+            children: &mut Children,
+        ) -> Size {
+            let min_width = children.min_intrinsic_width(ctx, f64::INFINITY);
+            let max_width = children.max_intrinsic_width(ctx, f64::INFINITY);
+            self.0.set(Intrinsics {
+                min_width,
+                max_width,
+                narrow_height: children.min_intrinsic_height(ctx, min_width),
+                wide_height: children.min_intrinsic_height(ctx, f64::INFINITY),
+            });
+            children.layout_stacked(ctx, bc)
+        }
+
+        fn paint(&mut self, ctx: &mut PaintCtx, children: &mut Children) {
+            for child in children {
+                child.paint(ctx);
+            }
+        }
+    }
+
+    #[test]
+    fn intrinsic_size_of_wrapping_text() {
+        let intrinsics = Rc::new(Cell::new(Intrinsics::default()));
+        let measured = intrinsics.clone();
+        let mut harness = Harness::new(move |ui| {
+            Flex::column().build(ui, |ui| {
+                let caller = Location::caller().into();
+                ui.render_object(caller, Measure(measured.clone()), |ui| {
+                    Label::new("Text that wraps onto several lines")
+                        .line_break_mode(LineBreaking::WordWrap)
+                        .build(ui);
+                });
+            });
+        });
+        let intrinsics = intrinsics.get();
+        assert!(intrinsics.min_width < intrinsics.max_width);
+        assert!(intrinsics.narrow_height > intrinsics.wide_height);
+
+        // Unless it has to, the label does not wrap.
+        let label = testing::assert_exists::<Label>(
+            harness.tree(),
+            testing::with_label("Text that wraps onto several lines"),
+        );
+        let size = harness.window_rect(label).size();
+        assert_eq!(size.width, intrinsics.max_width);
+        assert_eq!(size.height, intrinsics.wide_height);
+    }
+
+    /// The number of pixels of the label with `text` that differ from the background.
+    fn ink(harness: &mut Harness, text: &str) -> usize {
+        let label = testing::assert_exists::<Label>(harness.tree(), testing::with_label(text));
+        let rect = harness.window_rect(label).expand();
+        let snapshot = harness.snapshot();
+        let background = snapshot.pixel(0, snapshot.height() - 1);
+        let (x0, x1) = (rect.x0 as usize, rect.x1 as usize);
+        (rect.y0 as usize..rect.y1 as usize)
+            .flat_map(|y| (x0..x1).map(move |x| (x, y)))
+            .filter(|&(x, y)| snapshot.pixel(x, y) != background)
+            .count()
+    }
+
+    #[test]
+    fn styles() {
+        let style = Rc::new(Cell::new(0));
+        let styled = style.clone();
+        let mut harness = Harness::new(move |ui| {
+            Flex::column().build(ui, |ui| {
+                let label = Label::new("Styled");
+                let label = match styled.get() {
+                    0 => label,
+                    1 => label.weight(FontWeight::BOLD),
+                    2 => label.underline(true),
+                    3 => label.strikethrough(true),
+                    _ => label.text_size(30.0),
+                };
+                label.build(ui);
+            });
+        });
+        let plain = ink(&mut harness, "Styled");
+        let label = testing::assert_exists::<Label>(harness.tree(), testing::with_label("Styled"));
+        let height = harness.window_rect(label).height();
+
+        for (index, name) in ["bold", "underlined", "struck through"].iter().enumerate() {
+            style.set(index + 1);
+            harness.rebuild();
+            let ink = ink(&mut harness, "Styled");
+            assert!(
+                ink > plain,
+                "The {} label has {} pixels, the plain one {}",
+                name,
+                ink,
+                plain
+            );
+        }
+
+        style.set(4);
+        harness.rebuild();
+        assert!(harness.window_rect(label).height() > height);
+    }
+}
@@ -0,0 +1,216 @@
+//! A hyperlink, for URLs and in-app navigation.
+
+use crate::{
+    event::{Cursor, MouseButton},
+    kurbo::Point,
+    object::prelude::*,
+    piet::Color,
+    theme::Theme,
+    widgets::Label,
+};
+
+/// Underlined text in the accent color of the theme, which opens a URL or
+/// reports a click when it is clicked.
+///
+/// ```ignore
+/// Link::url("https://github.com/Finnerale/coat").labeled(ui, "Source code");
+/// if Link::new().labeled(ui, "Settings") {
+///     state.page = Page::Settings;
+/// }
+/// ```
+#[derive(Debug, Default, PartialEq)]
+pub struct Link {
+    url: Option<String>,
+    color: Option<Color>,
+}
+
+impl Properties for Link {
+    type Object = LinkObject;
+}
+
+impl Link {
+    /// A link for in-app navigation, which only reports clicks.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A link that opens `url` with the default application of the system,
+    /// see [`Opener`].
+    ///
+    /// [`Opener`]: crate::services::Opener
+    pub fn url(url: impl Into<String>) -> Self {
+        Link {
+            url: Some(url.into()),
+            ..Link::default()
+        }
+    }
+
+    /// Paint the text in `color` instead of the accent color of the theme.
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    /// Build the link with `text`, returns `true` if it was clicked.
+    #[track_caller]
+    pub fn labeled(self, ui: &mut Ui, text: impl Into<String>) -> bool {
+        let caller = Location::caller().into();
+        let color = match &self.color {
+            Some(color) => color.clone(),
+            None => {
+                let theme = ui.provided::<Theme>().unwrap_or_else(|| Theme::fallback());
+                theme.colors.accent.clone()
+            }
+        };
+        ui.render_object(caller, self, |ui| {
+            Label::new(text).text_color(color).underline(true).build(ui);
+        })
+    }
+}
+
+pub struct LinkObject {
+    props: Link,
+    clicked: bool,
+}
+
+impl LinkObject {
+    /// Open the URL of the link, if it has one.
+    fn open(&self, ctx: &EventCtx) {
+        let url = match &self.props.url {
+            Some(url) => url,
+            None => return,
+        };
+        match ctx.services().opener() {
+            Some(opener) => {
+                if let Err(err) = opener.open(url) {
+                    log::warn!("Failed to open {}: {}", url, err);
+                }
+            }
+            None => log::warn!("No opener for the link to {}", url),
+        }
+    }
+}
+
+impl RenderObject<Link> for LinkObject {
+    type Action = bool;
+
+    fn create(props: Link) -> Self {
+        LinkObject {
+            props,
+            clicked: false,
+        }
+    }
+
+    fn update(&mut self, _ctx: &mut UpdateCtx, props: Link) -> bool {
+        // The color is applied to the label, which updates itself.
+        self.props = props;
+
+        let was_clicked = self.clicked;
+        self.clicked = false;
+        was_clicked
+    }
+}
+
+impl RenderObjectInterface for LinkObject {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, children: &mut Children) {
+        match event {
+            Event::MouseDown(mouse) if mouse.button == MouseButton::Left => {
+                ctx.set_active(true);
+            }
+            Event::MouseMove(_) => ctx.set_cursor(&Cursor::OpenHand),
+            Event::MouseUp(mouse) if ctx.is_active() && mouse.button == MouseButton::Left => {
+                ctx.set_active(false);
+                if ctx.is_hot() {
+                    self.open(ctx);
+                    self.clicked = true;
+                    ctx.request_update();
+                    ctx.set_handled();
+                }
+            }
+            _ => {}
+        }
+
+        for child in children {
+            child.event(ctx, event);
+        }
+    }
+
+    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle) {}
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        children: &mut Children,
+    ) -> Size {
+        bc.debug_check("Link");
+        ctx.set_hit_target(true);
+        let mut size = bc.min();
+        for child in &mut *children {
+            size = child.layout(ctx, bc);
+            child.set_origin(ctx, Point::ORIGIN);
+            ctx.set_baseline_offset(child.baseline_offset());
+        }
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, children: &mut Children) {
+        for child in children {
+            child.paint(ctx);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        services::{Opener, Services},
+        state::Mutable,
+        testing::{self, Harness},
+        widgets::Flex,
+    };
+    use std::{cell::RefCell, io, rc::Rc};
+
+    #[derive(Default)]
+    struct FakeOpener(RefCell<Vec<String>>);
+
+    impl Opener for FakeOpener {
+        fn open(&self, target: &str) -> io::Result<()> {
+            self.0.borrow_mut().push(target.to_string());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn click_opens_url_or_reports_click() {
+        let opener = Rc::new(FakeOpener::default());
+        let services = Services::new().with::<dyn Opener>(opener.clone());
+        let mut harness = Harness::new(move |ui| {
+            ui.provide(services.clone(), |ui| {
+                Flex::column().build(ui, |ui| {
+                    Link::url("https://example.com").labeled(ui, "Website");
+                    Mutable::new().use_in(ui, |ui, clicks: &mut usize| {
+                        if Link::new().labeled(ui, "Settings") {
+                            *clicks += 1;
+                        }
+                        Label::new(clicks.to_string()).build(ui);
+                    });
+                });
+            });
+        });
+        assert_eq!(harness.label_texts(), ["Website", "Settings", "0"]);
+
+        let website =
+            testing::assert_exists::<LinkObject>(harness.tree(), testing::with_label("Website"));
+        harness.click_on(website);
+        assert_eq!(*opener.0.borrow(), ["https://example.com"]);
+        assert_eq!(harness.label_texts(), ["Website", "Settings", "0"]);
+
+        let settings =
+            testing::assert_exists::<LinkObject>(harness.tree(), testing::with_label("Settings"));
+        harness.click_on(settings);
+        assert_eq!(opener.0.borrow().len(), 1);
+        assert_eq!(harness.label_texts(), ["Website", "Settings", "1"]);
+    }
+}
@@ -0,0 +1,51 @@
+//! The render object behind `Ui::memoize`.
+
+use crate::object::prelude::*;
+
+/// Remembers the key its content was last built with.
+pub(crate) struct Memo<K> {
+    pub(crate) key: K,
+}
+
+impl<K: 'static> Properties for Memo<K> {
+    type Object = Memo<K>;
+}
+
+impl<K: 'static> RenderObject<Memo<K>> for Memo<K> {
+    type Action = ();
+
+    fn create(props: Memo<K>) -> Self {
+        props
+    }
+
+    fn update(&mut self, _ctx: &mut UpdateCtx, props: Memo<K>) {
+        self.key = props.key;
+    }
+}
+
+impl<K: 'static> RenderObjectInterface for Memo<K> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, children: &mut Children) {
+        for child in children {
+            child.event(ctx, event);
+        }
+    }
+
+    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle) {}
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        children: &mut Children,
+    ) -> Size {
+        bc.debug_check("Memo");
+
+        children.layout_stacked(ctx, bc)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, children: &mut Children) {
+        for child in children {
+            child.paint(ctx);
+        }
+    }
+}
@@ -4,6 +4,12 @@ pub use button::Button;
 pub mod label;
 pub use label::Label;
 
+pub mod selectable_label;
+pub use selectable_label::SelectableLabel;
+
+pub mod link;
+pub use link::Link;
+
 pub mod padding;
 pub use padding::Padding;
 
@@ -15,3 +21,111 @@ pub use flex::Flex;
 
 pub mod textbox;
 pub use textbox::TextBox;
+
+pub mod overlay;
+pub use overlay::Overlay;
+
+pub mod retain;
+pub use retain::Retain;
+
+pub(crate) mod memo;
+
+pub mod repeatable;
+pub use repeatable::{RepeatBehavior, Repeatable};
+
+pub mod context_menu;
+pub use context_menu::ContextMenu;
+
+pub mod drag;
+pub use drag::{DragSource, DropTarget};
+
+pub mod modal;
+pub use modal::Modal;
+
+pub mod command_handler;
+pub use command_handler::CommandHandler;
+
+pub mod image;
+pub use image::{Image, ImageFit, ImageSet, ImageSource};
+
+#[cfg(feature = "svg")]
+pub mod svg;
+#[cfg(feature = "svg")]
+pub use svg::{Svg, SvgDocument};
+
+pub mod canvas;
+pub use canvas::Canvas;
+
+pub mod table;
+pub use table::{Column, ColumnWidth, Table};
+
+pub mod tabs;
+pub use tabs::{Tab, Tabs, TabsAction};
+
+pub mod split;
+pub use split::Split;
+
+pub mod expander;
+pub use expander::Expander;
+
+pub mod code_view;
+#[cfg(feature = "syntect")]
+pub use code_view::SyntectHighlighter;
+pub use code_view::{CodeView, Highlighter};
+
+pub mod rich_label;
+pub use rich_label::RichLabel;
+
+pub mod text_area;
+pub use text_area::TextArea;
+
+pub mod stepper;
+pub use stepper::Stepper;
+
+pub mod toasts;
+pub use toasts::{Toast, Toasts};
+
+pub mod badge;
+pub use badge::{Badge, BadgeCorner};
+
+pub mod charts;
+pub use charts::{BarChart, LineChart, Series};
+
+pub mod gauge;
+pub use gauge::Gauge;
+
+pub mod breadcrumbs;
+pub use breadcrumbs::Breadcrumbs;
+
+pub mod pagination;
+pub use pagination::Pagination;
+
+pub mod autocomplete;
+pub use autocomplete::Autocomplete;
+
+pub mod search_input;
+pub use search_input::{SearchAction, SearchInput};
+
+pub mod zoom_pan;
+pub use zoom_pan::ZoomPan;
+
+pub mod clip;
+pub use clip::Clip;
+
+pub mod transformed;
+pub use transformed::Transformed;
+
+pub mod opacity;
+pub use opacity::Opacity;
+
+pub mod elevation;
+pub use elevation::Elevation;
+
+pub mod visibility;
+pub use visibility::Visibility;
+
+pub mod aspect_ratio;
+pub use aspect_ratio::AspectRatio;
+
+pub mod fractionally_sized;
+pub use fractionally_sized::FractionallySized;
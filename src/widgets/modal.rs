@@ -0,0 +1,73 @@
+//! A dialog that blocks the rest of the window.
+
+use crate::{
+    object::prelude::*,
+    widgets::overlay::{Overlay, Placement},
+};
+
+/// How a [`Modal`] dialog was closed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ModalResult<R> {
+    /// The dialog content returned a result.
+    Closed(R),
+    /// The dialog was dismissed with the escape key.
+    Cancelled,
+}
+
+/// Shows dialog content centered above the dimmed window.
+///
+/// While open, the rest of the window does not receive input
+/// and keyboard focus stays inside of the dialog.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Modal {
+    dismiss_on_escape: bool,
+}
+
+impl Default for Modal {
+    fn default() -> Self {
+        Modal::new()
+    }
+}
+
+impl Modal {
+    pub fn new() -> Self {
+        Modal {
+            dismiss_on_escape: true,
+        }
+    }
+
+    /// Whether pressing escape cancels the dialog.
+    ///
+    /// The default value is `true`.
+    pub fn dismiss_on_escape(mut self, dismiss: bool) -> Self {
+        self.dismiss_on_escape = dismiss;
+        self
+    }
+
+    /// Build the window `content` and, if `open`, the `dialog` above it.
+    ///
+    /// The `dialog` closes by returning a result, which is then returned
+    /// from this method. It is up to the caller to stop passing `open`.
+    #[track_caller]
+    pub fn build<R>(
+        self,
+        ui: &mut Ui,
+        open: bool,
+        content: impl FnOnce(&mut Ui),
+        dialog: impl FnOnce(&mut Ui) -> Option<R>,
+    ) -> Option<ModalResult<R>> {
+        let mut result = None;
+        let cancelled = Overlay::new(Placement::Center)
+            .modal(true)
+            .dismiss_on_click_outside(false)
+            .dismiss_on_escape(self.dismiss_on_escape)
+            .build(ui, open, content, |ui| {
+                result = dialog(ui).map(ModalResult::Closed);
+            });
+
+        if cancelled && result.is_none() {
+            result = Some(ModalResult::Cancelled);
+        }
+        result
+    }
+}
@@ -0,0 +1,130 @@
+//! A container that fades its content.
+
+use crate::object::prelude::*;
+
+/// Paints its content with an opacity, as a whole.
+///
+/// Overlapping parts of the content do not show through each other, like
+/// they would if every child made its colors transparent. Content that is
+/// fully opaque is painted directly, otherwise it is painted into a bitmap
+/// first, so fading large areas for a long time is not free.
+///
+/// ```ignore
+/// let opacity = ui.animate(if visible { 1.0 } else { 0.0 }, FADE, Easing::EaseOut);
+/// Opacity::new(opacity).build(ui, |ui| details(ui, &item));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Opacity {
+    opacity: f64,
+}
+
+impl Properties for Opacity {
+    type Object = Opacity;
+}
+
+impl Opacity {
+    /// `opacity` goes from `0.0` for invisible to `1.0` for opaque.
+    pub fn new(opacity: f64) -> Self {
+        Opacity { opacity }
+    }
+
+    #[track_caller]
+    pub fn build(self, ui: &mut Ui, content: impl FnOnce(&mut Ui)) {
+        let caller = Location::caller().into();
+        ui.render_object(caller, self, content);
+    }
+}
+
+impl RenderObject<Opacity> for Opacity {
+    type Action = ();
+
+    fn create(props: Opacity) -> Self {
+        props
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, props: Opacity) {
+        if *self != props {
+            *self = props;
+            ctx.request_paint();
+        }
+    }
+}
+
+impl RenderObjectInterface for Opacity {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, children: &mut Children) {
+        for child in children {
+            child.event(ctx, event);
+        }
+    }
+
+    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle) {}
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        children: &mut Children,
+    ) -> Size {
+        bc.debug_check("Opacity");
+        let size = children.layout_stacked(ctx, bc);
+        if !children.is_empty() {
+            ctx.set_paint_insets(children[0].compute_parent_paint_insets(size));
+        }
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, children: &mut Children) {
+        ctx.with_opacity(self.opacity, |ctx| {
+            for child in children {
+                child.paint(ctx);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        piet::Color,
+        testing::{Harness, Log, Probe},
+        widgets::Flex,
+    };
+    use std::{cell::Cell, rc::Rc};
+
+    #[test]
+    fn translucent_content_is_painted() {
+        let log = Log::default();
+        let opacity = Rc::new(Cell::new(1.0));
+        let (probes, faded) = (log.clone(), opacity.clone());
+        let mut harness = Harness::new(move |ui| {
+            Flex::column().build(ui, |ui| {
+                Opacity::new(faded.get()).build(ui, |ui| {
+                    let red = Color::rgb8(255, 0, 0);
+                    Probe::new("content", &probes).color(red).build(ui, |_| {});
+                });
+            });
+        });
+        let content = Probe::find(harness.tree(), "content");
+        let center = harness.window_rect(content).center();
+        let (x, y) = (center.x as usize, center.y as usize);
+        let snapshot = harness.snapshot();
+        assert_eq!(snapshot.pixel(x, y), [255, 0, 0, 255]);
+        let background = snapshot.pixel(x, 300);
+
+        opacity.set(0.5);
+        harness.rebuild();
+        let pixel = harness.snapshot().pixel(x, y);
+        for channel in 0..3 {
+            let red = if channel == 0 { 255.0 } else { 0.0 };
+            let expected = (f64::from(background[channel]) + red) / 2.0;
+            let difference = (f64::from(pixel[channel]) - expected).abs();
+            assert!(
+                difference <= 2.0,
+                "{:?} is not half red over {:?}",
+                pixel,
+                background
+            );
+        }
+    }
+}
@@ -0,0 +1,402 @@
+//! Content that floats above the rest of the window.
+
+use crate::{
+    kurbo::{Point, Vec2},
+    object::prelude::*,
+};
+use druid::Selector;
+
+/// Sent to a layer when it should be dismissed.
+pub(crate) const DISMISS_OVERLAY: Selector<DismissReason> = Selector::new("coat.overlay.dismiss");
+
+/// Why an overlay was asked to close.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DismissReason {
+    /// The user clicked outside of the overlay.
+    ClickOutside,
+    /// The user pressed the escape key.
+    Escape,
+}
+
+/// Where the overlay content is placed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Placement {
+    /// Below the anchor content, aligned to its leading edge.
+    Below,
+    /// Above the anchor content, aligned to its leading edge.
+    Above,
+    /// Left of the anchor content, aligned to its top edge.
+    Left,
+    /// Right of the anchor content, aligned to its top edge.
+    Right,
+    /// At a fixed position in window coordinates.
+    At(Point),
+    /// Centered in the window.
+    Center,
+    /// Centered at the bottom edge of the window.
+    Bottom,
+}
+
+/// Pushes content onto the overlay layer.
+///
+/// The overlay content is painted above, and receives events before,
+/// everything else in the window. It is placed relative to the bounds of
+/// the anchor content, or at a fixed window position, and kept inside the window.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Overlay {
+    placement: Placement,
+    dismiss_on_click_outside: bool,
+    dismiss_on_escape: bool,
+    interactive: bool,
+    modal: bool,
+}
+
+impl Properties for Overlay {
+    type Object = Overlay;
+}
+
+impl Overlay {
+    pub fn new(placement: Placement) -> Self {
+        Overlay {
+            placement,
+            dismiss_on_click_outside: true,
+            dismiss_on_escape: true,
+            interactive: true,
+            modal: false,
+        }
+    }
+
+    /// Whether a click outside of the overlay content requests dismissal.
+    ///
+    /// The default value is `true`.
+    pub fn dismiss_on_click_outside(mut self, dismiss: bool) -> Self {
+        self.dismiss_on_click_outside = dismiss;
+        self
+    }
+
+    /// Whether pressing escape requests dismissal.
+    ///
+    /// The default value is `true`.
+    pub fn dismiss_on_escape(mut self, dismiss: bool) -> Self {
+        self.dismiss_on_escape = dismiss;
+        self
+    }
+
+    /// Whether the overlay content receives events.
+    ///
+    /// A non-interactive overlay lets all events through to the content
+    /// below it and is never dismissed.
+    ///
+    /// The default value is `true`.
+    pub fn interactive(mut self, interactive: bool) -> Self {
+        self.interactive = interactive;
+        self
+    }
+
+    /// Whether the overlay blocks input to the rest of the window.
+    ///
+    /// A modal overlay covers and dims the whole window, its content is
+    /// centered. Keyboard focus is moved into the overlay while it is open.
+    ///
+    /// The default value is `false`.
+    pub fn modal(mut self, modal: bool) -> Self {
+        self.modal = modal;
+        self
+    }
+
+    /// Build the anchor `content` and, if `open`, the `overlay` content.
+    ///
+    /// Returns `true` if the overlay should be dismissed.
+    #[must_use]
+    #[track_caller]
+    pub fn build(
+        self,
+        ui: &mut Ui,
+        open: bool,
+        content: impl FnOnce(&mut Ui),
+        overlay: impl FnOnce(&mut Ui),
+    ) -> bool {
+        let caller = Location::caller().into();
+        let mut dismissed = false;
+        ui.render_object(caller, self, |ui| {
+            content(ui);
+            if open {
+                let layer = Layer {
+                    dismiss_on_click_outside: self.dismiss_on_click_outside,
+                    dismiss_on_escape: self.dismiss_on_escape,
+                    interactive: self.interactive,
+                    modal: self.modal,
+                };
+                dismissed = ui.render_object(Location::caller().into(), layer, overlay);
+            }
+        });
+        dismissed
+    }
+
+    fn place(
+        &self,
+        size: Size,
+        layer_size: Size,
+        window_origin: Point,
+        window_size: Size,
+    ) -> Point {
+        let origin = match self.placement {
+            Placement::Below => Point::new(0.0, size.height),
+            Placement::Above => Point::new(0.0, -layer_size.height),
+            Placement::Left => Point::new(-layer_size.width, 0.0),
+            Placement::Right => Point::new(size.width, 0.0),
+            Placement::At(point) => point - window_origin.to_vec2(),
+            Placement::Center => {
+                let extra = (window_size - layer_size).to_vec2() / 2.0;
+                (extra - window_origin.to_vec2()).to_point()
+            }
+            Placement::Bottom => {
+                let x = (window_size.width - layer_size.width) / 2.0;
+                let y = window_size.height - layer_size.height;
+                Point::new(x, y) - window_origin.to_vec2()
+            }
+        };
+
+        // Keep the layer inside of the window.
+        let window_pos = origin + window_origin.to_vec2();
+        let max = (window_size - layer_size).to_vec2().to_point();
+        let x = window_pos.x.min(max.x).max(0.0);
+        let y = window_pos.y.min(max.y).max(0.0);
+        origin + Vec2::new(x - window_pos.x, y - window_pos.y)
+    }
+}
+
+impl RenderObject<Overlay> for Overlay {
+    type Action = ();
+
+    fn create(props: Overlay) -> Self {
+        props
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, props: Overlay) {
+        if self != &props {
+            *self = props;
+            ctx.request_layout();
+        }
+    }
+}
+
+impl RenderObjectInterface for Overlay {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, children: &mut Children) {
+        for child in children {
+            if !child.state.is_layer {
+                child.event(ctx, event);
+            }
+        }
+    }
+
+    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle) {}
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        children: &mut Children,
+    ) -> Size {
+        bc.debug_check("Overlay");
+
+        let mut size = bc.min();
+        for child in children.iter() {
+            if child.as_any().is::<LayerObject>() {
+                child.state.is_layer = true;
+                child.state.is_modal = self.modal;
+            } else {
+                let child_size = child.layout(ctx, bc);
+                size.width = size.width.max(child_size.width);
+                size.height = size.height.max(child_size.height);
+                child.set_origin(ctx, Point::ORIGIN);
+            }
+        }
+
+        let window_size = ctx.window_size();
+        let window_origin = ctx.child_state.window_origin();
+        let layer_bc = BoxConstraints::new(Size::ZERO, window_size);
+        for child in children.iter() {
+            if child.state.is_layer {
+                let layer_size = child.layout(ctx, &layer_bc);
+                let origin = self.place(size, layer_size, window_origin, window_size);
+                child.set_origin(ctx, origin);
+            }
+        }
+
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, children: &mut Children) {
+        for child in children {
+            if !child.state.is_layer {
+                child.paint(ctx);
+            }
+        }
+    }
+}
+
+/// The root of the overlay content.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Layer {
+    dismiss_on_click_outside: bool,
+    dismiss_on_escape: bool,
+    interactive: bool,
+    modal: bool,
+}
+
+impl Properties for Layer {
+    type Object = LayerObject;
+}
+
+struct LayerObject {
+    props: Layer,
+    dismissed: bool,
+}
+
+impl RenderObject<Layer> for LayerObject {
+    type Action = bool;
+
+    fn create(props: Layer) -> Self {
+        LayerObject {
+            props,
+            dismissed: false,
+        }
+    }
+
+    fn update(&mut self, _ctx: &mut UpdateCtx, props: Layer) -> bool {
+        self.props = props;
+        let was_dismissed = self.dismissed;
+        self.dismissed = false;
+        was_dismissed
+    }
+}
+
+impl RenderObjectInterface for LayerObject {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, children: &mut Children) {
+        if !self.props.interactive {
+            return;
+        }
+
+        if let Event::Command(cmd) = event {
+            if let Some(reason) = cmd.get(DISMISS_OVERLAY) {
+                let dismiss = match reason {
+                    DismissReason::ClickOutside => self.props.dismiss_on_click_outside,
+                    DismissReason::Escape => self.props.dismiss_on_escape,
+                };
+                if dismiss {
+                    self.dismissed = true;
+                    ctx.request_update();
+                    if *reason == DismissReason::Escape {
+                        ctx.set_handled();
+                    }
+                }
+                return;
+            }
+        }
+
+        for child in children.iter() {
+            child.event(ctx, event);
+        }
+
+        // Content below the layer must not receive clicks on the layer.
+        if let Event::MouseDown(_) | Event::MouseUp(_) | Event::Wheel(_) = event {
+            if ctx.is_hot() {
+                ctx.set_handled();
+            }
+        }
+    }
+
+    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle) {}
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        children: &mut Children,
+    ) -> Size {
+        if self.props.modal {
+            // Cover the whole window and center the content.
+            let size = bc.max();
+            for child in children.iter() {
+                let dialog_padding = ctx.theme().spacing.dialog_padding;
+                let padding = Size::new(2.0 * dialog_padding, 2.0 * dialog_padding);
+                let child_size = child.layout(ctx, &bc.loosen().shrink(padding));
+                let origin = ((size - child_size).to_vec2() / 2.0).to_point();
+                child.set_origin(ctx, origin);
+            }
+            return size;
+        }
+
+        children.layout_stacked(ctx, bc)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, children: &mut Children) {
+        if self.props.modal {
+            let theme = ctx.theme().clone();
+            let rect = ctx.size().to_rect();
+            ctx.fill(rect, &theme.colors.scrim);
+            for child in children.iter() {
+                let padding = theme.spacing.dialog_padding;
+                let rect = child.layout_rect().inflate(padding, padding);
+                let rect = rect.to_rounded_rect(theme.spacing.corner_radius);
+                ctx.fill(rect, &theme.colors.panel);
+                ctx.stroke(rect, &theme.colors.panel_border, 1.0);
+            }
+        }
+        for child in children {
+            child.paint(ctx);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        state::Mutable,
+        testing::{Harness, Log, Probe, Record},
+        widgets::Flex,
+    };
+
+    #[test]
+    fn popup_above_the_content_until_dismissed() {
+        let log = Log::default();
+        let probes = log.clone();
+        let mut harness = Harness::new(move |ui| {
+            Mutable::with(|| true).use_in(ui, |ui, open: &mut bool| {
+                Flex::column().build(ui, |ui| {
+                    let dismissed = Overlay::new(Placement::Below).build(
+                        ui,
+                        *open,
+                        |ui| Probe::new("anchor", &probes).build(ui, |_| {}),
+                        |ui| {
+                            Probe::new("popup", &probes)
+                                .size(100.0, 50.0)
+                                .build(ui, |_| {})
+                        },
+                    );
+                    if dismissed {
+                        *open = false;
+                    }
+                    Probe::new("below", &probes).build(ui, |_| {});
+                });
+            });
+        });
+        let anchor = Probe::find(harness.tree(), "anchor");
+        let anchor = harness.window_rect(anchor);
+        let popup = Probe::find(harness.tree(), "popup");
+        let popup = harness.window_rect(popup);
+        assert_eq!(popup.origin(), Point::new(anchor.x0, anchor.y1));
+
+        // The popup covers the top of the widget below the anchor.
+        let clicks = |log: &Log| Probe::take(log, |record| *record == Record::Click);
+        harness.click(popup.center());
+        assert_eq!(clicks(&log), [("popup", Record::Click)]);
+
+        harness.click(Point::new(700.0, 500.0));
+        harness.rebuild();
+        harness.click(popup.center());
+        assert_eq!(clicks(&log), [("below", Record::Click)]);
+    }
+}
@@ -2,6 +2,7 @@
 
 use crate::{
     context::{EventCtx, LayoutCtx, LifeCycleCtx, PaintCtx, UpdateCtx},
+    environment::LayoutDirection,
     event::{Event, LifeCycle},
     kurbo::{Insets, Point, Size},
     object::{Properties, RenderObject, RenderObjectInterface},
@@ -18,6 +19,9 @@ pub struct Padding {
     right: f64,
     top: f64,
     bottom: f64,
+    /// `left` and `right` are the start and the end, which are swapped
+    /// right to left.
+    directional: bool,
 }
 
 impl Properties for Padding {
@@ -59,6 +63,34 @@ impl Padding {
             right: insets.x1,
             top: insets.y0,
             bottom: insets.y1,
+            directional: false,
+        }
+    }
+
+    /// Create a new widget with padding at the start and the end instead of
+    /// the left and the right, which follows the [`LayoutDirection`].
+    ///
+    /// The `x0` of the insets is the start, the `x1` is the end:
+    ///
+    /// ```
+    /// use coat::widgets::Padding;
+    ///
+    /// // Indents the content on the leading side.
+    /// let _: Padding = Padding::directional((24.0, 0.0, 0.0, 0.0));
+    /// ```
+    pub fn directional(insets: impl Into<Insets>) -> Padding {
+        Padding {
+            directional: true,
+            ..Padding::new(insets)
+        }
+    }
+
+    /// The padding on the left, resolved against the layout direction of `ctx`.
+    fn resolved_left(&self, ctx: &LayoutCtx) -> f64 {
+        if self.directional && ctx.layout_direction() == LayoutDirection::RightToLeft {
+            self.right
+        } else {
+            self.left
         }
     }
 
@@ -105,15 +137,56 @@ impl RenderObjectInterface for Padding {
 
         let child_bc = bc.shrink((hpad, vpad));
         let size = child.layout(ctx, &child_bc);
-        let origin = Point::new(self.left, self.top);
+        let origin = Point::new(self.resolved_left(ctx), self.top);
         child.set_origin(ctx, origin);
 
         let my_size = Size::new(size.width + hpad, size.height + vpad);
+        ctx.set_baseline_offset(child.baseline_offset() + self.bottom);
         let my_insets = child.compute_parent_paint_insets(my_size);
         ctx.set_paint_insets(my_insets);
         my_size
     }
 
+    fn compute_min_intrinsic_width(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        height: f64,
+        children: &mut Children,
+    ) -> f64 {
+        let vpad = self.top + self.bottom;
+        children.min_intrinsic_width(ctx, (height - vpad).max(0.0)) + self.left + self.right
+    }
+
+    fn compute_max_intrinsic_width(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        height: f64,
+        children: &mut Children,
+    ) -> f64 {
+        let vpad = self.top + self.bottom;
+        children.max_intrinsic_width(ctx, (height - vpad).max(0.0)) + self.left + self.right
+    }
+
+    fn compute_min_intrinsic_height(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        width: f64,
+        children: &mut Children,
+    ) -> f64 {
+        let hpad = self.left + self.right;
+        children.min_intrinsic_height(ctx, (width - hpad).max(0.0)) + self.top + self.bottom
+    }
+
+    fn compute_max_intrinsic_height(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        width: f64,
+        children: &mut Children,
+    ) -> f64 {
+        let hpad = self.left + self.right;
+        children.max_intrinsic_height(ctx, (width - hpad).max(0.0)) + self.top + self.bottom
+    }
+
     fn paint(&mut self, ctx: &mut PaintCtx, children: &mut Children) {
         children[0].paint(ctx);
     }
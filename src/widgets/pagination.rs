@@ -0,0 +1,257 @@
+//! Buttons to move between the pages of a long list.
+
+use crate::{
+    event::MouseButton,
+    kurbo::Point,
+    object::prelude::*,
+    widgets::{Button, Flex, Label},
+};
+
+const PAGE_PADDING: Size = Size::new(6.0, 3.0);
+
+/// Page numbers with previous and next buttons, for tables and lists
+/// that show one page of their items at a time.
+///
+/// Pages are counted from zero, but shown counted from one. The first and
+/// last page and the ones next to the current page are always shown, the
+/// others are collapsed into an ellipsis.
+///
+/// ```ignore
+/// if let Some(new_page) = Pagination::new(page, rows.len() / PAGE_SIZE + 1).build(ui) {
+///     page = new_page;
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pagination {
+    page: usize,
+    page_count: usize,
+    siblings: usize,
+}
+
+impl Pagination {
+    pub fn new(page: usize, page_count: usize) -> Self {
+        Pagination {
+            page,
+            page_count,
+            siblings: 1,
+        }
+    }
+
+    /// How many pages before and after the current one are shown.
+    ///
+    /// The default value is `1`.
+    pub fn siblings(mut self, siblings: usize) -> Self {
+        self.siblings = siblings;
+        self
+    }
+
+    /// Returns the page the user moved to.
+    #[must_use]
+    #[track_caller]
+    pub fn build(self, ui: &mut Ui) -> Option<usize> {
+        let Pagination {
+            page,
+            page_count,
+            siblings,
+        } = self;
+        let mut changed = None;
+        Flex::row().build(ui, |ui| {
+            let previous = Button::new().disabled(page == 0);
+            if previous.labeled(ui, "‹") && page > 0 {
+                changed = Some(page - 1);
+            }
+            for item in page_items(page, page_count, siblings) {
+                match item {
+                    PageItem::Page(index) => ui.keyed(index, |ui| {
+                        let caller = Location::caller().into();
+                        let props = PageButton {
+                            current: index == page,
+                        };
+                        let label = (index + 1).to_string();
+                        if ui.render_object(caller, props, |ui| Label::new(label).build(ui)) {
+                            changed = Some(index);
+                        }
+                    }),
+                    PageItem::Ellipsis => Label::new("…").build(ui),
+                }
+            }
+            let next = Button::new().disabled(page + 1 >= page_count);
+            if next.labeled(ui, "›") && page + 1 < page_count {
+                changed = Some(page + 1);
+            }
+        });
+        changed.filter(|&index| index != page)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PageItem {
+    Page(usize),
+    Ellipsis,
+}
+
+/// The pages shown around `current`, with ellipses for the collapsed ones.
+///
+/// An ellipsis never stands for a single page, that page is shown instead.
+fn page_items(current: usize, count: usize, siblings: usize) -> Vec<PageItem> {
+    if count == 0 {
+        return Vec::new();
+    }
+    let last = count - 1;
+    let current = current.min(last);
+    let start = current.saturating_sub(siblings);
+    let end = current.saturating_add(siblings).min(last);
+
+    let mut pages = vec![0];
+    pages.extend(start..=end);
+    pages.push(last);
+    pages.dedup();
+
+    let mut items = Vec::new();
+    let mut previous: Option<usize> = None;
+    for page in pages {
+        match previous {
+            Some(previous) if page <= previous => continue,
+            Some(previous) if page - previous == 2 => items.push(PageItem::Page(previous + 1)),
+            Some(previous) if page - previous > 2 => items.push(PageItem::Ellipsis),
+            _ => {}
+        }
+        items.push(PageItem::Page(page));
+        previous = Some(page);
+    }
+    items
+}
+
+/// The number of a page, highlighted if it is the current one.
+#[derive(PartialEq)]
+struct PageButton {
+    current: bool,
+}
+
+impl Properties for PageButton {
+    type Object = PageButtonObject;
+}
+
+struct PageButtonObject {
+    current: bool,
+    clicked: bool,
+}
+
+impl RenderObject<PageButton> for PageButtonObject {
+    type Action = bool;
+
+    fn create(props: PageButton) -> Self {
+        PageButtonObject {
+            current: props.current,
+            clicked: false,
+        }
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, props: PageButton) -> bool {
+        if self.current != props.current {
+            self.current = props.current;
+            ctx.request_paint();
+        }
+        std::mem::take(&mut self.clicked)
+    }
+}
+
+impl RenderObjectInterface for PageButtonObject {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, _children: &mut Children) {
+        match event {
+            Event::MouseDown(mouse) if mouse.button == MouseButton::Left => {
+                ctx.set_active(true);
+                ctx.request_paint();
+            }
+            Event::MouseUp(mouse) if ctx.is_active() && mouse.button == MouseButton::Left => {
+                ctx.set_active(false);
+                if ctx.is_hot() {
+                    self.clicked = true;
+                    ctx.request_update();
+                    ctx.set_handled();
+                }
+                ctx.request_paint();
+            }
+            _ => {}
+        }
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        if let LifeCycle::HotChanged(_) = event {
+            ctx.request_paint();
+        }
+    }
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        children: &mut Children,
+    ) -> Size {
+        ctx.set_hit_target(true);
+        let padding = PAGE_PADDING * 2.0;
+        let label = children[0].layout(ctx, &bc.loosen().shrink(padding));
+        // Pages with few digits are square.
+        let height = label.height + padding.height;
+        let size = bc.constrain(Size::new((label.width + padding.width).max(height), height));
+        let origin = Point::new((size.width - label.width) / 2.0, PAGE_PADDING.height);
+        children[0].set_origin(ctx, origin);
+        let baseline = children[0].baseline_offset();
+        ctx.set_baseline_offset(baseline + PAGE_PADDING.height);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, children: &mut Children) {
+        let theme = ctx.theme();
+        let radius = theme.spacing.corner_radius;
+        let accent = theme.colors.accent.clone();
+        let ghost = theme.colors.ghost.clone();
+        let rect = ctx.size().to_rect().inset(-0.5).to_rounded_rect(radius);
+        if self.current || ctx.is_hot() {
+            ctx.fill(rect, &ghost);
+        }
+        if self.current {
+            ctx.stroke(rect, &accent, 1.0);
+        }
+        children[0].paint(ctx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use PageItem::{Ellipsis, Page};
+
+    #[test]
+    fn collapse_pages() {
+        assert_eq!(page_items(0, 0, 1), []);
+        assert_eq!(page_items(0, 1, 1), [Page(0)]);
+        assert_eq!(page_items(0, 10, 1), [Page(0), Page(1), Ellipsis, Page(9)]);
+        assert_eq!(
+            page_items(5, 10, 1),
+            [
+                Page(0),
+                Ellipsis,
+                Page(4),
+                Page(5),
+                Page(6),
+                Ellipsis,
+                Page(9)
+            ]
+        );
+        // A gap of a single page shows that page.
+        assert_eq!(
+            page_items(3, 10, 1),
+            [
+                Page(0),
+                Page(1),
+                Page(2),
+                Page(3),
+                Page(4),
+                Ellipsis,
+                Page(9)
+            ]
+        );
+        assert_eq!(page_items(2, 4, 1), [Page(0), Page(1), Page(2), Page(3)]);
+    }
+}
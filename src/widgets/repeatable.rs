@@ -0,0 +1,128 @@
+//! Auto-repeat for press-and-hold controls.
+
+use crate::{context::EventCtx, event::Event};
+use druid::TimerToken;
+use std::time::Duration;
+
+/// How a press-and-hold control repeats its action.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RepeatBehavior {
+    /// The time between the press and the first repetition.
+    pub delay: Duration,
+    /// The time between subsequent repetitions.
+    pub interval: Duration,
+}
+
+impl Default for RepeatBehavior {
+    fn default() -> Self {
+        RepeatBehavior {
+            delay: Duration::from_millis(400),
+            interval: Duration::from_millis(80),
+        }
+    }
+}
+
+/// Drives the auto-repeat of a press-and-hold control.
+///
+/// Call [`press`] when the control is pressed and [`release`] when it is
+/// released. While pressed, [`is_repeat`] returns `true` for each repetition.
+///
+/// [`press`]: #method.press
+/// [`release`]: #method.release
+/// [`is_repeat`]: #method.is_repeat
+#[derive(Debug)]
+pub struct Repeatable {
+    behavior: RepeatBehavior,
+    timer: TimerToken,
+}
+
+impl Repeatable {
+    pub fn new(behavior: RepeatBehavior) -> Self {
+        Repeatable {
+            behavior,
+            timer: TimerToken::INVALID,
+        }
+    }
+
+    pub fn set_behavior(&mut self, behavior: RepeatBehavior) {
+        self.behavior = behavior;
+    }
+
+    /// Whether the control is currently held down.
+    pub fn is_pressed(&self) -> bool {
+        self.timer != TimerToken::INVALID
+    }
+
+    /// Start repeating after the initial delay.
+    pub fn press(&mut self, ctx: &mut EventCtx) {
+        self.timer = ctx.request_timer(self.behavior.delay);
+    }
+
+    /// Stop repeating.
+    pub fn release(&mut self) {
+        self.timer = TimerToken::INVALID;
+    }
+
+    /// Returns `true` if `event` is a repetition of the held action.
+    ///
+    /// This also schedules the next repetition.
+    pub fn is_repeat(&mut self, ctx: &mut EventCtx, event: &Event) -> bool {
+        match event {
+            Event::Timer(token) if self.is_pressed() && *token == self.timer => {
+                self.timer = ctx.request_timer(self.behavior.interval);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Default for Repeatable {
+    fn default() -> Self {
+        Repeatable::new(RepeatBehavior::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        state::Mutable,
+        testing::{self, Harness},
+        widgets::{button::ButtonObject, Button, Flex, Label},
+    };
+
+    #[test]
+    fn repeat_while_held() {
+        let mut harness = Harness::new(|ui| {
+            Mutable::new().use_in(ui, |ui, clicks: &mut usize| {
+                Flex::column().build(ui, |ui| {
+                    if Button::new()
+                        .repeat(RepeatBehavior::default())
+                        .labeled(ui, "Next")
+                    {
+                        *clicks += 1;
+                    }
+                    Label::new(clicks.to_string()).build(ui);
+                });
+            });
+        });
+        let button =
+            testing::assert_exists::<ButtonObject>(harness.tree(), testing::with_label("Next"));
+        let center = harness.window_rect(button).center();
+        let clicks = |harness: &mut Harness| harness.label_texts()[1].clone();
+
+        harness.mouse_down(center);
+        assert_eq!(clicks(&mut harness), "1");
+        harness.advance(Duration::from_millis(399));
+        assert_eq!(clicks(&mut harness), "1");
+        harness.advance(Duration::from_millis(1));
+        assert_eq!(clicks(&mut harness), "2");
+        harness.advance(Duration::from_millis(160));
+        assert_eq!(clicks(&mut harness), "4");
+
+        harness.mouse_up(center);
+        harness.advance(Duration::from_secs(1));
+        assert_eq!(clicks(&mut harness), "4");
+    }
+}
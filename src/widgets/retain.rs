@@ -0,0 +1,135 @@
+//! A widget that keeps the state of its content while it is not built.
+
+use crate::object::prelude::*;
+
+/// Keeps the state of its content while it is not built.
+///
+/// Usually, content that is no longer built is dropped together with the
+/// state of its render objects. When a `Retain` is no longer built, its
+/// subtree is kept around instead, and restored when it is built again at
+/// the same location. This way tabs or pages keep their scroll offsets,
+/// text selections and focus when the user navigates back to them.
+///
+/// Only a few subtrees are kept in the same parent, see [`Retain::limit`],
+/// so that content built with ever new keys does not pile up.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Retain {
+    limit: usize,
+}
+
+impl Properties for Retain {
+    type Object = Retain;
+}
+
+impl Default for Retain {
+    fn default() -> Self {
+        Retain { limit: 8 }
+    }
+}
+
+impl Retain {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep at most `limit` subtrees that are not built in the parent of
+    /// this widget, 8 by default. Once there are more, the subtree that was
+    /// removed first is dropped.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    #[track_caller]
+    pub fn build(self, ui: &mut Ui, content: impl FnOnce(&mut Ui)) {
+        let caller = Location::caller().into();
+        let limit = self.limit;
+        ui.render_object(caller, self, content);
+        ui.retain_last_render_object(limit);
+    }
+}
+
+impl RenderObject<Retain> for Retain {
+    type Action = ();
+
+    fn create(props: Retain) -> Self {
+        props
+    }
+
+    fn update(&mut self, _ctx: &mut UpdateCtx, props: Retain) {
+        *self = props;
+    }
+}
+
+impl RenderObjectInterface for Retain {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, children: &mut Children) {
+        for child in children {
+            child.event(ctx, event);
+        }
+    }
+
+    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle) {}
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        children: &mut Children,
+    ) -> Size {
+        bc.debug_check("Retain");
+
+        children.layout_stacked(ctx, bc)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, children: &mut Children) {
+        for child in children {
+            child.paint(ctx);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::Harness;
+    use std::{cell::RefCell, rc::Rc};
+
+    #[test]
+    fn retains_and_evicts_pages() {
+        let page = Rc::new(RefCell::new(0));
+        let mounts = Rc::new(RefCell::new(Vec::new()));
+        let unmounts = Rc::new(RefCell::new(Vec::new()));
+        let mut harness = Harness::new({
+            let (page, mounts, unmounts) = (page.clone(), mounts.clone(), unmounts.clone());
+            move |ui| {
+                let page = *page.borrow();
+                ui.keyed(page, |ui| {
+                    Retain::new().limit(1).build(ui, |ui| {
+                        let mounts = mounts.clone();
+                        ui.on_mount(move || mounts.borrow_mut().push(page));
+                        let unmounts = unmounts.clone();
+                        ui.on_unmount(move || unmounts.borrow_mut().push(page));
+                    });
+                });
+            }
+        });
+        let mut show = |number: usize| {
+            *page.borrow_mut() = number;
+            harness.rebuild();
+        };
+
+        show(1);
+        assert_eq!(*mounts.borrow(), [0, 1]);
+        // The first page kept its state.
+        show(0);
+        assert_eq!(*mounts.borrow(), [0, 1]);
+        assert!(unmounts.borrow().is_empty());
+
+        // Only the page removed last is kept.
+        show(2);
+        assert_eq!(*unmounts.borrow(), [1]);
+        show(1);
+        assert_eq!(*mounts.borrow(), [0, 1, 2, 1]);
+        assert_eq!(*unmounts.borrow(), [1, 0]);
+    }
+}
@@ -0,0 +1,129 @@
+//! A label with styled runs of text.
+
+use crate::{
+    kurbo::Point,
+    object::prelude::*,
+    piet::TextAlignment,
+    text::{RichText, TextLayout},
+    widgets::label::LineBreaking,
+};
+use druid::Data;
+
+// added padding between the edges of the widget and the text.
+const LABEL_X_PADDING: f64 = 2.0;
+
+/// A label whose runs of text can have their own color, weight, size or underline.
+///
+/// Text without attributes uses the font and text color of the theme.
+///
+/// ```ignore
+/// let mut text = RichTextBuilder::new();
+/// text.push("Saved ");
+/// text.push("3 files").weight(FontWeight::BOLD);
+/// text.push(" to disk").text_color(Color::grey(0.6));
+/// RichLabel::new(text.build()).build(ui);
+/// ```
+#[derive(Debug, Clone)]
+pub struct RichLabel {
+    layout: TextLayout<RichText>,
+    line_break_mode: LineBreaking,
+}
+
+impl Properties for RichLabel {
+    type Object = RichLabel;
+}
+
+impl RichLabel {
+    pub fn new(text: RichText) -> Self {
+        RichLabel {
+            layout: TextLayout::from_text(text),
+            line_break_mode: LineBreaking::Overflow,
+        }
+    }
+
+    pub fn line_break_mode(mut self, mode: LineBreaking) -> Self {
+        self.line_break_mode = mode;
+        self
+    }
+
+    pub fn text_alignment(mut self, alignment: TextAlignment) -> Self {
+        self.layout.set_text_alignment(alignment);
+        self
+    }
+
+    #[track_caller]
+    pub fn build(self, ui: &mut Ui) {
+        let caller = Location::caller().into();
+        ui.render_object(caller, self, |_| {});
+    }
+}
+
+impl RenderObject<RichLabel> for RichLabel {
+    type Action = ();
+
+    fn create(props: RichLabel) -> Self {
+        props
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, props: RichLabel) {
+        let same_text = match (self.layout.text(), props.layout.text()) {
+            (Some(old), Some(new)) => old.same(new),
+            _ => false,
+        };
+        if !same_text || self.line_break_mode != props.line_break_mode {
+            ctx.request_layout();
+            self.layout = props.layout;
+            self.line_break_mode = props.line_break_mode;
+        }
+        if self.layout.layout().is_none() {
+            ctx.request_layout();
+        }
+    }
+}
+
+impl RenderObjectInterface for RichLabel {
+    fn event(&mut self, _ctx: &mut EventCtx, _event: &Event, _children: &mut Children) {}
+
+    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle) {}
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        _children: &mut Children,
+    ) -> Size {
+        bc.debug_check("RichLabel");
+
+        if self.line_break_mode == LineBreaking::WordWrap && !bc.max().width.is_finite() {
+            ctx.report_layout_issue("Word wrapping label without a max width never wraps");
+        }
+        let width = match self.line_break_mode {
+            LineBreaking::WordWrap => bc.max().width - LABEL_X_PADDING * 2.0,
+            _ => f64::INFINITY,
+        };
+
+        let theme = ctx.theme();
+        self.layout.set_text_color(theme.colors.text.clone());
+        self.layout.set_font(theme.font.clone());
+        self.layout.set_wrap_width(width);
+        let env = ctx.env().clone();
+        self.layout.rebuild_if_needed(ctx.text(), &env);
+
+        let text_metrics = self.layout.layout_metrics();
+        ctx.set_baseline_offset(text_metrics.size.height - text_metrics.first_baseline);
+        bc.constrain(Size::new(
+            text_metrics.size.width + 2. * LABEL_X_PADDING,
+            text_metrics.size.height,
+        ))
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, _children: &mut Children) {
+        if self.line_break_mode == LineBreaking::Clip {
+            let size = ctx.size();
+            ctx.clip(size.to_rect());
+        }
+        if let Some(layout) = self.layout.layout() {
+            ctx.draw_text(layout, Point::new(LABEL_X_PADDING, 0.0));
+        }
+    }
+}
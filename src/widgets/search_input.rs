@@ -0,0 +1,238 @@
+//! A text field for search queries, with suggestions.
+
+use crate::{
+    kurbo::Point,
+    object::prelude::*,
+    widgets::{Autocomplete, Button, TextBox},
+};
+use std::time::{Duration, Instant};
+
+/// What happened in a [`SearchInput`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SearchAction {
+    /// The query changed and then stayed the same for the debounce duration.
+    ///
+    /// Clearing the query is reported at once.
+    Changed(String),
+    /// The query was submitted with enter, or by choosing a suggestion.
+    Submitted(String),
+}
+
+/// A text field with a clear button, which suggests queries in a popup below it.
+///
+/// Changes are reported once typing paused, so searching does not run on
+/// every key stroke. The suggestions are passed in every build, usually
+/// computed from the query reported by the last [`SearchAction::Changed`].
+/// They can be chosen with the arrow keys and enter, or by clicking them.
+///
+/// ```ignore
+/// match SearchInput::new(&mut query).suggestions(&recent).build(ui) {
+///     Some(SearchAction::Changed(query)) => results = search(&query),
+///     Some(SearchAction::Submitted(query)) => open_first_result(&query),
+///     None => {}
+/// }
+/// ```
+pub struct SearchInput<'a> {
+    text: &'a mut String,
+    placeholder: &'a str,
+    debounce: Duration,
+    suggestions: Vec<String>,
+}
+
+struct SearchState {
+    /// The text the last change was reported for.
+    reported: String,
+    /// When the pending change is reported.
+    deadline: Option<Instant>,
+}
+
+impl<'a> SearchInput<'a> {
+    pub fn new(text: &'a mut String) -> Self {
+        SearchInput {
+            text,
+            placeholder: "Search",
+            debounce: Duration::from_millis(250),
+            suggestions: Vec::new(),
+        }
+    }
+
+    /// The default value is `"Search"`.
+    pub fn placeholder(mut self, placeholder: &'a str) -> Self {
+        self.placeholder = placeholder;
+        self
+    }
+
+    /// How long the query has to stay the same before a change is reported.
+    ///
+    /// The default value is 250 milliseconds.
+    pub fn debounce(mut self, debounce: Duration) -> Self {
+        self.debounce = debounce;
+        self
+    }
+
+    /// The suggestions shown below the field while the query is edited.
+    pub fn suggestions<S: Into<String>>(
+        mut self,
+        suggestions: impl IntoIterator<Item = S>,
+    ) -> Self {
+        self.suggestions = suggestions.into_iter().map(Into::into).collect();
+        self
+    }
+
+    #[must_use]
+    #[track_caller]
+    pub fn build(self, ui: &mut Ui) -> Option<SearchAction> {
+        let caller = Location::caller().into();
+        let SearchInput {
+            text,
+            placeholder,
+            debounce,
+            suggestions,
+        } = self;
+        let initial = text.clone();
+        let mut action = None;
+        let now = ui.now();
+        ui.state_node(
+            caller,
+            || SearchState {
+                reported: initial,
+                deadline: None,
+            },
+            |ui, state: &mut SearchState| {
+                let mut activated = false;
+                let mut cleared = false;
+                let autocomplete = Autocomplete::new(suggestions).filter(false);
+                let committed = autocomplete.build(ui, text, |ui, text| {
+                    let caller = Location::caller().into();
+                    ui.render_object(caller, SearchField, |ui| {
+                        // The button comes first, so clearing shows in the text box.
+                        if !text.is_empty() && Button::new().labeled(ui, "×") {
+                            text.clear();
+                            cleared = true;
+                        }
+                        activated = TextBox::new(text).placeholder(placeholder).build(ui);
+                    });
+                });
+
+                if cleared {
+                    state.reported.clear();
+                    state.deadline = None;
+                    action = Some(SearchAction::Changed(String::new()));
+                } else if committed.is_some() || activated {
+                    state.reported.clone_from(text);
+                    state.deadline = None;
+                    action = Some(SearchAction::Submitted(text.clone()));
+                } else if *text != state.reported {
+                    state.reported.clone_from(text);
+                    state.deadline = Some(now + debounce);
+                }
+                if let Some(deadline) = state.deadline {
+                    if now >= deadline {
+                        state.deadline = None;
+                        action = Some(SearchAction::Changed(text.clone()));
+                    } else {
+                        ui.request_build_at(deadline);
+                    }
+                }
+            },
+        );
+        action
+    }
+}
+
+/// Lays out the clear button and the text box in a row.
+struct SearchField;
+
+impl Properties for SearchField {
+    type Object = SearchField;
+}
+
+impl RenderObject<SearchField> for SearchField {
+    type Action = ();
+
+    fn create(props: SearchField) -> Self {
+        props
+    }
+
+    fn update(&mut self, _ctx: &mut UpdateCtx, _props: SearchField) {}
+}
+
+impl RenderObjectInterface for SearchField {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, children: &mut Children) {
+        for child in children {
+            child.event(ctx, event);
+        }
+    }
+
+    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle) {}
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        children: &mut Children,
+    ) -> Size {
+        bc.debug_check("SearchInput");
+
+        // The text box is the last child, after the clear button if there is one.
+        let text_index = children.len() - 1;
+        let mut button = Size::ZERO;
+        if text_index > 0 {
+            button = children[0].layout(ctx, &bc.loosen());
+        }
+        let text_bc = BoxConstraints::new(
+            Size::new((bc.min().width - button.width).max(0.0), 0.0),
+            Size::new((bc.max().width - button.width).max(0.0), bc.max().height),
+        );
+        let text = children[text_index].layout(ctx, &text_bc);
+        let height = text.height.max(button.height);
+
+        let text_child = &mut children[text_index];
+        text_child.set_origin(ctx, Point::new(0.0, (height - text.height) / 2.0));
+        let baseline = text_child.baseline_offset();
+        if text_index > 0 {
+            let origin = Point::new(text.width, (height - button.height) / 2.0);
+            children[0].set_origin(ctx, origin);
+        }
+        ctx.set_baseline_offset(baseline + (height - text.height) / 2.0);
+        bc.constrain(Size::new(text.width + button.width, height))
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, children: &mut Children) {
+        for child in children {
+            child.paint(ctx);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        state::Mutable,
+        testing::{self, Harness},
+        widgets::textbox::TextBoxObject,
+    };
+    use std::{cell::RefCell, rc::Rc};
+
+    #[test]
+    fn debounce_changes() {
+        let actions = Rc::new(RefCell::new(Vec::new()));
+        let reported = actions.clone();
+        let mut harness = Harness::new(move |ui| {
+            Mutable::new().use_in(ui, |ui, query: &mut String| {
+                if let Some(action) = SearchInput::new(query).build(ui) {
+                    reported.borrow_mut().push(action);
+                }
+            });
+        });
+        let text_box = testing::assert_exists::<TextBoxObject>(harness.tree(), testing::any());
+        harness.click_on(text_box);
+        harness.type_text("ab");
+
+        harness.advance(Duration::from_millis(200));
+        assert!(actions.borrow().is_empty());
+        harness.advance(Duration::from_millis(50));
+        assert_eq!(*actions.borrow(), [SearchAction::Changed("ab".into())]);
+    }
+}
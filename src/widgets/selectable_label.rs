@@ -0,0 +1,222 @@
+//! A label whose text can be selected and copied.
+
+use crate::{object::prelude::*, style::State, text::Selection, widgets::Label};
+use druid::{Cursor, HotKey, KbKey, MouseEvent, Point, SysMods};
+use std::ops::Range;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// A [`Label`] whose text the user can select with the pointer and copy
+/// with Ctrl+C, for messages worth pasting elsewhere, like errors or logs.
+///
+/// A double click selects a word, Ctrl+A all of the text. The selection is
+/// shown while the label has the focus.
+///
+/// ```ignore
+/// Label::new(&error)
+///     .line_break_mode(LineBreaking::WordWrap)
+///     .selectable()
+///     .build(ui);
+/// ```
+pub struct SelectableLabel {
+    label: Label,
+}
+
+impl Properties for SelectableLabel {
+    type Object = SelectableLabelObject;
+}
+
+impl SelectableLabel {
+    pub fn new(label: Label) -> Self {
+        SelectableLabel { label }
+    }
+
+    #[track_caller]
+    pub fn build(self, ui: &mut Ui) {
+        let caller = Location::caller().into();
+        ui.render_object(caller, self, |_| {});
+    }
+}
+
+pub struct SelectableLabelObject {
+    label: Label,
+    /// The selected byte range of the text.
+    selection: Selection,
+}
+
+impl SelectableLabelObject {
+    /// The position in the text at `pos` in the widget.
+    fn text_position(&self, ctx: &EventCtx, pos: Point) -> usize {
+        let origin = self.label.text_origin(ctx.layout_direction(), ctx.size());
+        self.label
+            .text_layout()
+            .text_position_for_point(pos - origin.to_vec2())
+    }
+
+    fn mouse_down(&mut self, ctx: &mut EventCtx, mouse: &MouseEvent) {
+        let position = self.text_position(ctx, mouse.pos);
+        self.selection = match mouse.count {
+            1 if mouse.mods.shift() => Selection::new(self.selection.start, position),
+            1 => Selection::caret(position),
+            2 => {
+                let word = word_at(self.label.text(), position);
+                Selection::new(word.start, word.end)
+            }
+            _ => Selection::new(0, self.label.text().len()),
+        };
+    }
+
+    fn copy(&self, ctx: &EventCtx) {
+        let text = &self.label.text()[self.selection.range()];
+        if text.is_empty() {
+            return;
+        }
+        if let Some(clipboard) = ctx.services().clipboard() {
+            clipboard.put_string(text);
+        }
+    }
+}
+
+impl RenderObject<SelectableLabel> for SelectableLabelObject {
+    type Action = ();
+
+    fn create(props: SelectableLabel) -> Self {
+        SelectableLabelObject {
+            label: props.label,
+            selection: Selection::caret(0),
+        }
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, props: SelectableLabel) {
+        if self.label.text() != props.label.text() {
+            self.selection = Selection::caret(0);
+            ctx.request_paint();
+        }
+        RenderObject::update(&mut self.label, ctx, props.label);
+    }
+}
+
+impl RenderObjectInterface for SelectableLabelObject {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, _children: &mut Children) {
+        match event {
+            Event::MouseDown(mouse) if mouse.button.is_left() => {
+                ctx.request_focus();
+                ctx.capture_pointer();
+                self.mouse_down(ctx, mouse);
+                ctx.request_paint();
+            }
+            Event::MouseMove(mouse) => {
+                ctx.set_cursor(&Cursor::IBeam);
+                if ctx.is_active() {
+                    let position = self.text_position(ctx, mouse.pos);
+                    self.selection = Selection::new(self.selection.start, position);
+                    ctx.request_paint();
+                }
+            }
+            Event::MouseUp(_) if ctx.is_active() => ctx.release_pointer(),
+            Event::KeyDown(key) => match key {
+                key if HotKey::new(SysMods::Cmd, "c").matches(key) => self.copy(ctx),
+                key if HotKey::new(SysMods::Cmd, "a").matches(key) => {
+                    self.selection = Selection::new(0, self.label.text().len());
+                    ctx.request_paint();
+                }
+                key if HotKey::new(None, KbKey::Tab).matches(key) => ctx.focus_next(),
+                key if HotKey::new(SysMods::Shift, KbKey::Tab).matches(key) => ctx.focus_prev(),
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        self.label.lifecycle(ctx, event);
+    }
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        children: &mut Children,
+    ) -> Size {
+        self.label.layout(ctx, bc, children)
+    }
+
+    fn compute_min_intrinsic_width(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        height: f64,
+        children: &mut Children,
+    ) -> f64 {
+        self.label
+            .compute_min_intrinsic_width(ctx, height, children)
+    }
+
+    fn compute_max_intrinsic_width(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        height: f64,
+        children: &mut Children,
+    ) -> f64 {
+        self.label
+            .compute_max_intrinsic_width(ctx, height, children)
+    }
+
+    fn compute_min_intrinsic_height(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        width: f64,
+        children: &mut Children,
+    ) -> f64 {
+        self.label
+            .compute_min_intrinsic_height(ctx, width, children)
+    }
+
+    fn compute_max_intrinsic_height(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        width: f64,
+        children: &mut Children,
+    ) -> f64 {
+        self.label
+            .compute_max_intrinsic_height(ctx, width, children)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, children: &mut Children) {
+        if ctx.is_focused() && !self.selection.is_caret() {
+            // The same color as selections in text boxes.
+            let state = State::new(false, false, false, true);
+            let color = ctx.theme().text_box.style(state).selection_color;
+            let origin = self.label.text_origin(ctx.layout_direction(), ctx.size());
+            for rect in self
+                .label
+                .text_layout()
+                .rects_for_range(self.selection.range())
+            {
+                ctx.fill(rect + origin.to_vec2(), &color);
+            }
+        }
+        self.label.paint(ctx, children);
+    }
+}
+
+/// The byte range of the word at `index` in `text`, or of the space or
+/// punctuation there.
+fn word_at(text: &str, index: usize) -> Range<usize> {
+    text.split_word_bound_indices()
+        .map(|(start, word)| start..start + word.len())
+        .find(|range| range.contains(&index))
+        .unwrap_or(index..index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn words() {
+        let text = "Failed to open config.toml";
+        assert_eq!(word_at(text, 0), 0..6);
+        assert_eq!(word_at(text, 8), 7..9);
+        assert_eq!(word_at(text, 9), 9..10);
+        assert_eq!(word_at(text, text.len()), text.len()..text.len());
+    }
+}
@@ -1,6 +1,6 @@
 //! A widget with predefined size.
 
-use crate::object::prelude::*;
+use crate::{kurbo::Point, object::prelude::*};
 use std::f64::INFINITY;
 
 /// A widget with predefined size.
@@ -108,6 +108,11 @@ impl SizedBox {
     }
 }
 
+/// A set extent, unless the box expands in that direction.
+fn fixed(extent: Option<f64>) -> Option<f64> {
+    extent.filter(|extent| extent.is_finite())
+}
+
 impl RenderObject<SizedBox> for SizedBox {
     type Action = ();
 
@@ -142,7 +147,12 @@ impl RenderObjectInterface for SizedBox {
 
         let child_bc = self.child_constraints(bc);
         let size = match children.get_mut(0) {
-            Some(inner) => inner.layout(ctx, &child_bc),
+            Some(inner) => {
+                let size = inner.layout(ctx, &child_bc);
+                inner.set_origin(ctx, Point::ORIGIN);
+                ctx.set_baseline_offset(inner.baseline_offset());
+                size
+            }
             None => bc.constrain((self.width.unwrap_or(0.0), self.height.unwrap_or(0.0))),
         };
 
@@ -157,6 +167,46 @@ impl RenderObjectInterface for SizedBox {
         size
     }
 
+    fn compute_min_intrinsic_width(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        height: f64,
+        children: &mut Children,
+    ) -> f64 {
+        let height = fixed(self.height).unwrap_or(height);
+        fixed(self.width).unwrap_or_else(|| children.min_intrinsic_width(ctx, height))
+    }
+
+    fn compute_max_intrinsic_width(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        height: f64,
+        children: &mut Children,
+    ) -> f64 {
+        let height = fixed(self.height).unwrap_or(height);
+        fixed(self.width).unwrap_or_else(|| children.max_intrinsic_width(ctx, height))
+    }
+
+    fn compute_min_intrinsic_height(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        width: f64,
+        children: &mut Children,
+    ) -> f64 {
+        let width = fixed(self.width).unwrap_or(width);
+        fixed(self.height).unwrap_or_else(|| children.min_intrinsic_height(ctx, width))
+    }
+
+    fn compute_max_intrinsic_height(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        width: f64,
+        children: &mut Children,
+    ) -> f64 {
+        let width = fixed(self.width).unwrap_or(width);
+        fixed(self.height).unwrap_or_else(|| children.max_intrinsic_height(ctx, width))
+    }
+
     fn paint(&mut self, ctx: &mut PaintCtx, children: &mut Children) {
         if !children.is_empty() {
             children[0].paint(ctx);
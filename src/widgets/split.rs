@@ -0,0 +1,361 @@
+//! Two panes separated by a draggable sash.
+
+use crate::{
+    event::Gesture,
+    kurbo::{Line, Point, Rect, Vec2},
+    object::prelude::*,
+    widgets::flex::Axis,
+};
+use druid::Cursor;
+
+const SASH_WIDTH: f64 = 6.0;
+
+/// Two panes next to each other, resized by dragging the sash between them.
+///
+/// The split fraction is the share of the first pane, it is returned from
+/// [`build`](Split::build) whenever the user changes it, so it can be persisted
+/// and passed back with [`fraction`](Split::fraction).
+/// Double clicking the sash resets it to the [`default_fraction`](Split::default_fraction).
+///
+/// ```ignore
+/// let fraction = ui.use_state(|| settings.sidebar_fraction);
+/// if let Some(changed) = Split::new(Axis::Horizontal)
+///     .fraction(*fraction.get())
+///     .min_sizes(150.0, 300.0)
+///     .build(ui, |ui| sidebar(ui), |ui| editor(ui))
+/// {
+///     fraction.set(changed);
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Split {
+    axis: Axis,
+    fraction: Option<f64>,
+    default_fraction: f64,
+    min_sizes: (f64, f64),
+}
+
+impl Properties for Split {
+    type Object = SplitObject;
+}
+
+impl Split {
+    /// Panes next to each other along `axis`, so [`Axis::Horizontal`]
+    /// puts the first pane left of the second.
+    pub fn new(axis: Axis) -> Self {
+        Split {
+            axis,
+            fraction: None,
+            default_fraction: 0.5,
+            min_sizes: (0.0, 0.0),
+        }
+    }
+
+    /// Set the split fraction, for example to restore a persisted one.
+    ///
+    /// It is applied whenever it changes, dragging the sash changes the
+    /// fraction until a different one is passed.
+    pub fn fraction(mut self, fraction: f64) -> Self {
+        self.fraction = Some(fraction);
+        self
+    }
+
+    /// The fraction the split starts with and is reset to with a double click.
+    ///
+    /// The default value is `0.5`.
+    pub fn default_fraction(mut self, fraction: f64) -> Self {
+        self.default_fraction = fraction;
+        self
+    }
+
+    /// The sizes along the axis that the panes are never shrunk below,
+    /// unless there is not enough space for both.
+    ///
+    /// The default value is `(0.0, 0.0)`.
+    pub fn min_sizes(mut self, first: f64, second: f64) -> Self {
+        self.min_sizes = (first, second);
+        self
+    }
+
+    /// Build the panes, returning the new fraction if the user moved the sash.
+    #[track_caller]
+    pub fn build(
+        self,
+        ui: &mut Ui,
+        first: impl FnOnce(&mut Ui),
+        second: impl FnOnce(&mut Ui),
+    ) -> Option<f64> {
+        let caller = Location::caller().into();
+        ui.render_object(caller, self, |ui| {
+            SplitPane.build(ui, first);
+            SplitPane.build(ui, second);
+        })
+    }
+}
+
+/// Limit `fraction` so both panes get their minimum size out of `total`,
+/// which is the size along the axis without the sash.
+///
+/// If both minimums do not fit, the first pane wins.
+fn clamp_fraction(fraction: f64, total: f64, min_sizes: (f64, f64)) -> f64 {
+    if total <= 0.0 {
+        return fraction.clamp(0.0, 1.0);
+    }
+    let max = ((total - min_sizes.1) / total).max(0.0);
+    let min = (min_sizes.0 / total).min(1.0);
+    fraction.min(max).max(min).clamp(0.0, 1.0)
+}
+
+pub struct SplitObject {
+    props: Split,
+    fraction: f64,
+    /// The size along the axis without the sash, from the last layout.
+    total: f64,
+    /// Where the sash was grabbed, relative to its start.
+    grab: Option<f64>,
+    changed: bool,
+}
+
+impl SplitObject {
+    fn sash_rect(&self, size: Size) -> Rect {
+        let start = (self.total * self.fraction).round();
+        let (x, y) = self.props.axis.pack(start, 0.0);
+        let (width, height) = self
+            .props
+            .axis
+            .pack(SASH_WIDTH, self.props.axis.minor(size));
+        Rect::from_origin_size((x, y), (width, height))
+    }
+
+    fn cursor(&self) -> Cursor {
+        match self.props.axis {
+            Axis::Horizontal => Cursor::ResizeLeftRight,
+            Axis::Vertical => Cursor::ResizeUpDown,
+        }
+    }
+}
+
+impl RenderObject<Split> for SplitObject {
+    type Action = Option<f64>;
+
+    fn create(props: Split) -> Self {
+        SplitObject {
+            fraction: props.fraction.unwrap_or(props.default_fraction),
+            props,
+            total: 0.0,
+            grab: None,
+            changed: false,
+        }
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, props: Split) -> Self::Action {
+        if self.props != props {
+            if let Some(fraction) = props.fraction {
+                if props.fraction != self.props.fraction {
+                    self.fraction = fraction;
+                }
+            }
+            self.props = props;
+            ctx.request_layout();
+        }
+        std::mem::take(&mut self.changed).then_some(self.fraction)
+    }
+}
+
+impl RenderObjectInterface for SplitObject {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, children: &mut Children) {
+        let axis = self.props.axis;
+        match event {
+            Event::MouseDown(mouse) if self.sash_rect(ctx.size()).contains(mouse.pos) => {
+                let start = axis.major_pos(self.sash_rect(ctx.size()).origin());
+                self.grab = Some(axis.major_pos(mouse.pos) - start);
+                ctx.capture_pointer();
+                ctx.set_handled();
+                return;
+            }
+            Event::MouseMove(mouse) if ctx.is_active() => {
+                if let Some(grab) = self.grab {
+                    let start = axis.major_pos(mouse.pos) - grab;
+                    let fraction = if self.total > 0.0 {
+                        clamp_fraction(start / self.total, self.total, self.props.min_sizes)
+                    } else {
+                        self.fraction
+                    };
+                    if fraction != self.fraction {
+                        self.fraction = fraction;
+                        self.changed = true;
+                        ctx.request_update();
+                        ctx.request_layout();
+                    }
+                }
+                ctx.set_cursor(&self.cursor());
+                ctx.set_handled();
+                return;
+            }
+            Event::MouseMove(mouse) if ctx.is_hot() => {
+                if self.sash_rect(ctx.size()).contains(mouse.pos) {
+                    ctx.set_cursor(&self.cursor());
+                } else {
+                    ctx.clear_cursor();
+                }
+                ctx.request_paint();
+            }
+            Event::MouseUp(_) if ctx.is_active() => {
+                self.grab = None;
+                ctx.release_pointer();
+                ctx.request_paint();
+                ctx.set_handled();
+                return;
+            }
+            _ => {}
+        }
+        if let Some(Gesture::DoubleClick(pos)) = Gesture::from_event(event) {
+            if self.sash_rect(ctx.size()).contains(*pos) {
+                self.fraction = clamp_fraction(
+                    self.props.default_fraction,
+                    self.total,
+                    self.props.min_sizes,
+                );
+                self.changed = true;
+                ctx.request_update();
+                ctx.request_layout();
+                ctx.set_handled();
+                return;
+            }
+        }
+        for child in children {
+            child.event(ctx, event);
+        }
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        if let LifeCycle::HotChanged(_) = event {
+            ctx.request_paint();
+        }
+    }
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        children: &mut Children,
+    ) -> Size {
+        bc.debug_check("Split");
+        let axis = self.props.axis;
+        let major = axis.major(bc.max());
+        if !major.is_finite() {
+            ctx.report_layout_issue("A split needs a bounded size along its axis");
+        }
+        let major = if major.is_finite() {
+            major
+        } else {
+            self.props.min_sizes.0 + self.props.min_sizes.1 + SASH_WIDTH
+        };
+
+        self.total = (major - SASH_WIDTH).max(0.0);
+        self.fraction = clamp_fraction(self.fraction, self.total, self.props.min_sizes);
+        let first = (self.total * self.fraction).round();
+        let second = self.total - first;
+
+        let mut minor: f64 = axis.minor(bc.min());
+        let first_bc = axis.constraints(bc, first, first);
+        let first_size = children[0].layout(ctx, &first_bc);
+        children[0].set_origin(ctx, Point::ORIGIN);
+        minor = minor.max(axis.minor(first_size));
+
+        let second_bc = axis.constraints(bc, second, second);
+        let second_size = children[1].layout(ctx, &second_bc);
+        children[1].set_origin(ctx, Point::from(axis.pack(first + SASH_WIDTH, 0.0)));
+        minor = minor.max(axis.minor(second_size));
+
+        bc.constrain(Size::from(axis.pack(major, minor)))
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, children: &mut Children) {
+        for child in &mut *children {
+            child.paint(ctx);
+        }
+
+        let sash = self.sash_rect(ctx.size());
+        let theme = ctx.theme();
+        let color = if ctx.is_active() {
+            theme.colors.accent.clone()
+        } else {
+            theme.colors.panel_border.clone()
+        };
+        // The edges of the line are snapped to physical pixels.
+        let line_width = ctx.snap_width(1.0);
+        let offset = Vec2::new(line_width / 2.0, line_width / 2.0);
+        let center = ctx.snap_point(sash.center() - offset) + offset;
+        let line = match self.props.axis {
+            Axis::Horizontal => Line::new((center.x, sash.y0), (center.x, sash.y1)),
+            Axis::Vertical => Line::new((sash.x0, center.y), (sash.x1, center.y)),
+        };
+        ctx.stroke(line, &color, line_width);
+    }
+}
+
+/// Holds the content of a pane.
+struct SplitPane;
+
+impl Properties for SplitPane {
+    type Object = SplitPane;
+}
+
+impl SplitPane {
+    #[track_caller]
+    fn build(self, ui: &mut Ui, content: impl FnOnce(&mut Ui)) {
+        let caller = Location::caller().into();
+        ui.render_object(caller, self, content);
+    }
+}
+
+impl RenderObject<SplitPane> for SplitPane {
+    type Action = ();
+
+    fn create(props: SplitPane) -> Self {
+        props
+    }
+
+    fn update(&mut self, _ctx: &mut UpdateCtx, _props: SplitPane) {}
+}
+
+impl RenderObjectInterface for SplitPane {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, children: &mut Children) {
+        for child in children {
+            child.event(ctx, event);
+        }
+    }
+
+    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle) {}
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        children: &mut Children,
+    ) -> Size {
+        children.layout_stacked(ctx, bc)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, children: &mut Children) {
+        for child in children {
+            child.paint(ctx);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clamp_to_min_sizes() {
+        assert_eq!(clamp_fraction(0.5, 400.0, (0.0, 0.0)), 0.5);
+        assert_eq!(clamp_fraction(0.1, 400.0, (100.0, 0.0)), 0.25);
+        assert_eq!(clamp_fraction(0.9, 400.0, (0.0, 100.0)), 0.75);
+        // The first pane wins when both do not fit.
+        assert_eq!(clamp_fraction(0.5, 100.0, (80.0, 80.0)), 0.8);
+        assert_eq!(clamp_fraction(1.5, 0.0, (80.0, 80.0)), 1.0);
+    }
+}
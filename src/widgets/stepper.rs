@@ -0,0 +1,239 @@
+//! A numeric text field with increment and decrement buttons.
+
+use crate::{
+    kurbo::Point,
+    object::prelude::*,
+    widgets::{Button, RepeatBehavior, TextBox},
+};
+use std::{cell::Cell, ops::RangeInclusive, rc::Rc};
+
+/// A text field for numbers, with buttons to step the value up and down.
+///
+/// The mouse wheel steps the value while the pointer is over the stepper.
+/// Typed values are clamped to the range, text that is not a number is
+/// ignored and replaced with the value when the text box is activated.
+///
+/// ```ignore
+/// let volume = ui.use_state(|| 50.0);
+/// let value = Stepper::new(volume.get()).range(0.0..=100.0).step(5.0).build(ui);
+/// volume.set(value);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stepper {
+    value: f64,
+    range: RangeInclusive<f64>,
+    step: f64,
+}
+
+struct StepperState {
+    text: String,
+    /// The value the text was last synced with.
+    value: f64,
+}
+
+impl Stepper {
+    pub fn new(value: f64) -> Self {
+        Stepper {
+            value,
+            range: f64::NEG_INFINITY..=f64::INFINITY,
+            step: 1.0,
+        }
+    }
+
+    /// The values that can be entered.
+    ///
+    /// By default, the value is unbounded.
+    pub fn range(mut self, range: RangeInclusive<f64>) -> Self {
+        self.range = range;
+        self
+    }
+
+    /// How much the buttons and the mouse wheel change the value.
+    ///
+    /// The value is shown with as many decimals as the step has.
+    /// The default value is `1.0`.
+    pub fn step(mut self, step: f64) -> Self {
+        self.step = step;
+        self
+    }
+
+    /// Returns the new value, which is the passed value unless the user changed it.
+    #[must_use]
+    #[track_caller]
+    pub fn build(self, ui: &mut Ui) -> f64 {
+        let caller = Location::caller().into();
+        let Stepper { value, range, step } = self;
+        let clamp = |value: f64| value.max(*range.start()).min(*range.end());
+        let decimals = decimals(step);
+        let mut result = value;
+        ui.state_node(
+            caller,
+            || StepperState {
+                text: format_value(value, decimals),
+                value,
+            },
+            |ui, state: &mut StepperState| {
+                if state.value != value {
+                    state.text = format_value(value, decimals);
+                    state.value = value;
+                }
+
+                let caller = Location::caller().into();
+                let wheel = Rc::new(Cell::new(0));
+                let area = StepperArea {
+                    steps: wheel.clone(),
+                };
+                ui.render_object(caller, area, |ui| {
+                    if wheel.get() != 0 {
+                        result = clamp(result + wheel.get() as f64 * step);
+                        state.text = format_value(result, decimals);
+                    }
+
+                    // The buttons are built first, so the text box shows their changes.
+                    let repeat = RepeatBehavior::default();
+                    if Button::new().repeat(repeat).labeled(ui, "−") {
+                        result = clamp(result - step);
+                        state.text = format_value(result, decimals);
+                    }
+                    if Button::new().repeat(repeat).labeled(ui, "+") {
+                        result = clamp(result + step);
+                        state.text = format_value(result, decimals);
+                    }
+
+                    let activated = TextBox::new(&mut state.text).build(ui);
+                    if let Ok(typed) = state.text.trim().parse::<f64>() {
+                        result = clamp(typed);
+                    }
+                    if activated {
+                        state.text = format_value(result, decimals);
+                    }
+                });
+                state.value = result;
+            },
+        );
+        result
+    }
+}
+
+/// The number of decimals needed to show multiples of `step`.
+fn decimals(step: f64) -> usize {
+    let text = step.abs().to_string();
+    match text.split_once('.') {
+        Some((_, fraction)) => fraction.len(),
+        None => 0,
+    }
+}
+
+fn format_value(value: f64, decimals: usize) -> String {
+    format!("{:.*}", decimals, value)
+}
+
+/// Lays out the text box and buttons in a row, and turns wheel events into steps.
+struct StepperArea {
+    /// Where the steps taken with the mouse wheel are passed to the build code.
+    steps: Rc<Cell<i32>>,
+}
+
+impl Properties for StepperArea {
+    type Object = StepperAreaObject;
+}
+
+#[derive(Default)]
+struct StepperAreaObject {
+    /// The steps taken with the mouse wheel since the last update.
+    steps: i32,
+}
+
+impl StepperAreaObject {
+    fn update_steps(&mut self, props: StepperArea) {
+        props.steps.set(std::mem::take(&mut self.steps));
+    }
+}
+
+impl RenderObject<StepperArea> for StepperAreaObject {
+    type Action = ();
+
+    fn create(props: StepperArea) -> Self {
+        let mut object = StepperAreaObject::default();
+        object.update_steps(props);
+        object
+    }
+
+    fn update(&mut self, _ctx: &mut UpdateCtx, props: StepperArea) {
+        self.update_steps(props);
+    }
+}
+
+impl RenderObjectInterface for StepperAreaObject {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, children: &mut Children) {
+        if let Event::Wheel(wheel) = event {
+            if ctx.is_hot() && wheel.wheel_delta.y != 0.0 {
+                // Scrolling up increases the value.
+                self.steps -= wheel.wheel_delta.y.signum() as i32;
+                ctx.request_update();
+                ctx.set_handled();
+                return;
+            }
+        }
+        for child in children {
+            child.event(ctx, event);
+        }
+    }
+
+    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle) {}
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        children: &mut Children,
+    ) -> Size {
+        bc.debug_check("Stepper");
+
+        // The buttons keep their size, the text box gets the remaining width.
+        let mut buttons = Size::ZERO;
+        for child in children.iter().take(2) {
+            let size = child.layout(ctx, &bc.loosen());
+            buttons.width += size.width;
+            buttons.height = buttons.height.max(size.height);
+        }
+        let text_bc = BoxConstraints::new(
+            Size::ZERO,
+            Size::new((bc.max().width - buttons.width).max(0.0), bc.max().height),
+        );
+        let text = children[2].layout(ctx, &text_bc);
+        let height = text.height.max(buttons.height);
+
+        // The text box comes first, followed by the buttons.
+        let mut x = 0.0;
+        for index in [2, 0, 1] {
+            let child = &mut children[index];
+            let size = child.layout_rect().size();
+            child.set_origin(ctx, Point::new(x, (height - size.height) / 2.0));
+            x += size.width;
+        }
+        let baseline = children[2].baseline_offset();
+        ctx.set_baseline_offset(baseline + (height - text.height) / 2.0);
+        bc.constrain(Size::new(x, height))
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, children: &mut Children) {
+        for child in children {
+            child.paint(ctx);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decimals_of_step() {
+        assert_eq!(decimals(1.0), 0);
+        assert_eq!(decimals(0.5), 1);
+        assert_eq!(decimals(0.25), 2);
+        assert_eq!(format_value(2.5, decimals(0.25)), "2.50");
+        assert_eq!(format_value(3.0, decimals(1.0)), "3");
+    }
+}
@@ -0,0 +1,518 @@
+//! A widget that displays vector graphics from SVG documents.
+//!
+//! Requires the `svg` feature. The documents are parsed by coat itself,
+//! which supports the subset of SVG that icons use, see [`SvgDocument`].
+
+use crate::{
+    kurbo::{Affine, BezPath, Circle, Ellipse, Line, Point, Rect, Shape, Vec2},
+    object::prelude::*,
+    piet::{Color, Piet},
+};
+use std::{
+    error::Error,
+    fmt,
+    rc::Rc,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+/// Identifies documents in the raster cache.
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// How a shape is filled or stroked.
+#[derive(Debug, Clone, PartialEq)]
+enum Paint {
+    Color(Color),
+    /// `currentColor`, the tint of the widget.
+    Current,
+}
+
+#[derive(Debug, Clone)]
+struct Style {
+    fill: Option<Paint>,
+    stroke: Option<Paint>,
+    stroke_width: f64,
+    even_odd: bool,
+    transform: Affine,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Style {
+            fill: Some(Paint::Color(Color::BLACK)),
+            stroke: None,
+            stroke_width: 1.0,
+            even_odd: false,
+            transform: Affine::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct SvgShape {
+    path: BezPath,
+    style: Style,
+}
+
+/// A parsed SVG document, see [`Svg`].
+///
+/// Only a subset of SVG is supported, which covers typical icons: the
+/// `path`, `rect`, `circle`, `ellipse`, `line`, `polyline` and `polygon`
+/// elements, nested in `g` elements, with `fill`, `stroke`, `stroke-width`,
+/// `fill-rule` and `transform` as attributes or in `style`. Colors are
+/// hex colors, `none`, `currentColor`, `black` and `white`. Everything else,
+/// like gradients, text and CSS, is ignored.
+#[derive(Debug)]
+pub struct SvgDocument {
+    id: u64,
+    view_box: Rect,
+    shapes: Vec<SvgShape>,
+}
+
+/// The reason an SVG document could not be parsed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SvgError {
+    message: String,
+}
+
+impl fmt::Display for SvgError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid SVG: {}", self.message)
+    }
+}
+
+impl Error for SvgError {}
+
+fn error(message: impl Into<String>) -> SvgError {
+    SvgError {
+        message: message.into(),
+    }
+}
+
+impl SvgDocument {
+    pub fn parse(text: &str) -> Result<Self, SvgError> {
+        let mut view_box = None;
+        let mut shapes = Vec::new();
+        // The styles of the open elements, inherited by their children.
+        let mut styles = vec![Style::default()];
+
+        let mut rest = text;
+        while let Some(start) = rest.find('<') {
+            rest = &rest[start + 1..];
+            if let Some(comment) = rest.strip_prefix("!--") {
+                let end = comment
+                    .find("-->")
+                    .ok_or_else(|| error("Unclosed comment"))?;
+                rest = &comment[end + 3..];
+                continue;
+            }
+            let end = rest.find('>').ok_or_else(|| error("Unclosed tag"))?;
+            let tag = &rest[..end];
+            rest = &rest[end + 1..];
+            if tag.starts_with('?') || tag.starts_with('!') {
+                continue;
+            }
+            if tag.starts_with('/') {
+                if styles.len() > 1 {
+                    styles.pop();
+                }
+                continue;
+            }
+            let self_closing = tag.ends_with('/');
+            let tag = tag.trim_end_matches('/');
+            let name = tag.split_whitespace().next().unwrap_or_default();
+            let attributes = parse_attributes(&tag[name.len()..])?;
+            let attribute = |key: &str| {
+                attributes
+                    .iter()
+                    .find(|(name, _)| *name == key)
+                    .map(|(_, value)| value.as_str())
+            };
+
+            let parent = styles.last().cloned().unwrap_or_default();
+            let style = apply_style(parent, &attributes)?;
+            if name == "svg" && view_box.is_none() {
+                view_box = Some(parse_view_box(attribute("viewBox"), &attribute)?);
+            }
+            if let Some(path) = element_path(name, &attribute)? {
+                shapes.push(SvgShape {
+                    path,
+                    style: style.clone(),
+                });
+            }
+            if !self_closing {
+                styles.push(style);
+            }
+        }
+
+        Ok(SvgDocument {
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            view_box: view_box.ok_or_else(|| error("No svg element"))?,
+            shapes,
+        })
+    }
+
+    /// The size of the document, from its `width` and `height` or its `viewBox`.
+    pub fn size(&self) -> Size {
+        self.view_box.size()
+    }
+
+    /// Draw the document scaled to `size`, keeping its aspect ratio.
+    fn draw(&self, render_ctx: &mut Piet, size: Size, tint: &Color) {
+        let view_box = self.view_box;
+        if view_box.area() == 0.0 {
+            return;
+        }
+        let scale = (size.width / view_box.width()).min(size.height / view_box.height());
+        let offset = Vec2::new(
+            (size.width - view_box.width() * scale) / 2.0,
+            (size.height - view_box.height() * scale) / 2.0,
+        );
+        let fit = Affine::translate(offset)
+            * Affine::scale(scale)
+            * Affine::translate(-view_box.origin().to_vec2());
+        for shape in &self.shapes {
+            let style = &shape.style;
+            let path = (fit * style.transform) * shape.path.clone();
+            let color = |paint: &Paint| match paint {
+                Paint::Color(color) => color.clone(),
+                Paint::Current => tint.clone(),
+            };
+            if let Some(fill) = &style.fill {
+                if style.even_odd {
+                    render_ctx.fill_even_odd(&path, &color(fill));
+                } else {
+                    render_ctx.fill(&path, &color(fill));
+                }
+            }
+            if let Some(stroke) = &style.stroke {
+                // Strokes scale with the transform of the shape.
+                let width = style.stroke_width * scale * style.transform.determinant().abs().sqrt();
+                render_ctx.stroke(&path, &color(stroke), width);
+            }
+        }
+    }
+}
+
+fn parse_attributes(text: &str) -> Result<Vec<(&str, String)>, SvgError> {
+    let mut attributes = Vec::new();
+    let mut rest = text.trim_start();
+    while !rest.is_empty() {
+        let eq = rest
+            .find('=')
+            .ok_or_else(|| error("Attribute without value"))?;
+        let name = rest[..eq].trim();
+        let value = rest[eq + 1..].trim_start();
+        let quote = value.chars().next().filter(|c| *c == '"' || *c == '\'');
+        let quote = quote.ok_or_else(|| error("Unquoted attribute value"))?;
+        let value = &value[1..];
+        let end = value
+            .find(quote)
+            .ok_or_else(|| error("Unclosed attribute value"))?;
+        attributes.push((name, value[..end].to_string()));
+        rest = value[end + 1..].trim_start();
+    }
+    Ok(attributes)
+}
+
+/// The style of an element with `attributes`, inheriting from `parent`.
+fn apply_style(parent: Style, attributes: &[(&str, String)]) -> Result<Style, SvgError> {
+    let mut style = parent;
+    // Transforms compose with the ones of the parents.
+    let declarations = attributes
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.clone()))
+        .chain(
+            attributes
+                .iter()
+                .filter(|(name, _)| *name == "style")
+                .flat_map(|(_, value)| {
+                    value
+                        .split(';')
+                        .filter_map(|declaration| declaration.split_once(':'))
+                        .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+                        .collect::<Vec<_>>()
+                }),
+        );
+    for (name, value) in declarations {
+        match name.as_str() {
+            "fill" => style.fill = parse_paint(&value)?,
+            "stroke" => style.stroke = parse_paint(&value)?,
+            "stroke-width" => style.stroke_width = parse_length(&value)?,
+            "fill-rule" => style.even_odd = value == "evenodd",
+            "transform" => style.transform *= parse_transform(&value)?,
+            _ => {}
+        }
+    }
+    Ok(style)
+}
+
+fn parse_paint(value: &str) -> Result<Option<Paint>, SvgError> {
+    let paint = match value.trim() {
+        "none" => return Ok(None),
+        "currentColor" => Paint::Current,
+        "black" => Paint::Color(Color::BLACK),
+        "white" => Paint::Color(Color::WHITE),
+        hex if hex.starts_with('#') => {
+            let hex = &hex[1..];
+            let hex = match hex.len() {
+                3 => hex.chars().flat_map(|c| [c, c]).collect(),
+                6 => hex.to_string(),
+                _ => return Err(error(format!("Unsupported color {}", value))),
+            };
+            let rgb = u32::from_str_radix(&hex, 16)
+                .map_err(|_| error(format!("Invalid color {}", value)))?;
+            Paint::Color(Color::from_rgba32_u32(rgb << 8 | 0xff))
+        }
+        _ => return Err(error(format!("Unsupported color {}", value))),
+    };
+    Ok(Some(paint))
+}
+
+fn parse_length(value: &str) -> Result<f64, SvgError> {
+    value
+        .trim()
+        .trim_end_matches("px")
+        .parse()
+        .map_err(|_| error(format!("Invalid length {}", value)))
+}
+
+fn parse_numbers(value: &str) -> Result<Vec<f64>, SvgError> {
+    value
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|number| !number.is_empty())
+        .map(parse_length)
+        .collect()
+}
+
+fn parse_transform(value: &str) -> Result<Affine, SvgError> {
+    let mut transform = Affine::default();
+    let mut rest = value.trim();
+    while !rest.is_empty() {
+        let open = rest.find('(').ok_or_else(|| error("Invalid transform"))?;
+        let close = rest.find(')').ok_or_else(|| error("Invalid transform"))?;
+        let name = rest[..open].trim_matches(|c: char| c == ',' || c.is_whitespace());
+        let args = parse_numbers(&rest[open + 1..close])?;
+        let arg = |ix: usize| args.get(ix).copied();
+        let next = match (name, args.len()) {
+            ("matrix", 6) => Affine::new([args[0], args[1], args[2], args[3], args[4], args[5]]),
+            ("translate", 1..=2) => Affine::translate((args[0], arg(1).unwrap_or(0.0))),
+            ("scale", 1..=2) => Affine::scale_non_uniform(args[0], arg(1).unwrap_or(args[0])),
+            ("rotate", 1) => Affine::rotate(args[0].to_radians()),
+            ("rotate", 3) => {
+                let center = Vec2::new(args[1], args[2]);
+                Affine::translate(center)
+                    * Affine::rotate(args[0].to_radians())
+                    * Affine::translate(-center)
+            }
+            _ => return Err(error(format!("Unsupported transform {}", name))),
+        };
+        transform *= next;
+        rest = rest[close + 1..].trim_start();
+    }
+    Ok(transform)
+}
+
+fn parse_view_box<'a>(
+    view_box: Option<&str>,
+    attribute: &impl Fn(&str) -> Option<&'a str>,
+) -> Result<Rect, SvgError> {
+    if let Some(view_box) = view_box {
+        match parse_numbers(view_box)?[..] {
+            [x, y, width, height] => return Ok(Rect::new(x, y, x + width, y + height)),
+            _ => return Err(error("Invalid viewBox")),
+        }
+    }
+    let width = attribute("width").map(parse_length).transpose()?;
+    let height = attribute("height").map(parse_length).transpose()?;
+    match (width, height) {
+        (Some(width), Some(height)) => Ok(Rect::new(0.0, 0.0, width, height)),
+        _ => Err(error("Neither a viewBox nor a size")),
+    }
+}
+
+/// The outline of a shape element, `None` for other elements.
+fn element_path<'a>(
+    name: &str,
+    attribute: &impl Fn(&str) -> Option<&'a str>,
+) -> Result<Option<BezPath>, SvgError> {
+    let number =
+        |key: &str| -> Result<f64, SvgError> { attribute(key).map_or(Ok(0.0), parse_length) };
+    let path = match name {
+        "path" => {
+            let data = attribute("d").unwrap_or_default();
+            BezPath::from_svg(data).map_err(|err| error(format!("Invalid path: {}", err)))?
+        }
+        "rect" => {
+            let rect = Rect::new(0.0, 0.0, number("width")?, number("height")?)
+                + Vec2::new(number("x")?, number("y")?);
+            let radius = number("rx")?.max(number("ry")?);
+            rect.to_rounded_rect(radius).to_path(0.1)
+        }
+        "circle" => Circle::new((number("cx")?, number("cy")?), number("r")?).to_path(0.1),
+        "ellipse" => {
+            let radii = Vec2::new(number("rx")?, number("ry")?);
+            Ellipse::new((number("cx")?, number("cy")?), radii, 0.0).to_path(0.1)
+        }
+        "line" => Line::new(
+            (number("x1")?, number("y1")?),
+            (number("x2")?, number("y2")?),
+        )
+        .to_path(0.1),
+        "polyline" | "polygon" => {
+            let numbers = parse_numbers(attribute("points").unwrap_or_default())?;
+            let mut path = BezPath::new();
+            for (ix, point) in numbers.chunks_exact(2).enumerate() {
+                let point = Point::new(point[0], point[1]);
+                if ix == 0 {
+                    path.move_to(point);
+                } else {
+                    path.line_to(point);
+                }
+            }
+            if name == "polygon" {
+                path.close_path();
+            }
+            path
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(path))
+}
+
+/// A widget that displays an [`SvgDocument`].
+///
+/// The document is scaled to the size of the widget, keeping its aspect
+/// ratio, and prefers the size of the document. It is rendered through the
+/// raster cache of the window for each size and scale factor, so icons stay
+/// crisp on every display and are cheap to repaint.
+///
+/// `currentColor` in the document is the text color of the theme, unless
+/// another [`color`](Svg::color) is set.
+pub struct Svg {
+    document: Rc<SvgDocument>,
+    color: Option<Color>,
+}
+
+impl Properties for Svg {
+    type Object = SvgObject;
+}
+
+impl Svg {
+    pub fn new(document: Rc<SvgDocument>) -> Self {
+        Svg {
+            document,
+            color: None,
+        }
+    }
+
+    /// The color of `currentColor` in the document.
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    #[track_caller]
+    pub fn build(self, ui: &mut Ui) {
+        let caller = Location::caller().into();
+        ui.render_object(caller, self, |_| {});
+    }
+}
+
+pub struct SvgObject {
+    document: Rc<SvgDocument>,
+    color: Option<Color>,
+}
+
+impl RenderObject<Svg> for SvgObject {
+    type Action = ();
+
+    fn create(props: Svg) -> Self {
+        SvgObject {
+            document: props.document,
+            color: props.color,
+        }
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, props: Svg) {
+        if !Rc::ptr_eq(&self.document, &props.document) {
+            if self.document.size() != props.document.size() {
+                ctx.request_layout();
+            }
+            self.document = props.document;
+            ctx.request_paint();
+        }
+        if self.color != props.color {
+            self.color = props.color;
+            ctx.request_paint();
+        }
+    }
+}
+
+impl RenderObjectInterface for SvgObject {
+    fn event(&mut self, _ctx: &mut EventCtx, _event: &Event, _children: &mut Children) {}
+
+    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle) {}
+
+    fn layout(
+        &mut self,
+        _ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        _children: &mut Children,
+    ) -> Size {
+        bc.debug_check("Svg");
+        bc.constrain(self.document.size())
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, _children: &mut Children) {
+        let color = match &self.color {
+            Some(color) => color.clone(),
+            None => ctx.theme().colors.text.clone(),
+        };
+        let rect = ctx.size().to_rect();
+        let document = &self.document;
+        ctx.paint_cached(document.id, rect, &color, |render_ctx, size, tint| {
+            document.draw(render_ctx, size, tint)
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_icon() {
+        let document = SvgDocument::parse(
+            r##"<?xml version="1.0"?>
+            <svg xmlns="http://www.w3.org/2000/svg" width="48" height="48" viewBox="0 0 24 24">
+                <!-- A check mark in a circle -->
+                <g fill="none" stroke="currentColor" transform="translate(1, 1)">
+                    <circle cx="11" cy="11" r="10"/>
+                    <path d="M6 11l3 3l6-6" style="stroke-width: 2"/>
+                </g>
+                <rect x="2" y="2" width="4" height="4" fill="#f00"/>
+            </svg>"##,
+        )
+        .unwrap();
+        assert_eq!(document.size(), Size::new(24.0, 24.0));
+        assert_eq!(document.shapes.len(), 3);
+
+        let circle = &document.shapes[0].style;
+        assert_eq!(circle.fill, None);
+        assert_eq!(circle.stroke, Some(Paint::Current));
+        assert_eq!(circle.transform, Affine::translate((1.0, 1.0)));
+        assert_eq!(document.shapes[1].style.stroke_width, 2.0);
+
+        let rect = &document.shapes[2];
+        assert_eq!(rect.style.fill, Some(Paint::Color(Color::rgb8(0xff, 0, 0))));
+        assert_eq!(rect.style.transform, Affine::default());
+        assert_eq!(rect.path.bounding_box(), Rect::new(2.0, 2.0, 6.0, 6.0));
+    }
+
+    #[test]
+    fn invalid_documents() {
+        assert!(SvgDocument::parse("<g></g>").is_err());
+        assert!(SvgDocument::parse(r#"<svg viewBox="0 0 1 1"><path fill=red/></svg>"#).is_err());
+        assert!(SvgDocument::parse(r#"<svg viewBox="0 0 1 1"><path fill="red"/></svg>"#).is_err());
+    }
+}
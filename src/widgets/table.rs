@@ -0,0 +1,543 @@
+//! A table of rows and columns that only builds the visible rows.
+
+use crate::{
+    kurbo::{Line, Point, Rect, Vec2},
+    object::prelude::*,
+    wheel::WheelScroll,
+    widgets::Label,
+};
+use druid::TimerToken;
+use std::{cell::Cell, rc::Rc, time::Duration};
+
+/// How the width of a [`Column`] is negotiated.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColumnWidth {
+    /// Always this wide.
+    Fixed(f64),
+    /// A share of the width left by the fixed columns, proportional to the factor.
+    Flex(f64),
+}
+
+/// A column of a [`Table`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Column {
+    title: String,
+    width: ColumnWidth,
+    min_width: f64,
+}
+
+impl Column {
+    /// A flexible column with `title` in the header row.
+    pub fn new(title: impl Into<String>) -> Self {
+        Column {
+            title: title.into(),
+            width: ColumnWidth::Flex(1.0),
+            min_width: 40.0,
+        }
+    }
+
+    pub fn fixed(mut self, width: f64) -> Self {
+        self.width = ColumnWidth::Fixed(width);
+        self
+    }
+
+    pub fn flex(mut self, factor: f64) -> Self {
+        self.width = ColumnWidth::Flex(factor);
+        self
+    }
+
+    /// The width flexible columns never shrink below.
+    ///
+    /// The default value is `40.0`.
+    pub fn min_width(mut self, width: f64) -> Self {
+        self.min_width = width;
+        self
+    }
+}
+
+/// Distribute `available` width to `columns`.
+///
+/// Fixed columns get their width, flexible columns share the rest according to
+/// their factors, but get at least their minimum width. If that does not fit,
+/// the columns are wider than `available` in total.
+fn column_widths(columns: &[Column], available: f64) -> Vec<f64> {
+    let mut widths: Vec<Option<f64>> = columns
+        .iter()
+        .map(|column| match column.width {
+            ColumnWidth::Fixed(width) => Some(width),
+            ColumnWidth::Flex(_) => None,
+        })
+        .collect();
+    // Columns whose share is below their minimum are fixed at the minimum,
+    // which leaves less for the others, until all shares fit.
+    loop {
+        let used: f64 = widths.iter().flatten().sum();
+        let remaining = (available - used).max(0.0);
+        let factors: f64 = columns
+            .iter()
+            .zip(&widths)
+            .filter(|(_, width)| width.is_none())
+            .map(|(column, _)| factor(column))
+            .sum();
+        let mut changed = false;
+        for (column, width) in columns.iter().zip(&mut widths) {
+            if width.is_none() && remaining * factor(column) / factors < column.min_width {
+                *width = Some(column.min_width);
+                changed = true;
+            }
+        }
+        if !changed {
+            return columns
+                .iter()
+                .zip(widths)
+                .map(|(column, width)| {
+                    width.unwrap_or_else(|| remaining * factor(column) / factors)
+                })
+                .collect();
+        }
+    }
+}
+
+/// The smallest change of `offset` that shows the content from `top` to
+/// `bottom` in a view of `height`, preferring the top if it does not fit.
+fn offset_to_view(offset: f64, height: f64, top: f64, bottom: f64) -> f64 {
+    if top < offset || bottom - top > height {
+        top
+    } else if bottom > offset + height {
+        bottom - height
+    } else {
+        offset
+    }
+}
+
+fn factor(column: &Column) -> f64 {
+    match column.width {
+        ColumnWidth::Flex(factor) => factor,
+        ColumnWidth::Fixed(_) => 0.0,
+    }
+}
+
+/// A table with a header row, whose rows scroll vertically.
+///
+/// Only the rows that are visible are built, so tables with many rows
+/// are as cheap as the rows on screen. All rows have the same height.
+/// Cells that receive focus or call [`EventCtx::scroll_to_view`] are
+/// scrolled into view.
+///
+/// ```ignore
+/// Table::new(people.len())
+///     .column(Column::new("Name").flex(2.0))
+///     .column(Column::new("Age").fixed(60.0))
+///     .build(ui, |ui, row, column| match column {
+///         0 => Label::new(&people[row].name).build(ui),
+///         _ => Label::new(people[row].age.to_string()).build(ui),
+///     });
+/// ```
+pub struct Table {
+    rows: usize,
+    columns: Vec<Column>,
+    row_height: f64,
+    /// Where the table tells the build code which rows are visible.
+    visible: Rc<Cell<(usize, usize)>>,
+}
+
+impl Properties for Table {
+    type Object = TableObject;
+}
+
+impl Table {
+    pub fn new(rows: usize) -> Self {
+        Table {
+            rows,
+            columns: Vec::new(),
+            row_height: 24.0,
+            visible: Rc::new(Cell::new((0, 0))),
+        }
+    }
+
+    pub fn column(mut self, column: Column) -> Self {
+        self.columns.push(column);
+        self
+    }
+
+    /// The height of every row.
+    ///
+    /// The default value is `24.0`.
+    pub fn row_height(mut self, height: f64) -> Self {
+        self.row_height = height;
+        self
+    }
+
+    /// Build the visible rows, with `cell` building the content of each cell.
+    ///
+    /// `cell` is called with the row and the column index.
+    #[track_caller]
+    pub fn build(self, ui: &mut Ui, mut cell: impl FnMut(&mut Ui, usize, usize)) {
+        let caller = Location::caller().into();
+        let visible = self.visible.clone();
+        let titles: Vec<_> = self.columns.iter().map(|c| c.title.clone()).collect();
+        ui.render_object(caller, self, |ui| {
+            for (column, title) in titles.iter().enumerate() {
+                ui.keyed(column, |ui| {
+                    let header = TableCell { row: None, column };
+                    header.build(ui, |ui| Label::new(title.as_str()).build(ui));
+                });
+            }
+            let (start, end) = visible.get();
+            for row in start..end {
+                for column in 0..titles.len() {
+                    ui.keyed((row, column), |ui| {
+                        let cell_props = TableCell {
+                            row: Some(row),
+                            column,
+                        };
+                        cell_props.build(ui, |ui| cell(ui, row, column));
+                    });
+                }
+            }
+        });
+    }
+}
+
+pub struct TableObject {
+    rows: usize,
+    columns: Vec<Column>,
+    row_height: f64,
+    /// How far the rows are scrolled.
+    offset: f64,
+    scroll: WheelScroll<f64>,
+    /// The height of the area below the header row, from the last layout.
+    body_height: Option<f64>,
+    header_height: f64,
+    /// The rows that were built.
+    built: (usize, usize),
+    /// A timer to build more rows, when the table grew.
+    grow_timer: Option<TimerToken>,
+}
+
+impl TableObject {
+    /// The rows that need to be built for the current offset.
+    ///
+    /// Until the height of the table is known, it is assumed to be as high as the window.
+    fn visible_rows(&self, window_height: f64) -> (usize, usize) {
+        if self.row_height <= 0.0 {
+            return (0, 0);
+        }
+        let height = self.body_height.unwrap_or(window_height);
+        let start = (self.offset / self.row_height).floor() as usize;
+        let end = ((self.offset + height) / self.row_height).ceil() as usize;
+        (start.min(self.rows), end.min(self.rows))
+    }
+
+    fn max_offset(&self) -> f64 {
+        let content = self.rows as f64 * self.row_height;
+        (content - self.body_height.unwrap_or(0.0)).max(0.0)
+    }
+
+    fn set_offset(&mut self, ctx: &mut EventCtx, offset: f64) {
+        let offset = offset.clamp(0.0, self.max_offset());
+        if offset != self.offset {
+            self.offset = offset;
+            ctx.request_layout();
+            ctx.request_paint();
+            if self.visible_rows(ctx.window_size().height) != self.built {
+                ctx.request_update();
+            }
+        }
+    }
+}
+
+impl RenderObject<Table> for TableObject {
+    type Action = ();
+
+    fn create(props: Table) -> Self {
+        TableObject {
+            rows: props.rows,
+            columns: props.columns,
+            row_height: props.row_height,
+            offset: 0.0,
+            scroll: WheelScroll::new(),
+            body_height: None,
+            header_height: 0.0,
+            built: (0, 0),
+            grow_timer: None,
+        }
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, props: Table) {
+        if (self.rows, &self.columns, self.row_height)
+            != (props.rows, &props.columns, props.row_height)
+        {
+            self.rows = props.rows;
+            self.columns = props.columns;
+            self.row_height = props.row_height;
+            self.offset = self.offset.min(self.max_offset());
+            ctx.request_layout();
+        }
+        self.built = self.visible_rows(ctx.window_size().height);
+        props.visible.set(self.built);
+    }
+}
+
+impl RenderObjectInterface for TableObject {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, children: &mut Children) {
+        match event {
+            Event::Wheel(wheel) if ctx.is_hot() => {
+                let target = self.scroll.target(self.offset) + wheel.wheel_delta.y;
+                let target = target.clamp(0.0, self.max_offset());
+                let offset = self.scroll.scroll(ctx, self.offset, target);
+                self.set_offset(ctx, offset);
+                ctx.set_handled();
+                return;
+            }
+            Event::AnimFrame(_) => {
+                if let Some(offset) = self.scroll.anim_frame(ctx, event) {
+                    self.set_offset(ctx, offset);
+                }
+            }
+            Event::Timer(token) if Some(*token) == self.grow_timer => {
+                self.grow_timer = None;
+                ctx.request_update();
+                return;
+            }
+            _ => {}
+        }
+        for child in children {
+            child.event(ctx, event);
+        }
+    }
+
+    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle) {}
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        children: &mut Children,
+    ) -> Size {
+        bc.debug_check("Table");
+        if !bc.max().height.is_finite() {
+            ctx.report_layout_issue("A table needs a bounded height to scroll its rows");
+        }
+
+        // Without a width to share, flexible columns get their minimum width.
+        let available = if bc.max().width.is_finite() {
+            bc.max().width
+        } else {
+            0.0
+        };
+        let widths = column_widths(&self.columns, available);
+        let mut lefts = Vec::with_capacity(widths.len());
+        let mut left = 0.0;
+        for width in &widths {
+            lefts.push(left);
+            left += width;
+        }
+        let content_width = left;
+        let cell_bc = |column: usize, height: f64| {
+            BoxConstraints::new(
+                Size::new(widths[column], 0.0),
+                Size::new(widths[column], height),
+            )
+        };
+
+        let mut header_height: f64 = 0.0;
+        for child in &mut *children {
+            let cell = cell_of(child);
+            match cell.row {
+                None => {
+                    let size = child.layout(ctx, &cell_bc(cell.column, f64::INFINITY));
+                    header_height = header_height.max(size.height);
+                }
+                Some(_) => {
+                    child.layout(ctx, &cell_bc(cell.column, self.row_height));
+                }
+            }
+        }
+        self.header_height = header_height;
+
+        let content_height = header_height + self.rows as f64 * self.row_height;
+        let size = bc.constrain(Size::new(content_width, content_height));
+        let body_height = (size.height - header_height).max(0.0);
+        self.body_height = Some(body_height);
+        self.offset = self.offset.min(self.max_offset());
+
+        // The rect was measured with the rows at the current offset.
+        if let Some(rect) = ctx.take_scroll_to_view() {
+            let top = rect.y0 - header_height + self.offset;
+            let bottom = rect.y1 - header_height + self.offset;
+            let offset = offset_to_view(self.offset, body_height, top, bottom);
+            let offset = offset.clamp(0.0, self.max_offset());
+            let moved = rect - Vec2::new(0.0, offset - self.offset);
+            if offset != self.offset {
+                self.scroll.stop();
+            }
+            self.offset = offset;
+            let body = Rect::new(0.0, header_height, size.width, size.height);
+            ctx.scroll_to_view(moved.intersect(body));
+        }
+
+        for child in children {
+            let cell = cell_of(child);
+            match cell.row {
+                None => {
+                    let height = child.layout_rect().height();
+                    let y = (header_height - height) / 2.0;
+                    child.set_origin(ctx, Point::new(lefts[cell.column], y));
+                }
+                Some(row) => {
+                    let child_size = child.layout_rect().size();
+                    let top = header_height + row as f64 * self.row_height - self.offset;
+                    let y = top + (self.row_height - child_size.height) / 2.0;
+                    child.set_origin(ctx, Point::new(lefts[cell.column], y));
+                }
+            }
+        }
+
+        // Build the rows that became visible when the table grew.
+        let visible = self.visible_rows(ctx.window_size().height);
+        if (visible.0 < self.built.0 || visible.1 > self.built.1) && self.grow_timer.is_none() {
+            self.grow_timer = Some(ctx.request_timer(Duration::ZERO));
+        }
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, children: &mut Children) {
+        let size = ctx.size();
+        let theme = ctx.theme();
+        let panel = theme.colors.panel.clone();
+        let border = theme.colors.panel_border.clone();
+        let header_height = self.header_height;
+
+        // The lines are drawn inside the rows, above their bottom edges.
+        let line_width = ctx.snap_width(1.0);
+        ctx.with_save(|ctx| {
+            let body = Rect::new(0.0, header_height, size.width, size.height);
+            ctx.clip(body);
+            // The lines below the rows.
+            let (start, end) = self.built;
+            for row in start..end {
+                let bottom = header_height + (row + 1) as f64 * self.row_height - self.offset;
+                let y = ctx.snap_point(Point::new(0.0, bottom)).y - line_width / 2.0;
+                ctx.stroke(Line::new((0.0, y), (size.width, y)), &border, line_width);
+            }
+            for child in &mut *children {
+                if cell_of(child).row.is_some() {
+                    child.paint(ctx);
+                }
+            }
+        });
+
+        let header = Rect::new(0.0, 0.0, size.width, header_height);
+        ctx.fill(header, &panel);
+        let y = ctx.snap_point(Point::new(0.0, header_height)).y - line_width / 2.0;
+        ctx.stroke(Line::new((0.0, y), (size.width, y)), &border, line_width);
+        for child in children {
+            if cell_of(child).row.is_none() {
+                child.paint(ctx);
+            }
+        }
+    }
+}
+
+fn cell_of(child: &mut Child) -> TableCellObject {
+    match child.object.as_any().downcast_ref::<TableCellObject>() {
+        Some(cell) => *cell,
+        None => unreachable!("Tables only contain cells"),
+    }
+}
+
+/// Holds the content of a cell, so the table knows where it belongs.
+struct TableCell {
+    /// `None` for the header row.
+    row: Option<usize>,
+    column: usize,
+}
+
+impl Properties for TableCell {
+    type Object = TableCellObject;
+}
+
+impl TableCell {
+    #[track_caller]
+    fn build(self, ui: &mut Ui, content: impl FnOnce(&mut Ui)) {
+        let caller = Location::caller().into();
+        ui.render_object(caller, self, content);
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct TableCellObject {
+    row: Option<usize>,
+    column: usize,
+}
+
+impl RenderObject<TableCell> for TableCellObject {
+    type Action = ();
+
+    fn create(props: TableCell) -> Self {
+        TableCellObject {
+            row: props.row,
+            column: props.column,
+        }
+    }
+
+    fn update(&mut self, _ctx: &mut UpdateCtx, props: TableCell) {
+        self.row = props.row;
+        self.column = props.column;
+    }
+}
+
+impl RenderObjectInterface for TableCellObject {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, children: &mut Children) {
+        for child in children {
+            child.event(ctx, event);
+        }
+    }
+
+    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle) {}
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        children: &mut Children,
+    ) -> Size {
+        children.layout_stacked(ctx, bc)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, children: &mut Children) {
+        for child in children {
+            child.paint(ctx);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn negotiate_column_widths() {
+        let columns = [
+            Column::new("a").fixed(100.0),
+            Column::new("b").flex(1.0),
+            Column::new("c").flex(3.0),
+        ];
+        assert_eq!(column_widths(&columns, 500.0), [100.0, 100.0, 300.0]);
+        // The first flexible column keeps its minimum width.
+        assert_eq!(column_widths(&columns, 220.0), [100.0, 40.0, 80.0]);
+        assert_eq!(column_widths(&columns, 100.0), [100.0, 40.0, 40.0]);
+    }
+
+    #[test]
+    fn scroll_rows_into_view() {
+        // Already visible.
+        assert_eq!(offset_to_view(100.0, 200.0, 150.0, 170.0), 100.0);
+        // Above and below the view.
+        assert_eq!(offset_to_view(100.0, 200.0, 50.0, 70.0), 50.0);
+        assert_eq!(offset_to_view(100.0, 200.0, 320.0, 340.0), 140.0);
+        // Too high for the view.
+        assert_eq!(offset_to_view(100.0, 200.0, 250.0, 500.0), 250.0);
+    }
+}
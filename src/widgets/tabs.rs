@@ -0,0 +1,537 @@
+//! A strip of tabs above the content of the selected tab.
+
+use crate::{
+    event::MouseButton,
+    kurbo::{Line, Point, Rect},
+    object::prelude::*,
+    widgets::Label,
+};
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+};
+
+const TAB_PADDING: f64 = 8.0;
+const CLOSE_SIZE: f64 = 12.0;
+
+/// What happened to the [`Tabs`] in the last event pass.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TabsAction {
+    /// The tab with this index was clicked.
+    Select(usize),
+    /// The close button of the tab with this index was clicked.
+    Close(usize),
+}
+
+/// A tab of [`Tabs`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tab {
+    title: String,
+    key: u64,
+    closable: bool,
+}
+
+impl Tab {
+    /// A tab with `title` in the tab strip.
+    ///
+    /// Tabs are told apart by their title, unless they have a [`key`](Tab::key).
+    pub fn new(title: impl Into<String>) -> Self {
+        let title = title.into();
+        let key = hash(&title);
+        Tab {
+            title,
+            key,
+            closable: false,
+        }
+    }
+
+    /// Identify the tab with `key`, so it keeps its content when it moves or is renamed.
+    pub fn key(mut self, key: impl Hash) -> Self {
+        self.key = hash(key);
+        self
+    }
+
+    /// Show a close button, which is also triggered by a middle click.
+    ///
+    /// The default value is `false`.
+    pub fn closable(mut self, closable: bool) -> Self {
+        self.closable = closable;
+        self
+    }
+}
+
+fn hash(key: impl Hash) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// A tab view, which shows the content of the selected tab below a strip of tabs.
+///
+/// Selecting and closing tabs is up to the caller, based on the returned
+/// [`TabsAction`]. The render objects of inactive tabs keep their state,
+/// like scroll offsets and text selections, until the tab is removed.
+///
+/// ```ignore
+/// let selected = ui.use_state(|| 0);
+/// let action = Tabs::new(*selected.get())
+///     .tab(Tab::new("Editor"))
+///     .tab(Tab::new("Log").closable(true))
+///     .build(ui, |ui, index| match index {
+///         0 => editor(ui),
+///         _ => log(ui),
+///     });
+/// if let Some(TabsAction::Select(index)) = action {
+///     selected.set(index);
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tabs {
+    selected: usize,
+    tabs: Vec<Tab>,
+    keep_inactive: bool,
+}
+
+impl Properties for Tabs {
+    type Object = TabsObject;
+}
+
+impl Tabs {
+    /// Tabs where the tab at index `selected` is shown.
+    pub fn new(selected: usize) -> Self {
+        Tabs {
+            selected,
+            tabs: Vec::new(),
+            keep_inactive: true,
+        }
+    }
+
+    pub fn tab(mut self, tab: Tab) -> Self {
+        self.tabs.push(tab);
+        self
+    }
+
+    /// Whether inactive tabs keep the state of their content.
+    ///
+    /// Otherwise, the content is built from scratch when the tab is selected again.
+    /// The default value is `true`.
+    pub fn keep_inactive(mut self, keep: bool) -> Self {
+        self.keep_inactive = keep;
+        self
+    }
+
+    /// Build the tab strip and the content of the selected tab,
+    /// with `content` getting the index of the tab.
+    #[must_use]
+    #[track_caller]
+    pub fn build(self, ui: &mut Ui, content: impl FnOnce(&mut Ui, usize)) -> Option<TabsAction> {
+        let caller = Location::caller().into();
+        let tabs = self.tabs.clone();
+        let selected = self.selected;
+        let keep_inactive = self.keep_inactive;
+        let mut action = None;
+        ui.render_object(caller, self, |ui| {
+            for (index, tab) in tabs.iter().enumerate() {
+                ui.keyed(tab.key, |ui| {
+                    let header = TabHeader {
+                        selected: index == selected,
+                        closable: tab.closable,
+                    };
+                    let clicked = header.build(ui, |ui| Label::new(tab.title.as_str()).build(ui));
+                    match clicked {
+                        Some(TabHeaderAction::Select) => action = Some(TabsAction::Select(index)),
+                        Some(TabHeaderAction::Close) => action = Some(TabsAction::Close(index)),
+                        None => {}
+                    }
+                });
+            }
+
+            if let Some(tab) = tabs.get(selected) {
+                ui.keyed(tab.key, |ui| {
+                    TabPage { key: tab.key }.build(ui, |ui| content(ui, selected));
+                    if keep_inactive {
+                        // Removed tabs are dropped below.
+                        ui.retain_last_render_object(usize::MAX);
+                    }
+                });
+            }
+
+            // Forget the content of the tabs that were removed.
+            ui.drop_retained(
+                |child| match child.object.as_any().downcast_ref::<TabPage>() {
+                    Some(page) => keep_inactive && tabs.iter().any(|tab| tab.key == page.key),
+                    None => true,
+                },
+            );
+        });
+        action
+    }
+}
+
+pub struct TabsObject {
+    tabs: usize,
+    has_page: bool,
+}
+
+impl RenderObject<Tabs> for TabsObject {
+    type Action = ();
+
+    fn create(props: Tabs) -> Self {
+        TabsObject {
+            tabs: props.tabs.len(),
+            has_page: props.selected < props.tabs.len(),
+        }
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, props: Tabs) {
+        let has_page = props.selected < props.tabs.len();
+        if (self.tabs, self.has_page) != (props.tabs.len(), has_page) {
+            self.tabs = props.tabs.len();
+            self.has_page = has_page;
+            ctx.request_layout();
+        }
+    }
+}
+
+impl RenderObjectInterface for TabsObject {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, children: &mut Children) {
+        for child in children {
+            child.event(ctx, event);
+        }
+    }
+
+    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle) {}
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        children: &mut Children,
+    ) -> Size {
+        bc.debug_check("Tabs");
+
+        let header_bc = BoxConstraints::new(Size::ZERO, Size::new(f64::INFINITY, bc.max().height));
+        let mut strip = Size::ZERO;
+        for child in children.iter().take(self.tabs) {
+            let size = child.layout(ctx, &header_bc);
+            child.set_origin(ctx, Point::new(strip.width, 0.0));
+            strip.width += size.width;
+            strip.height = strip.height.max(size.height);
+        }
+
+        let mut page = Size::ZERO;
+        if self.has_page {
+            let page_bc = bc.shrink((0.0, strip.height));
+            if let Some(child) = children.get_mut(self.tabs) {
+                page = child.layout(ctx, &page_bc);
+                child.set_origin(ctx, Point::new(0.0, strip.height));
+            }
+        }
+
+        bc.constrain(Size::new(
+            strip.width.max(page.width),
+            strip.height + page.height,
+        ))
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, children: &mut Children) {
+        let size = ctx.size();
+        let strip = children
+            .iter()
+            .take(self.tabs)
+            .map(|child| child.layout_rect().height())
+            .fold(0.0, f64::max);
+        let theme = ctx.theme();
+        let panel = theme.colors.panel.clone();
+        let border = theme.colors.panel_border.clone();
+
+        ctx.fill(Rect::new(0.0, 0.0, size.width, strip), &panel);
+        let line_width = ctx.snap_width(1.0);
+        let y = ctx.snap_point(Point::new(0.0, strip)).y - line_width / 2.0;
+        ctx.stroke(Line::new((0.0, y), (size.width, y)), &border, line_width);
+        for child in children {
+            child.paint(ctx);
+        }
+    }
+}
+
+/// The content of a tab, which remembers the tab it belongs to.
+struct TabPage {
+    key: u64,
+}
+
+impl Properties for TabPage {
+    type Object = TabPage;
+}
+
+impl TabPage {
+    #[track_caller]
+    fn build(self, ui: &mut Ui, content: impl FnOnce(&mut Ui)) {
+        let caller = Location::caller().into();
+        ui.render_object(caller, self, content);
+    }
+}
+
+impl RenderObject<TabPage> for TabPage {
+    type Action = ();
+
+    fn create(props: TabPage) -> Self {
+        props
+    }
+
+    fn update(&mut self, _ctx: &mut UpdateCtx, props: TabPage) {
+        self.key = props.key;
+    }
+}
+
+impl RenderObjectInterface for TabPage {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, children: &mut Children) {
+        for child in children {
+            child.event(ctx, event);
+        }
+    }
+
+    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle) {}
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        children: &mut Children,
+    ) -> Size {
+        children.layout_stacked(ctx, bc)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, children: &mut Children) {
+        for child in children {
+            child.paint(ctx);
+        }
+    }
+}
+
+enum TabHeaderAction {
+    Select,
+    Close,
+}
+
+/// A tab in the tab strip.
+#[derive(PartialEq)]
+struct TabHeader {
+    selected: bool,
+    closable: bool,
+}
+
+impl Properties for TabHeader {
+    type Object = TabHeaderObject;
+}
+
+impl TabHeader {
+    #[track_caller]
+    fn build(self, ui: &mut Ui, content: impl FnOnce(&mut Ui)) -> Option<TabHeaderAction> {
+        let caller = Location::caller().into();
+        ui.render_object(caller, self, content)
+    }
+}
+
+struct TabHeaderObject {
+    props: TabHeader,
+    action: Option<TabHeaderAction>,
+}
+
+impl TabHeaderObject {
+    fn close_rect(&self, size: Size) -> Option<Rect> {
+        self.props.closable.then(|| {
+            let x = size.width - TAB_PADDING - CLOSE_SIZE;
+            let y = (size.height - CLOSE_SIZE) / 2.0;
+            Rect::from_origin_size((x, y), (CLOSE_SIZE, CLOSE_SIZE))
+        })
+    }
+}
+
+impl RenderObject<TabHeader> for TabHeaderObject {
+    type Action = Option<TabHeaderAction>;
+
+    fn create(props: TabHeader) -> Self {
+        TabHeaderObject {
+            props,
+            action: None,
+        }
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, props: TabHeader) -> Self::Action {
+        if self.props != props {
+            self.props = props;
+            ctx.request_layout();
+        }
+        self.action.take()
+    }
+}
+
+impl RenderObjectInterface for TabHeaderObject {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, _children: &mut Children) {
+        match event {
+            Event::MouseDown(mouse) if ctx.is_hot() => {
+                ctx.set_active(true);
+                ctx.set_handled();
+                ctx.request_paint();
+                if mouse.button == MouseButton::Left {
+                    let on_close = self
+                        .close_rect(ctx.size())
+                        .is_some_and(|rect| rect.contains(mouse.pos));
+                    if !on_close {
+                        self.action = Some(TabHeaderAction::Select);
+                        ctx.request_update();
+                    }
+                }
+            }
+            Event::MouseUp(mouse) if ctx.is_active() => {
+                ctx.set_active(false);
+                ctx.request_paint();
+                if !ctx.is_hot() {
+                    return;
+                }
+                let on_close = self
+                    .close_rect(ctx.size())
+                    .is_some_and(|rect| rect.contains(mouse.pos));
+                let middle = mouse.button == MouseButton::Middle;
+                if self.props.closable && (on_close || middle) {
+                    self.action = Some(TabHeaderAction::Close);
+                    ctx.request_update();
+                    ctx.set_handled();
+                }
+            }
+            Event::MouseMove(_) if self.props.closable && ctx.is_hot() => {
+                ctx.request_paint();
+            }
+            _ => {}
+        }
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        if let LifeCycle::HotChanged(_) = event {
+            ctx.request_paint();
+        }
+    }
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        children: &mut Children,
+    ) -> Size {
+        ctx.set_hit_target(true);
+        let close = if self.props.closable {
+            CLOSE_SIZE + TAB_PADDING / 2.0
+        } else {
+            0.0
+        };
+        let padding = Size::new(2.0 * TAB_PADDING + close, TAB_PADDING);
+        let label = children[0].layout(ctx, &bc.loosen().shrink(padding));
+        children[0].set_origin(ctx, Point::new(TAB_PADDING, TAB_PADDING / 2.0));
+        bc.constrain(label + padding)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, children: &mut Children) {
+        let size = ctx.size();
+        let theme = ctx.theme();
+        let background = if self.props.selected {
+            Some(theme.colors.background.clone())
+        } else if ctx.is_hot() {
+            Some(theme.colors.ghost.clone())
+        } else {
+            None
+        };
+        let accent = theme.colors.accent.clone();
+        let border = theme.colors.panel_border.clone();
+        let text = theme.colors.text.clone();
+
+        if let Some(background) = background {
+            ctx.fill(size.to_rect(), &background);
+        }
+        if self.props.selected {
+            let y = size.height - 1.0;
+            ctx.stroke(Line::new((0.0, y), (size.width, y)), &accent, 2.0);
+        }
+        let line_width = ctx.snap_width(1.0);
+        let x = ctx.snap_point(Point::new(size.width, 0.0)).x - line_width / 2.0;
+        ctx.stroke(Line::new((x, 0.0), (x, size.height)), &border, line_width);
+
+        children[0].paint(ctx);
+
+        if let Some(rect) = self.close_rect(size) {
+            let rect = rect.inset(-3.0);
+            let color = if ctx.is_hot() { text } else { border };
+            ctx.stroke(
+                Line::new((rect.x0, rect.y0), (rect.x1, rect.y1)),
+                &color,
+                1.5,
+            );
+            ctx.stroke(
+                Line::new((rect.x0, rect.y1), (rect.x1, rect.y0)),
+                &color,
+                1.5,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        state::Mutable,
+        testing::{self, Harness},
+        widgets::{Flex, Link},
+    };
+
+    fn counter(ui: &mut Ui) {
+        Mutable::new().use_in(ui, |ui, clicks: &mut usize| {
+            Flex::column().build(ui, |ui| {
+                if Link::new().labeled(ui, "Add") {
+                    *clicks += 1;
+                }
+                Label::new(format!("Clicked {}", clicks)).build(ui);
+            });
+        });
+    }
+
+    #[test]
+    fn inactive_tabs_keep_their_state() {
+        let mut harness = Harness::new(|ui| {
+            Mutable::new().use_in(ui, |ui, selected: &mut usize| {
+                Flex::column().build(ui, |ui| {
+                    let action = Tabs::new(*selected)
+                        .tab(Tab::new("Counter"))
+                        .tab(Tab::new("Other"))
+                        .build(ui, |ui, index| match index {
+                            0 => counter(ui),
+                            _ => Label::new("Other page").build(ui),
+                        });
+                    if let Some(TabsAction::Select(index)) = action {
+                        *selected = index;
+                    }
+                });
+            });
+        });
+        let add = testing::assert_exists::<Label>(harness.tree(), testing::with_label("Add"));
+        harness.click_on(add);
+        harness.rebuild();
+        assert_eq!(
+            harness.label_texts(),
+            ["Counter", "Other", "Add", "Clicked 1"]
+        );
+
+        let other = testing::assert_exists::<Label>(harness.tree(), testing::with_label("Other"));
+        harness.click_on(other);
+        harness.rebuild();
+        assert_eq!(harness.label_texts(), ["Counter", "Other", "Other page"]);
+
+        let first = testing::assert_exists::<Label>(harness.tree(), testing::with_label("Counter"));
+        harness.click_on(first);
+        harness.rebuild();
+        assert_eq!(
+            harness.label_texts(),
+            ["Counter", "Other", "Add", "Clicked 1"]
+        );
+    }
+}
@@ -0,0 +1,462 @@
+//! A multi-line text editor.
+
+use crate::{
+    object::prelude::*,
+    piet::TextLayout as _,
+    style::State,
+    text::{BasicTextInput, Edit, EditAction, Editor, TextInput, TextLayout},
+    wheel::WheelScroll,
+    widgets::textbox::StyleSheet,
+};
+use druid::{Affine, Cursor, HotKey, Insets, KbKey, SysMods, TimerToken, Vec2};
+use std::time::Duration;
+
+const CURSOR_BLINK_DURATION: Duration = Duration::from_millis(500);
+
+/// A text editor for multiple lines of text, which wraps long lines and
+/// scrolls vertically.
+///
+/// Enter inserts a line break, Ctrl+Enter (Cmd+Enter on macOS) activates it.
+#[derive(PartialEq)]
+pub struct TextArea<'a> {
+    placeholder: &'a str,
+    editable: &'a mut String,
+    lines: usize,
+    journal: Option<&'a str>,
+    style: Option<Box<dyn StyleSheet>>,
+}
+
+impl<'a> TextArea<'a> {
+    pub fn new(text: &'a mut String) -> Self {
+        TextArea {
+            placeholder: "",
+            editable: text,
+            lines: 5,
+            journal: None,
+            style: None,
+        }
+    }
+
+    pub fn placeholder(mut self, text: &'a str) -> Self {
+        self.placeholder = text;
+        self
+    }
+
+    /// The number of lines the text area is high, unless its height is constrained.
+    ///
+    /// The default value is `5`.
+    pub fn lines(mut self, lines: usize) -> Self {
+        self.lines = lines;
+        self
+    }
+
+    /// Log all edits to the journal with the given name.
+    ///
+    /// See [`TextBox::journal`](crate::widgets::TextBox::journal).
+    pub fn journal(mut self, name: &'a str) -> Self {
+        self.journal = Some(name);
+        self
+    }
+
+    pub fn style(mut self, style: impl Into<Box<dyn StyleSheet>>) -> Self {
+        self.style = Some(style.into());
+        self
+    }
+
+    /// Returns `true` if the text area was activated.
+    #[track_caller]
+    pub fn build(self, ui: &mut Ui) -> bool {
+        let caller = Location::caller().into();
+        if let Some(name) = self.journal {
+            ui.replay_journal(name, &mut *self.editable);
+        }
+        ui.render_object(caller, self, |_| {})
+    }
+}
+
+impl Properties for TextArea<'_> {
+    type Object = TextAreaObject;
+}
+
+pub struct TextAreaObject {
+    placeholder: TextLayout<String>,
+    text: String,
+    editor: Editor<String>,
+    lines: usize,
+    activated: bool,
+    journal: Option<String>,
+    style: Option<Box<dyn StyleSheet>>,
+
+    input_handler: BasicTextInput,
+    vscroll_offset: f64,
+    scroll: WheelScroll<f64>,
+    /// Scroll the cursor into view in the next layout pass.
+    reveal_cursor: bool,
+    cursor_timer: TimerToken,
+    cursor_on: bool,
+}
+
+impl TextAreaObject {
+    fn journal_edit(&self, ctx: &mut EventCtx, old_text: Option<String>) {
+        if let (Some(name), Some(old_text)) = (&self.journal, old_text) {
+            if let Some(edit) = Edit::between(&old_text, &self.text) {
+                if let Some(mut journal) = ctx.journal(name) {
+                    if let Err(err) = journal.append(&edit) {
+                        log::warn!("Failed to write journal {}: {}", name, err);
+                    }
+                }
+            }
+        }
+    }
+
+    fn reset_cursor_blink(&mut self, token: TimerToken) {
+        self.cursor_on = true;
+        self.cursor_timer = token;
+    }
+
+    /// The height of a line of text, from the last layout.
+    fn line_height(&self) -> f64 {
+        let layout = if self.text.is_empty() {
+            self.placeholder.layout()
+        } else {
+            self.editor.layout().layout()
+        };
+        layout
+            .and_then(|layout| layout.line_metric(0))
+            .map_or(0.0, |metric| metric.height)
+    }
+
+    fn max_scroll(&self, view_height: f64) -> f64 {
+        (self.editor.layout().size().height - view_height).max(0.0)
+    }
+
+    /// Keep the scroll offset within the text, and the cursor in view if requested.
+    fn clamp_scroll(&mut self, view_height: f64) {
+        if std::mem::take(&mut self.reveal_cursor) {
+            let cursor = self.editor.cursor_line();
+            let (top, bottom) = (cursor.p0.y.min(cursor.p1.y), cursor.p0.y.max(cursor.p1.y));
+            if top < self.vscroll_offset {
+                self.vscroll_offset = top;
+                self.scroll.stop();
+            } else if bottom > self.vscroll_offset + view_height {
+                self.vscroll_offset = bottom - view_height;
+                self.scroll.stop();
+            }
+        }
+        self.vscroll_offset = self.vscroll_offset.clamp(0.0, self.max_scroll(view_height));
+    }
+
+    fn should_draw_cursor(&self) -> bool {
+        if cfg!(target_os = "macos") {
+            self.cursor_on && self.editor.selection().is_caret()
+        } else {
+            self.cursor_on
+        }
+    }
+}
+
+impl RenderObject<TextArea<'_>> for TextAreaObject {
+    type Action = bool;
+
+    fn create(props: TextArea<'_>) -> Self {
+        let mut editor = Editor::from_text(&*props.editable);
+        editor.set_multiline(true);
+        TextAreaObject {
+            placeholder: TextLayout::from_text(props.placeholder),
+            text: String::from(&*props.editable),
+            editor,
+            lines: props.lines,
+            activated: false,
+            journal: props.journal.map(String::from),
+            style: props.style,
+
+            input_handler: BasicTextInput::new(),
+            vscroll_offset: 0.0,
+            scroll: WheelScroll::new(),
+            reveal_cursor: false,
+            cursor_timer: TimerToken::INVALID,
+            cursor_on: false,
+        }
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, props: TextArea<'_>) -> Self::Action {
+        if props.journal != self.journal.as_deref() {
+            self.journal = props.journal.map(String::from);
+        }
+
+        if props.editable != &self.text {
+            if props.editable == self.editor.layout().text().unwrap() {
+                props.editable.replace_range(.., &self.text);
+                self.editor.set_text(self.text.clone());
+            } else {
+                self.text = props.editable.to_owned();
+                self.editor.set_text(props.editable.to_owned());
+            }
+            ctx.request_layout();
+        }
+        if props.placeholder != self.placeholder.text().unwrap() {
+            self.placeholder.set_text(props.placeholder.to_owned());
+            ctx.request_layout();
+        }
+        if props.lines != self.lines {
+            self.lines = props.lines;
+            ctx.request_layout();
+        }
+        if props.style != self.style {
+            self.style = props.style;
+            ctx.request_paint();
+        }
+
+        std::mem::take(&mut self.activated)
+    }
+}
+
+impl RenderObjectInterface for TextAreaObject {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, _children: &mut Children) {
+        let text_insets = Insets::uniform(ctx.theme().spacing.text_insets);
+        let to_text = Vec2::new(-text_insets.x0, self.vscroll_offset - text_insets.y0);
+        match event {
+            Event::MouseDown(mouse) => {
+                ctx.request_focus();
+                ctx.capture_pointer();
+                let mut mouse = mouse.clone();
+                mouse.pos += to_text;
+
+                if !mouse.focus {
+                    self.reset_cursor_blink(ctx.request_timer(CURSOR_BLINK_DURATION));
+                    self.editor.click(&mouse, &mut self.text);
+                }
+
+                ctx.request_paint();
+            }
+            Event::MouseMove(mouse) => {
+                let mut mouse = mouse.clone();
+                mouse.pos += to_text;
+                ctx.set_cursor(&Cursor::IBeam);
+                if ctx.is_active() {
+                    self.editor.drag(&mouse, &mut self.text);
+                    self.reveal_cursor = true;
+                    ctx.request_layout();
+                    ctx.request_paint();
+                }
+            }
+            Event::MouseUp(_) if ctx.is_active() => {
+                ctx.release_pointer();
+                ctx.request_paint();
+            }
+            Event::Wheel(wheel) if ctx.is_hot() => {
+                let view_height = ctx.size().height - text_insets.y_value();
+                let current = self.scroll.target(self.vscroll_offset);
+                let target =
+                    (current + wheel.wheel_delta.y).clamp(0.0, self.max_scroll(view_height));
+                // At the ends, the wheel scrolls the containers around the text area.
+                if target != current {
+                    self.vscroll_offset = self.scroll.scroll(ctx, self.vscroll_offset, target);
+                    self.clamp_scroll(view_height);
+                    ctx.request_paint();
+                    ctx.set_handled();
+                }
+            }
+            Event::AnimFrame(_) => {
+                if let Some(offset) = self.scroll.anim_frame(ctx, event) {
+                    self.vscroll_offset = offset;
+                    self.clamp_scroll(ctx.size().height - text_insets.y_value());
+                    ctx.request_paint();
+                }
+            }
+            Event::Timer(id) if *id == self.cursor_timer => {
+                self.cursor_on = !self.cursor_on;
+                ctx.request_paint();
+                self.cursor_timer = ctx.request_timer(CURSOR_BLINK_DURATION);
+            }
+            Event::Paste(ref item) => {
+                if let Some(string) = item.get_string() {
+                    let old_text = self.journal.as_ref().map(|_| self.text.clone());
+                    self.editor.paste(string, &mut self.text);
+                    self.journal_edit(ctx, old_text);
+                    self.reveal_cursor = true;
+                    ctx.request_update();
+                }
+            }
+            Event::KeyDown(key_event) => {
+                match key_event {
+                    k_e if HotKey::new(None, KbKey::Tab).matches(k_e) => ctx.focus_next(),
+                    k_e if HotKey::new(SysMods::Shift, KbKey::Tab).matches(k_e) => ctx.focus_prev(),
+                    k_e if HotKey::new(SysMods::Cmd, KbKey::Enter).matches(k_e) => {
+                        self.activated = true;
+                        ctx.request_update();
+                    }
+                    k_e if HotKey::new(SysMods::Cmd, "c").matches(k_e) => {
+                        if let Some(clipboard) = ctx.services().clipboard() {
+                            self.editor.copy(&mut self.text, &*clipboard);
+                        }
+                    }
+                    k_e if HotKey::new(SysMods::Cmd, "x").matches(k_e) => {
+                        if let Some(clipboard) = ctx.services().clipboard() {
+                            let old_text = self.journal.as_ref().map(|_| self.text.clone());
+                            self.editor.cut(&mut self.text, &*clipboard);
+                            self.journal_edit(ctx, old_text);
+                            self.reveal_cursor = true;
+                            ctx.request_update();
+                            ctx.request_layout();
+                        }
+                    }
+                    k_e if HotKey::new(SysMods::Cmd, "v").matches(k_e) => {
+                        let clipboard = ctx.services().clipboard();
+                        if let Some(string) = clipboard.and_then(|c| c.get_string()) {
+                            let old_text = self.journal.as_ref().map(|_| self.text.clone());
+                            self.editor.paste(string, &mut self.text);
+                            self.journal_edit(ctx, old_text);
+                            self.reveal_cursor = true;
+                            ctx.request_update();
+                            ctx.request_layout();
+                        }
+                    }
+                    k_e => {
+                        if let Some(edit) = self.input_handler.handle_event(k_e) {
+                            self.reveal_cursor = !matches!(edit, EditAction::SelectAll);
+                            let old_text = self.journal.as_ref().map(|_| self.text.clone());
+                            self.editor.do_edit(edit, &mut self.text);
+                            self.journal_edit(ctx, old_text);
+                            ctx.request_update();
+                            ctx.request_layout();
+                        }
+                    }
+                };
+                self.reset_cursor_blink(ctx.request_timer(CURSOR_BLINK_DURATION));
+                ctx.request_paint();
+            }
+            _ => (),
+        }
+    }
+
+    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle) {}
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        _children: &mut Children,
+    ) -> Size {
+        bc.debug_check("TextArea");
+        ctx.set_hit_target(true);
+        let theme = ctx.theme();
+        let text_insets = Insets::uniform(theme.spacing.text_insets);
+        for layout in [&mut self.placeholder, self.editor.layout_mut()] {
+            layout.set_text_color(theme.colors.text.clone());
+            layout.set_font(theme.font.clone());
+            layout.set_font_fallback(theme.font_fallback.clone());
+        }
+
+        let width = if bc.max().width.is_finite() {
+            bc.max().width
+        } else {
+            200.0
+        };
+        let wrap_width = width - text_insets.x_value();
+        self.placeholder.set_wrap_width(wrap_width);
+        self.editor.set_wrap_width(wrap_width);
+
+        let env = ctx.env().clone();
+        self.placeholder.rebuild_if_needed(ctx.text(), &env);
+        self.editor.rebuild_if_needed(ctx.text(), &env);
+
+        let height = self.lines as f64 * self.line_height() + text_insets.y_value();
+        let size = bc.constrain((width, height));
+        self.clamp_scroll(size.height - text_insets.y_value());
+
+        let metrics = self.editor.layout().layout_metrics();
+        ctx.set_baseline_offset(size.height - text_insets.y0 - metrics.first_baseline);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, _children: &mut Children) {
+        let size = ctx.size();
+        let is_focused = ctx.is_focused();
+        let state = State::new(ctx.is_disabled(), ctx.is_hot(), ctx.is_active(), is_focused);
+        let style = match self.style {
+            Some(ref sheet) => sheet.style(state),
+            None => ctx.theme().text_box.style(state),
+        };
+        let text_insets = Insets::uniform(ctx.theme().spacing.text_insets);
+
+        let rect = ctx.snap_rect(size.to_rect());
+        let border = style.border.snapped(ctx);
+        ctx.fill(border.outer_path(rect), &style.background);
+
+        ctx.with_save(|rc| {
+            rc.clip(border.inner_path(rect));
+            rc.transform(Affine::translate((0.0, -self.vscroll_offset)));
+
+            let text_pos = (text_insets.x0, text_insets.y0);
+            if self.text.is_empty() {
+                self.placeholder.draw(rc, text_pos);
+            } else {
+                if is_focused {
+                    for sel in self.editor.selection_rects() {
+                        let sel = sel + Vec2::from(text_pos);
+                        rc.fill(sel.to_rounded_rect(1.0), &style.selection_color);
+                    }
+                }
+                self.editor.draw(rc, text_pos);
+            }
+
+            if is_focused && self.should_draw_cursor() {
+                let cursor = self.editor.cursor_line() + Vec2::from(text_pos);
+                rc.stroke(cursor, &style.cursor_color, 1.);
+            }
+        });
+
+        border.paint(ctx, rect);
+        ctx.publish_style(style);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        testing::{self, Harness},
+        text::{Edit, Journals},
+    };
+    use druid::Modifiers;
+    use std::{cell::RefCell, fs, rc::Rc};
+
+    #[test]
+    fn recovers_the_journal_in_the_first_build() {
+        let dir = std::env::temp_dir().join(format!("coat-text-area-{}", std::process::id()));
+        let edit = Edit::between("Dear", "Dear Ada,\n").unwrap();
+        Journals::new(&dir).journal("letter").append(&edit).unwrap();
+
+        let text = Rc::new(RefCell::new(String::from("Dear")));
+        let edited = text.clone();
+        let mut harness = Harness::with_journals(
+            move |ui| {
+                TextArea::new(&mut edited.borrow_mut())
+                    .journal("letter")
+                    .build(ui);
+            },
+            &dir,
+        );
+        assert_eq!(*text.borrow(), "Dear Ada,\n");
+
+        let text_area = testing::assert_exists::<TextAreaObject>(harness.tree(), testing::any());
+        harness.click_on(text_area);
+        let cmd = match cfg!(target_os = "macos") {
+            true => Modifiers::META,
+            false => Modifiers::CONTROL,
+        };
+        harness.key_with_mods(cmd, KbKey::End);
+        harness.type_text("thanks");
+        harness.rebuild();
+        assert_eq!(*text.borrow(), "Dear Ada,\nthanks");
+
+        // The journal holds the edits since the last save, and nothing twice.
+        let mut recovered = String::from("Dear");
+        Journals::new(&dir)
+            .replay("letter", &mut recovered)
+            .unwrap();
+        assert_eq!(recovered, "Dear Ada,\nthanks");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
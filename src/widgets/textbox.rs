@@ -1,13 +1,18 @@
 use crate::{
     object::prelude::*,
-    text::{BasicTextInput, EditAction, Editor, LayoutMetrics, Selection, TextInput, TextLayout},
+    style::State,
+    text::{
+        BasicTextInput, Edit, EditAction, Editor, LayoutMetrics, Selection, TextInput, TextLayout,
+    },
 };
 use druid::{
-    piet::PietText, Affine, Color, Cursor, HotKey, Insets, KbKey, LifeCycle, Point, SysMods,
-    TextAlignment, TimerToken, Vec2,
+    piet::PietText, Affine, Cursor, HotKey, Insets, KbKey, Point, SysMods, TextAlignment,
+    TimerToken, Vec2,
 };
 use std::time::Duration;
 
+pub use style::{Style, StyleSheet};
+
 const CURSOR_BLINK_DURATION: Duration = Duration::from_millis(500);
 
 #[derive(PartialEq)]
@@ -15,6 +20,8 @@ pub struct TextBox<'a> {
     placeholder: &'a str,
     editable: &'a mut String,
     alignment: TextAlignment,
+    journal: Option<&'a str>,
+    style: Option<Box<dyn StyleSheet>>,
 }
 
 impl<'a> TextBox<'a> {
@@ -23,6 +30,8 @@ impl<'a> TextBox<'a> {
             placeholder: "",
             editable: text,
             alignment: TextAlignment::Start,
+            journal: None,
+            style: None,
         }
     }
 
@@ -36,8 +45,26 @@ impl<'a> TextBox<'a> {
         self
     }
 
+    /// Log all edits to the journal with the given name.
+    ///
+    /// Edits that are still in the journal are replayed onto the text,
+    /// to recover them after a crash. Clear the journal whenever the text
+    /// is saved. Does nothing if the app has no journal directory.
+    pub fn journal(mut self, name: &'a str) -> Self {
+        self.journal = Some(name);
+        self
+    }
+
+    pub fn style(mut self, style: impl Into<Box<dyn StyleSheet>>) -> Self {
+        self.style = Some(style.into());
+        self
+    }
+
     pub fn build(self, ui: &mut Ui) -> bool {
         let caller = Location::caller().into();
+        if let Some(name) = self.journal {
+            ui.replay_journal(name, &mut *self.editable);
+        }
         ui.render_object(caller, self, |_| {})
     }
 }
@@ -52,6 +79,8 @@ pub struct TextBoxObject {
     editor: Editor<String>,
     alignment: TextAlignment,
     activated: bool,
+    journal: Option<String>,
+    style: Option<Box<dyn StyleSheet>>,
 
     // this can be Box<dyn TextInput> in the future
     input_handler: BasicTextInput,
@@ -81,6 +110,18 @@ impl TextBoxObject {
     pub fn text_position(&self) -> Point {
         self.text_pos
     }
+
+    fn journal_edit(&self, ctx: &mut EventCtx, old_text: Option<String>) {
+        if let (Some(name), Some(old_text)) = (&self.journal, old_text) {
+            if let Some(edit) = Edit::between(&old_text, &self.text) {
+                if let Some(mut journal) = ctx.journal(name) {
+                    if let Err(err) = journal.append(&edit) {
+                        log::warn!("Failed to write journal {}: {}", name, err);
+                    }
+                }
+            }
+        }
+    }
 }
 
 impl RenderObject<TextBox<'_>> for TextBoxObject {
@@ -93,6 +134,8 @@ impl RenderObject<TextBox<'_>> for TextBoxObject {
             editor: Editor::from_text(&*props.editable),
             input_handler: BasicTextInput::default(),
             activated: false,
+            journal: props.journal.map(String::from),
+            style: props.style,
 
             hscroll_offset: 0.,
             suppress_adjust_hscroll: false,
@@ -106,6 +149,10 @@ impl RenderObject<TextBox<'_>> for TextBoxObject {
     }
 
     fn update(&mut self, ctx: &mut UpdateCtx, props: TextBox<'_>) -> Self::Action {
+        if props.journal != self.journal.as_deref() {
+            self.journal = props.journal.map(String::from);
+        }
+
         if props.editable != &self.text {
             if props.editable == self.editor.layout().text().unwrap() {
                 props.editable.replace_range(.., &self.text);
@@ -124,6 +171,10 @@ impl RenderObject<TextBox<'_>> for TextBoxObject {
             self.alignment = props.alignment;
             ctx.request_layout();
         }
+        if props.style != self.style {
+            self.style = props.style;
+            ctx.request_paint();
+        }
 
         let was_activated = self.activated;
         self.activated = false;
@@ -137,7 +188,7 @@ impl RenderObjectInterface for TextBoxObject {
         match event {
             Event::MouseDown(mouse) => {
                 ctx.request_focus();
-                ctx.set_active(true);
+                ctx.capture_pointer();
                 let mut mouse = mouse.clone();
                 mouse.pos += Vec2::new(self.hscroll_offset - self.alignment_offset, 0.0);
 
@@ -160,7 +211,7 @@ impl RenderObjectInterface for TextBoxObject {
             }
             Event::MouseUp(_) => {
                 if ctx.is_active() {
-                    ctx.set_active(false);
+                    ctx.release_pointer();
                     ctx.request_paint();
                 }
             }
@@ -171,21 +222,15 @@ impl RenderObjectInterface for TextBoxObject {
                     self.cursor_timer = ctx.request_timer(CURSOR_BLINK_DURATION);
                 }
             }
-            // Event::Command(ref cmd) if ctx.is_focused() && cmd.is(crate::commands::COPY) => {
-            //     self.editor.copy(data);
-            //     ctx.set_handled();
-            // }
-            // Event::Command(ref cmd) if ctx.is_focused() && cmd.is(crate::commands::CUT) => {
-            //     self.editor.cut(data);
-            //     ctx.set_handled();
-            // }
             // Event::Command(cmd) if cmd.is(TextBox::PERFORM_EDIT) => {
             //     let edit = cmd.get_unchecked(TextBox::PERFORM_EDIT);
             //     self.editor.do_edit(edit.to_owned(), data);
             // }
             Event::Paste(ref item) => {
                 if let Some(string) = item.get_string() {
+                    let old_text = self.journal.as_ref().map(|_| self.text.clone());
                     self.editor.paste(string, &mut self.text);
+                    self.journal_edit(ctx, old_text);
                 }
             }
             Event::KeyDown(key_event) => {
@@ -204,10 +249,34 @@ impl RenderObjectInterface for TextBoxObject {
                         self.activated = true;
                         ctx.request_update();
                     }
+                    k_e if HotKey::new(SysMods::Cmd, "c").matches(k_e) => {
+                        if let Some(clipboard) = ctx.services().clipboard() {
+                            self.editor.copy(&mut self.text, &*clipboard);
+                        }
+                    }
+                    k_e if HotKey::new(SysMods::Cmd, "x").matches(k_e) => {
+                        if let Some(clipboard) = ctx.services().clipboard() {
+                            let old_text = self.journal.as_ref().map(|_| self.text.clone());
+                            self.editor.cut(&mut self.text, &*clipboard);
+                            self.journal_edit(ctx, old_text);
+                            ctx.request_update();
+                        }
+                    }
+                    k_e if HotKey::new(SysMods::Cmd, "v").matches(k_e) => {
+                        let clipboard = ctx.services().clipboard();
+                        if let Some(string) = clipboard.and_then(|c| c.get_string()) {
+                            let old_text = self.journal.as_ref().map(|_| self.text.clone());
+                            self.editor.paste(string, &mut self.text);
+                            self.journal_edit(ctx, old_text);
+                            ctx.request_update();
+                        }
+                    }
                     k_e => {
                         if let Some(edit) = self.input_handler.handle_event(k_e) {
                             self.suppress_adjust_hscroll = matches!(edit, EditAction::SelectAll);
+                            let old_text = self.journal.as_ref().map(|_| self.text.clone());
                             self.editor.do_edit(edit, &mut self.text);
+                            self.journal_edit(ctx, old_text);
                             ctx.request_update();
                             ctx.request_paint();
                         }
@@ -228,8 +297,15 @@ impl RenderObjectInterface for TextBoxObject {
         bc: &BoxConstraints,
         _children: &mut Children,
     ) -> Size {
+        ctx.set_hit_target(true);
         let width = 200.0;
-        let text_insets = Insets::uniform(3.0);
+        let theme = ctx.theme();
+        let text_insets = Insets::uniform(theme.spacing.text_insets);
+        for layout in [&mut self.placeholder, self.editor.layout_mut()] {
+            layout.set_text_color(theme.colors.text.clone());
+            layout.set_font(theme.font.clone());
+            layout.set_font_fallback(theme.font_fallback.clone());
+        }
 
         let env = ctx.env().clone();
         self.placeholder.rebuild_if_needed(ctx.text(), &env);
@@ -261,31 +337,22 @@ impl RenderObjectInterface for TextBoxObject {
 
     fn paint(&mut self, ctx: &mut PaintCtx, _children: &mut Children) {
         let size = ctx.size();
-        let background_color = Color::GRAY;
-        let selection_color = Color::BLUE;
-        let cursor_color = Color::WHITE;
-        let border_width = 1.0;
-        let text_insets = Insets::uniform(3.0);
-
         let is_focused = ctx.is_focused();
-
-        let border_color = if is_focused {
-            Color::WHITE
-        } else {
-            Color::BLACK
+        let state = State::new(ctx.is_disabled(), ctx.is_hot(), ctx.is_active(), is_focused);
+        let style = match self.style {
+            Some(ref sheet) => sheet.style(state),
+            None => ctx.theme().text_box.style(state),
         };
+        let text_insets = Insets::uniform(ctx.theme().spacing.text_insets);
 
         // Paint the background
-        let clip_rect = Size::new(size.width - border_width, size.height)
-            .to_rect()
-            .inset(-border_width / 2.0)
-            .to_rounded_rect(3.0);
-
-        ctx.fill(clip_rect, &background_color);
+        let rect = ctx.snap_rect(size.to_rect());
+        let border = style.border.snapped(ctx);
+        ctx.fill(border.outer_path(rect), &style.background);
 
         // Render text, selection, and cursor inside a clip
         ctx.with_save(|rc| {
-            rc.clip(clip_rect);
+            rc.clip(border.inner_path(rect));
 
             // Shift everything inside the clip by the hscroll_offset
             rc.transform(Affine::translate((-self.hscroll_offset, 0.)));
@@ -297,7 +364,7 @@ impl RenderObjectInterface for TextBoxObject {
                     for sel in self.editor.selection_rects() {
                         let sel = sel + text_pos.to_vec2();
                         let rounded = sel.to_rounded_rect(1.0);
-                        rc.fill(rounded, &selection_color);
+                        rc.fill(rounded, &style.selection_color);
                     }
                 }
                 self.editor.draw(rc, text_pos);
@@ -327,12 +394,13 @@ impl RenderObjectInterface for TextBoxObject {
                     }
                     cursor
                 };
-                rc.stroke(cursor, &cursor_color, 1.);
+                rc.stroke(cursor, &style.cursor_color, 1.);
             }
         });
 
         // Paint the border
-        ctx.stroke(clip_rect, &border_color, border_width);
+        border.paint(ctx, rect);
+        ctx.publish_style(style);
     }
 }
 
@@ -420,3 +488,207 @@ impl TextBoxObject {
         }
     }
 }
+
+pub mod style {
+    use crate::{
+        piet::Color,
+        style::{impl_style_sheet, Border, State},
+    };
+    use std::any::Any;
+
+    /// The appearance of a text box.
+    #[derive(Debug, Clone)]
+    pub struct Style {
+        pub border: Border,
+        pub background: Color,
+
+        pub selection_color: Color,
+        pub cursor_color: Color,
+    }
+
+    /// A set of rules that dictate the style of a text box.
+    pub trait StyleSheet: Any {
+        fn eq(&self, other: &dyn StyleSheet) -> bool;
+
+        fn enabled(&self) -> Style;
+
+        fn hovered(&self) -> Style {
+            self.enabled()
+        }
+
+        fn pressed(&self) -> Style {
+            self.focused()
+        }
+
+        fn focused(&self) -> Style {
+            self.enabled()
+        }
+
+        fn disabled(&self) -> Style {
+            let active = self.enabled();
+
+            Style {
+                background: active.background.with_alpha(0.5),
+                border: active.border.clone().with_alpha(0.5),
+                ..active
+            }
+        }
+
+        /// The style in `state`.
+        fn style(&self, state: State) -> Style {
+            match state {
+                State::Enabled => self.enabled(),
+                State::Hovered => self.hovered(),
+                State::Pressed => self.pressed(),
+                State::Focused => self.focused(),
+                State::Disabled => self.disabled(),
+            }
+        }
+    }
+
+    /// The text box style of the dark theme.
+    #[derive(Debug, PartialEq)]
+    pub struct Default;
+
+    impl StyleSheet for Default {
+        fn enabled(&self) -> Style {
+            Style {
+                border: Border::uniform(1.0, Color::BLACK).radius(3.0),
+                background: Color::GRAY,
+                selection_color: Color::BLUE,
+                cursor_color: Color::WHITE,
+            }
+        }
+
+        fn focused(&self) -> Style {
+            Style {
+                border: self.enabled().border.with_color(Color::WHITE),
+                ..self.enabled()
+            }
+        }
+
+        fn eq(&self, other: &dyn StyleSheet) -> bool {
+            Any::type_id(other) == std::any::TypeId::of::<Self>()
+        }
+    }
+
+    /// The text box style of the light theme.
+    #[derive(Debug, PartialEq)]
+    pub struct Light;
+
+    impl StyleSheet for Light {
+        fn enabled(&self) -> Style {
+            Style {
+                border: Border::uniform(1.0, Color::rgb8(0xa0, 0xa0, 0xa0)).radius(3.0),
+                background: Color::WHITE,
+                selection_color: Color::rgb8(0xb4, 0xd5, 0xfe),
+                cursor_color: Color::BLACK,
+            }
+        }
+
+        fn focused(&self) -> Style {
+            Style {
+                border: self.enabled().border.with_color(Color::rgb(0.4, 0.4, 0.85)),
+                ..self.enabled()
+            }
+        }
+
+        fn eq(&self, other: &dyn StyleSheet) -> bool {
+            Any::type_id(other) == std::any::TypeId::of::<Self>()
+        }
+    }
+
+    impl_style_sheet!(StyleSheet);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        services::{Clipboard, Services},
+        state::Mutable,
+        testing::{self, Harness},
+        text::{Edit, Journals},
+        widgets::{Flex, Label},
+    };
+    use druid::Modifiers;
+    use std::{cell::RefCell, fs, rc::Rc};
+
+    #[derive(Default)]
+    struct FakeClipboard(RefCell<Option<String>>);
+
+    impl Clipboard for FakeClipboard {
+        fn get_string(&self) -> Option<String> {
+            self.0.borrow().clone()
+        }
+
+        fn put_string(&self, text: &str) {
+            *self.0.borrow_mut() = Some(text.to_string());
+        }
+    }
+
+    #[test]
+    fn clipboard_shortcuts() {
+        let clipboard = Rc::new(FakeClipboard::default());
+        let services = Services::new().with::<dyn Clipboard>(clipboard.clone());
+        let mut harness = Harness::new(move |ui| {
+            ui.provide(services.clone(), |ui| {
+                Flex::column().build(ui, |ui| {
+                    Mutable::new().use_in(ui, |ui, text: &mut String| {
+                        TextBox::new(text).build(ui);
+                        Label::new(text.clone()).build(ui);
+                    });
+                });
+            });
+        });
+        let cmd = match cfg!(target_os = "macos") {
+            true => Modifiers::META,
+            false => Modifiers::CONTROL,
+        };
+
+        let text_box = testing::assert_exists::<TextBoxObject>(harness.tree(), testing::any());
+        harness.click_on(text_box);
+        harness.type_text("hello");
+        harness.key_with_mods(cmd, KbKey::Character("a".into()));
+        harness.key_with_mods(cmd, KbKey::Character("c".into()));
+        assert_eq!(clipboard.get_string().as_deref(), Some("hello"));
+
+        harness.key_with_mods(cmd, KbKey::Character("x".into()));
+        assert_eq!(harness.label_texts(), [""]);
+        harness.key_with_mods(cmd, KbKey::Character("v".into()));
+        harness.key_with_mods(cmd, KbKey::Character("v".into()));
+        assert_eq!(harness.label_texts(), ["hellohello"]);
+    }
+
+    #[test]
+    fn recovers_the_journal_in_the_first_build() {
+        let dir = std::env::temp_dir().join(format!("coat-text-box-{}", std::process::id()));
+        let edit = Edit::between("draft", "first draft").unwrap();
+        Journals::new(&dir).journal("notes").append(&edit).unwrap();
+
+        let text = Rc::new(RefCell::new(String::from("draft")));
+        let edited = text.clone();
+        let mut harness = Harness::with_journals(
+            move |ui| {
+                TextBox::new(&mut edited.borrow_mut())
+                    .journal("notes")
+                    .build(ui);
+            },
+            &dir,
+        );
+        assert_eq!(*text.borrow(), "first draft");
+
+        let text_box = testing::assert_exists::<TextBoxObject>(harness.tree(), testing::any());
+        harness.click_on(text_box);
+        harness.key(KbKey::End);
+        harness.type_text("!");
+        harness.rebuild();
+        assert_eq!(*text.borrow(), "first draft!");
+
+        // The journal holds the edits since the last save, and nothing twice.
+        let mut recovered = String::from("draft");
+        Journals::new(&dir).replay("notes", &mut recovered).unwrap();
+        assert_eq!(recovered, "first draft!");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
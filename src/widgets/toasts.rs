@@ -0,0 +1,273 @@
+//! Short notifications that stack above the window content.
+
+use crate::{
+    kurbo::Point,
+    object::prelude::*,
+    widgets::{
+        overlay::{Overlay, Placement},
+        Button, Flex, Label,
+    },
+};
+use std::{
+    cell::RefCell,
+    fmt,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+const TOAST_PADDING: Size = Size::new(12.0, 8.0);
+const TOAST_MARGIN: f64 = 8.0;
+
+/// A notification shown by [`Ui::toast`](crate::ui::Ui::toast).
+pub struct Toast {
+    message: String,
+    action: Option<(String, Box<dyn FnOnce()>)>,
+    duration: Duration,
+}
+
+impl Toast {
+    pub fn new(message: impl Into<String>) -> Self {
+        Toast {
+            message: message.into(),
+            action: None,
+            duration: Duration::from_secs(4),
+        }
+    }
+
+    /// Show a button labeled with `label`, which calls `action` and dismisses the toast.
+    pub fn action(mut self, label: impl Into<String>, action: impl FnOnce() + 'static) -> Self {
+        self.action = Some((label.into(), Box::new(action)));
+        self
+    }
+
+    /// How long the toast is shown.
+    ///
+    /// The default value is 4 seconds.
+    pub fn duration(mut self, duration: Duration) -> Self {
+        self.duration = duration;
+        self
+    }
+}
+
+impl<S: Into<String>> From<S> for Toast {
+    fn from(message: S) -> Self {
+        Toast::new(message)
+    }
+}
+
+impl fmt::Debug for Toast {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Toast")
+            .field("message", &self.message)
+            .field("action", &self.action.as_ref().map(|(label, _)| label))
+            .field("duration", &self.duration)
+            .finish()
+    }
+}
+
+struct Entry {
+    id: u64,
+    toast: Toast,
+    expires: Instant,
+}
+
+/// The toasts of a [`Toasts`], which build code reaches through [`Ui::provided`].
+///
+/// [`Ui::provided`]: crate::ui::Ui::provided
+#[derive(Clone, Default)]
+pub(crate) struct ToastQueue {
+    entries: Rc<RefCell<Vec<Entry>>>,
+}
+
+impl PartialEq for ToastQueue {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.entries, &other.entries)
+    }
+}
+
+impl ToastQueue {
+    /// Show `toast` from `now` on.
+    pub(crate) fn push(&self, toast: Toast, now: Instant) {
+        let mut entries = self.entries.borrow_mut();
+        let id = entries.last().map_or(0, |entry| entry.id + 1);
+        let expires = now + toast.duration;
+        entries.push(Entry { id, toast, expires });
+    }
+}
+
+/// Shows the toasts requested by the build code in `content`.
+///
+/// Toasts stack at the bottom of the window, the newest one at the bottom,
+/// and disappear once their duration passed.
+///
+/// ```ignore
+/// Toasts::new().build(ui, |ui| {
+///     if Button::new().labeled(ui, "Save") {
+///         save();
+///         ui.toast("Saved");
+///     }
+/// });
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Toasts;
+
+impl Toasts {
+    pub fn new() -> Self {
+        Toasts
+    }
+
+    #[track_caller]
+    pub fn build(self, ui: &mut Ui, content: impl FnOnce(&mut Ui)) {
+        let caller = Location::caller().into();
+        ui.state_node(caller, ToastQueue::default, |ui, queue: &mut ToastQueue| {
+            let queue = queue.clone();
+            let provided = queue.clone();
+            let _ = Overlay::new(Placement::Bottom)
+                .dismiss_on_click_outside(false)
+                .dismiss_on_escape(false)
+                .build(
+                    ui,
+                    true,
+                    |ui| ui.provide(provided, content),
+                    |ui| {
+                        let now = ui.now();
+                        queue
+                            .entries
+                            .borrow_mut()
+                            .retain(|entry| entry.expires > now);
+                        let next = queue.entries.borrow().iter().map(|e| e.expires).min();
+                        if let Some(next) = next {
+                            ui.request_build_at(next);
+                        }
+                        Flex::column().build(ui, |ui| {
+                            let mut clicked = None;
+                            for entry in queue.entries.borrow().iter() {
+                                ui.keyed(entry.id, |ui| {
+                                    let caller = Location::caller().into();
+                                    ui.render_object(caller, ToastCard, |ui| {
+                                        Label::new(entry.toast.message.as_str()).build(ui);
+                                        if let Some((label, _)) = &entry.toast.action {
+                                            if Button::new().labeled(ui, label.as_str()) {
+                                                clicked = Some(entry.id);
+                                            }
+                                        }
+                                    });
+                                });
+                            }
+                            if let Some(id) = clicked {
+                                let mut entries = queue.entries.borrow_mut();
+                                if let Some(index) = entries.iter().position(|e| e.id == id) {
+                                    let entry = entries.remove(index);
+                                    drop(entries);
+                                    if let Some((_, action)) = entry.toast.action {
+                                        action();
+                                    }
+                                }
+                            }
+                        });
+                    },
+                );
+        });
+    }
+}
+
+/// The message and action button of a toast on a panel.
+struct ToastCard;
+
+impl Properties for ToastCard {
+    type Object = ToastCard;
+}
+
+impl RenderObject<ToastCard> for ToastCard {
+    type Action = ();
+
+    fn create(props: ToastCard) -> Self {
+        props
+    }
+
+    fn update(&mut self, _ctx: &mut UpdateCtx, _props: ToastCard) {}
+}
+
+impl RenderObjectInterface for ToastCard {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, children: &mut Children) {
+        for child in children {
+            child.event(ctx, event);
+        }
+    }
+
+    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle) {}
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        children: &mut Children,
+    ) -> Size {
+        let mut content = Size::ZERO;
+        for child in children.iter() {
+            let child_size = child.layout(ctx, &bc.loosen());
+            content.width += child_size.width;
+            content.height = content.height.max(child_size.height);
+        }
+        let mut x = TOAST_PADDING.width;
+        for child in children {
+            let child_size = child.layout_rect().size();
+            let y =
+                TOAST_MARGIN + TOAST_PADDING.height + (content.height - child_size.height) / 2.0;
+            child.set_origin(ctx, Point::new(x, y));
+            x += child_size.width + TOAST_PADDING.width;
+        }
+        bc.constrain(Size::new(
+            x,
+            content.height + 2.0 * TOAST_PADDING.height + TOAST_MARGIN,
+        ))
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, children: &mut Children) {
+        let size = ctx.size();
+        let theme = ctx.theme();
+        let panel = theme.colors.panel.clone();
+        let border = theme.colors.panel_border.clone();
+        let radius = theme.spacing.corner_radius;
+
+        let rect = Size::new(size.width, size.height - TOAST_MARGIN)
+            .to_rect()
+            .with_origin((0.0, TOAST_MARGIN))
+            .inset(-0.5)
+            .to_rounded_rect(radius);
+        ctx.fill(rect, &panel);
+        ctx.stroke(rect, &border, 1.0);
+        for child in children {
+            child.paint(ctx);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        testing::{self, Harness},
+        widgets::button::ButtonObject,
+    };
+
+    #[test]
+    fn toasts_expire() {
+        let mut harness = Harness::new(|ui| {
+            Toasts::new().build(ui, |ui| {
+                if Button::new().labeled(ui, "Save") {
+                    ui.toast(Toast::new("Saved").duration(Duration::from_secs(2)));
+                }
+            });
+        });
+        let save =
+            testing::assert_exists::<ButtonObject>(harness.tree(), testing::with_label("Save"));
+        harness.click_on(save);
+        assert_eq!(harness.label_texts(), ["Save", "Saved"]);
+
+        harness.advance(Duration::from_secs(1));
+        assert_eq!(harness.label_texts(), ["Save", "Saved"]);
+        harness.advance(Duration::from_secs(1));
+        assert_eq!(harness.label_texts(), ["Save"]);
+    }
+}
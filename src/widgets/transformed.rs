@@ -0,0 +1,124 @@
+//! A container that rotates, scales or moves its content.
+
+use crate::{
+    kurbo::{Affine, Insets, Point, Shape},
+    object::prelude::*,
+};
+
+/// Paints its content transformed by an [`Affine`], which stays interactive.
+///
+/// The transform is applied around the center of the content, so
+/// `Affine::rotate(angle)` spins it in place and `Affine::scale(2.0)` grows
+/// it in all directions. Pointer events are mapped back into the
+/// coordinates of the content. The layout is not affected, the content
+/// takes the space it would take without the transform, and only receives
+/// pointer events within that space.
+///
+/// ```ignore
+/// Transformed::new(Affine::rotate(-0.1)).build(ui, |ui| {
+///     Label::new("Draft").build(ui);
+/// });
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transformed {
+    transform: Affine,
+}
+
+impl Properties for Transformed {
+    type Object = TransformedObject;
+}
+
+impl Transformed {
+    pub fn new(transform: Affine) -> Self {
+        Transformed { transform }
+    }
+
+    #[track_caller]
+    pub fn build(self, ui: &mut Ui, content: impl FnOnce(&mut Ui)) {
+        let caller = Location::caller().into();
+        ui.render_object(caller, self, content);
+    }
+}
+
+pub struct TransformedObject {
+    props: Transformed,
+    /// The transform around the center, for the current size.
+    transform: Affine,
+    size: Size,
+}
+
+impl RenderObject<Transformed> for TransformedObject {
+    type Action = ();
+
+    fn create(props: Transformed) -> Self {
+        TransformedObject {
+            props,
+            transform: Affine::default(),
+            size: Size::ZERO,
+        }
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, props: Transformed) {
+        if self.props != props {
+            self.props = props;
+            // The paint insets depend on the transform.
+            ctx.request_layout();
+            ctx.request_paint();
+        }
+    }
+}
+
+impl RenderObjectInterface for TransformedObject {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, children: &mut Children) {
+        for child in children {
+            child.event(ctx, event);
+        }
+    }
+
+    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle) {}
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        children: &mut Children,
+    ) -> Size {
+        bc.debug_check("Transformed");
+        let size = children.layout_stacked(ctx, bc);
+        // The transform moves the baseline of the content.
+        ctx.set_baseline_offset(0.0);
+        self.size = size;
+        let center = size.to_rect().center().to_vec2();
+        self.transform =
+            Affine::translate(center) * self.props.transform * Affine::translate(-center);
+
+        let rect = size.to_rect();
+        let mut painted = rect;
+        for child in children {
+            child.set_transform(self.transform);
+            let child_paint = self
+                .transform
+                .transform_rect_bbox(child.paint_rect() - child.layout_rect().origin().to_vec2());
+            painted = painted.union(child_paint);
+        }
+        ctx.set_paint_insets(Insets::new(
+            rect.x0 - painted.x0,
+            rect.y0 - painted.y0,
+            painted.x1 - rect.x1,
+            painted.y1 - rect.y1,
+        ));
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, children: &mut Children) {
+        for child in children {
+            child.paint(ctx);
+        }
+    }
+
+    fn hit_test(&self, pos: Point) -> bool {
+        // Only where the content is, not in the corners it was turned away from.
+        let local = self.transform.inverse() * pos;
+        self.size.to_rect().winding(local) != 0
+    }
+}
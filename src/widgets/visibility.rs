@@ -0,0 +1,142 @@
+//! A container that hides its content without dropping it.
+
+use crate::{object::prelude::*, tree::is_input_event};
+
+/// Whether content is shown, hidden or removed from the layout.
+///
+/// Unlike not building the content at all, the content keeps its render
+/// objects and their state, like the text of a text box or the scroll
+/// offset of a list, and keeps receiving timers and animation frames.
+///
+/// ```ignore
+/// let visibility = if advanced { Visibility::Visible } else { Visibility::Collapsed };
+/// visibility.build(ui, |ui| advanced_settings(ui, &mut settings));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Visibility {
+    Visible,
+    /// Takes the space of the content, but is not painted and receives no input.
+    Hidden,
+    /// Takes no space, is not painted and receives no input.
+    Collapsed,
+}
+
+impl Properties for Visibility {
+    type Object = Visibility;
+}
+
+impl Visibility {
+    /// `Visible` if `visible` is `true`, `Collapsed` otherwise.
+    pub fn collapsed_unless(visible: bool) -> Self {
+        if visible {
+            Visibility::Visible
+        } else {
+            Visibility::Collapsed
+        }
+    }
+
+    #[track_caller]
+    pub fn build(self, ui: &mut Ui, content: impl FnOnce(&mut Ui)) {
+        let caller = Location::caller().into();
+        ui.render_object(caller, self, content);
+    }
+}
+
+impl RenderObject<Visibility> for Visibility {
+    type Action = ();
+
+    fn create(props: Visibility) -> Self {
+        props
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, props: Visibility) {
+        if *self != props {
+            *self = props;
+            ctx.request_layout();
+            ctx.request_paint();
+        }
+    }
+}
+
+impl RenderObjectInterface for Visibility {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, children: &mut Children) {
+        if *self != Visibility::Visible && is_input_event(event) {
+            return;
+        }
+        for child in children {
+            child.event(ctx, event);
+        }
+    }
+
+    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle) {}
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        children: &mut Children,
+    ) -> Size {
+        bc.debug_check("Visibility");
+        if *self == Visibility::Collapsed {
+            return bc.min();
+        }
+        let size = children.layout_stacked(ctx, bc);
+        if *self == Visibility::Visible && !children.is_empty() {
+            ctx.set_paint_insets(children[0].compute_parent_paint_insets(size));
+        }
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, children: &mut Children) {
+        if *self != Visibility::Visible {
+            return;
+        }
+        for child in children {
+            child.paint(ctx);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        testing::{Harness, Log, Probe, Record},
+        widgets::Flex,
+    };
+    use std::{cell::Cell, rc::Rc};
+
+    #[test]
+    fn hidden_keeps_its_space_and_collapsed_does_not() {
+        let log = Log::default();
+        let visibility = Rc::new(Cell::new(Visibility::Visible));
+        let (probes, shown) = (log.clone(), visibility.clone());
+        let mut harness = Harness::new(move |ui| {
+            Flex::column().build(ui, |ui| {
+                shown.get().build(ui, |ui| {
+                    Probe::new("content", &probes).build(ui, |_| {});
+                });
+                Probe::new("below", &probes).build(ui, |_| {});
+            });
+        });
+        let content_id = Probe::find(harness.tree(), "content");
+        let content = harness.window_rect(content_id).center();
+        let below = Probe::find(harness.tree(), "below");
+        assert_eq!(harness.window_rect(below).y0, 100.0);
+        let clicks = |log: &Log| Probe::take(log, |record| *record == Record::Click);
+        harness.click(content);
+        assert_eq!(clicks(&log), [("content", Record::Click)]);
+
+        visibility.set(Visibility::Hidden);
+        harness.rebuild();
+        assert_eq!(harness.window_rect(below).y0, 100.0);
+        harness.click(content);
+        assert!(clicks(&log).is_empty());
+
+        visibility.set(Visibility::Collapsed);
+        harness.rebuild();
+        assert_eq!(harness.window_rect(below).y0, 0.0);
+        // The content kept its render object.
+        assert_eq!(Probe::find(harness.tree(), "content"), content_id);
+    }
+}
@@ -0,0 +1,213 @@
+//! A viewport that the user can zoom and pan.
+
+use crate::{
+    event::{Gesture, MouseButton},
+    kurbo::{Affine, Point, Vec2},
+    object::prelude::*,
+};
+use std::ops::RangeInclusive;
+
+/// How much a pixel of wheel movement zooms, exponentially.
+const ZOOM_PER_PIXEL: f64 = 0.002;
+
+/// Shows its content scaled and moved, zoomed with the mouse wheel around
+/// the pointer and panned by dragging, for canvases, diagrams and maps.
+///
+/// Pinching on a touchpad zooms as well. The content is laid out with the
+/// size of the viewport and stays interactive, clicks and drags reach it in
+/// its own coordinate space. Dragging only pans where the content does not
+/// handle the mouse itself.
+///
+/// ```ignore
+/// ZoomPan::new()
+///     .zoom_range(0.5..=4.0)
+///     .build(ui, |ui| diagram(ui, &nodes));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ZoomPan {
+    zoom_range: RangeInclusive<f64>,
+}
+
+impl Properties for ZoomPan {
+    type Object = ZoomPanObject;
+}
+
+impl Default for ZoomPan {
+    fn default() -> Self {
+        ZoomPan::new()
+    }
+}
+
+impl ZoomPan {
+    pub fn new() -> Self {
+        ZoomPan {
+            zoom_range: 0.1..=10.0,
+        }
+    }
+
+    /// How far the content can be zoomed out and in.
+    ///
+    /// The default value is `0.1..=10.0`.
+    pub fn zoom_range(mut self, zoom_range: RangeInclusive<f64>) -> Self {
+        self.zoom_range = zoom_range;
+        self
+    }
+
+    #[track_caller]
+    pub fn build(self, ui: &mut Ui, content: impl FnOnce(&mut Ui)) {
+        let caller = Location::caller().into();
+        ui.render_object(caller, self, content);
+    }
+}
+
+/// The offset that keeps the content at `pos` in place when zooming from `zoom` to `new_zoom`.
+fn zoom_around(offset: Vec2, zoom: f64, new_zoom: f64, pos: Point) -> Vec2 {
+    let content = (pos.to_vec2() - offset) / zoom;
+    pos.to_vec2() - content * new_zoom
+}
+
+pub struct ZoomPanObject {
+    props: ZoomPan,
+    zoom: f64,
+    offset: Vec2,
+    /// Where the pointer is, pinches zoom around it.
+    pointer: Option<Point>,
+}
+
+impl ZoomPanObject {
+    /// Zoom by `factor` around `pos`, within the zoom range.
+    fn zoom_by(&mut self, ctx: &mut EventCtx, factor: f64, pos: Point) {
+        let range = &self.props.zoom_range;
+        let zoom = (self.zoom * factor).max(*range.start()).min(*range.end());
+        if zoom != self.zoom {
+            self.offset = zoom_around(self.offset, self.zoom, zoom, pos);
+            self.zoom = zoom;
+            ctx.request_paint();
+        }
+    }
+
+    fn transform(&self) -> Affine {
+        Affine::translate(self.offset) * Affine::scale(self.zoom)
+    }
+}
+
+impl RenderObject<ZoomPan> for ZoomPanObject {
+    type Action = ();
+
+    fn create(props: ZoomPan) -> Self {
+        ZoomPanObject {
+            props,
+            zoom: 1.0,
+            offset: Vec2::ZERO,
+            pointer: None,
+        }
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, props: ZoomPan) {
+        if self.props != props {
+            let range = &props.zoom_range;
+            self.zoom = self.zoom.max(*range.start()).min(*range.end());
+            self.props = props;
+            ctx.request_paint();
+        }
+    }
+}
+
+impl RenderObjectInterface for ZoomPanObject {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, children: &mut Children) {
+        let transform = self.transform();
+        for child in &mut *children {
+            child.set_transform(transform);
+            child.event(ctx, event);
+        }
+
+        match event {
+            Event::MouseMove(mouse) => self.pointer = Some(mouse.pos),
+            Event::MouseDown(mouse) if mouse.button == MouseButton::Left && !ctx.is_handled() => {
+                ctx.capture_pointer();
+                ctx.set_handled();
+            }
+            Event::MouseUp(mouse) if mouse.button == MouseButton::Left && ctx.is_active() => {
+                ctx.release_pointer();
+            }
+            Event::Wheel(wheel) if !ctx.is_handled() => {
+                let factor = (-wheel.wheel_delta.y * ZOOM_PER_PIXEL).exp();
+                self.zoom_by(ctx, factor, wheel.pos);
+                ctx.set_handled();
+            }
+            Event::Zoom(delta) => {
+                let pos = self
+                    .pointer
+                    .unwrap_or_else(|| ctx.size().to_rect().center());
+                self.zoom_by(ctx, 1.0 + delta, pos);
+                ctx.set_handled();
+            }
+            _ => {}
+        }
+
+        if ctx.is_active() {
+            if let Some(Gesture::DragStart { delta, .. }) | Some(Gesture::DragMove { delta, .. }) =
+                Gesture::from_event(event)
+            {
+                self.offset += *delta;
+                ctx.request_paint();
+            }
+        }
+        let transform = self.transform();
+        for child in children {
+            child.set_transform(transform);
+        }
+    }
+
+    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle) {}
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        children: &mut Children,
+    ) -> Size {
+        bc.debug_check("ZoomPan");
+        let mut size = children.layout_stacked(ctx, &bc.loosen());
+        // The transform moves the baseline of the content.
+        ctx.set_baseline_offset(0.0);
+        let transform = self.transform();
+        for child in children {
+            child.set_transform(transform);
+        }
+        let max = bc.max();
+        if max.width.is_finite() {
+            size.width = max.width;
+        }
+        if max.height.is_finite() {
+            size.height = max.height;
+        }
+        bc.constrain(size)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, children: &mut Children) {
+        let size = ctx.size();
+        ctx.with_save(|ctx| {
+            ctx.clip(size.to_rect());
+            for child in children {
+                child.paint(ctx);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zoom_keeps_pointer_in_place() {
+        let pos = Point::new(100.0, 50.0);
+        let offset = zoom_around(Vec2::ZERO, 1.0, 2.0, pos);
+        assert_eq!(offset, Vec2::new(-100.0, -50.0));
+        // The content under the pointer stays the same.
+        let content = (pos.to_vec2() - offset) / 2.0;
+        assert_eq!(content, pos.to_vec2());
+        assert_eq!(zoom_around(offset, 2.0, 1.0, pos), Vec2::ZERO);
+    }
+}
@@ -0,0 +1,118 @@
+//! Declarative window configuration.
+
+use crate::kurbo::Size;
+use druid::{Selector, WindowHandle, WindowId};
+
+/// Sent to a window to close it without asking its close request handler.
+pub(crate) const CLOSE_WINDOW_NOW: Selector = Selector::new("coat.window.close-now");
+
+/// The window properties declared during a build pass.
+///
+/// Properties which are not declared keep their current value.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct WindowConfig {
+    pub(crate) title: Option<String>,
+    pub(crate) size: Option<Size>,
+    pub(crate) min_size: Option<Size>,
+    pub(crate) resizable: Option<bool>,
+    /// Close requests are reported to build code instead of closing the window.
+    pub(crate) intercept_close: bool,
+}
+
+impl WindowConfig {
+    /// Apply the properties of `declared` that changed since they were last declared.
+    pub(crate) fn apply(&mut self, declared: WindowConfig, handle: &WindowHandle, size: Size) {
+        if let Some(title) = declared.title.filter(|t| self.title.as_ref() != Some(t)) {
+            handle.set_title(&title);
+            self.title = Some(title);
+        }
+        if let Some(new_size) = declared.size.filter(|s| self.size != Some(*s)) {
+            handle.set_size(new_size);
+            self.size = Some(new_size);
+        }
+        if let Some(resizable) = declared.resizable.filter(|r| self.resizable != Some(*r)) {
+            handle.resizable(resizable);
+            self.resizable = Some(resizable);
+        }
+        if let Some(min_size) = declared.min_size.filter(|s| self.min_size != Some(*s)) {
+            self.min_size = Some(min_size);
+            self.enforce_min_size(handle, size);
+        }
+        self.intercept_close = declared.intercept_close;
+    }
+
+    /// Grow the window if it is smaller than the declared minimum size.
+    ///
+    /// The platform windows can not be given a minimum size once they
+    /// are open, so they are grown back after the user shrunk them.
+    pub(crate) fn enforce_min_size(&self, handle: &WindowHandle, size: Size) {
+        if let Some(min_size) = self.min_size {
+            let grown = Size::new(
+                size.width.max(min_size.width),
+                size.height.max(min_size.height),
+            );
+            if grown != size && size != Size::ZERO {
+                handle.set_size(grown);
+            }
+        }
+    }
+}
+
+/// Configures the window that is being built, see [`Ui::window`].
+///
+/// Every property is applied when its declared value changes, so
+/// declaring it in every build pass does not undo changes the user
+/// made, like resizing the window.
+///
+/// ```ignore
+/// ui.window()
+///     .set_title(format!("{} - Editor", file_name))
+///     .set_min_size(Size::new(400.0, 300.0));
+/// ```
+///
+/// [`Ui::window`]: crate::ui::Ui::window
+pub struct Window<'a> {
+    id: WindowId,
+    size: Size,
+    config: &'a mut WindowConfig,
+}
+
+impl<'a> Window<'a> {
+    pub(crate) fn new(id: WindowId, size: Size, config: &'a mut WindowConfig) -> Self {
+        Window { id, size, config }
+    }
+
+    /// The id of the window.
+    pub fn id(&self) -> WindowId {
+        self.id
+    }
+
+    /// The size of the content area of the window.
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    /// The title shown by the platform, for example in the title bar.
+    pub fn set_title(&mut self, title: impl Into<String>) -> &mut Self {
+        self.config.title = Some(title.into());
+        self
+    }
+
+    /// Resize the window.
+    pub fn set_size(&mut self, size: Size) -> &mut Self {
+        self.config.size = Some(size);
+        self
+    }
+
+    /// The size the user can not shrink the window below.
+    pub fn set_min_size(&mut self, min_size: Size) -> &mut Self {
+        self.config.min_size = Some(min_size);
+        self
+    }
+
+    /// Whether the user can resize the window.
+    pub fn set_resizable(&mut self, resizable: bool) -> &mut Self {
+        self.config.resizable = Some(resizable);
+        self
+    }
+}